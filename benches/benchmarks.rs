@@ -159,6 +159,7 @@ fn bench_file_prioritization(c: &mut Criterion) {
             enhanced_context: false,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         };
 
         group.throughput(Throughput::Elements(file_count as u64));
@@ -201,6 +202,7 @@ fn bench_markdown_generation(c: &mut Criterion) {
             enhanced_context: false,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         };
 
         group.throughput(Throughput::Elements(file_count as u64));
@@ -255,6 +257,7 @@ fn bench_end_to_end_processing(c: &mut Criterion) {
                         enhanced_context: false,
                         git_context: false,
                         git_context_depth: 3,
+                        reporter: Default::default(),
                     };
 
                     let cache = Arc::new(FileCache::new());
@@ -1,6 +1,8 @@
 //! Command-line interface configuration and parsing
 
 use clap::{Parser, Subcommand, ValueEnum};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tracing::debug;
 
@@ -54,6 +56,11 @@ Git Diff Command:
   # Include semantic analysis of changed files
   context-creator --trace-imports --include-callers diff main HEAD
 
+Historical Snapshots:
+  # Analyze a tag or commit without checking it out
+  context-creator --at v1.2.0
+  context-creator --at HEAD~10 --include \"src/**/*.rs\"
+
 Semantic Analysis:
   # Trace import dependencies
   context-creator --trace-imports --include \"**/auth.py\"
@@ -79,10 +86,35 @@ LLM Integration:
 
 Remote Repositories:
   # Analyze GitHub repository
-  context-creator --repo https://github.com/owner/repo
+  context-creator --remote https://github.com/owner/repo
+  
+  # Any git host, over https or ssh
+  context-creator --remote https://gitlab.com/group/project
+  context-creator --remote git@bitbucket.org:team/service.git
+  
+  # Local bare repository or file:// URL
+  context-creator --remote /srv/git/project.git
+  
+  # Reuse the cached clone without network access
+  context-creator --remote https://github.com/owner/repo --offline
+  
+  # A release tag, or one package of a monorepo
+  context-creator --remote https://github.com/owner/repo --remote-ref v2.1.0
+  context-creator --remote https://github.com/owner/repo/tree/main/packages/api
   
   # With specific patterns
-  context-creator --repo https://github.com/facebook/react --include \"**/*.js\"
+  context-creator --remote https://github.com/facebook/react --include \"**/*.js\"
+
+Configuration:
+  # Write a .context-creator.toml tailored to this project
+  context-creator init
+  
+  # Show the effective configuration and where each value comes from
+  context-creator config show
+  context-creator --profile backend config show
+  
+  # JSON Schema for editor completion and validation of .context-creator.toml
+  context-creator config schema > context-creator.schema.json
 
 Advanced Options:
   # Copy to clipboard
@@ -116,7 +148,10 @@ For usage examples, run: context-creator examples
 ";
 
 /// Supported LLM CLI tools
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum LlmTool {
     /// Use gemini (default)
     #[value(name = "gemini")]
@@ -146,7 +181,10 @@ pub enum LogFormat {
 }
 
 /// Output format options for the generated context
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     /// Markdown format (default)
     #[value(name = "markdown")]
@@ -282,6 +320,32 @@ pub enum Commands {
 
     /// Show usage examples
     Examples,
+
+    /// Write a .context-creator.toml tailored to the project
+    Init {
+        /// Project directory to inspect and write the configuration to
+        #[arg(value_name = "PATH", default_value = ".")]
+        path: PathBuf,
+
+        /// Overwrite an existing .context-creator.toml
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+}
+
+/// Actions of the `config` subcommand
+#[derive(Subcommand, Debug, Clone)]
+pub enum ConfigCommand {
+    /// Print the effective configuration and the source of each value
+    Show,
+    /// Print the JSON Schema of the configuration file
+    Schema,
 }
 
 /// High-performance CLI tool to convert codebases to Markdown for LLM context
@@ -315,10 +379,68 @@ pub struct Config {
     )]
     pub ignore: Option<Vec<String>>,
 
-    /// GitHub repository URL to analyze (e.g., <https://github.com/owner/repo>)
-    #[arg(long, help = "Process a GitHub repository")]
+    /// Git repository to analyze: an http(s) or ssh URL for any git host,
+    /// `git@host:owner/repo.git`, a `file://` URL or a local repository path
+    #[arg(
+        long,
+        help = "Process a remote git repository (GitHub, GitLab, Bitbucket, Gitea, ssh, file:// or local path)"
+    )]
     pub remote: Option<String>,
 
+    /// Branch, tag or commit of the remote repository to analyze
+    #[arg(
+        long = "remote-ref",
+        value_name = "REF",
+        help = "Branch, tag or commit to check out (overrides /tree/<ref> in the URL)"
+    )]
+    pub remote_ref: Option<String>,
+
+    /// Subdirectory of the remote repository to analyze
+    #[arg(
+        long = "remote-subdir",
+        value_name = "DIR",
+        help = "Only check out and analyze this subdirectory of the remote repository"
+    )]
+    pub remote_subdir: Option<PathBuf>,
+
+    /// Only use remote repositories already in the local clone cache
+    #[arg(
+        long,
+        help = "Use only cached clones for --remote, without network access"
+    )]
+    pub offline: bool,
+
+    /// Clone remote repositories into a temporary directory instead of the cache
+    #[arg(
+        long = "no-remote-cache",
+        help = "Do not use the persistent clone cache for --remote"
+    )]
+    pub no_remote_cache: bool,
+
+    /// Directory of the clone cache (defaults to the user cache directory)
+    #[arg(
+        long = "remote-cache-dir",
+        value_name = "DIR",
+        help = "Directory for cached remote clones"
+    )]
+    pub remote_cache_dir: Option<PathBuf>,
+
+    /// Size cap of the clone cache in megabytes
+    #[arg(
+        long = "remote-cache-size",
+        value_name = "MB",
+        help = "Maximum size of the clone cache in MB; least recently used clones are evicted"
+    )]
+    pub remote_cache_size: Option<u64>,
+
+    /// Git revision to analyze instead of the working tree
+    #[arg(
+        long,
+        value_name = "REF",
+        help = "Analyze a branch, tag or commit straight from the git object database"
+    )]
+    pub at: Option<String>,
+
     /// Read prompt from stdin
     #[arg(long = "stdin", help = "Read prompt from standard input")]
     pub read_stdin: bool,
@@ -358,6 +480,14 @@ pub struct Config {
     #[arg(short = 'c', long)]
     pub config: Option<PathBuf>,
 
+    /// Named profile from the configuration file to apply
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Apply a [profiles.<name>] table from the configuration file"
+    )]
+    pub profile: Option<String>,
+
     /// Show progress indicators during processing
     #[arg(long)]
     pub progress: bool,
@@ -402,6 +532,14 @@ pub struct Config {
     )]
     pub semantic_depth: usize,
 
+    /// Directories searched for C and C++ includes, relative to the project root
+    #[arg(
+        long = "include-path",
+        value_name = "DIR",
+        help = "Directory searched for C and C++ #include files (repeatable)"
+    )]
+    pub include_paths: Vec<PathBuf>,
+
     /// Start MCP server mode
     #[arg(long, help = "Start MCP server mode")]
     pub mcp: bool,
@@ -422,7 +560,7 @@ pub struct Config {
     #[arg(
         long = "rmcp-transport",
         default_value = "stdio",
-        help = "Transport mode for RMCP server (stdio, http)"
+        help = "Transport mode for RMCP server (stdio, http, streamable-http)"
     )]
     pub rmcp_transport: String,
 
@@ -437,6 +575,10 @@ pub struct Config {
     /// Maximum tokens from config defaults (not a CLI argument)
     #[clap(skip)]
     pub config_defaults_max_tokens: Option<usize>,
+
+    /// Progress reports and cancellation for embedders (not a CLI argument)
+    #[clap(skip)]
+    pub reporter: crate::core::progress::ProgressReporter,
 }
 
 impl Default for Config {
@@ -448,6 +590,13 @@ impl Default for Config {
             include: None,
             ignore: None,
             remote: None,
+            remote_ref: None,
+            remote_subdir: None,
+            offline: false,
+            no_remote_cache: false,
+            remote_cache_dir: None,
+            remote_cache_size: None,
+            at: None,
             read_stdin: false,
             output_file: None,
             max_tokens: None,
@@ -457,6 +606,7 @@ impl Default for Config {
             verbose: 0,
             log_format: LogFormat::default(),
             config: None,
+            profile: None,
             progress: false,
             copy: false,
             enhanced_context: false,
@@ -467,6 +617,7 @@ impl Default for Config {
             include_callers: false,
             include_types: false,
             semantic_depth: 5,
+            include_paths: vec![],
             mcp: false,
             mcp_port: 9090,
             rmcp: false,
@@ -474,6 +625,7 @@ impl Default for Config {
             custom_priorities: vec![],
             config_token_limits: None,
             config_defaults_max_tokens: None,
+            reporter: Default::default(),
         }
     }
 }
@@ -547,15 +699,8 @@ impl Config {
         // - --copy with --output-file (can't copy to clipboard and write to file)
 
         // Validate repo URL if provided
-        if let Some(repo_url) = &self.remote {
-            if !repo_url.starts_with("https://github.com/")
-                && !repo_url.starts_with("http://github.com/")
-            {
-                return Err(ContextCreatorError::InvalidConfiguration(
-                    "Repository URL must be a GitHub URL (https://github.com/owner/repo)"
-                        .to_string(),
-                ));
-            }
+        if self.remote.is_some() {
+            self.remote_repository()?;
         } else {
             // Only validate paths if repo is not provided
             let paths = self.get_directories();
@@ -616,6 +761,24 @@ impl Config {
             ));
         }
 
+        if self.remote.is_none() && (self.remote_ref.is_some() || self.remote_subdir.is_some()) {
+            return Err(ContextCreatorError::InvalidConfiguration(
+                "--remote-ref and --remote-subdir require --remote".to_string(),
+            ));
+        }
+
+        if self.at.is_some() && self.remote.is_some() {
+            return Err(ContextCreatorError::InvalidConfiguration(
+                "--at cannot be combined with --remote; use --remote-ref instead".to_string(),
+            ));
+        }
+
+        if self.offline && self.remote_cache().is_none() {
+            return Err(ContextCreatorError::InvalidConfiguration(
+                "--offline requires the clone cache; remove --no-remote-cache".to_string(),
+            ));
+        }
+
         // Validate Ollama model requirement
         if self.llm_tool == LlmTool::Ollama
             && self.ollama_model.is_none()
@@ -629,39 +792,91 @@ impl Config {
         Ok(())
     }
 
-    /// Load configuration from file if specified
+    /// Load and apply the configuration files, see [`Config::config_layers`]
     pub fn load_from_file(&mut self) -> Result<(), crate::utils::error::ContextCreatorError> {
         use crate::config::ConfigFile;
+        use crate::utils::error::ContextCreatorError;
 
-        let config_file = if let Some(ref config_path) = self.config {
-            // Load from specified config file
-            Some(ConfigFile::load_from_file(config_path)?)
-        } else {
-            // Try to load from default locations
-            ConfigFile::load_default()?
-        };
-
-        if let Some(config_file) = config_file {
-            // Store custom priorities for the walker
-            self.custom_priorities = config_file.priorities.clone();
+        let layers = self.config_layers()?;
+        if layers.is_empty() {
+            return match &self.profile {
+                Some(name) => Err(ContextCreatorError::ConfigError(format!(
+                    "Profile '{name}' requested but no configuration file was found"
+                ))),
+                None => Ok(()),
+            };
+        }
 
-            // Store token limits for token resolution
-            self.config_token_limits = Some(config_file.tokens.clone());
+        if self.verbose > 0 {
+            for layer in &layers {
+                debug!(
+                    "Loaded configuration from: {} ({})",
+                    layer.path.display(),
+                    layer.source
+                );
+            }
+        }
 
-            config_file.apply_to_cli_config(self);
+        let config_file = ConfigFile::merge_layers(layers.into_iter().map(|layer| layer.config))?;
+        self.apply_config_file(config_file)?;
 
-            if self.verbose > 0 {
-                if let Some(ref config_path) = self.config {
-                    debug!("Loaded configuration from: {}", config_path.display());
-                } else {
-                    debug!("Loaded configuration from default location");
-                }
+        if self.verbose > 0 {
+            if let Some(ref name) = self.profile {
+                debug!("Applied configuration profile: {}", name);
             }
         }
 
         Ok(())
     }
 
+    /// Configuration files to merge, lowest precedence first
+    ///
+    /// A file given with `--config` is used on its own; otherwise the home,
+    /// repository and nested directory files are layered.
+    pub fn config_layers(
+        &self,
+    ) -> Result<Vec<crate::config::ConfigLayer>, crate::utils::error::ContextCreatorError> {
+        use crate::config::{ConfigFile, ConfigLayer, ConfigSource};
+
+        match &self.config {
+            Some(config_path) => Ok(vec![ConfigLayer {
+                path: config_path.clone(),
+                source: ConfigSource::ExplicitFile(config_path.clone()),
+                config: ConfigFile::load_from_file(config_path)?,
+            }]),
+            None => ConfigFile::discover_layers(std::path::Path::new(".")),
+        }
+    }
+
+    /// Apply a merged configuration file, including the selected profile
+    pub fn apply_config_file(
+        &mut self,
+        mut config_file: crate::config::ConfigFile,
+    ) -> Result<(), crate::utils::error::ContextCreatorError> {
+        if let Some(name) = &self.profile {
+            config_file.apply_profile(name)?;
+        }
+
+        // Store custom priorities for the walker
+        self.custom_priorities = config_file.priorities.clone();
+
+        // Store token limits for token resolution
+        self.config_token_limits = Some(config_file.tokens.clone());
+
+        config_file.apply_to_cli_config(self);
+        Ok(())
+    }
+
+    /// Whether the subcommand reads or writes configuration files itself
+    ///
+    /// These commands must see the command line before any file is applied.
+    pub fn manages_config_files(&self) -> bool {
+        matches!(
+            self.command,
+            Some(Commands::Init { .. }) | Some(Commands::Config { .. })
+        )
+    }
+
     /// Get the prompt from the explicit prompt flag
     pub fn get_prompt(&self) -> Option<String> {
         self.prompt
@@ -686,6 +901,45 @@ impl Config {
         }
     }
 
+    /// Get the remote repository to analyze, if any
+    /// `--remote-ref` and `--remote-subdir` override a ref or subdirectory in the URL
+    pub fn remote_repository(
+        &self,
+    ) -> Result<Option<crate::remote::RemoteRepository>, crate::utils::error::ContextCreatorError>
+    {
+        let Some(url) = &self.remote else {
+            return Ok(None);
+        };
+
+        let mut repo = crate::remote::parse_remote_url(url)?;
+        if let Some(git_ref) = &self.remote_ref {
+            repo = repo.with_ref(git_ref.clone());
+        }
+        if let Some(subdir) = &self.remote_subdir {
+            repo = repo.with_subdir(subdir.clone())?;
+        }
+        Ok(Some(repo))
+    }
+
+    /// Get the clone cache for remote repositories, unless disabled
+    pub fn remote_cache(&self) -> Option<crate::remote::RemoteCache> {
+        use crate::remote::RemoteCache;
+
+        if self.no_remote_cache {
+            return None;
+        }
+
+        let root = self
+            .remote_cache_dir
+            .clone()
+            .or_else(RemoteCache::default_root)?;
+        let mut cache = RemoteCache::new(root).offline(self.offline);
+        if let Some(megabytes) = self.remote_cache_size {
+            cache = cache.with_max_size(megabytes.saturating_mul(1024 * 1024));
+        }
+        Some(cache)
+    }
+
     /// Get include patterns if specified
     pub fn get_include_patterns(&self) -> Vec<String> {
         self.include.as_ref().cloned().unwrap_or_default()
@@ -776,6 +1030,11 @@ impl Config {
             return true;
         }
 
+        // Configuration commands never take a prompt
+        if self.manages_config_files() {
+            return false;
+        }
+
         // If stdin is not a terminal (i.e., it's piped) and no prompt is provided
         if !std::io::stdin().is_terminal() && self.get_prompt().is_none() {
             return true;
@@ -0,0 +1,276 @@
+//! `config` command implementation
+
+use crate::cli::{Commands, Config, ConfigCommand, LlmTool};
+use crate::config::{config_schema, ConfigFile, ConfigLayer, ConfigSource};
+use anyhow::{anyhow, Result};
+use clap::Parser;
+
+/// Run the config command
+pub fn run_config(config: Config) -> Result<()> {
+    match &config.command {
+        Some(Commands::Config {
+            action: ConfigCommand::Show,
+        }) => {
+            print!("{}", effective_config_report(&config)?);
+            Ok(())
+        }
+        Some(Commands::Config {
+            action: ConfigCommand::Schema,
+        }) => {
+            println!("{}", config_schema()?);
+            Ok(())
+        }
+        _ => Err(anyhow!("Invalid command for config execution")),
+    }
+}
+
+/// One line of the effective configuration report
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveValue {
+    /// Configuration key
+    pub key: &'static str,
+    /// Rendered value
+    pub value: String,
+    /// Human-readable origin of the value
+    pub source: String,
+}
+
+/// Renders one configuration value from a CLI config
+type Render = fn(&Config) -> String;
+
+/// Keys shown by `config show`, in display order
+const KEYS: &[(&str, Render)] = &[
+    ("paths", |c| {
+        render_list(c.get_directories().iter().map(|p| p.display()))
+    }),
+    ("max_tokens", |c| {
+        render_option(c.max_tokens.or(c.config_defaults_max_tokens))
+    }),
+    ("llm_tool", |c| c.llm_tool.command().to_string()),
+    ("tokens.gemini", |c| render_token_limit(c, LlmTool::Gemini)),
+    ("tokens.codex", |c| render_token_limit(c, LlmTool::Codex)),
+    ("tokens.claude", |c| render_token_limit(c, LlmTool::Claude)),
+    ("tokens.ollama", |c| render_token_limit(c, LlmTool::Ollama)),
+    ("progress", |c| c.progress.to_string()),
+    ("verbose", |c| c.verbose.to_string()),
+    ("quiet", |c| c.quiet.to_string()),
+    ("output_file", |c| {
+        render_option(c.output_file.as_ref().map(|p| p.display()))
+    }),
+    ("include", |c| render_list(c.get_include_patterns())),
+    ("ignore", |c| render_list(c.get_ignore_patterns())),
+    ("priorities", |c| {
+        render_list(
+            c.custom_priorities
+                .iter()
+                .map(|p| format!("{} = {}", p.pattern, p.weight)),
+        )
+    }),
+    ("trace_imports", |c| c.trace_imports.to_string()),
+    ("include_callers", |c| c.include_callers.to_string()),
+    ("include_types", |c| c.include_types.to_string()),
+    ("semantic_depth", |c| c.semantic_depth.to_string()),
+    ("include_paths", |c| {
+        render_list(c.include_paths.iter().map(|p| p.display()))
+    }),
+    ("style", |c| format!("{:?}", c.output_format).to_lowercase()),
+    ("git_context", |c| c.git_context.to_string()),
+    ("git_context_depth", |c| c.git_context_depth.to_string()),
+    ("enhanced_context", |c| c.enhanced_context.to_string()),
+    ("profile", |c| render_option(c.profile.as_ref())),
+];
+
+/// Keys whose values accumulate across configuration files
+const MERGED_KEYS: &[&str] = &["include", "ignore", "priorities"];
+
+/// Resolve every configuration value and the place it came from
+///
+/// `cli` must be the configuration as parsed from the command line, before
+/// any configuration file was applied.
+pub fn effective_values(cli: &Config) -> Result<Vec<EffectiveValue>> {
+    let builtin = Config::parse_from(["context-creator"]);
+    let layers = cli.config_layers()?;
+
+    let mut resolved = cli.clone();
+    resolved.load_from_file()?;
+
+    // Resolve again after each layer, without the profile, to find the
+    // layers that changed a value
+    let mut stages = Vec::with_capacity(layers.len());
+    let mut merged = ConfigFile::default();
+    for layer in &layers {
+        merged.merge(layer.config.clone())?;
+        let mut stage = cli.clone();
+        stage.profile = None;
+        stage.apply_config_file(merged.clone())?;
+        stages.push(stage);
+    }
+
+    Ok(KEYS
+        .iter()
+        .map(|(key, render)| {
+            let value = render(&resolved);
+            let source = if render(cli) != render(&builtin) {
+                ConfigSource::Cli.to_string()
+            } else if value == render(cli) {
+                ConfigSource::Default.to_string()
+            } else if stages.last().map(render).as_ref() != Some(&value) {
+                let profile = cli.profile.as_deref().unwrap_or_default();
+                format!("profile '{profile}'")
+            } else {
+                let mut previous = render(cli);
+                let mut setters = Vec::new();
+                for (layer, stage) in layers.iter().zip(&stages) {
+                    let current = render(stage);
+                    if current != previous {
+                        setters.push(layer.source.to_string());
+                    }
+                    previous = current;
+                }
+                if MERGED_KEYS.contains(key) {
+                    setters.join(" + ")
+                } else {
+                    setters.pop().unwrap_or_default()
+                }
+            };
+            EffectiveValue { key, value, source }
+        })
+        .collect())
+}
+
+/// Format the effective configuration as an aligned table
+pub fn effective_config_report(cli: &Config) -> Result<String> {
+    let values = effective_values(cli)?;
+
+    let mut report = String::from("# Effective configuration\n");
+    match cli.config_layers()?.as_slice() {
+        [] => report.push_str("# Config file: none\n"),
+        [layer] => report.push_str(&format!("# Config file: {}\n", describe_layer(layer))),
+        layers => {
+            report.push_str("# Config files, later ones take precedence:\n");
+            for layer in layers {
+                report.push_str(&format!("#   {}\n", describe_layer(layer)));
+            }
+        }
+    }
+    report.push('\n');
+
+    let key_width = values.iter().map(|v| v.key.len()).max().unwrap_or(0);
+    let value_width = values.iter().map(|v| v.value.len()).max().unwrap_or(0);
+    for value in &values {
+        report.push_str(&format!(
+            "{:key_width$}  {:value_width$}  # {}\n",
+            value.key, value.value, value.source
+        ));
+    }
+
+    Ok(report)
+}
+
+fn describe_layer(layer: &ConfigLayer) -> String {
+    match layer.source {
+        // Parent files are already named by their path
+        ConfigSource::ParentFile(_) => layer.path.display().to_string(),
+        ref source => format!("{} ({source})", layer.path.display()),
+    }
+}
+
+fn render_option<T: std::fmt::Display>(value: Option<T>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+fn render_list<T: std::fmt::Display>(items: impl IntoIterator<Item = T>) -> String {
+    let items: Vec<String> = items.into_iter().map(|item| item.to_string()).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn render_token_limit(config: &Config, tool: LlmTool) -> String {
+    let configured = config
+        .config_token_limits
+        .as_ref()
+        .and_then(|limits| match tool {
+            LlmTool::Gemini => limits.gemini,
+            LlmTool::Codex => limits.codex,
+            LlmTool::Claude => limits.claude,
+            LlmTool::Ollama => limits.ollama,
+        });
+    configured
+        .unwrap_or_else(|| tool.default_max_tokens())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn value<'a>(values: &'a [EffectiveValue], key: &str) -> &'a EffectiveValue {
+        values.iter().find(|v| v.key == key).unwrap()
+    }
+
+    #[test]
+    fn test_sources_of_effective_values() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            r#"
+ignore = ["target/**"]
+
+[defaults]
+max_tokens = 50000
+llm_tool = "claude"
+
+[profiles.deep]
+semantic_depth = 9
+"#,
+        )
+        .unwrap();
+
+        let cli = Config::parse_from([
+            "context-creator",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--profile",
+            "deep",
+            "--tool",
+            "codex",
+        ]);
+        let values = effective_values(&cli).unwrap();
+
+        let llm_tool = value(&values, "llm_tool");
+        assert_eq!(llm_tool.value, "codex");
+        assert_eq!(llm_tool.source, "CLI");
+
+        let max_tokens = value(&values, "max_tokens");
+        assert_eq!(max_tokens.value, "50000");
+        assert!(max_tokens.source.starts_with("--config"));
+
+        assert_eq!(value(&values, "ignore").value, "[target/**]");
+
+        let depth = value(&values, "semantic_depth");
+        assert_eq!(depth.value, "9");
+        assert!(depth.source.starts_with("profile 'deep'"));
+
+        let quiet = value(&values, "quiet");
+        assert_eq!(quiet.value, "false");
+        assert_eq!(quiet.source, "built-in default");
+    }
+
+    #[test]
+    fn test_report_names_the_config_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(&config_path, "[defaults]\nprogress = true\n").unwrap();
+
+        let cli =
+            Config::parse_from(["context-creator", "--config", config_path.to_str().unwrap()]);
+        let report = effective_config_report(&cli).unwrap();
+
+        assert!(report.contains(&format!("# Config file: {}", config_path.display())));
+        assert!(report.contains("progress"));
+    }
+}
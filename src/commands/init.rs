@@ -0,0 +1,290 @@
+//! `init` command implementation
+
+use crate::cli::{Commands, Config};
+use crate::config::{ConfigFile, Priority, CONFIG_FILE_NAME};
+use crate::core::walker::{walk_directory, WalkOptions};
+use crate::utils::error::ContextCreatorError;
+use crate::utils::file_ext::FileType;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Run the init command
+pub fn run_init(config: Config) -> Result<()> {
+    let (path, force) = match &config.command {
+        Some(Commands::Init { path, force }) => (path.clone(), *force),
+        _ => return Err(anyhow!("Invalid command for init execution")),
+    };
+
+    if !path.is_dir() {
+        return Err(ContextCreatorError::InvalidPath(format!(
+            "Not a directory: {}",
+            path.display()
+        ))
+        .into());
+    }
+
+    let target = path.join(CONFIG_FILE_NAME);
+    if target.exists() && !force {
+        return Err(ContextCreatorError::InvalidConfiguration(format!(
+            "{} already exists; use --force to overwrite it",
+            target.display()
+        ))
+        .into());
+    }
+
+    let survey = ProjectSurvey::inspect(&path)?;
+    std::fs::write(&target, survey.render_config())?;
+
+    if !config.quiet {
+        println!("Wrote {}", target.display());
+    }
+    Ok(())
+}
+
+/// What `init` learned about a project
+#[derive(Debug, Clone, Default)]
+pub struct ProjectSurvey {
+    /// Programming languages found by the walker with their file counts, most common first
+    pub languages: Vec<(FileType, usize)>,
+    /// Whether Markdown documentation was found
+    pub has_docs: bool,
+    /// A Rust `target/` directory or `Cargo.toml` exists
+    pub has_target: bool,
+    /// A `node_modules/` directory or `package.json` exists
+    pub has_node_modules: bool,
+    /// A `go.mod` file exists
+    pub has_go_mod: bool,
+    /// A `pyproject.toml` file exists
+    pub has_pyproject: bool,
+    /// Build manifests present in the project root
+    pub manifests: Vec<&'static str>,
+}
+
+impl ProjectSurvey {
+    /// Walk the project and look for well-known build artifacts and manifests
+    pub fn inspect(root: &Path) -> Result<Self> {
+        let files = walk_directory(root, WalkOptions::default())?;
+
+        let mut counts: HashMap<FileType, usize> = HashMap::new();
+        let mut has_docs = false;
+        for file in &files {
+            if file.file_type == FileType::Markdown {
+                has_docs = true;
+            } else if is_programming_language(&file.file_type) {
+                *counts.entry(file.file_type.clone()).or_default() += 1;
+            }
+        }
+        let mut languages: Vec<_> = counts.into_iter().collect();
+        languages.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.display_name().cmp(b.0.display_name()))
+        });
+
+        let manifests = ["Cargo.toml", "package.json", "go.mod", "pyproject.toml"]
+            .into_iter()
+            .filter(|name| root.join(name).is_file())
+            .collect::<Vec<_>>();
+
+        Ok(Self {
+            languages,
+            has_docs,
+            has_target: root.join("target").is_dir() || manifests.contains(&"Cargo.toml"),
+            has_node_modules: root.join("node_modules").is_dir()
+                || manifests.contains(&"package.json"),
+            has_go_mod: manifests.contains(&"go.mod"),
+            has_pyproject: manifests.contains(&"pyproject.toml"),
+            manifests,
+        })
+    }
+
+    /// Build a configuration suited to the surveyed project
+    pub fn to_config(&self) -> ConfigFile {
+        let mut config = ConfigFile::default();
+
+        if self.has_target {
+            config.ignore.push("target/**".to_string());
+        }
+        if self.has_node_modules {
+            config.ignore.push("node_modules/**".to_string());
+            config.ignore.push("dist/**".to_string());
+        }
+        if self.has_pyproject || self.has_language(FileType::Python) {
+            config.ignore.push("**/__pycache__/**".to_string());
+            config.ignore.push(".venv/**".to_string());
+        }
+        if self.has_go_mod {
+            config.ignore.push("vendor/**".to_string());
+        }
+
+        // Priorities are first-match-wins, so specific patterns come first
+        let mut entry_points = Vec::new();
+        let mut tests = Vec::new();
+        let mut sources = Vec::new();
+        for (file_type, _) in &self.languages {
+            let (entries, test_patterns, source_patterns) = language_patterns(file_type);
+            entry_points.extend(entries);
+            tests.extend(test_patterns);
+            sources.extend(source_patterns);
+        }
+
+        let weighted = |patterns: Vec<&str>, weight: f32| {
+            patterns
+                .into_iter()
+                .map(move |pattern| Priority {
+                    pattern: pattern.to_string(),
+                    weight,
+                })
+                .collect::<Vec<_>>()
+        };
+        config.priorities.extend(weighted(entry_points, 150.0));
+        config
+            .priorities
+            .extend(weighted(self.manifests.clone(), 80.0));
+        config.priorities.extend(weighted(tests, 30.0));
+        config.priorities.extend(weighted(sources, 100.0));
+        if self.has_docs {
+            config.priorities.extend(weighted(vec!["README.md"], 60.0));
+            config
+                .priorities
+                .extend(weighted(vec!["docs/**/*.md"], 20.0));
+        }
+
+        config
+    }
+
+    /// Render the configuration file with a header describing the survey
+    pub fn render_config(&self) -> String {
+        let mut output = String::from("# Generated by `context-creator init`\n");
+        if self.languages.is_empty() {
+            output.push_str("# No source files were found; adjust the patterns below as needed\n");
+        } else {
+            let languages: Vec<String> = self
+                .languages
+                .iter()
+                .map(|(file_type, count)| {
+                    let noun = if *count == 1 { "file" } else { "files" };
+                    format!("{} ({count} {noun})", file_type.display_name())
+                })
+                .collect();
+            output.push_str(&format!("# Languages: {}\n", languages.join(", ")));
+        }
+        output
+            .push_str("# Run `context-creator config show` to see the effective configuration\n\n");
+
+        output.push_str(
+            &toml::to_string_pretty(&self.to_config())
+                .unwrap_or_else(|_| "# Failed to generate config".to_string()),
+        );
+        output
+    }
+
+    fn has_language(&self, file_type: FileType) -> bool {
+        self.languages.iter().any(|(found, _)| *found == file_type)
+    }
+}
+
+/// Entry point, test and source patterns for a language
+fn language_patterns(
+    file_type: &FileType,
+) -> (Vec<&'static str>, Vec<&'static str>, Vec<&'static str>) {
+    match file_type {
+        FileType::Rust => (
+            vec!["src/main.rs", "src/lib.rs"],
+            vec!["tests/**/*.rs", "benches/**/*.rs"],
+            vec!["src/**/*.rs"],
+        ),
+        FileType::Python => (
+            vec!["**/__main__.py", "main.py"],
+            vec!["tests/**/*.py", "**/test_*.py"],
+            vec!["**/*.py"],
+        ),
+        FileType::JavaScript => (
+            vec!["src/index.js", "index.js"],
+            vec!["**/*.test.js", "**/*.spec.js"],
+            vec!["src/**/*.js", "**/*.js"],
+        ),
+        FileType::TypeScript => (
+            vec!["src/index.ts", "index.ts"],
+            vec!["**/*.test.ts", "**/*.spec.ts", "**/*.test.tsx"],
+            vec!["src/**/*.ts", "src/**/*.tsx", "**/*.ts"],
+        ),
+        FileType::Go => (
+            vec!["main.go", "cmd/**/main.go"],
+            vec!["**/*_test.go"],
+            vec!["**/*.go"],
+        ),
+        FileType::Java => (
+            vec![],
+            vec!["src/test/**/*.java"],
+            vec!["src/main/**/*.java", "**/*.java"],
+        ),
+        _ => (vec![], vec![], vec![]),
+    }
+}
+
+fn is_programming_language(file_type: &FileType) -> bool {
+    !matches!(
+        file_type,
+        FileType::Markdown
+            | FileType::Json
+            | FileType::Yaml
+            | FileType::Toml
+            | FileType::Xml
+            | FileType::Html
+            | FileType::Css
+            | FileType::Text
+            | FileType::Other
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_survey_of_rust_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\nname = \"demo\"\n").unwrap();
+        fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(root.join("src/util.rs"), "pub fn util() {}\n").unwrap();
+        fs::write(root.join("README.md"), "# Demo\n").unwrap();
+
+        let survey = ProjectSurvey::inspect(root).unwrap();
+        assert_eq!(survey.languages, vec![(FileType::Rust, 2)]);
+        assert!(survey.has_target);
+        assert!(!survey.has_node_modules);
+        assert!(survey.has_docs);
+
+        let config = survey.to_config();
+        assert_eq!(config.ignore, vec!["target/**"]);
+        assert_eq!(config.priorities[0].pattern, "src/main.rs");
+        assert!(config.priorities.iter().any(|p| p.pattern == "Cargo.toml"));
+    }
+
+    #[test]
+    fn test_rendered_config_parses_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("pyproject.toml"), "[project]\nname = \"demo\"\n").unwrap();
+        fs::write(root.join("app.py"), "print('hi')\n").unwrap();
+        fs::write(root.join("go.mod"), "module demo\n").unwrap();
+        fs::write(root.join("main.go"), "package main\n").unwrap();
+
+        let survey = ProjectSurvey::inspect(root).unwrap();
+        let rendered = survey.render_config();
+        assert!(rendered.contains("# Languages: Go (1 file), Python (1 file)"));
+
+        let parsed: ConfigFile = toml::from_str(&rendered).unwrap();
+        assert!(parsed.ignore.contains(&"vendor/**".to_string()));
+        assert!(parsed.ignore.contains(&".venv/**".to_string()));
+        assert!(parsed
+            .priorities
+            .iter()
+            .any(|p| p.pattern == "**/*_test.go"));
+    }
+}
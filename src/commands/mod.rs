@@ -1,7 +1,11 @@
 //! Command implementations
 
+pub mod config;
 pub mod diff;
+pub mod init;
 pub mod search;
 
+pub use config::run_config;
 pub use diff::run_diff;
+pub use init::run_init;
 pub use search::run_search;
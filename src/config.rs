@@ -4,14 +4,29 @@
 //! Configuration files can specify defaults for CLI options and additional
 //! settings like file priorities and ignore patterns.
 
-use crate::cli::{Config as CliConfig, LlmTool};
+use crate::cli::{Config as CliConfig, LlmTool, OutputFormat};
+use crate::core::walker::sanitize_pattern;
 use crate::utils::error::ContextCreatorError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
+/// Name of the per-directory configuration file
+pub const CONFIG_FILE_NAME: &str = ".context-creator.toml";
+
+/// Alternative name, used when a directory has no [`CONFIG_FILE_NAME`]
+pub const RC_FILE_NAME: &str = ".contextrc.toml";
+
 /// Configuration file structure
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+#[schemars(title = "context-creator configuration")]
 pub struct ConfigFile {
+    /// Inherited sections or values to discard before this file is merged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reset: Vec<String>,
+
     /// Default settings
     #[serde(default)]
     pub defaults: Defaults,
@@ -31,39 +46,267 @@ pub struct ConfigFile {
     /// Token limits for different LLM tools
     #[serde(default)]
     pub tokens: TokenLimits,
+
+    /// Named profiles selectable with `--profile <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// A named set of overrides, selected with `--profile <name>`
+///
+/// Values in `defaults` and `tokens` override the top-level ones individually;
+/// list sections that are present replace the top-level list. Anything unset
+/// falls back to the profile named in `extends`, and then to the top level of
+/// the file.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Profile to inherit unset sections from
+    pub extends: Option<String>,
+
+    /// Overrides for the `[defaults]` section
+    pub defaults: Option<Defaults>,
+
+    /// Replacement for the top-level priorities
+    pub priorities: Option<Vec<Priority>>,
+
+    /// Replacement for the top-level ignore patterns
+    pub ignore: Option<Vec<String>>,
+
+    /// Replacement for the top-level include patterns
+    pub include: Option<Vec<String>>,
+
+    /// Overrides for the `[tokens]` section
+    pub tokens: Option<TokenLimits>,
+
+    /// Enable import tracing
+    pub trace_imports: Option<bool>,
+
+    /// Include callers of functions in the selected files
+    pub include_callers: Option<bool>,
+
+    /// Include type definitions used by the selected files
+    pub include_types: Option<bool>,
+
+    /// Depth limit for semantic dependency traversal
+    pub semantic_depth: Option<usize>,
+}
+
+impl Profile {
+    /// Fill every unset field from `parent`
+    fn inherit(self, parent: &Profile) -> Profile {
+        Profile {
+            extends: parent.extends.clone(),
+            defaults: merge_sections(parent.defaults.clone(), self.defaults, Defaults::merge),
+            priorities: self.priorities.or_else(|| parent.priorities.clone()),
+            ignore: self.ignore.or_else(|| parent.ignore.clone()),
+            include: self.include.or_else(|| parent.include.clone()),
+            tokens: merge_sections(parent.tokens.clone(), self.tokens, TokenLimits::merge),
+            trace_imports: self.trace_imports.or(parent.trace_imports),
+            include_callers: self.include_callers.or(parent.include_callers),
+            include_types: self.include_types.or(parent.include_types),
+            semantic_depth: self.semantic_depth.or(parent.semantic_depth),
+        }
+    }
+
+    /// The profile's semantic flags as `[defaults]` overrides
+    fn semantic_defaults(&self) -> Defaults {
+        Defaults {
+            trace_imports: self.trace_imports,
+            include_callers: self.include_callers,
+            include_types: self.include_types,
+            semantic_depth: self.semantic_depth,
+            ..Defaults::default()
+        }
+    }
+}
+
+/// Merge two optional sections, `upper` taking precedence value by value
+fn merge_sections<T>(base: Option<T>, upper: Option<T>, merge: fn(&mut T, T)) -> Option<T> {
+    match (base, upper) {
+        (Some(mut base), Some(upper)) => {
+            merge(&mut base, upper);
+            Some(base)
+        }
+        (base, upper) => upper.or(base),
+    }
+}
+
+/// Where an effective configuration value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Given on the command line
+    Cli,
+    /// File passed with `--config`
+    ExplicitFile(PathBuf),
+    /// `.context-creator.toml` in the current directory
+    LocalFile,
+    /// `.contextrc.toml` in the current directory
+    RcFile,
+    /// Configuration file in a directory between the repository root and the
+    /// current directory
+    ParentFile(PathBuf),
+    /// `~/.context-creator.toml`
+    HomeFile,
+    /// Built-in default
+    Default,
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigSource::Cli => write!(f, "CLI"),
+            ConfigSource::ExplicitFile(_) => write!(f, "--config file"),
+            ConfigSource::LocalFile => write!(f, "local file"),
+            ConfigSource::RcFile => write!(f, ".contextrc.toml"),
+            ConfigSource::ParentFile(path) => write!(f, "{}", path.display()),
+            ConfigSource::HomeFile => write!(f, "home file"),
+            ConfigSource::Default => write!(f, "built-in default"),
+        }
+    }
+}
+
+/// One configuration file taking part in layered loading
+#[derive(Debug, Clone)]
+pub struct ConfigLayer {
+    /// Location of the file
+    pub path: PathBuf,
+    /// Role of the file in the precedence order
+    pub source: ConfigSource,
+    /// Parsed contents
+    pub config: ConfigFile,
 }
 
 /// Default configuration settings
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+///
+/// Semantic and output options only take effect when the command line left
+/// the corresponding flag at its default.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
 pub struct Defaults {
     /// Default maximum tokens
     pub max_tokens: Option<usize>,
 
     /// Default LLM tool
     #[serde(default)]
-    pub llm_tool: Option<String>,
+    pub llm_tool: Option<LlmTool>,
 
     /// Default to show progress
-    #[serde(default)]
-    pub progress: bool,
+    pub progress: Option<bool>,
 
     /// Default verbosity
-    #[serde(default)]
-    pub verbose: bool,
+    pub verbose: Option<bool>,
 
     /// Default quiet mode
-    #[serde(default)]
-    pub quiet: bool,
+    pub quiet: Option<bool>,
 
     /// Default directory
     pub directory: Option<PathBuf>,
 
     /// Default output file
     pub output_file: Option<PathBuf>,
+
+    /// Default to tracing imports of the selected files
+    pub trace_imports: Option<bool>,
+
+    /// Default to including callers of functions in the selected files
+    pub include_callers: Option<bool>,
+
+    /// Default to including type definitions used by the selected files
+    pub include_types: Option<bool>,
+
+    /// Default depth limit for semantic dependency traversal
+    pub semantic_depth: Option<usize>,
+
+    /// Directories searched for C and C++ includes, relative to the project root
+    pub include_paths: Option<Vec<PathBuf>>,
+
+    /// Default output style
+    pub style: Option<OutputFormat>,
+
+    /// Default to including git history in file headers
+    pub git_context: Option<bool>,
+
+    /// Default number of commits shown per file
+    pub git_context_depth: Option<usize>,
+
+    /// Default to including file metadata
+    pub enhanced_context: Option<bool>,
+}
+
+impl Defaults {
+    /// Override every value that `upper` sets and append its include paths
+    pub fn merge(&mut self, upper: Defaults) {
+        let Defaults {
+            max_tokens,
+            llm_tool,
+            progress,
+            verbose,
+            quiet,
+            directory,
+            output_file,
+            trace_imports,
+            include_callers,
+            include_types,
+            semantic_depth,
+            include_paths,
+            style,
+            git_context,
+            git_context_depth,
+            enhanced_context,
+        } = upper;
+
+        self.max_tokens = max_tokens.or(self.max_tokens);
+        self.llm_tool = llm_tool.or(self.llm_tool);
+        self.progress = progress.or(self.progress);
+        self.verbose = verbose.or(self.verbose);
+        self.quiet = quiet.or(self.quiet);
+        self.directory = directory.or(self.directory.take());
+        self.output_file = output_file.or(self.output_file.take());
+        self.trace_imports = trace_imports.or(self.trace_imports);
+        self.include_callers = include_callers.or(self.include_callers);
+        self.include_types = include_types.or(self.include_types);
+        self.semantic_depth = semantic_depth.or(self.semantic_depth);
+        if let Some(include_paths) = include_paths {
+            append_unique(
+                self.include_paths.get_or_insert_with(Vec::new),
+                include_paths,
+            );
+        }
+        self.style = style.or(self.style);
+        self.git_context = git_context.or(self.git_context);
+        self.git_context_depth = git_context_depth.or(self.git_context_depth);
+        self.enhanced_context = enhanced_context.or(self.enhanced_context);
+    }
+
+    /// Unset a single value, returning false for unknown names
+    fn clear(&mut self, field: &str) -> bool {
+        match field {
+            "max_tokens" => self.max_tokens = None,
+            "llm_tool" => self.llm_tool = None,
+            "progress" => self.progress = None,
+            "verbose" => self.verbose = None,
+            "quiet" => self.quiet = None,
+            "directory" => self.directory = None,
+            "output_file" => self.output_file = None,
+            "trace_imports" => self.trace_imports = None,
+            "include_callers" => self.include_callers = None,
+            "include_types" => self.include_types = None,
+            "semantic_depth" => self.semantic_depth = None,
+            "include_paths" => self.include_paths = None,
+            "style" => self.style = None,
+            "git_context" => self.git_context = None,
+            "git_context_depth" => self.git_context_depth = None,
+            "enhanced_context" => self.enhanced_context = None,
+            _ => return false,
+        }
+        true
+    }
 }
 
 /// File priority configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct Priority {
     /// Glob pattern to match files
     pub pattern: String,
@@ -72,7 +315,8 @@ pub struct Priority {
 }
 
 /// Token limits configuration for different LLM tools
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(deny_unknown_fields)]
 pub struct TokenLimits {
     /// Maximum tokens for Gemini
     pub gemini: Option<usize>,
@@ -84,6 +328,28 @@ pub struct TokenLimits {
     pub ollama: Option<usize>,
 }
 
+impl TokenLimits {
+    /// Override every limit that `upper` sets
+    pub fn merge(&mut self, upper: TokenLimits) {
+        self.gemini = upper.gemini.or(self.gemini);
+        self.codex = upper.codex.or(self.codex);
+        self.claude = upper.claude.or(self.claude);
+        self.ollama = upper.ollama.or(self.ollama);
+    }
+
+    /// Unset a single limit, returning false for unknown tools
+    fn clear(&mut self, tool: &str) -> bool {
+        match tool {
+            "gemini" => self.gemini = None,
+            "codex" => self.codex = None,
+            "claude" => self.claude = None,
+            "ollama" => self.ollama = None,
+            _ => return false,
+        }
+        true
+    }
+}
+
 impl ConfigFile {
     /// Load configuration from a file
     pub fn load_from_file(path: &Path) -> Result<Self, ContextCreatorError> {
@@ -103,39 +369,294 @@ impl ConfigFile {
         })?;
 
         let config: ConfigFile = toml::from_str(&content).map_err(|e| {
+            let location = e
+                .span()
+                .map(|span| {
+                    let (line, column) = line_and_column(&content, span.start);
+                    format!(" at line {line}, column {column}")
+                })
+                .unwrap_or_default();
             ContextCreatorError::ConfigError(format!(
-                "Failed to parse config file {}: {}",
+                "Failed to parse config file {}{location}: {}",
                 path.display(),
-                e
+                e.message()
             ))
         })?;
 
+        config.validate().map_err(|e| {
+            let message = match e {
+                ContextCreatorError::ConfigError(message) => message,
+                other => other.to_string(),
+            };
+            ContextCreatorError::ConfigError(format!("{}: {message}", path.display()))
+        })?;
+
         Ok(config)
     }
 
-    /// Load configuration from default locations
+    /// Check everything serde cannot: reset keys and glob patterns
+    pub fn validate(&self) -> Result<(), ContextCreatorError> {
+        // Reject unknown reset keys even when there is nothing to reset yet
+        for key in &self.reset {
+            ConfigFile::default().clear_section(key)?;
+        }
+
+        validate_sections("", &self.priorities, &self.ignore, &self.include)?;
+        for (name, profile) in &self.profiles {
+            validate_sections(
+                &format!("profiles.{name}."),
+                profile.priorities.as_deref().unwrap_or_default(),
+                profile.ignore.as_deref().unwrap_or_default(),
+                profile.include.as_deref().unwrap_or_default(),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Load and merge every configuration layer that applies to the current directory
     pub fn load_default() -> Result<Option<Self>, ContextCreatorError> {
-        // Try .context-creator.toml in current directory
-        let local_config = Path::new(".context-creator.toml");
-        if local_config.exists() {
-            return Ok(Some(Self::load_from_file(local_config)?));
+        let layers = Self::discover_layers(Path::new("."))?;
+        if layers.is_empty() {
+            return Ok(None);
+        }
+        Self::merge_layers(layers.into_iter().map(|layer| layer.config)).map(Some)
+    }
+
+    /// Find the configuration files that apply to `start`, lowest precedence first
+    ///
+    /// The order is `~/.context-creator.toml`, then every directory from the
+    /// enclosing git repository's root down to `start`. Each directory
+    /// contributes its `.context-creator.toml`, or its `.contextrc.toml` if
+    /// it has none. Outside a repository only `start` itself is searched.
+    pub fn discover_layers(start: &Path) -> Result<Vec<ConfigLayer>, ContextCreatorError> {
+        let home = dirs::home_dir().and_then(|home| home.canonicalize().ok());
+        Self::layers_between(home.as_deref(), start)
+    }
+
+    /// Paths of the configuration files that apply to `start`, lowest
+    /// precedence first, without reading them; see [`Self::discover_layers`]
+    pub fn discover_layer_paths(start: &Path) -> Result<Vec<PathBuf>, ContextCreatorError> {
+        let home = dirs::home_dir().and_then(|home| home.canonicalize().ok());
+        Ok(Self::layer_files_between(home.as_deref(), start)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect())
+    }
+
+    fn layers_between(
+        home: Option<&Path>,
+        start: &Path,
+    ) -> Result<Vec<ConfigLayer>, ContextCreatorError> {
+        Self::layer_files_between(home, start)?
+            .into_iter()
+            .map(|(path, source)| {
+                Ok(ConfigLayer {
+                    config: Self::load_from_file(&path)?,
+                    source,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    fn layer_files_between(
+        home: Option<&Path>,
+        start: &Path,
+    ) -> Result<Vec<(PathBuf, ConfigSource)>, ContextCreatorError> {
+        let start = start
+            .canonicalize()
+            .map_err(|e| ContextCreatorError::InvalidPath(format!("{}: {e}", start.display())))?;
+
+        let mut directories: Vec<&Path> = Vec::new();
+        let mut in_repository = false;
+        for directory in start.ancestors() {
+            directories.push(directory);
+            if directory.join(".git").exists() {
+                in_repository = true;
+                break;
+            }
+        }
+        if !in_repository {
+            directories.truncate(1);
+        }
+        directories.reverse();
+
+        let mut layers = Vec::new();
+        if let Some(home) = home.filter(|home| !directories.contains(home)) {
+            let path = home.join(CONFIG_FILE_NAME);
+            if path.is_file() {
+                layers.push((path, ConfigSource::HomeFile));
+            }
+        }
+
+        for directory in directories {
+            let (path, local_source) = if directory.join(CONFIG_FILE_NAME).is_file() {
+                (directory.join(CONFIG_FILE_NAME), ConfigSource::LocalFile)
+            } else if directory.join(RC_FILE_NAME).is_file() {
+                (directory.join(RC_FILE_NAME), ConfigSource::RcFile)
+            } else {
+                continue;
+            };
+
+            let source = if directory == start {
+                local_source
+            } else {
+                ConfigSource::ParentFile(path.clone())
+            };
+            layers.push((path, source));
+        }
+
+        Ok(layers)
+    }
+
+    /// Merge configuration files given lowest precedence first
+    pub fn merge_layers(
+        layers: impl IntoIterator<Item = ConfigFile>,
+    ) -> Result<Self, ContextCreatorError> {
+        let mut merged = ConfigFile::default();
+        for layer in layers {
+            merged.merge(layer)?;
         }
+        Ok(merged)
+    }
+
+    /// Merge a configuration file with higher precedence into this one
+    ///
+    /// Entries in `upper.reset` are cleared first. Values set in `upper`
+    /// override, ignore and include patterns are appended, `upper`'s
+    /// priorities go first so they win under first-match, and profiles are
+    /// replaced by name.
+    pub fn merge(&mut self, upper: ConfigFile) -> Result<(), ContextCreatorError> {
+        for key in &upper.reset {
+            self.clear_section(key)?;
+        }
+
+        self.defaults.merge(upper.defaults);
+        self.tokens.merge(upper.tokens);
+
+        let mut priorities = upper.priorities;
+        priorities.append(&mut self.priorities);
+        self.priorities = priorities;
+
+        append_unique(&mut self.ignore, upper.ignore);
+        append_unique(&mut self.include, upper.include);
+        self.profiles.extend(upper.profiles);
+
+        Ok(())
+    }
 
-        // Try .contextrc.toml in current directory
-        let rc_config = Path::new(".contextrc.toml");
-        if rc_config.exists() {
-            return Ok(Some(Self::load_from_file(rc_config)?));
+    /// Discard a section, or a single value written as `defaults.<key>` or `tokens.<tool>`
+    fn clear_section(&mut self, key: &str) -> Result<(), ContextCreatorError> {
+        let known = match key {
+            "all" => {
+                *self = ConfigFile::default();
+                true
+            }
+            "defaults" => {
+                self.defaults = Defaults::default();
+                true
+            }
+            "priorities" => {
+                self.priorities.clear();
+                true
+            }
+            "ignore" => {
+                self.ignore.clear();
+                true
+            }
+            "include" => {
+                self.include.clear();
+                true
+            }
+            "tokens" => {
+                self.tokens = TokenLimits::default();
+                true
+            }
+            "profiles" => {
+                self.profiles.clear();
+                true
+            }
+            _ => match key.split_once('.') {
+                Some(("defaults", field)) => self.defaults.clear(field),
+                Some(("tokens", tool)) => self.tokens.clear(tool),
+                _ => false,
+            },
+        };
+
+        if known {
+            Ok(())
+        } else {
+            Err(ContextCreatorError::ConfigError(format!(
+                "Unknown reset key '{key}' (expected a section name, 'all', \
+                 'defaults.<key>' or 'tokens.<tool>')"
+            )))
         }
+    }
 
-        // Try in home directory
-        if let Some(home) = dirs::home_dir() {
-            let home_config = home.join(".context-creator.toml");
-            if home_config.exists() {
-                return Ok(Some(Self::load_from_file(&home_config)?));
+    /// Resolve a profile and its `extends` chain into a single profile
+    pub fn resolve_profile(&self, name: &str) -> Result<Profile, ContextCreatorError> {
+        let mut chain = vec![name.to_string()];
+        let mut resolved = self.find_profile(name)?.clone();
+
+        while let Some(parent_name) = resolved.extends.clone() {
+            if chain.contains(&parent_name) {
+                chain.push(parent_name);
+                return Err(ContextCreatorError::ConfigError(format!(
+                    "Profile inheritance cycle: {}",
+                    chain.join(" -> ")
+                )));
             }
+            let parent = self.find_profile(&parent_name)?;
+            chain.push(parent_name);
+            resolved = resolved.inherit(parent);
+        }
+
+        Ok(resolved)
+    }
+
+    /// Apply the named profile on top of the top-level sections
+    ///
+    /// The profile's semantic flags end up in `defaults`, so they follow the
+    /// same precedence as every other default.
+    pub fn apply_profile(&mut self, name: &str) -> Result<Profile, ContextCreatorError> {
+        let profile = self.resolve_profile(name)?;
+
+        if let Some(defaults) = &profile.defaults {
+            self.defaults.merge(defaults.clone());
+        }
+        self.defaults.merge(profile.semantic_defaults());
+        if let Some(priorities) = &profile.priorities {
+            self.priorities = priorities.clone();
+        }
+        if let Some(ignore) = &profile.ignore {
+            self.ignore = ignore.clone();
+        }
+        if let Some(include) = &profile.include {
+            self.include = include.clone();
+        }
+        if let Some(tokens) = &profile.tokens {
+            self.tokens.merge(tokens.clone());
         }
 
-        Ok(None)
+        Ok(profile)
+    }
+
+    fn find_profile(&self, name: &str) -> Result<&Profile, ContextCreatorError> {
+        self.profiles.get(name).ok_or_else(|| {
+            let available = if self.profiles.is_empty() {
+                "none defined".to_string()
+            } else {
+                self.profiles
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            ContextCreatorError::ConfigError(format!(
+                "Unknown profile '{name}' (available: {available})"
+            ))
+        })
     }
 
     /// Apply configuration defaults to CLI config
@@ -151,37 +672,31 @@ impl ConfigFile {
             cli_config.config_defaults_max_tokens = self.defaults.max_tokens;
         }
 
-        if let Some(ref tool_str) = self.defaults.llm_tool {
+        if let Some(tool) = self.defaults.llm_tool {
             // Only apply if CLI used default
             if cli_config.llm_tool == LlmTool::default() {
-                match tool_str.as_str() {
-                    "gemini" => cli_config.llm_tool = LlmTool::Gemini,
-                    "codex" => cli_config.llm_tool = LlmTool::Codex,
-                    "claude" => cli_config.llm_tool = LlmTool::Claude,
-                    "ollama" => cli_config.llm_tool = LlmTool::Ollama,
-                    _ => {} // Ignore invalid tool names
-                }
+                cli_config.llm_tool = tool;
             }
         }
 
         // Apply boolean defaults only if they weren't explicitly set
-        if !cli_config.progress && self.defaults.progress {
-            cli_config.progress = self.defaults.progress;
+        if !cli_config.progress {
+            cli_config.progress = self.defaults.progress.unwrap_or(false);
         }
 
-        if cli_config.verbose == 0 && self.defaults.verbose {
+        if cli_config.verbose == 0 && self.defaults.verbose == Some(true) {
             cli_config.verbose = 1; // Convert bool true to verbose level 1
         }
 
-        if !cli_config.quiet && self.defaults.quiet {
-            cli_config.quiet = self.defaults.quiet;
+        if !cli_config.quiet {
+            cli_config.quiet = self.defaults.quiet.unwrap_or(false);
         }
 
         // Apply directory default if CLI used default (".") AND no repo is specified
         // This prevents conflict with --remote validation
         let current_paths = cli_config.get_directories();
         if current_paths.len() == 1
-            && current_paths[0] == PathBuf::from(".")
+            && current_paths[0] == Path::new(".")
             && self.defaults.directory.is_some()
             && cli_config.remote.is_none()
         {
@@ -193,18 +708,122 @@ impl ConfigFile {
             cli_config.output_file = self.defaults.output_file.clone();
         }
 
-        // Apply ignore patterns from config file if no CLI ignore patterns provided
-        // CLI ignore patterns always take precedence over config file patterns
-        if cli_config.ignore.is_none() && !self.ignore.is_empty() {
-            cli_config.ignore = Some(self.ignore.clone());
+        // Semantic and output flags can only be switched on from the CLI, so
+        // a false value there means the default was kept
+        if !cli_config.trace_imports {
+            cli_config.trace_imports = self.defaults.trace_imports.unwrap_or(false);
+        }
+        if !cli_config.include_callers {
+            cli_config.include_callers = self.defaults.include_callers.unwrap_or(false);
+        }
+        if !cli_config.include_types {
+            cli_config.include_types = self.defaults.include_types.unwrap_or(false);
+        }
+        if !cli_config.git_context {
+            cli_config.git_context = self.defaults.git_context.unwrap_or(false);
+        }
+        if !cli_config.enhanced_context {
+            cli_config.enhanced_context = self.defaults.enhanced_context.unwrap_or(false);
         }
 
-        // Apply include patterns from config file if no CLI include patterns provided
-        // CLI include patterns always take precedence over config file patterns
-        if cli_config.include.is_none() && !self.include.is_empty() {
-            cli_config.include = Some(self.include.clone());
+        // Valued options only apply if the CLI used the built-in default
+        let builtin = CliConfig::default();
+        if let Some(depth) = self.defaults.semantic_depth {
+            if cli_config.semantic_depth == builtin.semantic_depth {
+                cli_config.semantic_depth = depth;
+            }
+        }
+        if let Some(depth) = self.defaults.git_context_depth {
+            if cli_config.git_context_depth == builtin.git_context_depth {
+                cli_config.git_context_depth = depth;
+            }
         }
+        if let Some(style) = self.defaults.style {
+            if cli_config.output_format == builtin.output_format {
+                cli_config.output_format = style;
+            }
+        }
+
+        // Include paths given on the CLI are searched before the configured ones
+        for path in self.defaults.include_paths.iter().flatten() {
+            if !cli_config.include_paths.contains(path) {
+                cli_config.include_paths.push(path.clone());
+            }
+        }
+
+        // CLI patterns are layered on top of the configuration files' patterns
+        // like those of a nearer file; `reset` opts out of inherited ones
+        for (cli_patterns, file_patterns) in [
+            (&mut cli_config.ignore, &self.ignore),
+            (&mut cli_config.include, &self.include),
+        ] {
+            if file_patterns.is_empty() {
+                continue;
+            }
+            let mut patterns = file_patterns.clone();
+            append_unique(&mut patterns, cli_patterns.take().unwrap_or_default());
+            *cli_patterns = Some(patterns);
+        }
+    }
+}
+
+/// Append the entries from `upper` that `base` does not already contain
+fn append_unique<T: PartialEq>(base: &mut Vec<T>, upper: Vec<T>) {
+    for entry in upper {
+        if !base.contains(&entry) {
+            base.push(entry);
+        }
+    }
+}
+
+/// Check the patterns of one set of sections, `prefix` naming where they live
+fn validate_sections(
+    prefix: &str,
+    priorities: &[Priority],
+    ignore: &[String],
+    include: &[String],
+) -> Result<(), ContextCreatorError> {
+    let invalid = |pattern: &str, section: &str, reason: String| {
+        ContextCreatorError::ConfigError(format!(
+            "Invalid glob pattern '{pattern}' in {prefix}{section}: {reason}"
+        ))
+    };
+
+    for priority in priorities {
+        glob::Pattern::new(&priority.pattern)
+            .map_err(|e| invalid(&priority.pattern, "priorities", e.to_string()))?;
     }
+
+    // Ignore and include patterns go through the walker's override matcher
+    let mut builder = ignore::overrides::OverrideBuilder::new(".");
+    for (section, patterns) in [("ignore", ignore), ("include", include)] {
+        for pattern in patterns.iter().filter(|p| !p.trim().is_empty()) {
+            sanitize_pattern(pattern).map_err(|e| invalid(pattern, section, e.to_string()))?;
+            builder
+                .add(pattern)
+                .map_err(|e| invalid(pattern, section, e.to_string()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// One-based line and column of a byte offset
+fn line_and_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |line| line.chars().count())
+        + 1;
+    (line, column)
+}
+
+/// JSON Schema of the configuration file, for editor completion and validation
+pub fn config_schema() -> Result<String, ContextCreatorError> {
+    serde_json::to_string_pretty(&schemars::schema_for!(ConfigFile))
+        .map_err(|e| ContextCreatorError::ConfigError(format!("Failed to render schema: {e}")))
 }
 
 /// Create an example configuration file
@@ -212,12 +831,9 @@ pub fn create_example_config() -> String {
     let example = ConfigFile {
         defaults: Defaults {
             max_tokens: Some(150000),
-            llm_tool: Some("gemini".to_string()),
-            progress: true,
-            verbose: false,
-            quiet: false,
-            directory: None,
-            output_file: None,
+            llm_tool: Some(LlmTool::Gemini),
+            progress: Some(true),
+            ..Defaults::default()
         },
         tokens: TokenLimits {
             gemini: Some(2_000_000),
@@ -258,6 +874,14 @@ pub fn create_example_config() -> String {
             ".env".to_string(),
         ],
         include: vec!["!important/**".to_string()],
+        reset: vec![],
+        profiles: BTreeMap::from([(
+            "docs".to_string(),
+            Profile {
+                include: Some(vec!["**/*.md".to_string(), "docs/**".to_string()]),
+                ..Profile::default()
+            },
+        )]),
     };
 
     toml::to_string_pretty(&example)
@@ -299,8 +923,8 @@ weight = 50.0
         let config: ConfigFile = toml::from_str(config_content).unwrap();
 
         assert_eq!(config.defaults.max_tokens, Some(100000));
-        assert_eq!(config.defaults.llm_tool, Some("gemini".to_string()));
-        assert!(config.defaults.progress);
+        assert_eq!(config.defaults.llm_tool, Some(LlmTool::Gemini));
+        assert_eq!(config.defaults.progress, Some(true));
         assert_eq!(config.priorities.len(), 2);
         assert_eq!(config.priorities[0].pattern, "src/**/*.rs");
         assert_eq!(config.priorities[0].weight, 100.0);
@@ -323,7 +947,7 @@ progress = true
 
         let config = ConfigFile::load_from_file(&config_path).unwrap();
         assert_eq!(config.defaults.max_tokens, Some(50000));
-        assert!(config.defaults.progress);
+        assert_eq!(config.defaults.progress, Some(true));
     }
 
     #[test]
@@ -331,17 +955,19 @@ progress = true
         let config_file = ConfigFile {
             defaults: Defaults {
                 max_tokens: Some(75000),
-                llm_tool: Some("codex".to_string()),
-                progress: true,
-                verbose: true,
-                quiet: false,
+                llm_tool: Some(LlmTool::Codex),
+                progress: Some(true),
+                verbose: Some(true),
                 directory: Some(PathBuf::from("/tmp")),
                 output_file: Some(PathBuf::from("output.md")),
+                ..Defaults::default()
             },
             tokens: TokenLimits::default(),
             priorities: vec![],
             ignore: vec![],
             include: vec![],
+            profiles: BTreeMap::new(),
+            reset: vec![],
         };
 
         let mut cli_config = CliConfig {
@@ -426,12 +1052,9 @@ max_tokens = 200000
         let config_file = ConfigFile {
             defaults: Defaults {
                 max_tokens: Some(75000),
-                llm_tool: Some("gemini".to_string()),
-                progress: true,
-                verbose: false,
-                quiet: false,
-                directory: None,
-                output_file: None,
+                llm_tool: Some(LlmTool::Gemini),
+                progress: Some(true),
+                ..Defaults::default()
             },
             tokens: TokenLimits {
                 gemini: Some(2_500_000),
@@ -442,6 +1065,8 @@ max_tokens = 200000
             priorities: vec![],
             ignore: vec![],
             include: vec![],
+            profiles: BTreeMap::new(),
+            reset: vec![],
         };
 
         let mut cli_config = CliConfig {
@@ -459,4 +1084,393 @@ max_tokens = 200000
         assert_eq!(token_limits.gemini, Some(2_500_000));
         assert_eq!(token_limits.codex, Some(1_800_000));
     }
+
+    const PROFILES_CONFIG: &str = r#"
+ignore = ["target/**"]
+
+[defaults]
+max_tokens = 100000
+
+[profiles.backend]
+include = ["src/server/**"]
+trace_imports = true
+semantic_depth = 2
+
+[profiles.backend-review]
+extends = "backend"
+include_callers = true
+
+[[profiles.backend-review.priorities]]
+pattern = "src/server/api/**"
+weight = 50.0
+
+[profiles.docs]
+include = ["**/*.md"]
+ignore = []
+
+[profiles.docs.defaults]
+max_tokens = 20000
+"#;
+
+    #[test]
+    fn test_profile_overrides_sections() {
+        let mut config: ConfigFile = toml::from_str(PROFILES_CONFIG).unwrap();
+        assert_eq!(config.profiles.len(), 3);
+
+        config.apply_profile("docs").unwrap();
+
+        assert_eq!(config.include, vec!["**/*.md"]);
+        assert!(config.ignore.is_empty());
+        assert_eq!(config.defaults.max_tokens, Some(20000));
+    }
+
+    #[test]
+    fn test_profile_extends_inherits_unset_sections() {
+        let mut config: ConfigFile = toml::from_str(PROFILES_CONFIG).unwrap();
+        let profile = config.apply_profile("backend-review").unwrap();
+
+        assert_eq!(config.include, vec!["src/server/**"]);
+        assert_eq!(config.ignore, vec!["target/**"]);
+        assert_eq!(config.priorities.len(), 1);
+        assert_eq!(config.defaults.max_tokens, Some(100000));
+        assert_eq!(profile.trace_imports, Some(true));
+        assert_eq!(profile.include_callers, Some(true));
+        assert_eq!(profile.semantic_depth, Some(2));
+    }
+
+    #[test]
+    fn test_profile_semantic_flags_respect_cli() {
+        let mut config: ConfigFile = toml::from_str(PROFILES_CONFIG).unwrap();
+        config.apply_profile("backend").unwrap();
+
+        let mut cli_config = CliConfig::default();
+        config.apply_to_cli_config(&mut cli_config);
+        assert!(cli_config.trace_imports);
+        assert!(!cli_config.include_callers);
+        assert_eq!(cli_config.semantic_depth, 2);
+
+        let mut cli_config = CliConfig {
+            semantic_depth: 7,
+            ..CliConfig::default()
+        };
+        config.apply_to_cli_config(&mut cli_config);
+        assert_eq!(cli_config.semantic_depth, 7);
+    }
+
+    #[test]
+    fn test_unknown_profile_lists_available() {
+        let config: ConfigFile = toml::from_str(PROFILES_CONFIG).unwrap();
+        let err = config.resolve_profile("frontend").unwrap_err().to_string();
+
+        assert!(err.contains("Unknown profile 'frontend'"));
+        assert!(err.contains("backend, backend-review, docs"));
+    }
+
+    #[test]
+    fn test_profile_inheritance_cycle() {
+        let config: ConfigFile = toml::from_str(
+            r#"
+[profiles.a]
+extends = "b"
+
+[profiles.b]
+extends = "a"
+"#,
+        )
+        .unwrap();
+
+        let err = config.resolve_profile("a").unwrap_err().to_string();
+        assert!(err.contains("Profile inheritance cycle: a -> b -> a"));
+    }
+
+    #[test]
+    fn test_semantic_and_output_defaults() {
+        let config: ConfigFile = toml::from_str(
+            r#"
+[defaults]
+trace_imports = true
+semantic_depth = 8
+style = "xml"
+git_context = true
+git_context_depth = 10
+include_paths = ["include", "vendor/include"]
+"#,
+        )
+        .unwrap();
+
+        let mut cli_config = CliConfig::default();
+        config.apply_to_cli_config(&mut cli_config);
+        assert!(cli_config.trace_imports);
+        assert_eq!(cli_config.semantic_depth, 8);
+        assert_eq!(cli_config.output_format, OutputFormat::Xml);
+        assert!(cli_config.git_context);
+        assert_eq!(cli_config.git_context_depth, 10);
+
+        assert_eq!(
+            cli_config.include_paths,
+            [PathBuf::from("include"), PathBuf::from("vendor/include")]
+        );
+
+        let mut cli_config = CliConfig {
+            output_format: OutputFormat::Plain,
+            include_paths: vec![PathBuf::from("generated"), PathBuf::from("include")],
+            ..CliConfig::default()
+        };
+        config.apply_to_cli_config(&mut cli_config);
+        assert_eq!(cli_config.output_format, OutputFormat::Plain);
+        // Paths given on the CLI are searched first
+        assert_eq!(
+            cli_config.include_paths,
+            [
+                PathBuf::from("generated"),
+                PathBuf::from("include"),
+                PathBuf::from("vendor/include")
+            ]
+        );
+
+        assert!(toml::from_str::<ConfigFile>("[defaults]\nstyle = \"pdf\"\n").is_err());
+    }
+
+    #[test]
+    fn test_merge_appends_lists_and_overrides_scalars() {
+        let home: ConfigFile = toml::from_str(
+            r#"
+ignore = ["target/**"]
+
+[defaults]
+max_tokens = 100000
+progress = true
+
+[tokens]
+claude = 200000
+
+[[priorities]]
+pattern = "src/**"
+weight = 100.0
+"#,
+        )
+        .unwrap();
+        let project: ConfigFile = toml::from_str(
+            r#"
+ignore = ["dist/**", "target/**"]
+
+[defaults]
+max_tokens = 50000
+
+[tokens]
+gemini = 1000000
+
+[[priorities]]
+pattern = "src/main.rs"
+weight = 150.0
+"#,
+        )
+        .unwrap();
+
+        let merged = ConfigFile::merge_layers([home, project]).unwrap();
+        assert_eq!(merged.ignore, vec!["target/**", "dist/**"]);
+        assert_eq!(merged.defaults.max_tokens, Some(50000));
+        assert_eq!(merged.defaults.progress, Some(true));
+        assert_eq!(merged.tokens.claude, Some(200000));
+        assert_eq!(merged.tokens.gemini, Some(1000000));
+        let patterns: Vec<_> = merged.priorities.iter().map(|p| &p.pattern).collect();
+        assert_eq!(patterns, vec!["src/main.rs", "src/**"]);
+    }
+
+    #[test]
+    fn test_reset_discards_inherited_values() {
+        let home: ConfigFile = toml::from_str(
+            r#"
+ignore = ["target/**"]
+include = ["src/**"]
+
+[defaults]
+max_tokens = 100000
+progress = true
+"#,
+        )
+        .unwrap();
+        let project: ConfigFile = toml::from_str(
+            r#"
+reset = ["ignore", "defaults.progress"]
+ignore = ["build/**"]
+"#,
+        )
+        .unwrap();
+
+        let merged = ConfigFile::merge_layers([home, project]).unwrap();
+        assert_eq!(merged.ignore, vec!["build/**"]);
+        assert_eq!(merged.include, vec!["src/**"]);
+        assert_eq!(merged.defaults.max_tokens, Some(100000));
+        assert_eq!(merged.defaults.progress, None);
+    }
+
+    #[test]
+    fn test_include_paths_are_appended_across_layers() {
+        let home: ConfigFile = toml::from_str(
+            r#"
+[defaults]
+include_paths = ["include", "vendor/include"]
+"#,
+        )
+        .unwrap();
+        let project: ConfigFile = toml::from_str(
+            r#"
+[defaults]
+include_paths = ["generated", "include"]
+"#,
+        )
+        .unwrap();
+        let merged = ConfigFile::merge_layers([home.clone(), project]).unwrap();
+        assert_eq!(
+            merged.defaults.include_paths,
+            Some(vec![
+                PathBuf::from("include"),
+                PathBuf::from("vendor/include"),
+                PathBuf::from("generated"),
+            ])
+        );
+
+        // A nearer file without include paths keeps the inherited ones
+        let quiet: ConfigFile = toml::from_str("[defaults]\nquiet = true\n").unwrap();
+        let merged = ConfigFile::merge_layers([home.clone(), quiet]).unwrap();
+        assert_eq!(
+            merged.defaults.include_paths,
+            Some(vec![
+                PathBuf::from("include"),
+                PathBuf::from("vendor/include")
+            ])
+        );
+
+        let reset: ConfigFile = toml::from_str(
+            r#"
+reset = ["defaults.include_paths"]
+
+[defaults]
+include_paths = ["generated"]
+"#,
+        )
+        .unwrap();
+        let merged = ConfigFile::merge_layers([home, reset]).unwrap();
+        assert_eq!(
+            merged.defaults.include_paths,
+            Some(vec![PathBuf::from("generated")])
+        );
+    }
+
+    #[test]
+    fn test_unknown_reset_key_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(&config_path, "reset = [\"defaults.colour\"]\n").unwrap();
+
+        let err = ConfigFile::load_from_file(&config_path)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown reset key 'defaults.colour'"));
+    }
+
+    #[test]
+    fn test_layers_from_home_to_nested_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path().join("home");
+        let repo = temp_dir.path().join("repo");
+        let nested = repo.join("crates/app");
+        fs::create_dir_all(&home).unwrap();
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::write(home.join(CONFIG_FILE_NAME), "ignore = [\"home/**\"]\n").unwrap();
+        fs::write(repo.join(RC_FILE_NAME), "ignore = [\"repo/**\"]\n").unwrap();
+        fs::write(nested.join(CONFIG_FILE_NAME), "ignore = [\"app/**\"]\n").unwrap();
+
+        let home = home.canonicalize().unwrap();
+        let layers = ConfigFile::layers_between(Some(&home), &nested).unwrap();
+        let sources: Vec<_> = layers.iter().map(|l| l.source.clone()).collect();
+        assert_eq!(
+            sources,
+            vec![
+                ConfigSource::HomeFile,
+                ConfigSource::ParentFile(repo.canonicalize().unwrap().join(RC_FILE_NAME)),
+                ConfigSource::LocalFile,
+            ]
+        );
+
+        let merged = ConfigFile::merge_layers(layers.into_iter().map(|l| l.config)).unwrap();
+        assert_eq!(merged.ignore, vec!["home/**", "repo/**", "app/**"]);
+
+        // Outside a repository only the starting directory is searched
+        let layers = ConfigFile::layers_between(None, temp_dir.path()).unwrap();
+        assert!(layers.is_empty());
+    }
+
+    fn load_str(content: &str) -> Result<ConfigFile, ContextCreatorError> {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(&config_path, content).unwrap();
+        ConfigFile::load_from_file(&config_path)
+    }
+
+    #[test]
+    fn test_unknown_keys_report_line_and_column() {
+        let err = load_str(
+            "ignore = [\"target/**\"]\n\n[[priorities]]\npattern = \"src/**\"\nwieght = 10.0\n",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("at line 5, column 1"), "{err}");
+        assert!(err.contains("unknown field `wieght`"), "{err}");
+
+        let err = load_str("[defaults]\nllm_tool = \"gpt\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("at line 2, column 12"), "{err}");
+        assert!(err.contains("unknown variant `gpt`"), "{err}");
+    }
+
+    #[test]
+    fn test_invalid_globs_are_rejected_at_load() {
+        let err = load_str("[[priorities]]\npattern = \"src/[abc\"\nweight = 1.0\n")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Invalid glob pattern 'src/[abc' in priorities"),
+            "{err}"
+        );
+
+        let err = load_str("[profiles.docs]\ninclude = [\"docs/{a,b\"]\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("in profiles.docs.include"), "{err}");
+
+        let err = load_str("ignore = [\"../secrets/**\"]\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("'../secrets/**' in ignore"), "{err}");
+
+        assert!(load_str("include = [\"!important/**\", \"src/**/*.rs\"]\n").is_ok());
+    }
+
+    #[test]
+    fn test_schema_describes_config_file() {
+        let schema: serde_json::Value = serde_json::from_str(&config_schema().unwrap()).unwrap();
+        assert_eq!(schema["additionalProperties"], false);
+        for key in [
+            "defaults",
+            "priorities",
+            "ignore",
+            "include",
+            "tokens",
+            "profiles",
+            "reset",
+        ] {
+            assert!(schema["properties"].get(key).is_some(), "missing {key}");
+        }
+
+        let defaults = &schema["definitions"]["Defaults"];
+        assert_eq!(defaults["additionalProperties"], false);
+        assert!(defaults["properties"].get("semantic_depth").is_some());
+        let styles = serde_json::to_string(&schema["definitions"]["OutputFormat"]).unwrap();
+        assert!(styles.contains("\"xml\""));
+    }
 }
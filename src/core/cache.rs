@@ -1,8 +1,11 @@
 //! File caching functionality for eliminating redundant I/O
 //!
 //! This module provides a thread-safe cache for file contents using `Arc<str>`
-//! for cheap cloning across threads.
+//! for cheap cloning across threads. A cache can also be backed by a
+//! [`GitSnapshot`], in which case files inside the repository are read from
+//! the object database instead of the working tree.
 
+use crate::core::snapshot::GitSnapshot;
 use anyhow::Result;
 use dashmap::DashMap;
 use std::path::{Path, PathBuf};
@@ -11,6 +14,7 @@ use std::sync::Arc;
 /// Thread-safe file content cache
 pub struct FileCache {
     cache: DashMap<PathBuf, Arc<str>>,
+    snapshot: Option<Arc<GitSnapshot>>,
 }
 
 impl FileCache {
@@ -18,11 +22,31 @@ impl FileCache {
     pub fn new() -> Self {
         FileCache {
             cache: DashMap::new(),
+            snapshot: None,
         }
     }
 
+    /// Create a cache that serves repository files from a git snapshot
+    pub fn with_snapshot(snapshot: Arc<GitSnapshot>) -> Self {
+        FileCache {
+            cache: DashMap::new(),
+            snapshot: Some(snapshot),
+        }
+    }
+
+    /// The snapshot backing this cache, if any
+    pub fn snapshot(&self) -> Option<&Arc<GitSnapshot>> {
+        self.snapshot.as_ref()
+    }
+
     /// Get file content from cache or load from disk
     pub fn get_or_load(&self, path: &Path) -> Result<Arc<str>> {
+        if let Some(snapshot) = &self.snapshot {
+            if let Some(relative) = snapshot.relative_path(path) {
+                return self.get_or_load_from_snapshot(snapshot, &relative);
+            }
+        }
+
         // Canonicalize path to avoid cache misses from different representations
         let canonical_path = path.canonicalize()?;
 
@@ -41,6 +65,33 @@ impl FileCache {
         Ok(arc_content)
     }
 
+    /// Drop the cached content of a file so the next read goes to disk
+    pub fn invalidate(&self, path: &Path) {
+        self.cache.remove(path);
+        if let Ok(canonical) = path.canonicalize() {
+            self.cache.remove(&canonical);
+        }
+    }
+
+    /// Load a repository file from the snapshot, keyed by its working tree path
+    fn get_or_load_from_snapshot(
+        &self,
+        snapshot: &GitSnapshot,
+        relative: &Path,
+    ) -> Result<Arc<str>> {
+        let path = snapshot.root().join(relative);
+
+        if let Some(content) = self.cache.get(&path) {
+            return Ok(content.clone());
+        }
+
+        let content = snapshot.read_to_string(&path)?;
+        let arc_content: Arc<str> = Arc::from(content.as_str());
+        self.cache.insert(path, arc_content.clone());
+
+        Ok(arc_content)
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
         CacheStats {
@@ -0,0 +1,696 @@
+//! Dependency-graph queries over a project analysis
+//!
+//! Answers the structural questions an agent asks before requesting a context
+//! bundle: what a file imports, which files import it, who calls a function,
+//! where a symbol is defined and how types relate. Paths in the results are
+//! relative to the project root and line numbers are 1-based.
+
+use crate::cli::Config;
+use crate::core::cache::FileCache;
+use crate::core::project_analyzer::ProjectAnalysis;
+use crate::core::semantic::analyzer::SemanticContext;
+use crate::core::semantic::function_call_index::FunctionCallIndex;
+use crate::core::semantic::type_hierarchy::{extract_type_facts, RelationKind, TypeFacts};
+use crate::core::semantic::{get_analyzer_for_file, get_resolver_for_file};
+use crate::core::semantic_cache::SemanticCache;
+use crate::core::walker::WalkOptions;
+use crate::utils::error::ContextCreatorError;
+use anyhow::Result;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// Kind of relationship between two nodes of the graph
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// A file imports another file
+    Import,
+    /// A file calls a function
+    FunctionCall,
+    /// A type extends a class, interface or supertrait
+    Extends,
+    /// A type implements an interface or trait
+    Implements,
+}
+
+impl From<RelationKind> for EdgeKind {
+    fn from(kind: RelationKind) -> Self {
+        match kind {
+            RelationKind::Extends => EdgeKind::Extends,
+            RelationKind::Implements => EdgeKind::Implements,
+        }
+    }
+}
+
+/// An import between two files
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct ImportEdge {
+    /// The importing file
+    pub from: PathBuf,
+    /// The imported file
+    pub to: PathBuf,
+    /// Always `import`
+    pub kind: EdgeKind,
+    /// Line of the import statement in `from`, when it could be located
+    pub line: Option<usize>,
+    /// The module as written in the import statement
+    pub module: Option<String>,
+    /// Symbols named by the import statement
+    pub symbols: Vec<String>,
+    /// Whether the module is loaded at runtime (`import()`, `require.resolve`,
+    /// `jest.mock`) rather than by a static import
+    pub dynamic: bool,
+    /// Distance from the queried file, starting at 1
+    pub depth: usize,
+}
+
+/// A call to a function
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct CallSite {
+    /// The calling file
+    pub file: PathBuf,
+    /// Line of the call
+    pub line: usize,
+    /// The called function
+    pub function: String,
+    /// Always `function_call`
+    pub kind: EdgeKind,
+    /// 1 for direct callers of the symbol, 2 for callers of the functions
+    /// exported by those files, and so on
+    pub depth: usize,
+}
+
+/// Where a symbol is defined
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct Definition {
+    /// Name of the symbol
+    pub name: String,
+    /// Kind of definition: function, struct, enum, trait, class, interface, ...
+    pub kind: String,
+    /// The defining file
+    pub file: PathBuf,
+    /// Line of the definition
+    pub line: usize,
+    /// Whether a function is exported, when known
+    pub exported: Option<bool>,
+}
+
+/// A subtype-supertype relationship
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct TypeEdge {
+    /// The extending or implementing type
+    pub subtype: String,
+    /// The extended or implemented type
+    pub supertype: String,
+    /// `extends` or `implements`
+    pub kind: EdgeKind,
+    /// The file declaring the relationship
+    pub file: PathBuf,
+    /// Line of the declaration
+    pub line: usize,
+    /// Distance from the queried type, starting at 1
+    pub depth: usize,
+}
+
+/// Supertypes and subtypes of a type
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct TypeHierarchy {
+    /// Definitions of the queried type
+    pub definitions: Vec<Definition>,
+    /// Types the queried type extends or implements, transitively
+    pub supertypes: Vec<TypeEdge>,
+    /// Types extending or implementing the queried type, transitively
+    pub subtypes: Vec<TypeEdge>,
+}
+
+/// Import, call and type relationships of a project
+pub struct CodeGraph {
+    analysis: ProjectAnalysis,
+    call_index: FunctionCallIndex,
+    /// Original and canonical paths to indices in `analysis.all_files`
+    index: HashMap<PathBuf, usize>,
+    /// File index to the indices of the files importing it
+    importers: HashMap<usize, Vec<usize>>,
+    cache: Arc<FileCache>,
+    type_facts: OnceLock<Vec<(usize, TypeFacts)>>,
+    /// Directories searched for C and C++ includes
+    include_paths: Vec<PathBuf>,
+}
+
+impl CodeGraph {
+    /// Analyze every file under `root` with imports, calls and types enabled
+    ///
+    /// `config` supplies the ignore rules; its semantic flags are overridden.
+    pub fn build(root: &Path, config: &Config) -> Result<Self> {
+        Self::build_cached(
+            root,
+            config,
+            Arc::new(FileCache::new()),
+            &Arc::new(SemanticCache::new()),
+        )
+    }
+
+    /// Like [`CodeGraph::build`], reusing file contents and parse results
+    pub fn build_cached(
+        root: &Path,
+        config: &Config,
+        cache: Arc<FileCache>,
+        semantic_cache: &Arc<SemanticCache>,
+    ) -> Result<Self> {
+        let mut config = config.clone();
+        config.trace_imports = true;
+        config.include_callers = true;
+        config.include_types = true;
+
+        let walk_options = WalkOptions::from_config(&config)?;
+        let analysis =
+            ProjectAnalysis::analyze_root(root, &walk_options, &config, &cache, semantic_cache)?;
+        let mut graph = Self::from_analysis(analysis, cache);
+        graph.include_paths = config.include_paths;
+        Ok(graph)
+    }
+
+    /// Index an existing project analysis
+    pub fn from_analysis(analysis: ProjectAnalysis, cache: Arc<FileCache>) -> Self {
+        let call_index = FunctionCallIndex::build(&analysis.all_files);
+
+        let mut index = HashMap::with_capacity(analysis.all_files.len() * 2);
+        for (i, file) in analysis.all_files.iter().enumerate() {
+            index.insert(file.path.clone(), i);
+            if let Ok(canonical) = file.path.canonicalize() {
+                index.insert(canonical, i);
+            }
+        }
+
+        let mut graph = Self {
+            analysis,
+            call_index,
+            index,
+            importers: HashMap::new(),
+            cache,
+            type_facts: OnceLock::new(),
+            include_paths: Vec::new(),
+        };
+        for (i, file) in graph.analysis.all_files.iter().enumerate() {
+            for target in &file.imports {
+                if let Some(j) = graph.lookup(target) {
+                    let importers = graph.importers.entry(j).or_default();
+                    if !importers.contains(&i) {
+                        importers.push(i);
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// Project root the result paths are relative to
+    pub fn root(&self) -> &Path {
+        &self.analysis.project_root
+    }
+
+    /// Number of analyzed files
+    pub fn file_count(&self) -> usize {
+        self.analysis.all_files.len()
+    }
+
+    /// Files imported by `file`, following imports up to `depth` levels
+    pub fn imports(&self, file: &Path, depth: usize) -> Result<Vec<ImportEdge>> {
+        let start = self.require(file)?;
+        let mut edges = Vec::new();
+        self.breadth_first(start, depth, |from, level| {
+            let mut sites = self.import_sites(from);
+            let mut targets = Vec::new();
+            for target in &self.analysis.all_files[from].imports {
+                let Some(to) = self.lookup(target) else {
+                    continue;
+                };
+                if !targets.contains(&to) {
+                    edges.push(self.import_edge(from, to, sites.remove(&to), level));
+                    targets.push(to);
+                }
+            }
+            targets
+        });
+
+        Ok(edges)
+    }
+
+    /// Files importing `file`, following importers up to `depth` levels
+    pub fn importers(&self, file: &Path, depth: usize) -> Result<Vec<ImportEdge>> {
+        let start = self.require(file)?;
+        let mut edges = Vec::new();
+        self.breadth_first(start, depth, |to, level| {
+            let importers = self.importers.get(&to).cloned().unwrap_or_default();
+            for &from in &importers {
+                let site = self.import_sites(from).remove(&to);
+                edges.push(self.import_edge(from, to, site, level));
+            }
+            importers
+        });
+
+        Ok(edges)
+    }
+
+    /// Call sites of `symbol`, then of the functions exported by the calling
+    /// files, up to `depth` levels
+    pub fn callers(&self, symbol: &str, depth: usize) -> Vec<CallSite> {
+        let mut sites = Vec::new();
+        let mut seen_functions: HashSet<String> = HashSet::from([symbol.to_string()]);
+        let mut seen_files = HashSet::new();
+        let mut functions = vec![symbol.to_string()];
+
+        for level in 1..=depth {
+            let mut calling_files = Vec::new();
+            for function in &functions {
+                let Some(callers) = self.call_index.get_callers(function) else {
+                    continue;
+                };
+                for caller in callers {
+                    let Some(i) = self.lookup(caller) else {
+                        continue;
+                    };
+                    let file = &self.analysis.all_files[i];
+                    for call in file.function_calls.iter().filter(|c| &c.name == function) {
+                        sites.push(CallSite {
+                            file: file.relative_path.clone(),
+                            line: call.line,
+                            function: function.clone(),
+                            kind: EdgeKind::FunctionCall,
+                            depth: level,
+                        });
+                    }
+                    if seen_files.insert(i) {
+                        calling_files.push(i);
+                    }
+                }
+            }
+
+            functions = calling_files
+                .iter()
+                .flat_map(|&i| &self.analysis.all_files[i].exported_functions)
+                .filter(|definition| definition.is_exported)
+                .map(|definition| definition.name.clone())
+                .filter(|name| seen_functions.insert(name.clone()))
+                .collect();
+            if functions.is_empty() {
+                break;
+            }
+        }
+
+        sites.sort_by(|a, b| {
+            (a.depth, &a.file, a.line, &a.function).cmp(&(b.depth, &b.file, b.line, &b.function))
+        });
+        sites.dedup();
+        sites
+    }
+
+    /// Functions and types named `symbol`
+    pub fn definitions(&self, symbol: &str) -> Vec<Definition> {
+        let mut definitions: Vec<_> = self
+            .analysis
+            .all_files
+            .iter()
+            .flat_map(|file| {
+                file.exported_functions
+                    .iter()
+                    .filter(|function| function.name == symbol)
+                    .map(|function| Definition {
+                        name: function.name.clone(),
+                        kind: "function".to_string(),
+                        file: file.relative_path.clone(),
+                        line: function.line,
+                        exported: Some(function.is_exported),
+                    })
+            })
+            .collect();
+        definitions.extend(self.type_definitions(symbol));
+
+        definitions.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        definitions.dedup();
+        definitions
+    }
+
+    /// Supertypes and subtypes of the type `symbol`, up to `depth` levels
+    pub fn type_hierarchy(&self, symbol: &str, depth: usize) -> TypeHierarchy {
+        let relations: Vec<_> = self
+            .type_facts()
+            .iter()
+            .flat_map(|(i, facts)| facts.relations.iter().map(move |r| (*i, r)))
+            .collect();
+
+        let walk = |upwards: bool| {
+            let mut edges = Vec::new();
+            let mut seen: HashSet<&str> = HashSet::from([symbol]);
+            let mut frontier = vec![symbol];
+            for level in 1..=depth {
+                let mut next = Vec::new();
+                for &(i, relation) in &relations {
+                    let (from, to) = if upwards {
+                        (&relation.subtype, &relation.supertype)
+                    } else {
+                        (&relation.supertype, &relation.subtype)
+                    };
+                    if !frontier.contains(&from.as_str()) {
+                        continue;
+                    }
+                    edges.push(TypeEdge {
+                        subtype: relation.subtype.clone(),
+                        supertype: relation.supertype.clone(),
+                        kind: relation.kind.into(),
+                        file: self.analysis.all_files[i].relative_path.clone(),
+                        line: relation.line,
+                        depth: level,
+                    });
+                    if seen.insert(to.as_str()) {
+                        next.push(to.as_str());
+                    }
+                }
+                if next.is_empty() {
+                    break;
+                }
+                frontier = next;
+            }
+            edges.sort_by(|a, b| {
+                (a.depth, &a.file, a.line, &a.supertype, &a.subtype).cmp(&(
+                    b.depth,
+                    &b.file,
+                    b.line,
+                    &b.supertype,
+                    &b.subtype,
+                ))
+            });
+            edges
+        };
+
+        let mut definitions = self.type_definitions(symbol);
+        definitions.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        TypeHierarchy {
+            definitions,
+            supertypes: walk(true),
+            subtypes: walk(false),
+        }
+    }
+
+    fn type_definitions(&self, symbol: &str) -> Vec<Definition> {
+        self.type_facts()
+            .iter()
+            .flat_map(|(i, facts)| {
+                facts
+                    .definitions
+                    .iter()
+                    .filter(|definition| definition.name == symbol)
+                    .map(|definition| Definition {
+                        name: definition.name.clone(),
+                        kind: definition.kind.to_string(),
+                        file: self.analysis.all_files[*i].relative_path.clone(),
+                        line: definition.line,
+                        exported: None,
+                    })
+            })
+            .collect()
+    }
+
+    /// Type facts of every file, extracted on first use
+    fn type_facts(&self) -> &[(usize, TypeFacts)] {
+        self.type_facts.get_or_init(|| {
+            self.analysis
+                .all_files
+                .par_iter()
+                .enumerate()
+                .filter_map(|(i, file)| {
+                    let content = self.cache.get_or_load(&file.path).ok()?;
+                    let facts = extract_type_facts(&file.path, &content);
+                    (facts != TypeFacts::default()).then_some((i, facts))
+                })
+                .collect()
+        })
+    }
+
+    /// Visit files breadth-first up to `depth` levels
+    ///
+    /// `neighbours` receives each visited file with its level, starting at 1,
+    /// and returns the files reachable from it.
+    fn breadth_first(
+        &self,
+        start: usize,
+        depth: usize,
+        mut neighbours: impl FnMut(usize, usize) -> Vec<usize>,
+    ) {
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 1)]);
+        while let Some((current, level)) = queue.pop_front() {
+            if level > depth {
+                continue;
+            }
+            for next in neighbours(current, level) {
+                if visited.insert(next) {
+                    queue.push_back((next, level + 1));
+                }
+            }
+        }
+    }
+
+    fn import_edge(
+        &self,
+        from: usize,
+        to: usize,
+        site: Option<ImportSite>,
+        depth: usize,
+    ) -> ImportEdge {
+        let files = &self.analysis.all_files;
+        let site = site.unwrap_or_default();
+        ImportEdge {
+            from: files[from].relative_path.clone(),
+            to: files[to].relative_path.clone(),
+            kind: EdgeKind::Import,
+            line: site.line,
+            module: site.module,
+            symbols: site.symbols,
+            dynamic: site.dynamic,
+            depth,
+        }
+    }
+
+    /// Locate the import statements of a file by resolving them again
+    fn import_sites(&self, file_index: usize) -> HashMap<usize, ImportSite> {
+        let file = &self.analysis.all_files[file_index];
+        let mut sites = HashMap::new();
+        let (Ok(Some(analyzer)), Ok(Some(resolver))) = (
+            get_analyzer_for_file(&file.path),
+            get_resolver_for_file(&file.path),
+        ) else {
+            return sites;
+        };
+        let Ok(content) = self.cache.get_or_load(&file.path) else {
+            return sites;
+        };
+        let context = SemanticContext::new(file.path.clone(), self.root().to_path_buf(), 1)
+            .with_include_paths(self.include_paths.clone());
+        let Ok(result) = analyzer.analyze_file(&file.path, &content, &context) else {
+            return sites;
+        };
+
+        for import in result.imports {
+            let targets: Vec<usize> = resolver
+                .resolve_import_in_context(&import.module, &import.items, &context)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|resolved| !resolved.is_external)
+                .filter_map(|resolved| self.lookup(&resolved.path))
+                .collect();
+            for target in targets {
+                sites.entry(target).or_insert_with(|| ImportSite {
+                    line: Some(import.line),
+                    module: Some(import.module.clone()),
+                    symbols: import.items.clone(),
+                    dynamic: import.is_dynamic,
+                });
+            }
+        }
+        sites
+    }
+
+    fn lookup(&self, path: &Path) -> Option<usize> {
+        self.index.get(path).copied().or_else(|| {
+            path.canonicalize()
+                .ok()
+                .and_then(|canonical| self.index.get(&canonical).copied())
+        })
+    }
+
+    /// Resolve a query path, relative to the project root or absolute
+    fn require(&self, file: &Path) -> Result<usize> {
+        let path = if file.is_absolute() {
+            file.to_path_buf()
+        } else {
+            self.root().join(file)
+        };
+        self.lookup(&path).ok_or_else(|| {
+            ContextCreatorError::InvalidPath(format!(
+                "{} is not an analyzed file under {}",
+                file.display(),
+                self.root().display()
+            ))
+            .into()
+        })
+    }
+}
+
+/// Location of an import statement
+#[derive(Debug, Clone, Default)]
+struct ImportSite {
+    line: Option<usize>,
+    module: Option<String>,
+    symbols: Vec<String>,
+    dynamic: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn python_project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(
+            root.join("models.py"),
+            "class Base:\n    pass\n\n\nclass User(Base):\n    pass\n\n\nclass Admin(User):\n    pass\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("utils.py"),
+            "from models import User\n\n\ndef load_user(name):\n    return User()\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("service.py"),
+            "import os\nfrom utils import load_user\n\n\ndef handle(name):\n    return load_user(name)\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("main.py"),
+            "from service import handle\n\n\ndef main():\n    handle(\"root\")\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn graph(dir: &TempDir) -> CodeGraph {
+        let config = Config {
+            quiet: true,
+            ..Default::default()
+        };
+        CodeGraph::build(dir.path(), &config).unwrap()
+    }
+
+    fn summary(edge: &ImportEdge) -> (&str, &str, usize) {
+        (
+            edge.from.to_str().unwrap(),
+            edge.to.to_str().unwrap(),
+            edge.depth,
+        )
+    }
+
+    #[test]
+    fn test_imports_follow_depth() {
+        let dir = python_project();
+        let graph = graph(&dir);
+
+        let direct = graph.imports(Path::new("service.py"), 1).unwrap();
+        assert_eq!(
+            direct.iter().map(summary).collect::<Vec<_>>(),
+            vec![("service.py", "utils.py", 1)]
+        );
+        assert_eq!(direct[0].line, Some(2));
+        assert_eq!(direct[0].kind, EdgeKind::Import);
+        assert_eq!(direct[0].symbols, vec!["load_user".to_string()]);
+
+        let transitive = graph.imports(Path::new("service.py"), 2).unwrap();
+        assert_eq!(
+            transitive.iter().map(summary).collect::<Vec<_>>(),
+            vec![("service.py", "utils.py", 1), ("utils.py", "models.py", 2)]
+        );
+    }
+
+    #[test]
+    fn test_importers_follow_depth() {
+        let dir = python_project();
+        let graph = graph(&dir);
+
+        let importers = graph.importers(Path::new("utils.py"), 3).unwrap();
+        assert_eq!(
+            importers.iter().map(summary).collect::<Vec<_>>(),
+            vec![("service.py", "utils.py", 1), ("main.py", "service.py", 2)]
+        );
+        assert_eq!(importers[1].line, Some(1));
+
+        assert!(graph.importers(Path::new("missing.py"), 1).is_err());
+    }
+
+    #[test]
+    fn test_callers_and_definitions() {
+        let dir = python_project();
+        let graph = graph(&dir);
+
+        let callers = graph.callers("load_user", 2);
+        let sites: Vec<_> = callers
+            .iter()
+            .map(|c| {
+                (
+                    c.file.to_str().unwrap(),
+                    c.line,
+                    c.function.as_str(),
+                    c.depth,
+                )
+            })
+            .collect();
+        assert_eq!(
+            sites,
+            vec![
+                ("service.py", 6, "load_user", 1),
+                ("main.py", 5, "handle", 2)
+            ]
+        );
+
+        let definitions = graph.definitions("load_user");
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].file, PathBuf::from("utils.py"));
+        assert_eq!(definitions[0].line, 4);
+        assert_eq!(definitions[0].kind, "function");
+
+        let definitions = graph.definitions("User");
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].kind, "class");
+        assert_eq!(definitions[0].line, 5);
+    }
+
+    #[test]
+    fn test_type_hierarchy() {
+        let dir = python_project();
+        let graph = graph(&dir);
+
+        let hierarchy = graph.type_hierarchy("User", 1);
+        assert_eq!(hierarchy.definitions.len(), 1);
+        assert_eq!(hierarchy.supertypes.len(), 1);
+        assert_eq!(hierarchy.supertypes[0].supertype, "Base");
+        assert_eq!(hierarchy.supertypes[0].kind, EdgeKind::Extends);
+        assert_eq!(hierarchy.subtypes.len(), 1);
+        assert_eq!(hierarchy.subtypes[0].subtype, "Admin");
+
+        let hierarchy = graph.type_hierarchy("Base", 2);
+        let subtypes: Vec<_> = hierarchy
+            .subtypes
+            .iter()
+            .map(|edge| (edge.subtype.as_str(), edge.depth))
+            .collect();
+        assert_eq!(subtypes, vec![("User", 1), ("Admin", 2)]);
+    }
+}
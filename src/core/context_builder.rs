@@ -2,6 +2,7 @@
 
 use crate::cli::OutputFormat;
 use crate::core::cache::FileCache;
+use crate::core::progress::ProgressReporter;
 use crate::core::walker::FileInfo;
 use crate::formatters::{create_formatter, DigestData};
 use crate::utils::file_ext::FileType;
@@ -37,6 +38,8 @@ pub struct ContextOptions {
     pub git_context: bool,
     /// Number of git commits to show per file
     pub git_context_depth: usize,
+    /// Receives prioritization progress and stops it when cancelled
+    pub reporter: ProgressReporter,
 }
 
 impl ContextOptions {
@@ -54,6 +57,7 @@ impl ContextOptions {
             enhanced_context: config.enhanced_context,
             git_context: config.git_context,
             git_context_depth: config.git_context_depth,
+            reporter: config.reporter.clone(),
         })
     }
 }
@@ -72,6 +76,7 @@ impl Default for ContextOptions {
             enhanced_context: false,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         }
     }
 }
@@ -981,6 +986,7 @@ mod tests {
             enhanced_context: false,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         };
 
         let cache = create_test_cache();
@@ -1036,6 +1042,7 @@ mod tests {
             enhanced_context: true,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         };
 
         let cache = create_test_cache();
@@ -1077,6 +1084,7 @@ mod tests {
             enhanced_context: true,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         };
 
         let cache = create_test_cache();
@@ -1117,6 +1125,7 @@ mod tests {
             enhanced_context: false,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         };
 
         let cache = create_test_cache();
@@ -8,6 +8,7 @@ use crate::core::cache::FileCache;
 use crate::core::semantic::function_call_index::FunctionCallIndex;
 use crate::core::semantic::path_validator::validate_import_path;
 use crate::core::semantic::type_resolver::{ResolutionLimits, TypeResolver};
+use crate::core::source_tree::SourceTree;
 use crate::core::walker::{perform_semantic_analysis, walk_directory, FileInfo};
 use crate::utils::error::ContextCreatorError;
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
@@ -36,9 +37,9 @@ fn build_ignore_matcher(
 }
 
 /// Detect the project root directory using git root or fallback methods
-pub fn detect_project_root(start_path: &Path) -> PathBuf {
+pub fn detect_project_root(tree: &SourceTree, start_path: &Path) -> PathBuf {
     // If start_path is a file, start from its parent directory
-    let start_dir = if start_path.is_file() {
+    let start_dir = if tree.is_file(start_path) {
         start_path.parent().unwrap_or(start_path)
     } else {
         start_path
@@ -61,19 +62,19 @@ pub fn detect_project_root(start_path: &Path) -> PathBuf {
     current = start_dir;
     loop {
         // Check for Rust project markers
-        if current.join("Cargo.toml").exists() {
+        if tree.exists(&current.join("Cargo.toml")) {
             return current.to_path_buf();
         }
         // Check for Node.js project markers
-        if current.join("package.json").exists() {
+        if tree.exists(&current.join("package.json")) {
             return current.to_path_buf();
         }
         // Check for Python project markers
-        if current.join("pyproject.toml").exists() || current.join("setup.py").exists() {
+        if tree.exists(&current.join("pyproject.toml")) || tree.exists(&current.join("setup.py")) {
             return current.to_path_buf();
         }
         // Check for generic project markers
-        if current.join("README.md").exists() || current.join("readme.md").exists() {
+        if tree.exists(&current.join("README.md")) || tree.exists(&current.join("readme.md")) {
             return current.to_path_buf();
         }
 
@@ -136,10 +137,11 @@ fn expand_file_list_internal(
     }
 
     let mut files_map = files_map;
+    let tree = SourceTree::new(walk_options.snapshot.clone());
 
     // Detect the project root for secure path validation
     let project_root = if let Some((first_path, _)) = files_map.iter().next() {
-        detect_project_root(first_path)
+        detect_project_root(&tree, first_path)
     } else {
         // If no files, use current directory
         std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
@@ -165,6 +167,8 @@ fn expand_file_list_internal(
                         current_depth: 0,
                         max_depth: config.semantic_depth,
                         visited_files: HashSet::new(),
+                        include_paths: config.include_paths.clone(),
+                        source_tree: tree.clone(),
                     };
 
                     if let Ok(analysis) = analyzer.analyze_file(path, &content, &context) {
@@ -172,9 +176,9 @@ fn expand_file_list_internal(
                         file_info.imports = analysis
                             .imports
                             .iter()
-                            .filter_map(|imp| {
-                                // Try to resolve import to file path
-                                resolve_import_to_path(&imp.module, path, &project_root)
+                            .flat_map(|imp| {
+                                // Try to resolve import to file paths
+                                resolve_import_to_paths(&imp.module, &imp.items, &context)
                             })
                             .collect();
                         file_info.function_calls = analysis.function_calls;
@@ -355,9 +359,9 @@ fn expand_file_list_internal(
                     // If we have a definition path, add it
                     if let Some(ref def_path) = type_ref.definition_path {
                         tracing::debug!("    Type has definition_path: {}", def_path.display());
-                        if !visited_paths.contains(def_path) && def_path.exists() {
+                        if !visited_paths.contains(def_path) && tree.exists(def_path) {
                             // Validate the path for security using the project root
-                            match validate_import_path(&project_root, def_path) {
+                            match validate_import_path(&project_root, def_path, &tree) {
                                 Ok(validated_path) => {
                                     tracing::debug!(
                                         "    Adding type definition file: {}",
@@ -366,8 +370,11 @@ fn expand_file_list_internal(
                                     visited_paths.insert(validated_path.clone());
 
                                     // Create FileInfo for the definition file
-                                    let mut file_info =
-                                        create_file_info_for_path(&validated_path, &source_path)?;
+                                    let mut file_info = create_file_info_for_path(
+                                        &tree,
+                                        &validated_path,
+                                        &source_path,
+                                    )?;
 
                                     // Perform semantic analysis on the newly found file to get its type references
                                     if depth + 1 < config.semantic_depth {
@@ -382,7 +389,8 @@ fn expand_file_list_internal(
                                                     validated_path.clone(),
                                                     project_root.clone(),
                                                     config.semantic_depth,
-                                                );
+                                                )
+                                                .with_source_tree(tree.clone());
 
                                                 if let Ok(analysis) = analyzer.analyze_file(
                                                     &validated_path,
@@ -436,6 +444,7 @@ fn expand_file_list_internal(
                             module_name,
                             &source_path,
                             cache,
+                            &tree,
                         ) {
                             tracing::debug!(
                                 "    Found type definition file: {}",
@@ -443,7 +452,7 @@ fn expand_file_list_internal(
                             );
                             if !visited_paths.contains(&def_path) {
                                 // Validate the path for security using the project root
-                                match validate_import_path(&project_root, &def_path) {
+                                match validate_import_path(&project_root, &def_path, &tree) {
                                     Ok(validated_path) => {
                                         tracing::debug!(
                                             "    Adding found type definition file: {}",
@@ -453,6 +462,7 @@ fn expand_file_list_internal(
 
                                         // Create FileInfo for the definition file
                                         let mut file_info = create_file_info_for_path(
+                                            &tree,
                                             &validated_path,
                                             &source_path,
                                         )?;
@@ -471,7 +481,8 @@ fn expand_file_list_internal(
                                                         validated_path.clone(),
                                                         project_root.clone(),
                                                         config.semantic_depth,
-                                                    );
+                                                    )
+                                                    .with_source_tree(tree.clone());
 
                                                     if let Ok(analysis) = analyzer.analyze_file(
                                                         &validated_path,
@@ -520,13 +531,13 @@ fn expand_file_list_internal(
                 // Process each import in the source file
                 for import_path in &source_file.imports {
                     // Skip if doesn't exist
-                    if !import_path.exists() {
+                    if !tree.exists(import_path) {
                         continue;
                     }
 
                     // Check if already visited (need to check both original and canonical paths)
-                    let canonical_import = import_path
-                        .canonicalize()
+                    let canonical_import = tree
+                        .canonicalize(import_path)
                         .unwrap_or_else(|_| import_path.clone());
                     if visited_paths.contains(import_path)
                         || visited_paths.contains(&canonical_import)
@@ -535,7 +546,7 @@ fn expand_file_list_internal(
                     }
 
                     // Validate the import path for security
-                    match validate_import_path(&project_root, import_path) {
+                    match validate_import_path(&project_root, import_path, &tree) {
                         Ok(validated_path) => {
                             visited_paths.insert(validated_path.clone());
 
@@ -544,7 +555,7 @@ fn expand_file_list_internal(
                                 // Check if this is a module file (not main.rs or lib.rs itself)
                                 if let Some(parent) = validated_path.parent() {
                                     let lib_rs = parent.join("lib.rs");
-                                    if lib_rs.exists()
+                                    if tree.exists(&lib_rs)
                                         && lib_rs != validated_path
                                         && !visited_paths.contains(&lib_rs)
                                     {
@@ -569,6 +580,7 @@ fn expand_file_list_internal(
                                                     }
                                                 } else {
                                                     let lib_info = create_file_info_for_path(
+                                                        &tree,
                                                         &lib_rs,
                                                         &source_path,
                                                     )?;
@@ -583,8 +595,8 @@ fn expand_file_list_internal(
                             // Check if we have this file in the context first
                             let mut file_info = if let Some(context) = all_files_context {
                                 // Try to canonicalize for lookup, but fall back to validated_path
-                                let lookup_path = validated_path
-                                    .canonicalize()
+                                let lookup_path = tree
+                                    .canonicalize(&validated_path)
                                     .unwrap_or_else(|_| validated_path.clone());
 
                                 // Also try the non-canonical path
@@ -600,15 +612,21 @@ fn expand_file_list_internal(
                                     file
                                 } else {
                                     // Create FileInfo for the imported file
-                                    let mut file =
-                                        create_file_info_for_path(&validated_path, &source_path)?;
+                                    let mut file = create_file_info_for_path(
+                                        &tree,
+                                        &validated_path,
+                                        &source_path,
+                                    )?;
                                     file.imported_by.push(source_path.clone());
                                     file
                                 }
                             } else {
                                 // No context, create from scratch
-                                let mut file =
-                                    create_file_info_for_path(&validated_path, &source_path)?;
+                                let mut file = create_file_info_for_path(
+                                    &tree,
+                                    &validated_path,
+                                    &source_path,
+                                )?;
                                 file.imported_by.push(source_path.clone());
                                 file
                             };
@@ -650,7 +668,9 @@ fn expand_file_list_internal(
                                             validated_path.clone(),
                                             project_root.clone(),
                                             config.semantic_depth,
-                                        );
+                                        )
+                                        .with_source_tree(tree.clone())
+                                        .with_include_paths(config.include_paths.clone());
 
                                         if let Ok(analysis) = analyzer.analyze_file(
                                             &validated_path,
@@ -661,12 +681,12 @@ fn expand_file_list_internal(
                                             file_info.imports = analysis
                                                 .imports
                                                 .iter()
-                                                .filter_map(|imp| {
-                                                    // Try to resolve import to file path
-                                                    resolve_import_to_path(
+                                                .flat_map(|imp| {
+                                                    // Try to resolve import to file paths
+                                                    resolve_import_to_paths(
                                                         &imp.module,
-                                                        &validated_path,
-                                                        &project_root,
+                                                        &imp.items,
+                                                        &context,
                                                     )
                                                 })
                                                 .collect();
@@ -720,7 +740,7 @@ fn expand_file_list_internal(
             .keys()
             .filter(|path| {
                 // The ignore crate's Match type indicates if a path should be ignored
-                ignore_matcher.matched(path, path.is_dir()).is_ignore()
+                ignore_matcher.matched(path, tree.is_dir(path)).is_ignore()
             })
             .cloned()
             .collect();
@@ -742,13 +762,13 @@ enum ExpansionReason {
 
 /// Create a basic FileInfo for a newly discovered file
 fn create_file_info_for_path(
-    path: &PathBuf,
+    tree: &SourceTree,
+    path: &Path,
     source_path: &Path,
 ) -> Result<FileInfo, ContextCreatorError> {
     use crate::utils::file_ext::FileType;
-    use std::fs;
 
-    let metadata = fs::metadata(path)?;
+    let size = tree.len(path)?;
     let file_type = FileType::from_path(path);
 
     // Calculate relative path from common ancestor
@@ -758,9 +778,9 @@ fn create_file_info_for_path(
         .to_path_buf();
 
     Ok(FileInfo {
-        path: path.clone(),
+        path: path.to_path_buf(),
         relative_path,
-        size: metadata.len(),
+        size,
         file_type,
         priority: 1.0, // Default priority, will be adjusted by prioritizer
         imports: Vec::new(),
@@ -890,6 +910,7 @@ fn find_type_definition_file(
     module_name: Option<&str>,
     source_file: &Path,
     cache: &FileCache,
+    tree: &SourceTree,
 ) -> Option<PathBuf> {
     tracing::debug!(
         "find_type_definition_file: type_name={}, module_name={:?}, source_file={}",
@@ -904,7 +925,7 @@ fn find_type_definition_file(
     let mut project_root = source_dir;
     while let Some(parent) = project_root.parent() {
         // If we find a Cargo.toml or src directory, the parent is likely the project root
-        if parent.join("Cargo.toml").exists() || parent.join("src").exists() {
+        if tree.exists(&parent.join("Cargo.toml")) || tree.exists(&parent.join("src")) {
             project_root = parent;
             break;
         }
@@ -981,7 +1002,7 @@ fn find_type_definition_file(
     // Search in current directory first
     for pattern in &patterns {
         let candidate = source_dir.join(pattern);
-        if candidate.exists() {
+        if tree.exists(&candidate) {
             // Read the file to verify it contains the type definition
             if let Ok(content) = cache.get_or_load(&candidate) {
                 // Use AST-based validation to check for type definitions
@@ -996,7 +1017,7 @@ fn find_type_definition_file(
     if let Some(parent_dir) = source_dir.parent() {
         for pattern in &patterns {
             let candidate = parent_dir.join(pattern);
-            if candidate.exists() {
+            if tree.exists(&candidate) {
                 if let Ok(content) = cache.get_or_load(&candidate) {
                     if file_contains_definition(&candidate, &content, type_name) {
                         return Some(candidate);
@@ -1021,10 +1042,10 @@ fn find_type_definition_file(
     ];
 
     for search_dir in search_dirs {
-        if search_dir.exists() {
+        if tree.exists(&search_dir) {
             for pattern in &patterns {
                 let candidate = search_dir.join(pattern);
-                if candidate.exists() {
+                if tree.exists(&candidate) {
                     if let Ok(content) = cache.get_or_load(&candidate) {
                         if file_contains_definition(&candidate, &content, type_name) {
                             return Some(candidate);
@@ -1038,76 +1059,71 @@ fn find_type_definition_file(
     None
 }
 
-/// Resolve an import module name to a file path
-fn resolve_import_to_path(
+/// Resolve an import of `context.current_file` to the files that define
+/// the imported items
+fn resolve_import_to_paths(
     module_name: &str,
-    importing_file: &Path,
-    project_root: &Path,
-) -> Option<PathBuf> {
+    items: &[String],
+    context: &crate::core::semantic::analyzer::SemanticContext,
+) -> Vec<PathBuf> {
     // Use the semantic module resolver system
     use crate::core::semantic::get_module_resolver_for_file;
 
-    // Get the appropriate resolver for this file type
-    let resolver = match get_module_resolver_for_file(importing_file) {
-        Ok(Some(r)) => r,
-        _ => {
-            // No resolver available, fall back to simple resolution
-            let source_dir = importing_file.parent()?;
+    let importing_file = &context.current_file;
+    let project_root = &context.base_dir;
+    let tree = &context.source_tree;
 
-            // Handle relative imports (Python style: ".", "..", "..sibling")
-            if module_name.starts_with('.') {
-                return resolve_relative_import(module_name, source_dir, project_root);
-            }
-
-            // Language-specific resolution based on file extension
-            return match importing_file.extension().and_then(|s| s.to_str()) {
-                Some("rs") => resolve_rust_import(module_name, source_dir, project_root),
-                Some("py") => resolve_python_import(module_name, source_dir, project_root),
-                Some("js") | Some("jsx") => {
-                    resolve_javascript_import(module_name, source_dir, project_root)
-                }
-                Some("ts") | Some("tsx") => {
-                    resolve_typescript_import(module_name, source_dir, project_root)
-                }
-                Some("go") => resolve_go_import(module_name, source_dir, project_root),
-                _ => None,
-            };
+    // Resolve the import, falling back to simple resolution when no resolver
+    // is available or it fails
+    let resolved = match get_module_resolver_for_file(importing_file) {
+        Ok(Some(resolver)) => resolver.resolve_import_in_context(module_name, items, context),
+        _ => {
+            return resolve_import_fallback(module_name, importing_file, project_root, tree)
+                .into_iter()
+                .collect()
         }
     };
 
-    // Resolve the import
-    match resolver.resolve_import(module_name, importing_file, project_root) {
-        Ok(resolved) => {
-            if resolved.is_external {
-                // Skip external modules
-                None
-            } else {
-                Some(resolved.path)
-            }
-        }
-        Err(_) => {
-            // Fallback to simple resolution for backwards compatibility
-            let source_dir = importing_file.parent()?;
+    match resolved {
+        // Skip external modules
+        Ok(resolved) => resolved
+            .into_iter()
+            .filter(|resolved| !resolved.is_external)
+            .map(|resolved| resolved.path)
+            .collect(),
+        // Fallback to simple resolution for backwards compatibility
+        Err(_) => resolve_import_fallback(module_name, importing_file, project_root, tree)
+            .into_iter()
+            .collect(),
+    }
+}
 
-            // Handle relative imports (Python style: ".", "..", "..sibling")
-            if module_name.starts_with('.') {
-                return resolve_relative_import(module_name, source_dir, project_root);
-            }
+/// Resolve an import module name to a file path without a module resolver
+fn resolve_import_fallback(
+    module_name: &str,
+    importing_file: &Path,
+    project_root: &Path,
+    tree: &SourceTree,
+) -> Option<PathBuf> {
+    let source_dir = importing_file.parent()?;
 
-            // Language-specific resolution based on file extension
-            match importing_file.extension().and_then(|s| s.to_str()) {
-                Some("rs") => resolve_rust_import(module_name, source_dir, project_root),
-                Some("py") => resolve_python_import(module_name, source_dir, project_root),
-                Some("js") | Some("jsx") => {
-                    resolve_javascript_import(module_name, source_dir, project_root)
-                }
-                Some("ts") | Some("tsx") => {
-                    resolve_typescript_import(module_name, source_dir, project_root)
-                }
-                Some("go") => resolve_go_import(module_name, source_dir, project_root),
-                _ => None,
-            }
+    // Handle relative imports (Python style: ".", "..", "..sibling")
+    if module_name.starts_with('.') {
+        return resolve_relative_import(module_name, source_dir, project_root, tree);
+    }
+
+    // Language-specific resolution based on file extension
+    match importing_file.extension().and_then(|s| s.to_str()) {
+        Some("rs") => resolve_rust_import(module_name, source_dir, project_root, tree),
+        Some("py") => resolve_python_import(module_name, source_dir, project_root, tree),
+        Some("js") | Some("jsx") => {
+            resolve_javascript_import(module_name, source_dir, project_root, tree)
         }
+        Some("ts") | Some("tsx") => {
+            resolve_typescript_import(module_name, source_dir, project_root, tree)
+        }
+        Some("go") => resolve_go_import(module_name, source_dir, project_root, tree),
+        _ => None,
     }
 }
 
@@ -1116,6 +1132,7 @@ fn resolve_relative_import(
     module_name: &str,
     source_dir: &Path,
     _project_root: &Path,
+    tree: &SourceTree,
 ) -> Option<PathBuf> {
     let mut path = source_dir.to_path_buf();
 
@@ -1141,16 +1158,16 @@ fn resolve_relative_import(
     // Try common file extensions
     for ext in &["py", "js", "ts", "rs"] {
         let file_path = path.with_extension(ext);
-        if file_path.exists() {
+        if tree.exists(&file_path) {
             return Some(file_path);
         }
     }
 
     // Try as directory with index/mod/__init__ files
-    if path.is_dir() {
+    if tree.is_dir(&path) {
         for index_file in &["__init__.py", "index.js", "index.ts", "mod.rs"] {
             let index_path = path.join(index_file);
-            if index_path.exists() {
+            if tree.exists(&index_path) {
                 return Some(index_path);
             }
         }
@@ -1164,6 +1181,7 @@ fn resolve_rust_import(
     module_name: &str,
     source_dir: &Path,
     project_root: &Path,
+    tree: &SourceTree,
 ) -> Option<PathBuf> {
     // Handle crate:: prefix
     let module_path = if module_name.starts_with("crate::") {
@@ -1183,31 +1201,31 @@ fn resolve_rust_import(
 
     // Try as .rs file
     let rs_file = path.with_extension("rs");
-    if rs_file.exists() {
+    if tree.exists(&rs_file) {
         return Some(rs_file);
     }
 
     // Try as mod.rs in directory
     let mod_file = path.join("mod.rs");
-    if mod_file.exists() {
+    if tree.exists(&mod_file) {
         return Some(mod_file);
     }
 
     // Try from project root src directory
     let src_path = project_root.join("src");
-    if src_path.exists() {
+    if tree.exists(&src_path) {
         let mut path = src_path;
         for part in &parts {
             path = path.join(part);
         }
 
         let rs_file = path.with_extension("rs");
-        if rs_file.exists() {
+        if tree.exists(&rs_file) {
             return Some(rs_file);
         }
 
         let mod_file = path.join("mod.rs");
-        if mod_file.exists() {
+        if tree.exists(&mod_file) {
             return Some(mod_file);
         }
     }
@@ -1220,6 +1238,7 @@ fn resolve_python_import(
     module_name: &str,
     source_dir: &Path,
     project_root: &Path,
+    tree: &SourceTree,
 ) -> Option<PathBuf> {
     // Convert module path to file path (e.g., "foo.bar" -> "foo/bar")
     let parts: Vec<&str> = module_name.split('.').collect();
@@ -1232,13 +1251,13 @@ fn resolve_python_import(
 
     // Try as .py file
     let py_file = path.with_extension("py");
-    if py_file.exists() {
+    if tree.exists(&py_file) {
         return Some(py_file);
     }
 
     // Try as __init__.py in directory
     let init_file = path.join("__init__.py");
-    if init_file.exists() {
+    if tree.exists(&init_file) {
         return Some(init_file);
     }
 
@@ -1249,12 +1268,12 @@ fn resolve_python_import(
     }
 
     let py_file = path.with_extension("py");
-    if py_file.exists() {
+    if tree.exists(&py_file) {
         return Some(py_file);
     }
 
     let init_file = path.join("__init__.py");
-    if init_file.exists() {
+    if tree.exists(&init_file) {
         return Some(init_file);
     }
 
@@ -1266,31 +1285,32 @@ fn resolve_javascript_import(
     module_name: &str,
     source_dir: &Path,
     _project_root: &Path,
+    tree: &SourceTree,
 ) -> Option<PathBuf> {
     // Handle relative paths
     if module_name.starts_with("./") || module_name.starts_with("../") {
         let path = source_dir.join(module_name);
 
         // Try exact path first
-        if path.exists() {
+        if tree.exists(&path) {
             return Some(path);
         }
 
         // Try with .js extension
         let js_file = path.with_extension("js");
-        if js_file.exists() {
+        if tree.exists(&js_file) {
             return Some(js_file);
         }
 
         // Try with .jsx extension
         let jsx_file = path.with_extension("jsx");
-        if jsx_file.exists() {
+        if tree.exists(&jsx_file) {
             return Some(jsx_file);
         }
 
         // Try as directory with index.js
         let index_file = path.join("index.js");
-        if index_file.exists() {
+        if tree.exists(&index_file) {
             return Some(index_file);
         }
     }
@@ -1304,31 +1324,32 @@ fn resolve_typescript_import(
     module_name: &str,
     source_dir: &Path,
     _project_root: &Path,
+    tree: &SourceTree,
 ) -> Option<PathBuf> {
     // Handle relative paths
     if module_name.starts_with("./") || module_name.starts_with("../") {
         let path = source_dir.join(module_name);
 
         // Try exact path first
-        if path.exists() {
+        if tree.exists(&path) {
             return Some(path);
         }
 
         // Try with .ts extension
         let ts_file = path.with_extension("ts");
-        if ts_file.exists() {
+        if tree.exists(&ts_file) {
             return Some(ts_file);
         }
 
         // Try with .tsx extension
         let tsx_file = path.with_extension("tsx");
-        if tsx_file.exists() {
+        if tree.exists(&tsx_file) {
             return Some(tsx_file);
         }
 
         // Try as directory with index.ts
         let index_file = path.join("index.ts");
-        if index_file.exists() {
+        if tree.exists(&index_file) {
             return Some(index_file);
         }
     }
@@ -1342,6 +1363,7 @@ fn resolve_go_import(
     module_name: &str,
     _source_dir: &Path,
     project_root: &Path,
+    tree: &SourceTree,
 ) -> Option<PathBuf> {
     // Go imports are typically package-based
     // Skip external packages (those with dots in the first part usually)
@@ -1358,11 +1380,10 @@ fn resolve_go_import(
     }
 
     // Go files in a directory form a package
-    if path.is_dir() {
+    if tree.is_dir(&path) {
         // Return the first .go file in the directory (excluding tests)
-        if let Ok(entries) = std::fs::read_dir(&path) {
-            for entry in entries.flatten() {
-                let file_path = entry.path();
+        if let Ok(entries) = tree.read_dir(&path) {
+            for file_path in entries {
                 if file_path.extension() == Some(std::ffi::OsStr::new("go")) {
                     let file_name = file_path.file_name()?.to_string_lossy();
                     if !file_name.ends_with("_test.go") {
@@ -1443,6 +1464,8 @@ mod tests {
             include_patterns: vec![],
             custom_priorities: vec![],
             filter_binary_files: false,
+            snapshot: None,
+            reporter: Default::default(),
         };
         let result = expand_file_list(files_map.clone(), &config, &cache, &walk_options).unwrap();
 
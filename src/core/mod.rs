@@ -1,13 +1,17 @@
 //! Core functionality modules
 
 pub mod cache;
+pub mod code_graph;
 pub mod context_builder;
 pub mod file_expander;
 pub mod prioritizer;
+pub mod progress;
 pub mod project_analyzer;
 pub mod search;
 pub mod semantic;
 pub mod semantic_cache;
 pub mod semantic_graph;
+pub mod snapshot;
+pub mod source_tree;
 pub mod token;
 pub mod walker;
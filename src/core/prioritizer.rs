@@ -2,10 +2,12 @@
 
 use crate::core::cache::FileCache;
 use crate::core::context_builder::ContextOptions;
+use crate::core::progress::Phase;
 use crate::core::token::{would_exceed_limit, TokenCounter};
 use crate::core::walker::FileInfo;
 use anyhow::Result;
 use rayon::prelude::*;
+use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use tracing::{debug, warn};
 
@@ -46,9 +48,19 @@ pub fn prioritize_files(
     let structure_overhead = calculate_structure_overhead(options, &files)?;
 
     // Phase 1: Count tokens for all files in parallel with proper error handling
+    let reporter = &options.reporter;
+    reporter.start(
+        Phase::Prioritize,
+        format!("Counting tokens in {} files", files.len()),
+    );
+    let total = files.len();
+    let counted = AtomicUsize::new(0);
     let results: Vec<crate::utils::error::Result<FileWithTokens>> = files
         .into_par_iter()
         .map(|file| {
+            reporter.check()?;
+            reporter.advance(Phase::Prioritize, &counted, total);
+
             // Read file content from cache
             let content = cache.get_or_load(&file.path).map_err(|e| {
                 crate::utils::error::ContextCreatorError::FileProcessingError {
@@ -74,6 +86,8 @@ pub fn prioritize_files(
         })
         .collect();
 
+    reporter.check()?;
+
     // Use partition_result to separate successes from errors
     use itertools::Itertools;
     let (files_with_tokens, errors): (Vec<_>, Vec<_>) = results.into_iter().partition_result();
@@ -250,7 +264,7 @@ mod tests {
     use super::*;
     use crate::utils::file_ext::FileType;
     use std::fs;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
     use tempfile::TempDir;
 
     fn create_test_cache() -> Arc<FileCache> {
@@ -439,6 +453,7 @@ mod tests {
             enhanced_context: false,
             git_context: false,
             git_context_depth: 3,
+            reporter: Default::default(),
         };
 
         let overhead = calculate_structure_overhead(&options, &files).unwrap();
@@ -560,7 +575,7 @@ mod tests {
             (dir == "." || dir.is_empty())
                 && files
                     .iter()
-                    .any(|f| f.relative_path == PathBuf::from("main.rs"))
+                    .any(|f| f.relative_path == Path::new("main.rs"))
         });
         assert!(has_root_or_main);
 
@@ -568,7 +583,7 @@ mod tests {
             dir == "src/core"
                 && files
                     .iter()
-                    .any(|f| f.relative_path == PathBuf::from("src/core/mod.rs"))
+                    .any(|f| f.relative_path == Path::new("src/core/mod.rs"))
         });
         assert!(has_src_core);
     }
@@ -0,0 +1,247 @@
+//! Progress reporting and cancellation for long-running operations
+//!
+//! A [`ProgressReporter`] travels with [`Config`](crate::cli::Config) into the
+//! walker, semantic analysis, prioritizer and LLM call. Each phase reports when
+//! it starts and how many files it has processed, and checks between files
+//! whether the caller cancelled the operation.
+
+use crate::utils::error::ContextCreatorError;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Files processed between two progress reports of a phase
+const REPORT_EVERY: usize = 100;
+
+/// Phases of building a context and answering a prompt, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    /// Cloning or fetching a remote repository
+    Fetch,
+    /// Walking the directory
+    Walk,
+    /// Semantic analysis of imports, calls and types
+    Analyze,
+    /// Selecting files within the token budget
+    Prioritize,
+    /// Formatting the context
+    Render,
+    /// Waiting for the LLM CLI
+    Llm,
+}
+
+impl Phase {
+    /// All phases, in order
+    pub const ALL: [Phase; 6] = [
+        Phase::Fetch,
+        Phase::Walk,
+        Phase::Analyze,
+        Phase::Prioritize,
+        Phase::Render,
+        Phase::Llm,
+    ];
+
+    /// Short name of the phase
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Fetch => "fetch",
+            Phase::Walk => "walk",
+            Phase::Analyze => "analyze",
+            Phase::Prioritize => "prioritize",
+            Phase::Render => "render",
+            Phase::Llm => "llm",
+        }
+    }
+}
+
+/// A progress report
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressEvent {
+    /// The phase that is running
+    pub phase: Phase,
+    /// Files processed so far in this phase
+    pub completed: usize,
+    /// Files the phase will process, if known
+    pub total: Option<usize>,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl ProgressEvent {
+    /// Progress of the whole operation between 0 and 1
+    ///
+    /// Each phase takes an equal share; a phase without a known total counts
+    /// as just started.
+    pub fn fraction(&self) -> f64 {
+        let within = match self.total {
+            Some(total) if total > 0 => (self.completed.min(total) as f64) / (total as f64),
+            _ => 0.0,
+        };
+        (self.phase as usize as f64 + within) / Phase::ALL.len() as f64
+    }
+}
+
+type Sink = Arc<dyn Fn(&ProgressEvent) + Send + Sync>;
+
+/// Receives progress reports and carries a cancellation flag
+///
+/// Clones share the sink and the flag. The default reporter discards reports
+/// and is never cancelled unless [`cancel`](Self::cancel) is called on it or
+/// on one of its clones.
+#[derive(Clone, Default)]
+pub struct ProgressReporter {
+    sink: Option<Sink>,
+    cancelled: Arc<AtomicBool>,
+}
+
+// The flag is atomic and sinks only forward reports, so a panic cannot leave
+// either in an inconsistent state
+impl std::panic::UnwindSafe for ProgressReporter {}
+impl std::panic::RefUnwindSafe for ProgressReporter {}
+
+impl std::fmt::Debug for ProgressReporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProgressReporter")
+            .field("sink", &self.sink.is_some())
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+impl ProgressReporter {
+    /// Create a reporter that passes every report to `sink`
+    pub fn new(sink: impl Fn(&ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            sink: Some(Arc::new(sink)),
+            cancelled: Arc::default(),
+        }
+    }
+
+    /// Report that `phase` started
+    pub fn start(&self, phase: Phase, message: impl Into<String>) {
+        self.report(phase, 0, None, message);
+    }
+
+    /// Report progress within `phase`
+    pub fn report(
+        &self,
+        phase: Phase,
+        completed: usize,
+        total: Option<usize>,
+        message: impl Into<String>,
+    ) {
+        if let Some(sink) = &self.sink {
+            sink(&ProgressEvent {
+                phase,
+                completed,
+                total,
+                message: message.into(),
+            });
+        }
+    }
+
+    /// Count one processed file of `phase`, reporting every few files
+    ///
+    /// `counter` is shared by the workers of the phase.
+    pub fn advance(&self, phase: Phase, counter: &AtomicUsize, total: usize) {
+        let completed = counter.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.sink.is_some() && (completed % REPORT_EVERY == 0 || completed == total) {
+            self.report(
+                phase,
+                completed,
+                Some(total),
+                format!("{completed}/{total} files"),
+            );
+        }
+    }
+
+    /// Cancel the operation this reporter belongs to
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the operation was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Cancel the operation when the returned guard is dropped
+    ///
+    /// Async callers hold the guard across the blocking work, so dropping
+    /// their future stops that work instead of leaving it running.
+    pub fn cancel_on_drop(&self) -> CancelOnDrop {
+        CancelOnDrop(self.clone())
+    }
+
+    /// Fail with [`ContextCreatorError::Cancelled`] if the operation was cancelled
+    pub fn check(&self) -> Result<(), ContextCreatorError> {
+        if self.is_cancelled() {
+            Err(ContextCreatorError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Cancels its reporter when dropped, see [`ProgressReporter::cancel_on_drop`]
+#[derive(Debug)]
+pub struct CancelOnDrop(ProgressReporter);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_advance_reports_every_few_files_and_at_the_end() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let reporter = ProgressReporter::new(move |event| sink.lock().unwrap().push(event.clone()));
+
+        let counter = AtomicUsize::new(0);
+        for _ in 0..250 {
+            reporter.advance(Phase::Walk, &counter, 250);
+        }
+
+        let completed: Vec<_> = events.lock().unwrap().iter().map(|e| e.completed).collect();
+        assert_eq!(completed, vec![100, 200, 250]);
+    }
+
+    #[test]
+    fn test_cancel_is_shared_by_clones() {
+        let reporter = ProgressReporter::default();
+        let clone = reporter.clone();
+        assert!(reporter.check().is_ok());
+
+        clone.cancel();
+        assert!(reporter.is_cancelled());
+        assert!(matches!(
+            reporter.check(),
+            Err(ContextCreatorError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_fraction_increases_across_phases() {
+        let event = |phase, completed, total| ProgressEvent {
+            phase,
+            completed,
+            total,
+            message: String::new(),
+        };
+
+        let fractions = [
+            event(Phase::Walk, 0, None).fraction(),
+            event(Phase::Walk, 5, Some(10)).fraction(),
+            event(Phase::Analyze, 0, Some(10)).fraction(),
+            event(Phase::Llm, 0, None).fraction(),
+        ];
+        assert!(fractions.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(fractions[3] < 1.0);
+    }
+}
@@ -5,6 +5,8 @@
 
 use crate::cli::Config;
 use crate::core::cache::FileCache;
+use crate::core::semantic_cache::SemanticCache;
+use crate::core::source_tree::SourceTree;
 use crate::core::walker::{walk_directory, FileInfo, WalkOptions};
 use crate::utils::error::ContextCreatorError;
 use std::collections::HashMap;
@@ -20,6 +22,8 @@ pub struct ProjectAnalysis {
     pub file_map: HashMap<PathBuf, FileInfo>,
     /// Project root directory
     pub project_root: PathBuf,
+    /// The files paths are looked up in
+    source_tree: SourceTree,
 }
 
 impl ProjectAnalysis {
@@ -31,13 +35,35 @@ impl ProjectAnalysis {
         cache: &Arc<FileCache>,
     ) -> Result<Self, ContextCreatorError> {
         // Detect project root
-        let project_root = if start_path.is_file() {
-            super::file_expander::detect_project_root(start_path)
+        let tree = SourceTree::new(base_walk_options.snapshot.clone());
+        let project_root = if tree.is_file(start_path) {
+            super::file_expander::detect_project_root(&tree, start_path)
         } else {
             // For directories, detect project root directly
-            super::file_expander::detect_project_root(start_path)
+            super::file_expander::detect_project_root(&tree, start_path)
         };
 
+        Self::analyze_root(
+            &project_root,
+            base_walk_options,
+            config,
+            cache,
+            &Arc::new(SemanticCache::new()),
+        )
+    }
+
+    /// Analyze a project whose root is already known
+    ///
+    /// Files whose parse results are in `semantic_cache` are not parsed again.
+    pub fn analyze_root(
+        project_root: &Path,
+        base_walk_options: &WalkOptions,
+        config: &Config,
+        cache: &Arc<FileCache>,
+        semantic_cache: &Arc<SemanticCache>,
+    ) -> Result<Self, ContextCreatorError> {
+        let project_root = project_root.to_path_buf();
+
         // Create walk options for full project scan (no include patterns)
         let mut project_walk_options = base_walk_options.clone();
         project_walk_options.include_patterns.clear();
@@ -52,8 +78,13 @@ impl ProjectAnalysis {
 
         // Perform semantic analysis once
         if config.trace_imports || config.include_callers || config.include_types {
-            super::walker::perform_semantic_analysis(&mut all_files, config, cache)
-                .map_err(|e| ContextCreatorError::ContextGenerationError(e.to_string()))?;
+            super::semantic_graph::perform_semantic_analysis_graph_cached(
+                &mut all_files,
+                config,
+                cache,
+                semantic_cache,
+            )
+            .map_err(|e| ContextCreatorError::ContextGenerationError(e.to_string()))?;
 
             if config.progress && !config.quiet {
                 let import_count: usize = all_files.iter().map(|f| f.imports.len()).sum();
@@ -62,11 +93,12 @@ impl ProjectAnalysis {
         }
 
         // Build file map for fast lookups
+        let source_tree = SourceTree::new(base_walk_options.snapshot.clone());
         let mut file_map = HashMap::with_capacity(all_files.len());
         for file in &all_files {
             // Use both original and canonical paths as keys
             file_map.insert(file.path.clone(), file.clone());
-            if let Ok(canonical) = file.path.canonicalize() {
+            if let Ok(canonical) = source_tree.canonicalize(&file.path) {
                 file_map.insert(canonical, file.clone());
             }
         }
@@ -75,6 +107,7 @@ impl ProjectAnalysis {
             all_files,
             file_map,
             project_root,
+            source_tree,
         })
     }
 
@@ -86,7 +119,7 @@ impl ProjectAnalysis {
         }
 
         // Try canonical path
-        if let Ok(canonical) = path.canonicalize() {
+        if let Ok(canonical) = self.source_tree.canonicalize(path) {
             self.file_map.get(&canonical)
         } else {
             None
@@ -1,5 +1,6 @@
 //! Base trait and types for language-specific semantic analyzers
 
+use crate::core::source_tree::SourceTree;
 use crate::utils::error::ContextCreatorError;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -20,6 +21,10 @@ pub struct SemanticContext {
     pub max_depth: usize,
     /// Files already visited (for cycle detection)
     pub visited_files: HashSet<PathBuf>,
+    /// Directories searched for C and C++ includes, relative to `base_dir`
+    pub include_paths: Vec<PathBuf>,
+    /// Files imports are resolved against
+    pub source_tree: SourceTree,
 }
 
 impl SemanticContext {
@@ -31,9 +36,23 @@ impl SemanticContext {
             current_depth: 0,
             max_depth,
             visited_files: HashSet::new(),
+            include_paths: Vec::new(),
+            source_tree: SourceTree::default(),
         }
     }
 
+    /// Search `include_paths` for C and C++ includes
+    pub fn with_include_paths(mut self, include_paths: Vec<PathBuf>) -> Self {
+        self.include_paths = include_paths;
+        self
+    }
+
+    /// Resolve imports against `source_tree` instead of the working tree
+    pub fn with_source_tree(mut self, source_tree: SourceTree) -> Self {
+        self.source_tree = source_tree;
+        self
+    }
+
     /// Check if we've reached maximum depth
     pub fn at_max_depth(&self) -> bool {
         self.current_depth >= self.max_depth
@@ -62,6 +81,9 @@ pub struct Import {
     pub items: Vec<String>,
     /// Whether this is a relative import
     pub is_relative: bool,
+    /// Whether the module is loaded at runtime, as with `import("./page")`,
+    /// `require.resolve` or `jest.mock`, rather than by a static import
+    pub is_dynamic: bool,
     /// Line number where import appears
     pub line: usize,
 }
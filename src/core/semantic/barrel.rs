@@ -0,0 +1,364 @@
+//! Re-exports of JavaScript and TypeScript modules
+//!
+//! Barrel files such as `components/index.ts` re-export names defined in
+//! sibling modules (`export * from './Button'`, `export { Card } from './Card'`).
+//! Following these chains for the imported names makes an import depend on
+//! the modules defining them instead of on the barrel and everything it
+//! re-exports.
+
+use crate::core::semantic::manifest_cache;
+use crate::core::semantic::path_validator::validate_import_path;
+use crate::core::semantic::resolver::{ModuleResolver, ResolvedPath};
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tree_sitter::{Language, Node, Parser};
+
+/// Re-export chains longer than this are not followed
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// The names a module exports and where they are defined
+#[derive(Debug, Default, PartialEq)]
+pub struct ModuleExports {
+    /// Names declared by the module itself, `default` included
+    pub local: Vec<String>,
+    /// Names re-exported from another module, mapped to the module specifier
+    /// and the name there; `*` stands for the whole module (`export * as ns`)
+    pub reexports: HashMap<String, (String, String)>,
+    /// Module specifiers of `export * from` statements
+    pub star: Vec<String>,
+}
+
+impl ModuleExports {
+    /// Read the exports of a module from its source
+    pub fn parse(path: &Path, content: &str) -> Option<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(language_for(path)?).ok()?;
+        let tree = parser.parse(content, None)?;
+        let root = tree.root_node();
+        let source = content.as_bytes();
+
+        // Names bound by imports, which `export { name }` may re-export
+        let mut bindings: HashMap<String, (String, String)> = HashMap::new();
+        let mut cursor = root.walk();
+        for statement in root.named_children(&mut cursor) {
+            if statement.kind() != "import_statement" {
+                continue;
+            }
+            let Some(specifier) = statement.child_by_field_name("source") else {
+                continue;
+            };
+            let specifier = unquote(text(specifier, source));
+            let mut clause_cursor = statement.walk();
+            let clauses = statement
+                .named_children(&mut clause_cursor)
+                .filter(|child| child.kind() == "import_clause");
+            for clause in clauses {
+                collect_bindings(clause, &specifier, source, &mut bindings);
+            }
+        }
+
+        let mut exports = Self::default();
+        let mut cursor = root.walk();
+        for statement in root.named_children(&mut cursor) {
+            if statement.kind() == "export_statement" {
+                exports.add_export(statement, source, &bindings);
+            }
+        }
+        Some(exports)
+    }
+
+    /// The exports of the module at `path`, reusing the parsed ones while
+    /// the file is unchanged
+    pub fn for_file(tree: &SourceTree, path: &Path) -> Option<Arc<ModuleExports>> {
+        manifest_cache::load_cached(tree, path, |path| {
+            Self::parse(path, &tree.read_to_string(path).ok()?)
+        })
+    }
+
+    fn add_export(
+        &mut self,
+        statement: Node,
+        source: &[u8],
+        bindings: &HashMap<String, (String, String)>,
+    ) {
+        let specifier = statement
+            .child_by_field_name("source")
+            .map(|node| unquote(text(node, source)));
+        let mut cursor = statement.walk();
+        let is_default = statement
+            .children(&mut cursor)
+            .any(|child| child.kind() == "default");
+
+        if let Some(declaration) = statement.child_by_field_name("declaration") {
+            self.local.extend(declared_names(declaration, source));
+            if is_default {
+                self.local.push("default".to_string());
+            }
+            return;
+        }
+        if let Some(value) = statement.child_by_field_name("value") {
+            // `export default name` of an imported binding
+            match bindings.get(text(value, source)) {
+                Some(binding) if value.kind() == "identifier" => {
+                    self.reexports
+                        .insert("default".to_string(), binding.clone());
+                }
+                _ => self.local.push("default".to_string()),
+            }
+            return;
+        }
+
+        let mut cursor = statement.walk();
+        for child in statement.children(&mut cursor) {
+            match (child.kind(), &specifier) {
+                ("*", Some(specifier)) => self.star.push(specifier.clone()),
+                ("namespace_export", Some(specifier)) => {
+                    if let Some(name) = child.named_child(0) {
+                        let name = unquote(text(name, source));
+                        self.reexports
+                            .insert(name, (specifier.clone(), "*".to_string()));
+                    }
+                }
+                ("export_clause", _) => {
+                    let mut clause_cursor = child.walk();
+                    for export in child.named_children(&mut clause_cursor) {
+                        let Some(name) = export.child_by_field_name("name") else {
+                            continue;
+                        };
+                        let name = unquote(text(name, source));
+                        let exported = export
+                            .child_by_field_name("alias")
+                            .map(|alias| unquote(text(alias, source)))
+                            .unwrap_or_else(|| name.clone());
+                        match &specifier {
+                            Some(specifier) => {
+                                self.reexports.insert(exported, (specifier.clone(), name));
+                            }
+                            None => match bindings.get(&name) {
+                                Some(binding) => {
+                                    self.reexports.insert(exported, binding.clone());
+                                }
+                                None => self.local.push(exported),
+                            },
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Resolve an import of `items` from `module_path` to the modules defining
+/// them, following the re-exports of barrel files
+///
+/// Items that cannot be traced to a definition resolve to the imported
+/// module itself.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_items(
+    resolver: &dyn ModuleResolver,
+    module_path: &str,
+    items: &[String],
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+    let module = resolver.resolve_import(module_path, from_file, base_dir, tree)?;
+    if module.is_external || items.is_empty() {
+        return Ok(vec![module]);
+    }
+
+    let mut resolved: Vec<ResolvedPath> = Vec::new();
+    for name in items {
+        let path = find_definition(resolver, &module.path, name, base_dir, tree, 0)
+            .unwrap_or_else(|| module.path.clone());
+        let validated_path = validate_import_path(base_dir, &path, tree)?;
+        if !resolved.iter().any(|r| r.path == validated_path) {
+            resolved.push(ResolvedPath {
+                path: validated_path,
+                is_external: false,
+                confidence: module.confidence,
+            });
+        }
+    }
+    Ok(resolved)
+}
+
+/// The module defining `name`, following the re-exports of `file`
+#[allow(clippy::too_many_arguments)]
+fn find_definition(
+    resolver: &dyn ModuleResolver,
+    file: &Path,
+    name: &str,
+    base_dir: &Path,
+    tree: &SourceTree,
+    depth: usize,
+) -> Option<PathBuf> {
+    if depth >= MAX_REEXPORT_DEPTH {
+        return None;
+    }
+    let exports = ModuleExports::for_file(tree, file)?;
+    if exports.local.iter().any(|local| local == name) {
+        return Some(file.to_path_buf());
+    }
+
+    let resolve = |specifier: &str| {
+        resolver
+            .resolve_import(specifier, file, base_dir, tree)
+            .ok()
+            .filter(|target| !target.is_external)
+            .map(|target| target.path)
+    };
+    if let Some((specifier, original)) = exports.reexports.get(name) {
+        let target = resolve(specifier)?;
+        if original == "*" {
+            return Some(target);
+        }
+        return find_definition(resolver, &target, original, base_dir, tree, depth + 1)
+            .or(Some(target));
+    }
+
+    // `export *` re-exports every name but the default export
+    if name == "default" {
+        return None;
+    }
+    exports.star.iter().find_map(|specifier| {
+        let target = resolve(specifier)?;
+        find_definition(resolver, &target, name, base_dir, tree, depth + 1)
+    })
+}
+
+fn language_for(path: &Path) -> Option<Language> {
+    match path.extension()?.to_str()? {
+        "ts" | "mts" | "cts" => Some(tree_sitter_typescript::language_typescript()),
+        "tsx" => Some(tree_sitter_typescript::language_tsx()),
+        "js" | "jsx" | "mjs" | "cjs" => Some(tree_sitter_javascript::language()),
+        _ => None,
+    }
+}
+
+/// Record the local names bound by an import clause
+fn collect_bindings(
+    clause: Node,
+    specifier: &str,
+    source: &[u8],
+    bindings: &mut HashMap<String, (String, String)>,
+) {
+    let mut bind = |local: String, original: &str| {
+        bindings.insert(local, (specifier.to_string(), original.to_string()));
+    };
+    let mut cursor = clause.walk();
+    for child in clause.named_children(&mut cursor) {
+        match child.kind() {
+            "identifier" => bind(text(child, source).to_string(), "default"),
+            "namespace_import" => {
+                if let Some(name) = child.named_child(0) {
+                    bind(text(name, source).to_string(), "*");
+                }
+            }
+            "named_imports" => {
+                let mut import_cursor = child.walk();
+                for import in child.named_children(&mut import_cursor) {
+                    let Some(name) = import.child_by_field_name("name") else {
+                        continue;
+                    };
+                    let name = unquote(text(name, source));
+                    let local = import
+                        .child_by_field_name("alias")
+                        .map(|alias| text(alias, source).to_string())
+                        .unwrap_or_else(|| name.clone());
+                    bind(local, &name);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Names declared by an exported declaration
+fn declared_names(declaration: Node, source: &[u8]) -> Vec<String> {
+    match declaration.kind() {
+        "lexical_declaration" | "variable_declaration" => {
+            let mut cursor = declaration.walk();
+            declaration
+                .named_children(&mut cursor)
+                .filter_map(|declarator| declarator.child_by_field_name("name"))
+                .filter(|name| name.kind() == "identifier")
+                .map(|name| text(name, source).to_string())
+                .collect()
+        }
+        _ => declaration
+            .child_by_field_name("name")
+            .map(|name| vec![text(name, source).to_string()])
+            .unwrap_or_default(),
+    }
+}
+
+fn text<'a>(node: Node, source: &'a [u8]) -> &'a str {
+    node.utf8_text(source).unwrap_or("")
+}
+
+fn unquote(text: &str) -> String {
+    text.trim_matches(|c| c == '"' || c == '\'').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_barrel_exports() {
+        let content = r#"
+import Modal, { Dialog as BaseDialog } from './Modal';
+export * from './Button';
+export * as icons from './icons';
+export { Card, CardHeader as Header } from './Card';
+export { default as Avatar } from './Avatar';
+export { Modal, BaseDialog as Dialog };
+"#;
+        let exports = ModuleExports::parse(Path::new("index.ts"), content).unwrap();
+
+        assert!(exports.local.is_empty());
+        assert_eq!(exports.star, vec!["./Button".to_string()]);
+        let reexport = |name: &str| exports.reexports.get(name).cloned();
+        let expected = |specifier: &str, name: &str| Some((specifier.into(), name.into()));
+        assert_eq!(reexport("icons"), expected("./icons", "*"));
+        assert_eq!(reexport("Card"), expected("./Card", "Card"));
+        assert_eq!(reexport("Header"), expected("./Card", "CardHeader"));
+        assert_eq!(reexport("Avatar"), expected("./Avatar", "default"));
+        assert_eq!(reexport("Modal"), expected("./Modal", "default"));
+        assert_eq!(reexport("Dialog"), expected("./Modal", "Dialog"));
+    }
+
+    #[test]
+    fn test_local_exports() {
+        let content = r#"
+export const Button = () => null, SIZE = 3;
+export function useButton() {}
+export interface ButtonProps { label: string }
+export type Variant = 'primary' | 'secondary';
+export default class IconButton {}
+const helper = 1;
+export { helper as buttonHelper };
+"#;
+        let exports = ModuleExports::parse(Path::new("Button.tsx"), content).unwrap();
+
+        assert_eq!(
+            exports.local,
+            vec![
+                "Button",
+                "SIZE",
+                "useButton",
+                "ButtonProps",
+                "Variant",
+                "IconButton",
+                "default",
+                "buttonHelper",
+            ]
+        );
+        assert!(exports.reexports.is_empty());
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod tests {
+    use crate::core::semantic::{
+        analyzer::*,
+        languages::{
+            c::{CAnalyzer, CModuleResolver},
+            cpp::CppAnalyzer,
+        },
+        resolver::ModuleResolver,
+    };
+    use std::fs;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_c_analysis() {
+        let analyzer = CAnalyzer::new();
+        let content = r#"
+#include <stdio.h>
+#include "util/strings.h"
+
+struct point { int x; int y; };
+typedef struct point point_t;
+
+static int square(int n) { return n * n; }
+
+char *describe(point_t *p, struct ops *ops) {
+    ops->open(p);
+    printf("%d\n", square(p->x));
+    return format_point(p);
+}
+"#;
+        let path = PathBuf::from("main.c");
+        let context = SemanticContext::new(path.clone(), PathBuf::from("."), 3);
+
+        let result = analyzer.analyze_file(&path, content, &context).unwrap();
+
+        let imports: Vec<(&str, bool)> = result
+            .imports
+            .iter()
+            .map(|i| (i.module.as_str(), i.is_relative))
+            .collect();
+        assert_eq!(imports, vec![("stdio.h", false), ("util/strings.h", true)]);
+
+        let calls: Vec<&str> = result
+            .function_calls
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        for name in ["open", "printf", "square", "format_point"] {
+            assert!(calls.contains(&name), "Should find call to {name}");
+        }
+
+        let definitions: Vec<(&str, bool)> = result
+            .exported_functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.is_exported))
+            .collect();
+        assert_eq!(definitions, vec![("square", false), ("describe", true)]);
+
+        let types: Vec<&str> = result
+            .type_references
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        for name in ["point", "point_t", "ops"] {
+            assert!(types.contains(&name), "Should find type {name}");
+        }
+    }
+
+    #[test]
+    fn test_cpp_analysis() {
+        let analyzer = CppAnalyzer::new();
+        let content = r#"
+#include <vector>
+#include "shapes/circle.hpp"
+
+namespace geometry {
+class Circle : public Shape {
+public:
+    double area() const { return compute(radius); }
+};
+}
+
+std::vector<geometry::Point> Canvas::points() {
+    auto origin = geometry::origin();
+    shape.draw();
+    return convert<Point>(origin);
+}
+"#;
+        let path = PathBuf::from("canvas.cpp");
+        let context = SemanticContext::new(path.clone(), PathBuf::from("."), 3);
+
+        let result = analyzer.analyze_file(&path, content, &context).unwrap();
+
+        let modules: Vec<&str> = result.imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(modules, vec!["vector", "shapes/circle.hpp"]);
+
+        let calls: Vec<(&str, Option<&str>)> = result
+            .function_calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.module.as_deref()))
+            .collect();
+        for call in [
+            ("compute", None),
+            ("origin", Some("geometry")),
+            ("draw", None),
+            ("convert", None),
+        ] {
+            assert!(calls.contains(&call), "Should find call {call:?}");
+        }
+
+        let definitions: Vec<&str> = result
+            .exported_functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(definitions, vec!["area", "points"]);
+
+        let types: Vec<(&str, Option<&str>)> = result
+            .type_references
+            .iter()
+            .map(|t| (t.name.as_str(), t.module.as_deref()))
+            .collect();
+        assert!(types.contains(&("Shape", None)));
+        assert!(types.contains(&("Point", Some("geometry"))));
+    }
+
+    #[test]
+    fn test_c_headers_resolve_to_their_implementations() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("include/net")).unwrap();
+        fs::create_dir_all(root.join("src/net")).unwrap();
+        for file in [
+            "main.c",
+            "include/net/socket.h",
+            "src/net/socket.c",
+            "parser.v2.h",
+            "parser.v2.c",
+            "parser.c",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        let main = root.join("main.c");
+        let context = SemanticContext::new(main.clone(), root.clone(), 3)
+            .with_include_paths(vec![PathBuf::from("include")]);
+        let resolve = |header: &str| -> Vec<PathBuf> {
+            CModuleResolver
+                .resolve_import_in_context(header, &[], &context)
+                .unwrap()
+                .into_iter()
+                .map(|resolved| resolved.path)
+                .collect()
+        };
+
+        assert_eq!(
+            resolve("net/socket.h"),
+            vec![
+                root.join("include/net/socket.h"),
+                root.join("src/net/socket.c")
+            ]
+        );
+        // Only the last dot of a dotted name starts the extension
+        assert_eq!(
+            resolve("parser.v2.h"),
+            vec![root.join("parser.v2.h"), root.join("parser.v2.c")]
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! Crate manifests (`Cargo.toml`)
+//!
+//! A crate's manifest names its library target and declares the path
+//! dependencies through which `use other_crate::...` reaches sibling crates
+//! of the repository, directly or through `[workspace.dependencies]`.
+
+use crate::core::semantic::manifest_cache;
+use crate::core::source_tree::SourceTree;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Dependency tables that may name path dependencies
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// The parts of a crate's `Cargo.toml` module resolution needs
+#[derive(Debug, Clone, PartialEq)]
+pub struct CrateManifest {
+    /// Directory of the manifest
+    pub dir: PathBuf,
+    /// Name of the library crate in paths, with `-` replaced by `_`
+    pub lib_name: String,
+    /// Root file of the library target, `src/lib.rs` unless `[lib] path` says otherwise
+    pub lib_root: PathBuf,
+    /// Directories of path dependencies, by the name they are used under
+    pub path_dependencies: HashMap<String, PathBuf>,
+}
+
+impl CrateManifest {
+    /// Read the manifest of the crate in `dir`; workspace-only manifests
+    /// without a `[package]` are not crates
+    pub fn load(tree: &SourceTree, dir: &Path) -> Option<Self> {
+        let manifest = read_toml(tree, &dir.join("Cargo.toml"))?;
+        let package = manifest.get("package")?;
+        let lib = manifest.get("lib");
+        let lib_name = lib
+            .and_then(|lib| lib.get("name"))
+            .or_else(|| package.get("name"))?
+            .as_str()?
+            .replace('-', "_");
+        let lib_path = lib
+            .and_then(|lib| lib.get("path"))
+            .and_then(|path| path.as_str())
+            .unwrap_or("src/lib.rs");
+
+        // Target-specific tables: [target.'cfg(unix)'.dependencies]
+        let targets = manifest
+            .get("target")
+            .and_then(|targets| targets.as_table())
+            .into_iter()
+            .flat_map(|targets| targets.values());
+        let mut path_dependencies = HashMap::new();
+        for scope in std::iter::once(&manifest).chain(targets) {
+            let tables = DEPENDENCY_TABLES
+                .iter()
+                .filter_map(|table| scope.get(table)?.as_table());
+            for (name, dependency) in tables.flatten() {
+                if let Some(path) = dependency_path(tree, dir, name, dependency) {
+                    path_dependencies.insert(name.replace('-', "_"), path);
+                }
+            }
+        }
+
+        Some(Self {
+            dir: dir.to_path_buf(),
+            lib_name,
+            lib_root: dir.join(lib_path),
+            path_dependencies,
+        })
+    }
+
+    /// The manifest of the crate `file` belongs to: the nearest `Cargo.toml`
+    /// with a `[package]` between the file and `root`
+    pub fn find(tree: &SourceTree, file: &Path, root: &Path) -> Option<Arc<CrateManifest>> {
+        file.parent()
+            .into_iter()
+            .flat_map(Path::ancestors)
+            .take_while(|dir| dir.starts_with(root))
+            .find_map(|dir| {
+                manifest_cache::load_cached(tree, &dir.join("Cargo.toml"), |path| {
+                    CrateManifest::load(tree, path.parent()?)
+                })
+            })
+    }
+}
+
+/// The directory of a `{ path = "..." }` dependency, looking up
+/// `{ workspace = true }` in the enclosing workspace's `[workspace.dependencies]`
+fn dependency_path(
+    tree: &SourceTree,
+    dir: &Path,
+    name: &str,
+    dependency: &toml::Value,
+) -> Option<PathBuf> {
+    if let Some(path) = dependency.get("path").and_then(|path| path.as_str()) {
+        return Some(dir.join(path));
+    }
+    if dependency
+        .get("workspace")
+        .and_then(|inherit| inherit.as_bool())
+        != Some(true)
+    {
+        return None;
+    }
+    dir.ancestors().skip(1).find_map(|workspace_dir| {
+        let manifest = read_toml(tree, &workspace_dir.join("Cargo.toml"))?;
+        let path = manifest
+            .get("workspace")?
+            .get("dependencies")?
+            .get(name)?
+            .get("path")?
+            .as_str()?;
+        Some(workspace_dir.join(path))
+    })
+}
+
+fn read_toml(tree: &SourceTree, path: &Path) -> Option<toml::Value> {
+    toml::from_str(&tree.read_to_string(path).ok()?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_path_dependencies() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("Cargo.toml"),
+            r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.dependencies]
+shared-types = { path = "crates/types" }
+serde = "1"
+"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("crates/app/src")).unwrap();
+        fs::write(
+            root.join("crates/app/Cargo.toml"),
+            r#"
+[package]
+name = "my-app"
+
+[lib]
+path = "src/app.rs"
+
+[dependencies]
+core-utils = { path = "../utils" }
+shared-types = { workspace = true }
+serde = { workspace = true }
+
+[target.'cfg(unix)'.dev-dependencies]
+test-support = { path = "../support", package = "support" }
+"#,
+        )
+        .unwrap();
+
+        let app = root.join("crates/app");
+        let manifest =
+            CrateManifest::find(&SourceTree::default(), &app.join("src/app.rs"), root).unwrap();
+        assert_eq!(manifest.lib_name, "my_app");
+        assert_eq!(manifest.lib_root, app.join("src/app.rs"));
+        assert_eq!(
+            manifest.path_dependencies,
+            HashMap::from([
+                ("core_utils".to_string(), app.join("../utils")),
+                ("shared_types".to_string(), root.join("crates/types")),
+                ("test_support".to_string(), app.join("../support")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_workspace_manifest_is_not_a_crate() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = []\n").unwrap();
+
+        assert_eq!(CrateManifest::load(&SourceTree::default(), root), None);
+        assert_eq!(
+            CrateManifest::find(&SourceTree::default(), &root.join("src/lib.rs"), root),
+            None
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Include search paths of C and C++ sources
+//!
+//! Build systems such as CMake (`CMAKE_EXPORT_COMPILE_COMMANDS`), Meson and
+//! Bear write a compilation database, `compile_commands.json`, recording the
+//! command compiling each translation unit; its `-I` flags name the
+//! directories `#include` searches. Projects without one can list the flags
+//! shared by all their sources in `compile_flags.txt`, as clang tools do, or
+//! configure `include_paths`.
+
+use crate::core::semantic::manifest_cache;
+use crate::core::source_tree::SourceTree;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Flags followed by an include directory, separately or joined (`-Iinclude`)
+const INCLUDE_FLAGS: [&str; 4] = ["-I", "-iquote", "-isystem", "-idirafter"];
+
+/// Include directories read from `compile_commands.json` or `compile_flags.txt`
+#[derive(Debug, Default, PartialEq)]
+pub struct CompilationDatabase {
+    /// Include directories of each compiled file
+    pub files: HashMap<PathBuf, Vec<PathBuf>>,
+    /// Include directories of all files, for headers and files not compiled
+    pub all: Vec<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct CompileCommand {
+    directory: PathBuf,
+    file: PathBuf,
+    #[serde(default)]
+    arguments: Vec<String>,
+    #[serde(default)]
+    command: String,
+}
+
+impl CompilationDatabase {
+    /// Read a `compile_commands.json`
+    pub fn parse_json(content: &str) -> Option<Self> {
+        let commands: Vec<CompileCommand> = serde_json::from_str(content).ok()?;
+        let mut database = Self::default();
+        for command in commands {
+            let arguments = if command.arguments.is_empty() {
+                split_command(&command.command)
+            } else {
+                command.arguments
+            };
+            let dirs = include_dirs(&arguments, &command.directory);
+            for dir in &dirs {
+                if !database.all.contains(dir) {
+                    database.all.push(dir.clone());
+                }
+            }
+            database
+                .files
+                .insert(normalize(&command.directory.join(&command.file)), dirs);
+        }
+        Some(database)
+    }
+
+    /// Read a `compile_flags.txt` in `dir`, one flag per line
+    pub fn parse_flags(content: &str, dir: &Path) -> Self {
+        let arguments: Vec<String> = content
+            .lines()
+            .map(|line| line.trim().to_string())
+            .collect();
+        Self {
+            files: HashMap::new(),
+            all: include_dirs(&arguments, dir),
+        }
+    }
+
+    /// The database governing `file`: the first `compile_commands.json`,
+    /// `build/compile_commands.json` or `compile_flags.txt` found between the
+    /// file and `root`
+    pub fn find(tree: &SourceTree, file: &Path, root: &Path) -> Option<Arc<CompilationDatabase>> {
+        file.parent()
+            .into_iter()
+            .flat_map(Path::ancestors)
+            .take_while(|dir| dir.starts_with(root))
+            .find_map(|dir| {
+                [
+                    "compile_commands.json",
+                    "build/compile_commands.json",
+                    "compile_flags.txt",
+                ]
+                .iter()
+                .find_map(|name| {
+                    manifest_cache::load_cached(tree, &dir.join(name), |path| {
+                        read_database(tree, path)
+                    })
+                })
+            })
+    }
+
+    /// Include directories `file` is compiled with
+    pub fn include_dirs_for(&self, file: &Path) -> &[PathBuf] {
+        self.files
+            .get(&normalize(file))
+            .map_or(&self.all, |dirs| dirs)
+    }
+}
+
+/// Directories searched for the `#include`s of `file`, after its own
+/// directory: those of its compilation database
+pub fn search_dirs(tree: &SourceTree, file: &Path, root: &Path) -> Vec<PathBuf> {
+    CompilationDatabase::find(tree, file, root)
+        .map(|database| database.include_dirs_for(file).to_vec())
+        .unwrap_or_default()
+}
+
+/// Include directories named by compiler arguments, relative to `dir`
+fn include_dirs(arguments: &[String], dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let Some(flag) = INCLUDE_FLAGS
+            .iter()
+            .find(|flag| argument.starts_with(*flag))
+        else {
+            continue;
+        };
+        let include = match &argument[flag.len()..] {
+            "" => arguments.next().map(String::as_str),
+            joined => Some(joined),
+        };
+        if let Some(include) = include {
+            dirs.push(normalize(&dir.join(include)));
+        }
+    }
+    dirs
+}
+
+/// Split a shell command line into its arguments, honoring quotes
+fn split_command(command: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    let mut in_argument = false;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_argument = true;
+            }
+            (None, '\\') | (Some('"'), '\\') => {
+                current.extend(chars.next());
+                in_argument = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            (_, c) => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(current);
+    }
+    arguments
+}
+
+/// Remove `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Read a `compile_commands.json` or `compile_flags.txt`
+fn read_database(tree: &SourceTree, path: &Path) -> Option<CompilationDatabase> {
+    let content = tree.read_to_string(path).ok()?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        CompilationDatabase::parse_json(&content)
+    } else {
+        Some(CompilationDatabase::parse_flags(&content, path.parent()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_commands() {
+        let content = r#"[
+            {
+                "directory": "/project/build",
+                "file": "../src/main.c",
+                "command": "cc -I../include -iquote \"../third party\" -DNDEBUG -c ../src/main.c"
+            },
+            {
+                "directory": "/project",
+                "file": "lib/util.cpp",
+                "arguments": ["c++", "-I", "lib/include", "-isystem/opt/sdk", "-c", "lib/util.cpp"]
+            }
+        ]"#;
+        let database = CompilationDatabase::parse_json(content).unwrap();
+
+        assert_eq!(
+            database.include_dirs_for(Path::new("/project/src/main.c")),
+            [
+                PathBuf::from("/project/include"),
+                PathBuf::from("/project/third party"),
+            ]
+        );
+        assert_eq!(
+            database.include_dirs_for(Path::new("/project/lib/util.cpp")),
+            [
+                PathBuf::from("/project/lib/include"),
+                PathBuf::from("/opt/sdk"),
+            ]
+        );
+        // Headers search the include directories of every file
+        assert_eq!(
+            database
+                .include_dirs_for(Path::new("/project/include/util.h"))
+                .len(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_compile_flags() {
+        let database =
+            CompilationDatabase::parse_flags("-xc++\n-Iinclude\n-I\nvendor\n", Path::new("/p"));
+
+        assert_eq!(
+            database.include_dirs_for(Path::new("/p/src/main.cpp")),
+            [PathBuf::from("/p/include"), PathBuf::from("/p/vendor")]
+        );
+    }
+}
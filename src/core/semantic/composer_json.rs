@@ -0,0 +1,134 @@
+//! PSR-4 autoloading declared in `composer.json`
+//!
+//! PHP classes are loaded by Composer's autoloader: `autoload.psr-4` (and
+//! `autoload-dev.psr-4`) map namespace prefixes to directories, so
+//! `App\Models\User` under `"App\\": "app/"` lives in `app/Models/User.php`.
+
+use crate::core::semantic::manifest_cache;
+use crate::core::source_tree::SourceTree;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The PSR-4 mapping of a Composer package
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ComposerAutoload {
+    /// Namespace prefixes with their directories, longest prefix first
+    pub psr4: Vec<(String, Vec<PathBuf>)>,
+}
+
+impl ComposerAutoload {
+    /// Read the PSR-4 mapping of the `composer.json` in `dir`
+    pub fn load(tree: &SourceTree, dir: &Path) -> Option<Self> {
+        let content = tree.read_to_string(&dir.join("composer.json")).ok()?;
+        let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+
+        let mut psr4: Vec<(String, Vec<PathBuf>)> = Vec::new();
+        for section in ["autoload", "autoload-dev"] {
+            let Some(mapping) = manifest
+                .get(section)
+                .and_then(|autoload| autoload.get("psr-4"))
+                .and_then(|mapping| mapping.as_object())
+            else {
+                continue;
+            };
+            for (prefix, dirs) in mapping {
+                // A directory or a list of directories
+                let dirs = match dirs {
+                    serde_json::Value::Array(dirs) => {
+                        dirs.iter().filter_map(|d| d.as_str()).collect()
+                    }
+                    dirs => dirs.as_str().into_iter().collect::<Vec<_>>(),
+                };
+                let dirs = dirs
+                    .into_iter()
+                    .filter(|d| !d.contains(".."))
+                    .map(|d| dir.join(d))
+                    .collect();
+                psr4.push((prefix.trim_start_matches('\\').to_string(), dirs));
+            }
+        }
+        psr4.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+        Some(Self { psr4 })
+    }
+
+    /// The mapping of the package `file` belongs to: the nearest
+    /// `composer.json` between the file and `root`
+    pub fn find(tree: &SourceTree, file: &Path, root: &Path) -> Option<Arc<ComposerAutoload>> {
+        file.parent()
+            .into_iter()
+            .flat_map(Path::ancestors)
+            .take_while(|dir| dir.starts_with(root))
+            .find_map(|dir| {
+                manifest_cache::load_cached(tree, &dir.join("composer.json"), |path| {
+                    Self::load(tree, path.parent()?)
+                })
+            })
+    }
+
+    /// The file of the fully qualified class, interface, trait or enum `class`
+    pub fn resolve_class(&self, tree: &SourceTree, class: &str) -> Option<PathBuf> {
+        let class = class.trim_start_matches('\\');
+        self.psr4.iter().find_map(|(prefix, dirs)| {
+            let relative = class.strip_prefix(prefix.as_str())?;
+            let relative = format!("{}.php", relative.replace('\\', "/"));
+            dirs.iter()
+                .map(|dir| dir.join(&relative))
+                .find(|candidate| tree.is_file(candidate))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_psr4_mapping() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("composer.json"),
+            r#"{
+                "autoload": {
+                    "psr-4": {
+                        "App\\": "app/",
+                        "App\\Support\\": ["src/support/", "legacy/"]
+                    }
+                },
+                "autoload-dev": { "psr-4": { "Tests\\": "tests/" } }
+            }"#,
+        )
+        .unwrap();
+        for file in [
+            "app/Models/User.php",
+            "legacy/Str.php",
+            "tests/Feature/UserTest.php",
+        ] {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), "<?php\n").unwrap();
+        }
+
+        let tree = SourceTree::default();
+        let autoload =
+            ComposerAutoload::find(&tree, &root.join("app/Http/Kernel.php"), root).unwrap();
+        assert_eq!(
+            autoload.resolve_class(&tree, "App\\Models\\User"),
+            Some(root.join("app/Models/User.php"))
+        );
+        // The longest prefix wins, trying each of its directories
+        assert_eq!(
+            autoload.resolve_class(&tree, "\\App\\Support\\Str"),
+            Some(root.join("legacy/Str.php"))
+        );
+        assert_eq!(
+            autoload.resolve_class(&tree, "Tests\\Feature\\UserTest"),
+            Some(root.join("tests/Feature/UserTest.php"))
+        );
+        assert_eq!(
+            autoload.resolve_class(&tree, "Illuminate\\Support\\Str"),
+            None
+        );
+    }
+}
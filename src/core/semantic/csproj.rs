@@ -0,0 +1,254 @@
+//! .NET projects (`*.csproj`)
+//!
+//! C# does not tie namespaces to paths: a `using` directive names a namespace
+//! that any file of the project, or of the projects it references through
+//! `<ProjectReference>`, may declare. Projects index their files by the
+//! namespaces they declare; the index is rebuilt when the `.csproj` changes.
+
+use crate::core::semantic::manifest_cache;
+use crate::core::source_tree::SourceTree;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Build output directories of SDK-style projects, never compiled
+const OUTPUT_DIRS: [&str; 2] = ["bin", "obj"];
+
+/// A C# project and the namespaces its files declare
+#[derive(Debug, Default, PartialEq)]
+pub struct CSharpProject {
+    /// Directory of the `.csproj`
+    pub dir: PathBuf,
+    /// Manifests of the projects referenced by `<ProjectReference Include="...">`
+    pub references: Vec<PathBuf>,
+    /// Source files by the namespaces they declare
+    pub namespaces: HashMap<String, Vec<PathBuf>>,
+}
+
+impl CSharpProject {
+    /// Read the project of the `.csproj` at `manifest` and index its sources:
+    /// every `.cs` file below its directory but those of build output and of
+    /// nested projects
+    pub fn load(tree: &SourceTree, manifest: &Path) -> Option<Self> {
+        let content = tree.read_to_string(manifest).ok()?;
+        let dir = manifest.parent()?.to_path_buf();
+        let references = project_references(&content)
+            .into_iter()
+            .map(|include| dir.join(include.replace('\\', "/")))
+            .collect();
+
+        let mut namespaces: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        let mut sources = Vec::new();
+        collect_sources(tree, &dir, &mut sources);
+        for source in sources {
+            let Ok(content) = tree.read_to_string(&source) else {
+                continue;
+            };
+            for namespace in declared_namespaces(&content) {
+                let files = namespaces.entry(namespace).or_default();
+                if !files.contains(&source) {
+                    files.push(source.clone());
+                }
+            }
+        }
+
+        Some(Self {
+            dir,
+            references,
+            namespaces,
+        })
+    }
+
+    /// The project `file` belongs to: the nearest `.csproj` between the file
+    /// and `root`
+    pub fn find(tree: &SourceTree, file: &Path, root: &Path) -> Option<Arc<CSharpProject>> {
+        file.parent()
+            .into_iter()
+            .flat_map(Path::ancestors)
+            .take_while(|dir| dir.starts_with(root))
+            .find_map(|dir| load_cached(tree, &find_manifest(tree, dir)?))
+    }
+
+    /// Files declaring `namespace` in this project and, transitively, the
+    /// projects it references
+    pub fn namespace_files(&self, tree: &SourceTree, namespace: &str) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = self.namespaces.get(namespace).cloned().unwrap_or_default();
+        // Referenced paths climb through `..`, so projects are told apart by
+        // their canonical directories
+        let canonical = |dir: &Path| tree.canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let mut visited: HashSet<PathBuf> = HashSet::from([canonical(&self.dir)]);
+        let mut pending = self.references.clone();
+        while let Some(manifest) = pending.pop() {
+            let Some(project) = load_cached(tree, &manifest) else {
+                continue;
+            };
+            if !visited.insert(canonical(&project.dir)) {
+                continue;
+            }
+            files.extend(
+                project
+                    .namespaces
+                    .get(namespace)
+                    .into_iter()
+                    .flatten()
+                    .cloned(),
+            );
+            pending.extend(project.references.iter().cloned());
+        }
+        // Files deleted since the project was indexed
+        files.retain(|file| tree.is_file(file));
+        files
+    }
+}
+
+/// Namespaces declared by a C# source, block-scoped (`namespace A.B { }`,
+/// nested ones included) or file-scoped (`namespace A.B;`)
+pub fn declared_namespaces(content: &str) -> Vec<String> {
+    let mut namespaces = Vec::new();
+    // Enclosing block namespaces with the brace depth of their bodies
+    let mut enclosing: Vec<(String, usize)> = Vec::new();
+    let mut depth = 0;
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("namespace ") {
+            let name: String = rest
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                .collect();
+            if !name.is_empty() {
+                let name = match enclosing.last() {
+                    Some((outer, _)) => format!("{outer}.{name}"),
+                    None => name,
+                };
+                if !rest.trim_end().ends_with(';') {
+                    enclosing.push((name.clone(), depth + 1));
+                }
+                if !namespaces.contains(&name) {
+                    namespaces.push(name);
+                }
+            }
+        }
+        if line.starts_with("//") {
+            continue;
+        }
+        depth += line.matches('{').count();
+        if line.contains('}') {
+            depth = depth.saturating_sub(line.matches('}').count());
+            // The body of a namespace opens on its line or the next, and
+            // ends when the depth drops below it
+            while enclosing.last().is_some_and(|(_, body)| depth < *body) {
+                enclosing.pop();
+            }
+        }
+    }
+    namespaces
+}
+
+/// `Include` paths of the `<ProjectReference>` elements of a `.csproj`
+fn project_references(content: &str) -> Vec<String> {
+    content
+        .split("<ProjectReference")
+        .skip(1)
+        .filter_map(|element| {
+            let element = &element[..element.find('>')?];
+            let value = element.split("Include=").nth(1)?.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+            Some(value[..value.find(quote)?].to_string())
+        })
+        .collect()
+}
+
+/// Collect the `.cs` files below `dir`, skipping build output and the
+/// directories of nested projects
+fn collect_sources(tree: &SourceTree, dir: &Path, sources: &mut Vec<PathBuf>) {
+    let Ok(mut entries) = tree.read_dir(dir) else {
+        return;
+    };
+    entries.sort();
+    for path in entries {
+        if tree.is_dir(&path) {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or("");
+            if !is_excluded_dir(name) && find_manifest(tree, &path).is_none() {
+                collect_sources(tree, &path, sources);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "cs") {
+            sources.push(path);
+        }
+    }
+}
+
+/// The `.csproj` in `dir`, if any
+fn find_manifest(tree: &SourceTree, dir: &Path) -> Option<PathBuf> {
+    tree.read_dir(dir)
+        .ok()?
+        .into_iter()
+        .find(|path| path.extension().is_some_and(|ext| ext == "csproj") && tree.is_file(path))
+}
+
+fn is_excluded_dir(name: &str) -> bool {
+    name.starts_with('.') || OUTPUT_DIRS.contains(&name)
+}
+
+/// The project of `manifest`, indexed once per modification of the manifest
+fn load_cached(tree: &SourceTree, manifest: &Path) -> Option<Arc<CSharpProject>> {
+    manifest_cache::load_cached(tree, manifest, |manifest| {
+        CSharpProject::load(tree, manifest)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_namespaces() {
+        let content = r#"
+namespace Shop.Core
+{
+    namespace Models
+    {
+        public class Order { }
+    }
+
+    public class Cart { }
+}
+
+namespace Shop.Legacy { }
+"#;
+        assert_eq!(
+            declared_namespaces(content),
+            vec!["Shop.Core", "Shop.Core.Models", "Shop.Legacy"]
+        );
+        assert_eq!(
+            declared_namespaces("using System;\n\nnamespace Shop.Api.Controllers;\n"),
+            vec!["Shop.Api.Controllers"]
+        );
+    }
+
+    #[test]
+    fn test_project_references() {
+        let content = r#"
+<Project Sdk="Microsoft.NET.Sdk">
+  <ItemGroup>
+    <ProjectReference Include="..\Shop.Core\Shop.Core.csproj" />
+    <ProjectReference
+        Include='../Shop.Data/Shop.Data.csproj'>
+      <PrivateAssets>all</PrivateAssets>
+    </ProjectReference>
+    <PackageReference Include="Newtonsoft.Json" Version="13.0.3" />
+  </ItemGroup>
+</Project>
+"#;
+        assert_eq!(
+            project_references(content),
+            vec![
+                "..\\Shop.Core\\Shop.Core.csproj",
+                "../Shop.Data/Shop.Data.csproj"
+            ]
+        );
+    }
+}
@@ -134,4 +134,51 @@ function MyComponent({ title, items }) {
             "Should find 'Button' component reference"
         );
     }
+
+    #[test]
+    fn test_javascript_dynamic_import_parsing() {
+        let analyzer = JavaScriptAnalyzer::new();
+        let content = r#"
+import React from 'react';
+const Settings = React.lazy(() => import('./pages/Settings'));
+const worker = require.resolve('./worker');
+const config = require('./config');
+
+jest.mock('../api/client');
+const actual = jest.requireActual('../api/client');
+
+async function loadReport(name) {
+    const { render } = await import("./reports/" + name);
+    return import('./reports/summary');
+}
+"#;
+        let path = PathBuf::from("App.js");
+        let context = SemanticContext::new(path.clone(), PathBuf::from("."), 3);
+
+        let result = analyzer.analyze_file(&path, content, &context).unwrap();
+
+        let dynamic: Vec<&str> = result
+            .imports
+            .iter()
+            .filter(|i| i.is_dynamic)
+            .map(|i| i.module.as_str())
+            .collect();
+        assert_eq!(
+            dynamic,
+            vec![
+                "./pages/Settings",
+                "./worker",
+                "../api/client",
+                "../api/client",
+                "./reports/summary",
+            ]
+        );
+        let static_modules: Vec<&str> = result
+            .imports
+            .iter()
+            .filter(|i| !i.is_dynamic)
+            .map(|i| i.module.as_str())
+            .collect();
+        assert_eq!(static_modules, vec!["react", "./config"]);
+    }
 }
@@ -1,23 +1,34 @@
 //! Semantic analyzer for C
 
-use crate::core::semantic::analyzer::{
-    AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult,
+use crate::core::semantic::{
+    analyzer::{AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult},
+    compile_commands,
+    path_validator::{validate_import_path, validate_module_specifier},
+    query_engine::QueryEngine,
+    resolver::{ModuleResolver, ResolvedPath},
 };
-use std::path::Path;
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
+use std::path::{Path, PathBuf};
 use tree_sitter::Parser;
 
+/// Extensions of C and C++ headers
+const HEADER_EXTENSIONS: [&str; 4] = ["h", "hh", "hpp", "hxx"];
+
+/// Extensions of C and C++ sources implementing a header
+const SOURCE_EXTENSIONS: [&str; 4] = ["c", "cc", "cpp", "cxx"];
+
 #[allow(clippy::new_without_default)]
 pub struct CAnalyzer {
-    #[allow(dead_code)]
-    parser: Parser,
+    query_engine: QueryEngine,
 }
 
 impl CAnalyzer {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // Note: tree_sitter_c::language() would be used here when the crate is added
-        // parser.set_language(tree_sitter_c::language()).unwrap();
-        Self { parser }
+        let language = tree_sitter_c::language();
+        let query_engine =
+            QueryEngine::new(language, "c").expect("Failed to create C query engine");
+        Self { query_engine }
     }
 }
 
@@ -28,12 +39,28 @@ impl LanguageAnalyzer for CAnalyzer {
 
     fn analyze_file(
         &self,
-        _path: &Path,
-        _content: &str,
-        _context: &SemanticContext,
+        path: &Path,
+        content: &str,
+        context: &SemanticContext,
     ) -> SemanticResult<AnalysisResult> {
-        // TODO: Implement C analysis
-        Ok(AnalysisResult::default())
+        let mut parser = Parser::new();
+        parser
+            .set_language(tree_sitter_c::language())
+            .map_err(|e| ContextCreatorError::ParseError(format!("Failed to set language: {e}")))?;
+
+        let mut result = self
+            .query_engine
+            .analyze_with_parser(&mut parser, content)?;
+
+        // Resolve type definitions for the type references found
+        self.query_engine.resolve_type_definitions(
+            &mut result.type_references,
+            path,
+            &context.base_dir,
+            &context.source_tree,
+        )?;
+
+        Ok(result)
     }
 
     fn can_handle_extension(&self, extension: &str) -> bool {
@@ -44,3 +71,148 @@ impl LanguageAnalyzer for CAnalyzer {
         vec!["c", "h"]
     }
 }
+
+pub struct CModuleResolver;
+
+impl ModuleResolver for CModuleResolver {
+    fn resolve_import(
+        &self,
+        module_path: &str,
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<ResolvedPath, ContextCreatorError> {
+        resolve_include(module_path, from_file, base_dir, &[], tree)
+    }
+
+    fn resolve_import_items(
+        &self,
+        module_path: &str,
+        _items: &[String],
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        let header = self.resolve_import(module_path, from_file, base_dir, tree)?;
+        Ok(with_implementations(header, base_dir, tree))
+    }
+
+    fn resolve_import_in_context(
+        &self,
+        module_path: &str,
+        _items: &[String],
+        context: &SemanticContext,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        let header = resolve_include(
+            module_path,
+            &context.current_file,
+            &context.base_dir,
+            &context.include_paths,
+            &context.source_tree,
+        )?;
+        Ok(with_implementations(
+            header,
+            &context.base_dir,
+            &context.source_tree,
+        ))
+    }
+
+    fn get_file_extensions(&self) -> Vec<&'static str> {
+        vec!["c", "h"]
+    }
+}
+
+/// Resolve `#include "header"` of `from_file` against the directory of the
+/// including file, then the include directories of its compilation database,
+/// then the configured `include_paths` relative to `base_dir`
+pub(crate) fn resolve_include(
+    module_path: &str,
+    from_file: &Path,
+    base_dir: &Path,
+    include_paths: &[PathBuf],
+    tree: &SourceTree,
+) -> Result<ResolvedPath, ContextCreatorError> {
+    // Validate module name for security; includes may climb to parent
+    // directories but must resolve inside the project
+    validate_module_specifier(module_path)?;
+
+    let search_dirs = from_file
+        .parent()
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain(compile_commands::search_dirs(tree, from_file, base_dir))
+        .chain(include_paths.iter().map(|dir| base_dir.join(dir)));
+    for dir in search_dirs {
+        let candidate = dir.join(module_path);
+        if !tree.is_file(&candidate) {
+            continue;
+        }
+        // Include directories outside the project hold library headers
+        if let Ok(validated_path) = validate_import_path(base_dir, &candidate, tree) {
+            return Ok(ResolvedPath {
+                path: validated_path,
+                is_external: false,
+                confidence: 0.9,
+            });
+        }
+    }
+
+    // Otherwise, a header of the system or of a library
+    Ok(ResolvedPath {
+        path: PathBuf::from(module_path),
+        is_external: true,
+        confidence: 0.8,
+    })
+}
+
+/// A resolved header followed by the sources implementing it: `foo.c` or
+/// `foo.cpp` next to `foo.h`, or under `src/` for headers under `include/`
+pub(crate) fn with_implementations(
+    header: ResolvedPath,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Vec<ResolvedPath> {
+    let is_header = header
+        .path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| HEADER_EXTENSIONS.contains(&ext));
+    let (Some(dir), Some(stem)) = (header.path.parent(), header.path.file_stem()) else {
+        return vec![header];
+    };
+    if header.is_external || !is_header {
+        return vec![header];
+    }
+
+    let mut source_dirs = vec![dir.to_path_buf()];
+    // include/mylib/foo.h is implemented by src/mylib/foo.c or src/foo.c
+    if let Some(include_dir) = dir.ancestors().find(|d| d.ends_with("include")) {
+        if let (Some(project), Ok(nested)) = (include_dir.parent(), dir.strip_prefix(include_dir)) {
+            source_dirs.push(project.join("src").join(nested));
+            source_dirs.push(project.join("src"));
+        }
+    }
+
+    let implementations: Vec<ResolvedPath> = source_dirs
+        .iter()
+        .flat_map(|dir| {
+            SOURCE_EXTENSIONS
+                .iter()
+                .map(move |ext| dir.join(format!("{}.{ext}", stem.to_string_lossy())))
+        })
+        .filter(|candidate| tree.is_file(candidate))
+        .filter_map(|candidate| validate_import_path(base_dir, &candidate, tree).ok())
+        .map(|path| ResolvedPath {
+            path,
+            is_external: false,
+            confidence: header.confidence,
+        })
+        .collect();
+    let mut resolved = vec![header];
+    for implementation in implementations {
+        if !resolved.iter().any(|r| r.path == implementation.path) {
+            resolved.push(implementation);
+        }
+    }
+    resolved
+}
@@ -1,46 +1,116 @@
 //! Semantic analyzer for Cpp
 
-use crate::core::semantic::analyzer::{
-    AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult,
+use crate::core::semantic::{
+    analyzer::{AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult},
+    languages::c::{resolve_include, with_implementations},
+    query_engine::QueryEngine,
+    resolver::{ModuleResolver, ResolvedPath},
 };
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
 use std::path::Path;
 use tree_sitter::Parser;
 
 #[allow(clippy::new_without_default)]
 pub struct CppAnalyzer {
-    #[allow(dead_code)]
-    parser: Parser,
+    query_engine: QueryEngine,
 }
 
 impl CppAnalyzer {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // Note: tree_sitter_cpp::language() would be used here when the crate is added
-        // parser.set_language(tree_sitter_cpp::language()).unwrap();
-        Self { parser }
+        let language = tree_sitter_cpp::language();
+        let query_engine =
+            QueryEngine::new(language, "cpp").expect("Failed to create C++ query engine");
+        Self { query_engine }
     }
 }
 
 impl LanguageAnalyzer for CppAnalyzer {
     fn language_name(&self) -> &'static str {
-        "Cpp"
+        "C++"
     }
 
     fn analyze_file(
         &self,
-        _path: &Path,
-        _content: &str,
-        _context: &SemanticContext,
+        path: &Path,
+        content: &str,
+        context: &SemanticContext,
     ) -> SemanticResult<AnalysisResult> {
-        // TODO: Implement Cpp analysis
-        Ok(AnalysisResult::default())
+        let mut parser = Parser::new();
+        parser
+            .set_language(tree_sitter_cpp::language())
+            .map_err(|e| ContextCreatorError::ParseError(format!("Failed to set language: {e}")))?;
+
+        let mut result = self
+            .query_engine
+            .analyze_with_parser(&mut parser, content)?;
+
+        // Resolve type definitions for the type references found
+        self.query_engine.resolve_type_definitions(
+            &mut result.type_references,
+            path,
+            &context.base_dir,
+            &context.source_tree,
+        )?;
+
+        Ok(result)
     }
 
     fn can_handle_extension(&self, extension: &str) -> bool {
-        matches!(extension, "cpp" | "cc" | "cxx" | "hpp" | "h")
+        matches!(extension, "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" | "h")
     }
 
     fn supported_extensions(&self) -> Vec<&'static str> {
-        vec!["cpp", "cc", "cxx", "hpp", "h"]
+        vec!["cpp", "cc", "cxx", "hpp", "hh", "hxx", "h"]
+    }
+}
+
+pub struct CppModuleResolver;
+
+impl ModuleResolver for CppModuleResolver {
+    fn resolve_import(
+        &self,
+        module_path: &str,
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<ResolvedPath, ContextCreatorError> {
+        resolve_include(module_path, from_file, base_dir, &[], tree)
+    }
+
+    fn resolve_import_items(
+        &self,
+        module_path: &str,
+        _items: &[String],
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        let header = self.resolve_import(module_path, from_file, base_dir, tree)?;
+        Ok(with_implementations(header, base_dir, tree))
+    }
+
+    fn resolve_import_in_context(
+        &self,
+        module_path: &str,
+        _items: &[String],
+        context: &SemanticContext,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        let header = resolve_include(
+            module_path,
+            &context.current_file,
+            &context.base_dir,
+            &context.include_paths,
+            &context.source_tree,
+        )?;
+        Ok(with_implementations(
+            header,
+            &context.base_dir,
+            &context.source_tree,
+        ))
+    }
+
+    fn get_file_extensions(&self) -> Vec<&'static str> {
+        vec!["cpp", "cc", "cxx", "hpp", "hh", "hxx", "h"]
     }
 }
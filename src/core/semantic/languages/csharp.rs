@@ -1,24 +1,85 @@
 //! Semantic analyzer for CSharp
+//!
+//! No C# grammar builds against the tree-sitter version used here, so the
+//! analyzer scans the file line by line without parsing it: `using`
+//! directives, type declarations and their base types, method definitions
+//! and calls.
 
-use crate::core::semantic::analyzer::{
-    AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult,
+use crate::core::semantic::{
+    analyzer::{
+        AnalysisResult, FunctionCall, FunctionDefinition, Import, LanguageAnalyzer,
+        SemanticContext, SemanticResult, TypeReference,
+    },
+    csproj::CSharpProject,
+    path_validator::{validate_import_path, validate_module_name},
+    resolver::{ModuleResolver, ResolvedPath},
 };
-use std::path::Path;
-use tree_sitter::Parser;
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
+use std::path::{Path, PathBuf};
+
+/// Modifiers of type and member declarations
+const MODIFIERS: [&str; 20] = [
+    "public",
+    "private",
+    "protected",
+    "internal",
+    "file",
+    "static",
+    "abstract",
+    "sealed",
+    "partial",
+    "readonly",
+    "virtual",
+    "override",
+    "async",
+    "extern",
+    "unsafe",
+    "new",
+    "ref",
+    "required",
+    "volatile",
+    "const",
+];
+
+/// Keywords followed by parentheses that are neither calls nor definitions
+const KEYWORDS: [&str; 28] = [
+    "if",
+    "else",
+    "while",
+    "for",
+    "foreach",
+    "in",
+    "do",
+    "switch",
+    "case",
+    "when",
+    "catch",
+    "using",
+    "lock",
+    "fixed",
+    "return",
+    "throw",
+    "yield",
+    "await",
+    "is",
+    "as",
+    "nameof",
+    "typeof",
+    "sizeof",
+    "default",
+    "checked",
+    "unchecked",
+    "base",
+    "this",
+];
 
 #[allow(clippy::new_without_default)]
-pub struct CSharpAnalyzer {
-    #[allow(dead_code)]
-    #[allow(dead_code)]
-    parser: Parser,
-}
+pub struct CSharpAnalyzer;
 
 impl CSharpAnalyzer {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // Note: tree_sitter_c_sharp::language() would be used here when the crate is added
-        // parser.set_language(tree_sitter_c_sharp::language()).unwrap();
-        Self { parser }
+        Self
     }
 }
 
@@ -30,11 +91,89 @@ impl LanguageAnalyzer for CSharpAnalyzer {
     fn analyze_file(
         &self,
         _path: &Path,
-        _content: &str,
+        content: &str,
         _context: &SemanticContext,
     ) -> SemanticResult<AnalysisResult> {
-        // TODO: Implement CSharp analysis
-        Ok(AnalysisResult::default())
+        let mut result = AnalysisResult::default();
+        // Brace depth, and that of the body of the interface being read,
+        // whose members are public without a modifier
+        let mut depth = 0;
+        let mut interface_depth: Option<usize> = None;
+
+        let lines = code_lines(content);
+        let mut index = 0;
+        while index < lines.len() {
+            let line_number = index + 1;
+            let code = without_attributes(&lines[index]);
+            index += 1;
+
+            if let Some(module) = using_directive(code) {
+                result.imports.push(Import {
+                    module,
+                    items: Vec::new(),
+                    is_relative: false,
+                    is_dynamic: false,
+                    line: line_number,
+                });
+            } else if type_declaration(code) {
+                let mut declaration = code.to_string();
+                // Base lists may continue on the following lines up to the body
+                while !declaration.contains(['{', ';']) && index < lines.len() {
+                    declaration.push(' ');
+                    declaration.push_str(lines[index].trim());
+                    index += 1;
+                }
+                let (keyword, name, bases) = declared_type(&declaration);
+                let is_public = declaration.split_whitespace().any(|word| word == "public");
+                if keyword == "interface" {
+                    interface_depth = Some(depth + 1);
+                }
+                result.exported_functions.push(FunctionDefinition {
+                    name: name.to_string(),
+                    is_exported: is_public,
+                    line: line_number,
+                });
+                for base in bases {
+                    result
+                        .type_references
+                        .push(type_reference(base, line_number));
+                }
+                depth += declaration.matches('{').count();
+                depth = depth.saturating_sub(declaration.matches('}').count());
+                continue;
+            } else {
+                let mut calls = code;
+                if let Some((name, is_public, parameters)) = method_definition(code) {
+                    result.exported_functions.push(FunctionDefinition {
+                        name: name.to_string(),
+                        is_exported: is_public || interface_depth == Some(depth),
+                        line: line_number,
+                    });
+                    calls = parameters;
+                }
+                for (name, receiver, is_creation) in called_names(calls) {
+                    if is_creation {
+                        result
+                            .type_references
+                            .push(type_reference(name, line_number));
+                    } else {
+                        result.function_calls.push(FunctionCall {
+                            name: name.to_string(),
+                            module: receiver.map(str::to_string),
+                            line: line_number,
+                        });
+                    }
+                }
+            }
+
+            depth += code.matches('{').count();
+            depth = depth.saturating_sub(code.matches('}').count());
+            if interface_depth.is_some_and(|interface| depth < interface) {
+                interface_depth = None;
+            }
+        }
+
+        Ok(result)
     }
 
     fn can_handle_extension(&self, extension: &str) -> bool {
@@ -45,3 +184,513 @@ impl LanguageAnalyzer for CSharpAnalyzer {
         vec!["cs"]
     }
 }
+
+/// The code of each line of `content`, without comments and with the
+/// contents of string and character literals removed
+fn code_lines(content: &str) -> Vec<String> {
+    let mut in_comment = false;
+    content
+        .lines()
+        .map(|line| {
+            let mut code = String::with_capacity(line.len());
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_comment {
+                    if c == '*' && chars.peek() == Some(&'/') {
+                        chars.next();
+                        in_comment = false;
+                    }
+                    continue;
+                }
+                match c {
+                    '/' if chars.peek() == Some(&'/') => break,
+                    '/' if chars.peek() == Some(&'*') => {
+                        chars.next();
+                        in_comment = true;
+                        code.push(' ');
+                    }
+                    '"' | '\'' => {
+                        let verbatim = code.ends_with('@') || code.ends_with("@$");
+                        while let Some(next) = chars.next() {
+                            if next == '\\' && !verbatim {
+                                chars.next();
+                            } else if next == c {
+                                break;
+                            }
+                        }
+                        code.push(c);
+                        code.push(c);
+                    }
+                    _ => code.push(c),
+                }
+            }
+            code
+        })
+        .collect()
+}
+
+/// `code` without the attributes preceding it: `[Fact] public void Runs()`
+fn without_attributes(code: &str) -> &str {
+    let mut code = code.trim();
+    while code.starts_with('[') {
+        match closing(code, '[', ']') {
+            Some(end) => code = code[end + 1..].trim_start(),
+            None => return "",
+        }
+    }
+    code
+}
+
+/// The position of the bracket closing the one `code` starts with
+fn closing(code: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    for (position, c) in code.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(position);
+            }
+        }
+    }
+    None
+}
+
+/// The namespace or type named by a `using` directive: `using A.B;`,
+/// `global using A.B;`, `using static A.B.C;` or `using Alias = A.B.C;`
+///
+/// `using` statements and declarations (`using (var x = ...)`,
+/// `using var x = ...;`) are not directives.
+fn using_directive(line: &str) -> Option<String> {
+    let line = line.trim();
+    let line = line.strip_prefix("global ").unwrap_or(line);
+    let directive = line.strip_prefix("using ")?.strip_suffix(';')?.trim();
+    let directive = directive.strip_prefix("static ").unwrap_or(directive);
+    let target = match directive.split_once('=') {
+        Some((alias, target)) if is_identifier(alias.trim()) => target.trim(),
+        Some(_) => return None,
+        None => directive,
+    };
+    let target = target.strip_prefix("global::").unwrap_or(target);
+    target
+        .split('.')
+        .all(is_identifier)
+        .then(|| target.to_string())
+}
+
+/// Whether `code` declares a class, interface, struct, record or enum
+fn type_declaration(code: &str) -> bool {
+    let (keyword, name, _) = declared_type(code);
+    matches!(
+        keyword,
+        "class" | "interface" | "struct" | "record" | "enum"
+    ) && is_identifier(name)
+}
+
+/// The keyword, name and base types of a type declaration:
+/// `public sealed class UserService : ServiceBase<User>, IUserService`
+fn declared_type(declaration: &str) -> (&str, &str, Vec<&str>) {
+    let mut rest = declaration.trim_start();
+    let mut keyword = "";
+    while let Some((word, tail)) = rest.split_once(char::is_whitespace) {
+        rest = tail.trim_start();
+        if !MODIFIERS.contains(&word) {
+            keyword = word;
+            break;
+        }
+    }
+    if keyword == "record" {
+        for kind in ["class ", "struct "] {
+            rest = rest.strip_prefix(kind).unwrap_or(rest).trim_start();
+        }
+    }
+    let name_end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    let (name, rest) = rest.split_at(name_end);
+
+    // The base list follows the type parameters and primary constructor,
+    // up to the constraints or the body
+    let mut depth = 0;
+    let mut bases_start = None;
+    for (position, c) in rest.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ':' if depth == 0 => {
+                bases_start = Some(position + 1);
+                break;
+            }
+            '{' | ';' if depth == 0 => break,
+            _ => {}
+        }
+    }
+    let Some(bases_start) = bases_start else {
+        return (keyword, name, Vec::new());
+    };
+    let bases = &rest[bases_start..];
+    let bases = bases.split(['{', ';']).next().unwrap_or(bases);
+    let bases = bases.split(" where ").next().unwrap_or(bases);
+    (
+        keyword,
+        name,
+        top_level(bases, ',').filter_map(base_type).collect(),
+    )
+}
+
+/// The name of a base type without its type arguments or constructor
+/// arguments: `Repository<User>` or `Person(Name)`
+fn base_type(base: &str) -> Option<&str> {
+    let base = base.split(['<', '(']).next().unwrap_or(base).trim();
+    let base = base.strip_prefix("global::").unwrap_or(base);
+    base.split('.').all(is_identifier).then_some(base)
+}
+
+/// The parts of `code` separated by `separator` outside of brackets
+fn top_level(code: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut parts = Vec::new();
+    for (position, c) in code.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            _ if c == separator && depth == 0 => {
+                parts.push(&code[start..position]);
+                start = position + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&code[start..]);
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+}
+
+/// The name of the method or constructor `code` defines, whether it is
+/// `public`, and the rest of the line from its parameter list:
+/// `public async Task<User> FindAsync<T>(int id)`
+fn method_definition(code: &str) -> Option<(&str, bool, &str)> {
+    let parameters = code.find('(')?;
+    let head = &code[..parameters];
+    if head.contains('=') {
+        return None;
+    }
+    let words: Vec<&str> = top_level(head, ' ').collect();
+    let (name, prefix) = words.split_last()?;
+    let name = name.split('<').next().unwrap_or(name);
+    // Explicit interface implementations qualify the name: `void IDisposable.Dispose()`
+    let name = name.rsplit('.').next().unwrap_or(name);
+    if !is_identifier(name) || KEYWORDS.contains(&name) {
+        return None;
+    }
+    let mut is_public = false;
+    let mut is_member = false;
+    let mut return_types = 0;
+    for word in prefix {
+        if MODIFIERS.contains(word) {
+            is_public |= *word == "public";
+            is_member |= matches!(
+                *word,
+                "public" | "private" | "protected" | "internal" | "static"
+            );
+        } else if KEYWORDS.contains(word) || !is_type(word) {
+            return None;
+        } else {
+            return_types += 1;
+        }
+    }
+    // Constructors have no return type
+    let is_definition = return_types == 1 || (return_types == 0 && is_member);
+    is_definition.then_some((name, is_public, &code[parameters..]))
+}
+
+/// Whether `word` names a type: `int`, `Task<User>`, `string?` or `byte[]`
+fn is_type(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && word.chars().all(|c| {
+            c.is_alphanumeric() || matches!(c, '_' | '.' | '<' | '>' | ',' | '?' | '[' | ']' | ' ')
+        })
+}
+
+/// The names called in `code` with their receivers when these are types or
+/// namespaces (`Guard.NotNull(user)`), and whether the call creates an object
+/// (`new UserService(repository)`)
+fn called_names(code: &str) -> Vec<(&str, Option<&str>, bool)> {
+    let mut calls = Vec::new();
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut position = 0;
+    while let Some(offset) = code[position..].find(|c: char| c.is_alphabetic() || c == '_') {
+        let start = position + offset;
+        let end = code[start..]
+            .find(|c: char| !is_name_char(c))
+            .map_or(code.len(), |length| start + length);
+        position = end;
+        if code[..start].ends_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+
+        // Generic calls pass their type arguments first: `GetService<IClock>()`
+        let mut after = &code[end..];
+        if after.starts_with('<') {
+            match closing(after, '<', '>') {
+                Some(close) => after = &after[close + 1..],
+                None => continue,
+            }
+        }
+        let name = &code[start..end];
+        if !after.starts_with('(') || KEYWORDS.contains(&name) || MODIFIERS.contains(&name) {
+            continue;
+        }
+
+        let mut name_start = start;
+        let mut receiver = None;
+        if let Some(receiver_end) = code[..start].strip_suffix('.') {
+            let receiver_start = receiver_end
+                .rfind(|c: char| !(is_name_char(c) || c == '.'))
+                .map_or(0, |position| position + 1);
+            if receiver_start < receiver_end.len() {
+                receiver = Some(&receiver_end[receiver_start..]);
+                name_start = receiver_start;
+            }
+        }
+        let before = code[..name_start].trim_end();
+        if before.ends_with("new") && !before[..before.len() - 3].ends_with(is_name_char) {
+            calls.push((&code[name_start..end], None, true));
+        } else {
+            let receiver = receiver.filter(|receiver| receiver.starts_with(char::is_uppercase));
+            calls.push((name, receiver, false));
+        }
+    }
+    calls
+}
+
+/// A reference to the type `name`, qualified or not
+fn type_reference(name: &str, line: usize) -> TypeReference {
+    let (module, name) = match name.rsplit_once('.') {
+        Some((module, name)) => (Some(module.to_string()), name),
+        None => (None, name),
+    };
+    TypeReference {
+        name: name.to_string(),
+        module,
+        line,
+        definition_path: None,
+        is_external: false,
+        external_package: None,
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+}
+
+pub struct CSharpModuleResolver;
+
+impl CSharpModuleResolver {
+    /// Files of the project of `from_file` and of the projects it references
+    /// declaring the namespace `module_path`; a type (`using static A.B.C`)
+    /// resolves to the file named after it in its namespace
+    fn namespace_files(
+        module_path: &str,
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Vec<PathBuf> {
+        let Some(project) = CSharpProject::find(tree, from_file, base_dir) else {
+            return Vec::new();
+        };
+        let mut files = project.namespace_files(tree, module_path);
+        if files.is_empty() {
+            if let Some((namespace, type_name)) = module_path.rsplit_once('.') {
+                files = project.namespace_files(tree, namespace);
+                files.retain(|file| file.file_stem().is_some_and(|stem| stem == type_name));
+            }
+        }
+        files.retain(|file| file != from_file);
+        files
+    }
+}
+
+impl ModuleResolver for CSharpModuleResolver {
+    fn resolve_import(
+        &self,
+        module_path: &str,
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<ResolvedPath, ContextCreatorError> {
+        self.resolve_import_items(module_path, &[], from_file, base_dir, tree)
+            .map(|mut resolved| resolved.remove(0))
+    }
+
+    fn resolve_import_items(
+        &self,
+        module_path: &str,
+        _items: &[String],
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        // Validate module name for security
+        validate_module_name(module_path)?;
+
+        let resolved: Vec<ResolvedPath> =
+            Self::namespace_files(module_path, from_file, base_dir, tree)
+                .iter()
+                .filter_map(|file| validate_import_path(base_dir, file, tree).ok())
+                .map(|path| ResolvedPath {
+                    path,
+                    is_external: false,
+                    confidence: 0.9,
+                })
+                .collect();
+        if !resolved.is_empty() {
+            return Ok(resolved);
+        }
+
+        // Otherwise, a namespace of the framework or of a NuGet package
+        Ok(vec![ResolvedPath {
+            path: PathBuf::from(module_path),
+            is_external: true,
+            confidence: if self.is_external_module(module_path) {
+                1.0
+            } else {
+                0.5
+            },
+        }])
+    }
+
+    fn get_file_extensions(&self) -> Vec<&'static str> {
+        vec!["cs"]
+    }
+
+    fn is_external_module(&self, module_path: &str) -> bool {
+        let root = module_path.split('.').next().unwrap_or(module_path);
+        matches!(root, "System" | "Microsoft" | "Windows")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_using_directives() {
+        let directives = [
+            ("using System.Linq;", Some("System.Linq")),
+            ("global using Shop.Core;", Some("Shop.Core")),
+            ("using static Shop.Core.Guard;", Some("Shop.Core.Guard")),
+            ("using Json = Newtonsoft.Json;", Some("Newtonsoft.Json")),
+            ("using var stream = File.OpenRead(path);", None),
+            ("using (var scope = provider.CreateScope())", None),
+            ("// using Shop.Legacy;", None),
+        ];
+        for (line, expected) in directives {
+            assert_eq!(using_directive(line).as_deref(), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_csharp_analysis() {
+        let content = r#"using Shop.Core;
+using static Shop.Core.Guard;
+
+namespace Shop.Services;
+
+/// <summary>Finds users (cached)</summary>
+public interface IUserService
+{
+    Task<User?> FindAsync(int id);
+}
+
+[Service("users")]
+public sealed class UserService : ServiceBase<User>, IUserService, IDisposable
+{
+    private readonly Repository<User> _repository;
+
+    public UserService(Repository<User> repository) : base(repository)
+    {
+        NotNull(repository);
+        _repository = repository;
+    }
+
+    public async Task<User?> FindAsync(int id)
+    {
+        var user = await _repository.GetAsync<User>(id);
+        Console.WriteLine($"Found {id}"); // Log("ignored")
+        return user ?? new Shop.Models.Guest(id);
+    }
+
+    void IDisposable.Dispose() => _repository.Dispose();
+}
+
+internal record AuditEntry(string Action)
+    : Entry(Action), Shop.Core.IAuditable where T : class;
+"#;
+        let path = PathBuf::from("Services/UserService.cs");
+        let context = SemanticContext::new(path.clone(), PathBuf::from("."), 3);
+
+        let result = CSharpAnalyzer::new()
+            .analyze_file(&path, content, &context)
+            .unwrap();
+
+        let imports: Vec<&str> = result.imports.iter().map(|i| i.module.as_str()).collect();
+        assert_eq!(imports, vec!["Shop.Core", "Shop.Core.Guard"]);
+
+        let definitions: Vec<(&str, bool, usize)> = result
+            .exported_functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.is_exported, f.line))
+            .collect();
+        assert_eq!(
+            definitions,
+            vec![
+                ("IUserService", true, 7),
+                ("FindAsync", true, 9),
+                ("UserService", true, 13),
+                ("UserService", true, 17),
+                ("FindAsync", true, 23),
+                ("Dispose", false, 30),
+                ("AuditEntry", false, 33),
+            ]
+        );
+
+        let types: Vec<(&str, Option<&str>, usize)> = result
+            .type_references
+            .iter()
+            .map(|t| (t.name.as_str(), t.module.as_deref(), t.line))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("ServiceBase", None, 13),
+                ("IUserService", None, 13),
+                ("IDisposable", None, 13),
+                ("Guest", Some("Shop.Models"), 27),
+                ("Entry", None, 33),
+                ("IAuditable", Some("Shop.Core"), 33),
+            ]
+        );
+
+        let calls: Vec<(&str, Option<&str>, usize)> = result
+            .function_calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.module.as_deref(), c.line))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("NotNull", None, 19),
+                ("GetAsync", None, 25),
+                ("WriteLine", Some("Console"), 26),
+                ("Dispose", None, 30),
+            ]
+        );
+    }
+}
@@ -2,10 +2,13 @@
 
 use crate::core::semantic::{
     analyzer::{AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult},
-    path_validator::{validate_import_path, validate_module_name},
+    barrel, package_json,
+    path_validator::{validate_import_path, validate_module_specifier},
     query_engine::QueryEngine,
     resolver::{ModuleResolver, ResolvedPath},
+    tsconfig,
 };
+use crate::core::source_tree::SourceTree;
 use crate::utils::error::ContextCreatorError;
 use std::path::Path;
 use tree_sitter::Parser;
@@ -44,11 +47,22 @@ impl LanguageAnalyzer for JavaScriptAnalyzer {
             .query_engine
             .analyze_with_parser(&mut parser, content)?;
 
+        // Types imported from dependencies are external
+        package_json::mark_external_types(
+            &context.source_tree,
+            &result.imports,
+            &mut result.type_references,
+            path,
+            &context.base_dir,
+            &JavaScriptModuleResolver,
+        );
+
         // Resolve type definitions for the type references found
         self.query_engine.resolve_type_definitions(
             &mut result.type_references,
             path,
             &context.base_dir,
+            &context.source_tree,
         )?;
 
         Ok(result)
@@ -71,59 +85,73 @@ impl ModuleResolver for JavaScriptModuleResolver {
         module_path: &str,
         from_file: &Path,
         base_dir: &Path,
+        tree: &SourceTree,
     ) -> Result<ResolvedPath, ContextCreatorError> {
-        // Validate module name for security
-        validate_module_name(module_path)?;
-
-        // Handle Node.js built-in modules
-        if self.is_external_module(module_path) {
-            return Ok(ResolvedPath {
-                path: base_dir.join("package.json"), // Point to package.json as indicator
-                is_external: true,
-                confidence: 1.0,
-            });
-        }
+        // Validate module name for security; relative specifiers may climb
+        // to parent directories but must resolve inside the project
+        validate_module_specifier(module_path)?;
 
         // Handle relative imports (./, ../)
         if module_path.starts_with('.') {
             if let Some(parent) = from_file.parent() {
-                // Properly resolve relative paths by removing leading "./"
-                let clean_path = if let Some(stripped) = module_path.strip_prefix("./") {
-                    stripped // Remove "./" prefix
-                } else {
-                    module_path // Keep as-is for "../" or other relative paths
-                };
-                let resolved_path = parent.join(clean_path);
-
-                // Try different extensions
-                for ext in &["js", "jsx", "ts", "tsx"] {
-                    let with_ext = resolved_path.with_extension(ext);
-                    if with_ext.exists() {
-                        let validated_path = validate_import_path(base_dir, &with_ext)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.9,
-                        });
-                    }
+                let resolved_path = parent.join(module_path);
+                if let Some(path) =
+                    tsconfig::find_module_file(tree, &resolved_path, &["js", "jsx", "ts", "tsx"])
+                {
+                    let validated_path = validate_import_path(base_dir, &path, tree)?;
+                    return Ok(ResolvedPath {
+                        path: validated_path,
+                        is_external: false,
+                        confidence: 0.9,
+                    });
                 }
+            }
+        }
 
-                // Try as directory with index file
-                for ext in &["js", "jsx", "ts", "tsx"] {
-                    let index_path = resolved_path.join(format!("index.{ext}"));
-                    if index_path.exists() {
-                        let validated_path = validate_import_path(base_dir, &index_path)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.9,
-                        });
-                    }
-                }
+        // Handle aliases from tsconfig.json or jsconfig.json `paths` and `baseUrl`
+        if let Some(path) = tsconfig::resolve_alias(
+            tree,
+            module_path,
+            from_file,
+            base_dir,
+            &["js", "jsx", "ts", "tsx"],
+        ) {
+            if let Ok(validated_path) = validate_import_path(base_dir, &path, tree) {
+                return Ok(ResolvedPath {
+                    path: validated_path,
+                    is_external: false,
+                    confidence: 0.9,
+                });
+            }
+        }
+
+        // Handle packages of this repository linked by workspaces
+        if let Some(path) = package_json::resolve_workspace_package(
+            tree,
+            module_path,
+            from_file,
+            base_dir,
+            &["js", "jsx", "ts", "tsx"],
+        ) {
+            if let Ok(validated_path) = validate_import_path(base_dir, &path, tree) {
+                return Ok(ResolvedPath {
+                    path: validated_path,
+                    is_external: false,
+                    confidence: 0.9,
+                });
             }
         }
 
-        // Handle absolute imports from node_modules or project root
+        // Handle dependencies declared in package.json or installed in node_modules
+        if package_json::external_package(tree, module_path, from_file, base_dir).is_some() {
+            return Ok(ResolvedPath {
+                path: base_dir.join("package.json"), // Point to package.json as indicator
+                is_external: true,
+                confidence: 1.0,
+            });
+        }
+
+        // Handle absolute imports from the project root
         let search_paths = vec![
             base_dir.to_path_buf(),
             from_file.parent().unwrap_or(base_dir).to_path_buf(),
@@ -133,8 +161,8 @@ impl ModuleResolver for JavaScriptModuleResolver {
             // Try as a file
             for ext in &["js", "jsx", "ts", "tsx"] {
                 let file_path = search_path.join(format!("{module_path}.{ext}"));
-                if file_path.exists() {
-                    let validated_path = validate_import_path(base_dir, &file_path)?;
+                if tree.exists(&file_path) {
+                    let validated_path = validate_import_path(base_dir, &file_path, tree)?;
                     return Ok(ResolvedPath {
                         path: validated_path,
                         is_external: false,
@@ -146,8 +174,8 @@ impl ModuleResolver for JavaScriptModuleResolver {
             // Try as a directory with index file
             for ext in &["js", "jsx", "ts", "tsx"] {
                 let index_path = search_path.join(module_path).join(format!("index.{ext}"));
-                if index_path.exists() {
-                    let validated_path = validate_import_path(base_dir, &index_path)?;
+                if tree.exists(&index_path) {
+                    let validated_path = validate_import_path(base_dir, &index_path, tree)?;
                     return Ok(ResolvedPath {
                         path: validated_path,
                         is_external: false,
@@ -157,6 +185,15 @@ impl ModuleResolver for JavaScriptModuleResolver {
             }
         }
 
+        // Handle Node.js built-in modules not shadowed by a local module
+        if self.is_external_module(module_path) {
+            return Ok(ResolvedPath {
+                path: base_dir.join("package.json"), // Point to package.json as indicator
+                is_external: true,
+                confidence: 1.0,
+            });
+        }
+
         // Otherwise, assume it's an external package
         Ok(ResolvedPath {
             path: base_dir.join("package.json"),
@@ -165,89 +202,22 @@ impl ModuleResolver for JavaScriptModuleResolver {
         })
     }
 
+    fn resolve_import_items(
+        &self,
+        module_path: &str,
+        items: &[String],
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        barrel::resolve_items(self, module_path, items, from_file, base_dir, tree)
+    }
+
     fn get_file_extensions(&self) -> Vec<&'static str> {
         vec!["js", "jsx"]
     }
 
     fn is_external_module(&self, module_path: &str) -> bool {
-        // Node.js built-in modules
-        let builtin_modules = [
-            "assert",
-            "buffer",
-            "child_process",
-            "cluster",
-            "crypto",
-            "dgram",
-            "dns",
-            "domain",
-            "events",
-            "fs",
-            "http",
-            "https",
-            "net",
-            "os",
-            "path",
-            "punycode",
-            "querystring",
-            "readline",
-            "repl",
-            "stream",
-            "string_decoder",
-            "tls",
-            "tty",
-            "url",
-            "util",
-            "v8",
-            "vm",
-            "zlib",
-            "process",
-            "console",
-            "timers",
-            "module",
-        ];
-
-        // Common npm packages
-        let common_packages = [
-            "react",
-            "react-dom",
-            "vue",
-            "angular",
-            "lodash",
-            "express",
-            "next",
-            "webpack",
-            "babel",
-            "eslint",
-            "typescript",
-            "jest",
-            "mocha",
-            "chai",
-            "sinon",
-            "axios",
-            "moment",
-            "dayjs",
-            "socket.io",
-            "cors",
-            "helmet",
-            "bcrypt",
-            "jsonwebtoken",
-            "passport",
-            "multer",
-            "nodemailer",
-            "mongoose",
-            "sequelize",
-            "prisma",
-            "graphql",
-            "apollo",
-            "redux",
-            "mobx",
-            "zustand",
-            "styled-components",
-            "emotion",
-            "tailwindcss",
-        ];
-
-        let first_part = module_path.split('/').next().unwrap_or("");
-        builtin_modules.contains(&first_part) || common_packages.contains(&first_part)
+        package_json::is_node_builtin(module_path)
     }
 }
@@ -1,40 +1,91 @@
-//! Semantic analyzer for Php
+//! Semantic analyzer for PHP
+//!
+//! No PHP grammar builds against the tree-sitter version used here, so the
+//! analyzer scans the file line by line without parsing it: `use`
+//! declarations, `require`/`include` expressions, class, interface, trait
+//! and function declarations with their parents and type-hinted
+//! parameters, and calls.
 
-use crate::core::semantic::analyzer::{
-    AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult,
+use crate::core::semantic::{
+    analyzer::{
+        AnalysisResult, FunctionCall, FunctionDefinition, Import, LanguageAnalyzer,
+        SemanticContext, SemanticResult, TypeReference,
+    },
+    composer_json::ComposerAutoload,
+    path_validator::{validate_import_path, validate_module_name, validate_module_specifier},
+    resolver::{ModuleResolver, ResolvedPath},
 };
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
+use std::collections::HashMap;
 use std::path::Path;
-use tree_sitter::Parser;
+
+/// Expressions loading another file
+const INCLUDE_KEYWORDS: [&str; 4] = ["require_once", "include_once", "require", "include"];
+
+/// Keywords and language constructs followed by parentheses, which are not
+/// function calls
+const CALL_KEYWORDS: [&str; 26] = [
+    "if", "elseif", "while", "for", "foreach", "switch", "match", "catch", "declare", "function",
+    "fn", "use", "array", "list", "isset", "empty", "unset", "eval", "exit", "die", "return",
+    "echo", "print", "and", "or", "static",
+];
+
+/// Types of parameters that are not classes
+const BUILTIN_TYPES: [&str; 17] = [
+    "int", "float", "string", "bool", "array", "callable", "iterable", "object", "mixed", "void",
+    "null", "never", "false", "true", "self", "static", "parent",
+];
 
 #[allow(clippy::new_without_default)]
-pub struct PhpAnalyzer {
-    #[allow(dead_code)]
-    #[allow(dead_code)]
-    parser: Parser,
-}
+pub struct PhpAnalyzer;
 
 impl PhpAnalyzer {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // Note: tree_sitter_php::language() would be used here when the crate is added
-        // parser.set_language(tree_sitter_php::language()).unwrap();
-        Self { parser }
+        Self
     }
 }
 
 impl LanguageAnalyzer for PhpAnalyzer {
     fn language_name(&self) -> &'static str {
-        "Php"
+        "PHP"
     }
 
     fn analyze_file(
         &self,
         _path: &Path,
-        _content: &str,
+        content: &str,
         _context: &SemanticContext,
     ) -> SemanticResult<AnalysisResult> {
-        // TODO: Implement Php analysis
-        Ok(AnalysisResult::default())
+        let symbols = symbols(content);
+        let mut imports: Vec<Import> = Vec::new();
+        let mut add = |module: String, is_relative: bool, line: usize| {
+            if !imports.iter().any(|import| import.module == module) {
+                imports.push(Import {
+                    module,
+                    items: Vec::new(),
+                    is_relative,
+                    is_dynamic: false,
+                    line,
+                });
+            }
+        };
+        for (class, line) in symbols.classes {
+            add(class, false, line);
+        }
+        for (index, line) in content.lines().enumerate() {
+            if let Some(path) = included_path(line) {
+                add(path, true, index + 1);
+            }
+        }
+
+        Ok(AnalysisResult {
+            imports,
+            function_calls: symbols.calls,
+            type_references: symbols.types,
+            exported_functions: symbols.definitions,
+            errors: Vec::new(),
+        })
     }
 
     fn can_handle_extension(&self, extension: &str) -> bool {
@@ -45,3 +96,667 @@ impl LanguageAnalyzer for PhpAnalyzer {
         vec!["php"]
     }
 }
+
+/// What a file declares and the names it uses
+#[derive(Debug, Default)]
+struct Symbols {
+    /// Fully qualified names of the classes, interfaces and traits the file
+    /// imports with `use`, extends, implements or uses as traits
+    classes: Vec<(String, usize)>,
+    /// Functions, methods, classes, interfaces, traits and enums declared
+    definitions: Vec<FunctionDefinition>,
+    /// Functions, methods and static methods called
+    calls: Vec<FunctionCall>,
+    /// Parents, type-hinted parameters and instantiated classes
+    types: Vec<TypeReference>,
+}
+
+impl Symbols {
+    /// Records the calls and instantiations in `code`: `helper(`,
+    /// `$this->save(`, `Cache::remember(` and `new Invoice(`
+    fn add_calls(
+        &mut self,
+        code: &str,
+        line: usize,
+        namespace: &str,
+        aliases: &HashMap<String, String>,
+    ) {
+        let is_name_char = |c: char| c.is_alphanumeric() || c == '_' || c == '\\';
+        let mut position = 0;
+        while let Some(offset) = code[position..].find(|c: char| c.is_alphabetic() || c == '_') {
+            let mut start = position + offset;
+            let end = code[start..]
+                .find(|c: char| !is_name_char(c))
+                .map_or(code.len(), |length| start + length);
+            position = end;
+            if code[..start].ends_with('\\') {
+                start -= 1;
+            }
+            let before = &code[..start];
+            let name = &code[start..end];
+            if !code[end..].starts_with('(')
+                || before.ends_with(|c: char| c == '$' || c.is_ascii_digit())
+                || class_name(name).is_none()
+            {
+                continue;
+            }
+
+            if before.ends_with("->") {
+                self.calls.push(FunctionCall {
+                    name: name.to_string(),
+                    module: None,
+                    line,
+                });
+            } else if let Some(receiver_end) = before.strip_suffix("::") {
+                let receiver_start = receiver_end
+                    .rfind(|c: char| !(is_name_char(c) || c == '$'))
+                    .map_or(0, |position| position + 1);
+                let receiver = &receiver_end[receiver_start..];
+                let module = (!receiver.starts_with('$') && !is_relative_class(receiver))
+                    .then(|| qualify(receiver, namespace, aliases));
+                self.calls.push(FunctionCall {
+                    name: name.to_string(),
+                    module,
+                    line,
+                });
+            } else if before
+                .trim_end()
+                .strip_suffix("new")
+                .is_some_and(|before| !before.ends_with(is_name_char))
+            {
+                if !is_relative_class(name) && name != "class" {
+                    let class = qualify(name, namespace, aliases);
+                    self.types.push(type_reference(&class, line));
+                }
+            } else if !CALL_KEYWORDS.contains(&name.to_ascii_lowercase().as_str()) {
+                let (module, name) = match name.trim_start_matches('\\').rsplit_once('\\') {
+                    Some((module, name)) => (Some(module.to_string()), name),
+                    None => (None, name.trim_start_matches('\\')),
+                };
+                self.calls.push(FunctionCall {
+                    name: name.to_string(),
+                    module,
+                    line,
+                });
+            }
+        }
+    }
+}
+
+/// The classes, functions and calls of a file, read line by line
+fn symbols(content: &str) -> Symbols {
+    let mut symbols = Symbols::default();
+    let mut namespace = String::new();
+    // Imported names by alias, for qualifying the names used in the file
+    let mut aliases: HashMap<String, String> = HashMap::new();
+    // Brace depth of the namespace body, inside which `use` imports names
+    let mut namespace_depth = 0;
+    let mut depth = 0;
+
+    let lines = code_lines(content);
+    let mut index = 0;
+    while index < lines.len() {
+        let mut statement = lines[index].trim().to_string();
+        let line_number = index + 1;
+        index += 1;
+        if statement.starts_with("#[") {
+            continue;
+        }
+
+        if let Some(rest) = statement.strip_prefix("namespace ") {
+            namespace = rest
+                .trim_end_matches(['{', ';', ' '])
+                .trim_start_matches('\\')
+                .to_string();
+            namespace_depth = depth + usize::from(rest.contains('{'));
+        } else if statement.starts_with("use ") {
+            // Group declarations may span lines up to their `;`
+            while !statement.contains(';') && index < lines.len() {
+                statement.push(' ');
+                statement.push_str(lines[index].trim());
+                index += 1;
+            }
+            let body = statement["use ".len()..].split(';').next().unwrap_or("");
+            if depth > namespace_depth {
+                // Traits used by a class, relative to the namespace
+                let traits = body.split('{').next().unwrap_or("");
+                for name in traits.split(',').filter_map(class_name) {
+                    let class = qualify(name, &namespace, &aliases);
+                    symbols.classes.push((class, line_number));
+                }
+            } else {
+                for (name, alias) in imported_names(body) {
+                    aliases.insert(alias, name.clone());
+                    symbols.classes.push((name, line_number));
+                }
+            }
+        } else if let Some((name, parents)) = declared_class(&statement) {
+            symbols.definitions.push(FunctionDefinition {
+                name: name.to_string(),
+                is_exported: true,
+                line: line_number,
+            });
+            for name in parents {
+                let class = qualify(name, &namespace, &aliases);
+                symbols.types.push(type_reference(&class, line_number));
+                symbols.classes.push((class, line_number));
+            }
+        } else if let Some((name, is_exported)) = declared_function(&statement) {
+            symbols.definitions.push(FunctionDefinition {
+                name: name.to_string(),
+                is_exported,
+                line: line_number,
+            });
+            // Parameter lists may span lines up to their closing parenthesis
+            let mut closing = parameters_end(&statement);
+            while closing.is_none() && index < lines.len() {
+                statement.push(' ');
+                statement.push_str(lines[index].trim());
+                index += 1;
+                closing = parameters_end(&statement);
+            }
+            let parameters_start = statement.find('(').unwrap_or(0);
+            let parameters_end = closing.unwrap_or(statement.len());
+            for hint in parameter_types(&statement[parameters_start..parameters_end]) {
+                let class = qualify(hint, &namespace, &aliases);
+                symbols.types.push(type_reference(&class, line_number));
+            }
+            let body = &statement[parameters_end..];
+            symbols.add_calls(body, line_number, &namespace, &aliases);
+        } else {
+            symbols.add_calls(&statement, line_number, &namespace, &aliases);
+        }
+
+        depth += statement.matches('{').count();
+        depth = depth.saturating_sub(statement.matches('}').count());
+    }
+
+    symbols.classes.dedup();
+    symbols
+}
+
+/// The code of each line of `content`, without comments and with the
+/// contents of string literals removed
+fn code_lines(content: &str) -> Vec<String> {
+    let mut in_comment = false;
+    content
+        .lines()
+        .map(|line| {
+            let mut code = String::with_capacity(line.len());
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_comment {
+                    if c == '*' && chars.peek() == Some(&'/') {
+                        chars.next();
+                        in_comment = false;
+                    }
+                    continue;
+                }
+                match c {
+                    '/' if chars.peek() == Some(&'/') => break,
+                    '#' if chars.peek() != Some(&'[') => break,
+                    '/' if chars.peek() == Some(&'*') => {
+                        chars.next();
+                        in_comment = true;
+                        code.push(' ');
+                    }
+                    '"' | '\'' => {
+                        while let Some(next) = chars.next() {
+                            if next == '\\' {
+                                chars.next();
+                            } else if next == c {
+                                break;
+                            }
+                        }
+                        code.push(c);
+                        code.push(c);
+                    }
+                    _ => code.push(c),
+                }
+            }
+            code
+        })
+        .collect()
+}
+
+/// Names imported by the body of a `use` declaration, with their aliases:
+/// `A\B`, `A\B as C`, `A\{B, C as D}`; functions and constants are skipped
+fn imported_names(statement: &str) -> Vec<(String, String)> {
+    let statement = statement.trim();
+    if statement.starts_with("function ") || statement.starts_with("const ") {
+        return Vec::new();
+    }
+    let (prefix, names) = match statement.split_once('{') {
+        Some((prefix, group)) => (prefix.trim(), group.trim_end_matches('}')),
+        None => ("", statement),
+    };
+    names
+        .split(',')
+        .filter_map(|name| {
+            let (name, alias) = match name.split_once(" as ") {
+                Some((name, alias)) => (name.trim(), Some(alias.trim())),
+                None => (name.trim(), None),
+            };
+            let name = class_name(&format!("{prefix}{name}"))?
+                .trim_start_matches('\\')
+                .to_string();
+            let alias = alias
+                .or_else(|| name.rsplit('\\').next())
+                .unwrap_or_default()
+                .to_string();
+            Some((name, alias))
+        })
+        .collect()
+}
+
+/// The name and parents of a class, interface, trait or enum declaration:
+/// the names after `extends` and `implements`
+fn declared_class(line: &str) -> Option<(&str, Vec<&str>)> {
+    let declaration = ["class ", "interface ", "trait ", "enum "]
+        .iter()
+        .find_map(|keyword| {
+            let start = line.find(keyword)?;
+            let prefix = &line[..start];
+            let modifiers = prefix
+                .split_whitespace()
+                .all(|word| matches!(word, "abstract" | "final" | "readonly"));
+            modifiers.then(|| &line[start + keyword.len()..])
+        })?;
+    let declaration = declaration.split('{').next().unwrap_or(declaration);
+    let name_end = declaration
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(declaration.len());
+    let name = class_name(&declaration[..name_end])?;
+    let mut parents = Vec::new();
+    let mut in_parents = false;
+    for word in declaration[name_end..]
+        .split([' ', ','])
+        .filter(|word| !word.is_empty())
+    {
+        match word {
+            "extends" | "implements" => in_parents = true,
+            _ if in_parents => parents.extend(class_name(word)),
+            _ => {}
+        }
+    }
+    Some((name, parents))
+}
+
+/// The name of a function or method declaration and whether it is
+/// callable from other files: `public static function create(`
+fn declared_function(line: &str) -> Option<(&str, bool)> {
+    let start = line.find("function ")?;
+    let modifiers: Vec<&str> = line[..start].split_whitespace().collect();
+    let is_declaration = modifiers.iter().all(|word| {
+        matches!(
+            *word,
+            "public" | "protected" | "private" | "static" | "abstract" | "final"
+        )
+    });
+    if !is_declaration {
+        return None;
+    }
+    let rest = line[start + "function ".len()..].trim_start();
+    let rest = rest.strip_prefix('&').unwrap_or(rest);
+    let name_end = rest.find('(')?;
+    let name = class_name(&rest[..name_end]).filter(|name| !name.contains('\\'))?;
+    let is_exported = !modifiers
+        .iter()
+        .any(|word| matches!(*word, "protected" | "private"));
+    Some((name, is_exported))
+}
+
+/// The position of the parenthesis closing the parameter list of the
+/// declaration `statement`
+fn parameters_end(statement: &str) -> Option<usize> {
+    let start = statement.find('(')?;
+    let mut depth = 0;
+    for (position, c) in statement[start..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(start + position);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The classes type-hinting parameters: `(Request $request, ?User $user,
+/// int|Money $amount)`; builtin types are skipped
+fn parameter_types(parameters: &str) -> Vec<&str> {
+    let parameters = parameters.trim_start_matches('(');
+    parameters
+        .split(',')
+        .filter_map(|parameter| parameter.split('$').next())
+        .filter_map(|hint| hint.split_whitespace().last())
+        .flat_map(|hint| hint.split(['|', '&', '(', ')']))
+        .map(|hint| hint.trim_start_matches('?'))
+        .filter(|hint| !BUILTIN_TYPES.contains(&hint.to_ascii_lowercase().as_str()))
+        .filter(|hint| !matches!(*hint, "public" | "protected" | "private" | "readonly"))
+        .filter_map(class_name)
+        .collect()
+}
+
+/// `self`, `static` and `parent`, which name a class relative to the
+/// enclosing one
+fn is_relative_class(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "self" | "static" | "parent"
+    )
+}
+
+/// A reference to the fully qualified class `class`
+fn type_reference(class: &str, line: usize) -> TypeReference {
+    let (module, name) = match class.rsplit_once('\\') {
+        Some((module, name)) => (Some(module.to_string()), name),
+        None => (None, class),
+    };
+    TypeReference {
+        name: name.to_string(),
+        module,
+        line,
+        definition_path: None,
+        is_external: false,
+        external_package: None,
+    }
+}
+
+/// `name` if it is a valid, possibly qualified, class name
+fn class_name(name: &str) -> Option<&str> {
+    let name = name.trim();
+    let is_valid = !name.is_empty()
+        && name.trim_start_matches('\\').split('\\').all(|segment| {
+            segment.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        });
+    is_valid.then_some(name)
+}
+
+/// The fully qualified name of `name` as used in `namespace`
+fn qualify(name: &str, namespace: &str, aliases: &HashMap<String, String>) -> String {
+    if let Some(name) = name.strip_prefix('\\') {
+        return name.to_string();
+    }
+    let (first, rest) = match name.split_once('\\') {
+        Some((first, rest)) => (first, Some(rest)),
+        None => (name, None),
+    };
+    match (aliases.get(first), rest) {
+        (Some(imported), Some(rest)) => format!("{imported}\\{rest}"),
+        (Some(imported), None) => imported.clone(),
+        _ if namespace.is_empty() => name.to_string(),
+        _ => format!("{namespace}\\{name}"),
+    }
+}
+
+/// The path loaded by a `require` or `include` expression on `line`,
+/// relative to the including file: `require __DIR__ . '/../bootstrap.php'`
+/// or `include 'partials/header.php'`
+fn included_path(line: &str) -> Option<String> {
+    let expression = INCLUDE_KEYWORDS.iter().find_map(|keyword| {
+        let start = line.find(keyword)?;
+        let before = line[..start].chars().next_back();
+        let after = line[start + keyword.len()..].chars().next();
+        let is_word = !before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+            && matches!(after, Some(' ' | '('));
+        is_word.then(|| &line[start + keyword.len()..])
+    })?;
+    let expression = expression.split(';').next().unwrap_or(expression);
+
+    let quote_start = expression.find(['\'', '"'])?;
+    let quote = expression[quote_start..].chars().next()?;
+    let literal = &expression[quote_start + 1..];
+    let literal = &literal[..literal.find(quote)?];
+    if literal.is_empty() || literal.contains('$') {
+        return None;
+    }
+    let from_dir = expression[..quote_start].contains("__DIR__")
+        || expression[..quote_start].contains("__FILE__");
+    match literal.strip_prefix('/') {
+        Some(relative) if from_dir => Some(format!("./{relative}")),
+        // Absolute paths are outside the project
+        Some(_) => None,
+        None => Some(format!("./{literal}")),
+    }
+}
+
+pub struct PhpModuleResolver;
+
+impl ModuleResolver for PhpModuleResolver {
+    fn resolve_import(
+        &self,
+        module_path: &str,
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<ResolvedPath, ContextCreatorError> {
+        // Handle require and include, relative to the including file
+        if module_path.starts_with('.') {
+            // Included paths may climb to parent directories but must
+            // resolve inside the project
+            validate_module_specifier(module_path)?;
+            if let Some(parent) = from_file.parent() {
+                let path = parent.join(module_path);
+                if tree.is_file(&path) {
+                    let validated_path = validate_import_path(base_dir, &path, tree)?;
+                    return Ok(ResolvedPath {
+                        path: validated_path,
+                        is_external: false,
+                        confidence: 0.9,
+                    });
+                }
+            }
+        } else {
+            // Validate module name for security
+            validate_module_name(&module_path.replace('\\', "/"))?;
+
+            // Handle classes autoloaded through composer.json PSR-4 mappings
+            let class_file = ComposerAutoload::find(tree, from_file, base_dir)
+                .and_then(|autoload| autoload.resolve_class(tree, module_path));
+            if let Some(path) = class_file {
+                let validated_path = validate_import_path(base_dir, &path, tree)?;
+                return Ok(ResolvedPath {
+                    path: validated_path,
+                    is_external: false,
+                    confidence: 0.9,
+                });
+            }
+        }
+
+        // Otherwise, a class of a Composer package or of PHP itself
+        Ok(ResolvedPath {
+            path: base_dir.join("composer.json"), // Point to composer.json as indicator
+            is_external: true,
+            confidence: 0.8,
+        })
+    }
+
+    fn get_file_extensions(&self) -> Vec<&'static str> {
+        vec!["php"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_class_references() {
+        let content = r#"<?php
+
+namespace App\Http\Controllers;
+
+use App\Models\User;
+use App\Services\{Billing\Invoicer, Mailer as Mail};
+use function App\Support\helper;
+use Illuminate\Http\Request;
+
+final class UserController extends Controller implements Contracts\Exports
+{
+    use Concerns\Paginates, \App\Traits\Auditable;
+
+    public function store(Request $request)
+    {
+        $callback = function () use ($request) {};
+    }
+}
+"#;
+        let references: Vec<String> = symbols(content)
+            .classes
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(
+            references,
+            vec![
+                "App\\Models\\User",
+                "App\\Services\\Billing\\Invoicer",
+                "App\\Services\\Mailer",
+                "Illuminate\\Http\\Request",
+                "App\\Http\\Controllers\\Controller",
+                "App\\Http\\Controllers\\Contracts\\Exports",
+                "App\\Http\\Controllers\\Concerns\\Paginates",
+                "App\\Traits\\Auditable",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_php_analysis() {
+        let content = r#"<?php
+
+namespace App\Billing;
+
+use App\Models\User;
+use Illuminate\Support\Facades\Cache;
+
+interface Gateway
+{
+    public function charge(Money $amount): Receipt;
+}
+
+trait LogsCharges {}
+
+final class StripeGateway extends BaseGateway implements Gateway
+{
+    public function __construct(
+        private readonly HttpClient $client,
+        ?User $owner = null,
+    ) {
+        parent::__construct($client);
+    }
+
+    public function charge(int|Money $amount): Receipt
+    {
+        // $this->refund($amount);
+        $total = Cache::remember('total', fn () => $this->total("sum()"));
+        return new Receipt(format_amount($amount), static::currency());
+    }
+
+    private static function currency(): string
+    {
+        return \strtoupper(self::CURRENCY);
+    }
+}
+
+function format_amount(Money $amount): string
+{
+    return sprintf('%d', $amount->cents());
+}
+"#;
+        let path = PathBuf::from("src/Billing/StripeGateway.php");
+        let context = SemanticContext::new(path.clone(), PathBuf::from("."), 3);
+
+        let result = PhpAnalyzer::new()
+            .analyze_file(&path, content, &context)
+            .unwrap();
+
+        let definitions: Vec<(&str, bool, usize)> = result
+            .exported_functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.is_exported, f.line))
+            .collect();
+        assert_eq!(
+            definitions,
+            vec![
+                ("Gateway", true, 8),
+                ("charge", true, 10),
+                ("LogsCharges", true, 13),
+                ("StripeGateway", true, 15),
+                ("__construct", true, 17),
+                ("charge", true, 24),
+                ("currency", false, 31),
+                ("format_amount", true, 37),
+            ]
+        );
+
+        let types: Vec<(&str, Option<&str>, usize)> = result
+            .type_references
+            .iter()
+            .map(|t| (t.name.as_str(), t.module.as_deref(), t.line))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("Money", Some("App\\Billing"), 10),
+                ("BaseGateway", Some("App\\Billing"), 15),
+                ("Gateway", Some("App\\Billing"), 15),
+                ("HttpClient", Some("App\\Billing"), 17),
+                ("User", Some("App\\Models"), 17),
+                ("Money", Some("App\\Billing"), 24),
+                ("Receipt", Some("App\\Billing"), 28),
+                ("Money", Some("App\\Billing"), 37),
+            ]
+        );
+
+        let calls: Vec<(&str, Option<&str>, usize)> = result
+            .function_calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.module.as_deref(), c.line))
+            .collect();
+        assert_eq!(
+            calls,
+            vec![
+                ("__construct", None, 21),
+                ("remember", Some("Illuminate\\Support\\Facades\\Cache"), 27),
+                ("total", None, 27),
+                ("format_amount", None, 28),
+                ("currency", None, 28),
+                ("strtoupper", None, 33),
+                ("sprintf", None, 39),
+                ("cents", None, 39),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_included_paths() {
+        let includes = [
+            (
+                "require __DIR__ . '/../bootstrap/app.php';",
+                Some("./../bootstrap/app.php"),
+            ),
+            (
+                "$config = require_once(dirname(__FILE__) . \"/config.php\");",
+                Some("./config.php"),
+            ),
+            (
+                "include 'partials/header.php';",
+                Some("./partials/header.php"),
+            ),
+            ("require $path;", None),
+            ("include_once '/etc/php/shared.php';", None),
+            ("$required = requireAuth();", None),
+        ];
+        for (line, expected) in includes {
+            assert_eq!(included_path(line).as_deref(), expected, "{line}");
+        }
+    }
+}
@@ -3,11 +3,14 @@
 use crate::core::semantic::{
     analyzer::{AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult},
     path_validator::{validate_import_path, validate_module_name},
+    pyproject::PythonProject,
     query_engine::QueryEngine,
     resolver::{ModuleResolver, ResolvedPath, ResolverUtils},
+    workspace::{Ecosystem, Workspace},
 };
+use crate::core::source_tree::SourceTree;
 use crate::utils::error::ContextCreatorError;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tree_sitter::Parser;
 
 #[allow(clippy::new_without_default)]
@@ -52,6 +55,7 @@ impl LanguageAnalyzer for PythonAnalyzer {
             &mut result.type_references,
             path,
             &context.base_dir,
+            &context.source_tree,
         )?;
 
         Ok(result)
@@ -108,6 +112,7 @@ impl ModuleResolver for PythonModuleResolver {
         module_path: &str,
         from_file: &Path,
         base_dir: &Path,
+        tree: &SourceTree,
     ) -> Result<ResolvedPath, ContextCreatorError> {
         // Validate module name for security - allow Python relative imports
         if !module_path.starts_with('.') {
@@ -121,6 +126,16 @@ impl ModuleResolver for PythonModuleResolver {
             }
         }
 
+        // Handle packages of the same workspace
+        if let Some(resolved) = resolve_workspace_package(module_path, base_dir, tree) {
+            let validated_path = validate_import_path(base_dir, &resolved, tree)?;
+            return Ok(ResolvedPath {
+                path: validated_path,
+                is_external: false,
+                confidence: 0.9,
+            });
+        }
+
         // Handle standard library imports
         if self.is_external_module(module_path) {
             return Ok(ResolvedPath {
@@ -130,100 +145,21 @@ impl ModuleResolver for PythonModuleResolver {
             });
         }
 
-        // Handle relative imports (., ..)
-        if module_path.starts_with('.') {
-            let mut level = 0;
-            let mut chars = module_path.chars();
-            while chars.next() == Some('.') {
-                level += 1;
-            }
-
-            // Get the rest of the module path after dots
-            let rest = &module_path[level..];
-
-            if let Some(parent) = from_file.parent() {
-                let mut current = parent;
-
-                // Go up directories based on dot count
-                // For level=1 (.), stay in current directory
-                // For level=2 (..), go up 1 directory
-                // For level=3 (...), go up 2 directories
-                for _ in 0..(level.saturating_sub(1)) {
-                    if let Some(p) = current.parent() {
-                        current = p;
-                    }
-                }
-
-                // Resolve the rest of the path
-                if !rest.is_empty() {
-                    let path = ResolverUtils::module_to_path(rest);
-                    let full_path = current.join(&path);
-
-                    // Try as a Python file
-                    if let Some(resolved) = ResolverUtils::find_with_extensions(&full_path, &["py"])
-                    {
-                        let validated_path = validate_import_path(base_dir, &resolved)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.9,
-                        });
-                    }
-
-                    // Try as a package directory with __init__.py
-                    let init_path = full_path.join("__init__.py");
-                    if init_path.exists() {
-                        let validated_path = validate_import_path(base_dir, &init_path)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.9,
-                        });
-                    }
-                }
-            }
-        }
-
-        // Handle absolute imports
-        let parts: Vec<&str> = module_path.split('.').collect();
-
-        // Start from base directory or parent of current file
-        let search_paths = vec![
-            base_dir.to_path_buf(),
-            from_file.parent().unwrap_or(base_dir).to_path_buf(),
-        ];
-
-        for search_path in &search_paths {
-            let mut current_path = search_path.clone();
-
-            // Build path from module parts
-            for (i, part) in parts.iter().enumerate() {
-                current_path = current_path.join(part);
-
-                // Check if this is the final part
-                if i == parts.len() - 1 {
-                    // Try as a Python file
-                    let py_file = current_path.with_extension("py");
-                    if py_file.exists() {
-                        let validated_path = validate_import_path(base_dir, &py_file)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.8,
-                        });
-                    }
-
-                    // Try as a package directory
-                    let init_path = current_path.join("__init__.py");
-                    if init_path.exists() {
-                        let validated_path = validate_import_path(base_dir, &init_path)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.8,
-                        });
-                    }
-                }
+        // Handle relative imports (., ..) and absolute imports from the
+        // project's source roots
+        let confidence = if module_path.starts_with('.') {
+            0.9
+        } else {
+            0.8
+        };
+        for candidate in module_candidates(module_path, from_file, base_dir, tree) {
+            if let Some(resolved) = module_file(tree, &candidate) {
+                let validated_path = validate_import_path(base_dir, &resolved, tree)?;
+                return Ok(ResolvedPath {
+                    path: validated_path,
+                    is_external: false,
+                    confidence,
+                });
             }
         }
 
@@ -235,6 +171,17 @@ impl ModuleResolver for PythonModuleResolver {
         })
     }
 
+    fn resolve_import_items(
+        &self,
+        module_path: &str,
+        items: &[String],
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        resolve_items(module_path, items, from_file, base_dir, tree, 0)
+    }
+
     fn get_file_extensions(&self) -> Vec<&'static str> {
         vec!["py", "pyw", "pyi"]
     }
@@ -388,3 +335,346 @@ impl ModuleResolver for PythonModuleResolver {
         stdlib_modules.contains(&first_part) || third_party.contains(&first_part)
     }
 }
+
+/// Resolve a module of a workspace member package to its deepest module file
+fn resolve_workspace_package(
+    module_path: &str,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Option<PathBuf> {
+    let parts: Vec<&str> = module_path.split('.').collect();
+    let workspace = Workspace::for_root(tree, base_dir);
+    let member = workspace.member(Ecosystem::Python, parts.first()?)?;
+
+    (1..=parts.len()).rev().find_map(|i| {
+        let path = member.source_root.join(parts[..i].join("/"));
+        ResolverUtils::find_with_extensions(tree, &path, &["py"])
+    })
+}
+
+/// Longest chain of `__init__.py` re-exports followed
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// Resolve `from module import items` to the submodules and the modules
+/// defining the items
+///
+/// Items of a package are looked up as submodules first, then followed
+/// through the imports of its `__init__.py`. Packages without an
+/// `__init__.py` (PEP 420 namespace packages) only provide submodules.
+#[allow(clippy::too_many_arguments)]
+fn resolve_items(
+    module_path: &str,
+    items: &[String],
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+    depth: usize,
+) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+    let module = PythonModuleResolver.resolve_import(module_path, from_file, base_dir, tree);
+    let names: Vec<&String> = items.iter().filter(|item| *item != "*").collect();
+    if names.is_empty() {
+        return module.map(|module| vec![module]);
+    }
+
+    let init = module
+        .as_ref()
+        .ok()
+        .filter(|module| !module.is_external && module.path.ends_with("__init__.py"))
+        .map(|module| module.path.clone());
+    let package_dir = match &init {
+        Some(init) => init.parent().map(Path::to_path_buf),
+        None => module_candidates(module_path, from_file, base_dir, tree)
+            .into_iter()
+            .find(|candidate| tree.is_dir(candidate)),
+    };
+    let Some(package_dir) = package_dir else {
+        return module.map(|module| vec![module]);
+    };
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for name in names {
+        if let Some(submodule) = module_file(tree, &package_dir.join(name)) {
+            paths.push(submodule);
+        } else if let Some(init) = &init {
+            // The package itself stays a dependency: its `__init__.py` runs
+            // on import and is where the name is re-exported from
+            paths.push(init.clone());
+            if let Some(defining) = find_definition(init, name, base_dir, tree, depth) {
+                paths.push(defining);
+            }
+        }
+    }
+
+    let mut resolved: Vec<ResolvedPath> = Vec::new();
+    for path in paths {
+        let validated_path = validate_import_path(base_dir, &path, tree)?;
+        if !resolved.iter().any(|r| r.path == validated_path) {
+            resolved.push(ResolvedPath {
+                path: validated_path,
+                is_external: false,
+                confidence: 0.9,
+            });
+        }
+    }
+
+    if resolved.is_empty() {
+        return module.map(|module| vec![module]);
+    }
+    Ok(resolved)
+}
+
+/// The module defining `name`, following the re-exports of `file`
+fn find_definition(
+    file: &Path,
+    name: &str,
+    base_dir: &Path,
+    tree: &SourceTree,
+    depth: usize,
+) -> Option<PathBuf> {
+    if depth >= MAX_REEXPORT_DEPTH {
+        return None;
+    }
+    let content = tree.read_to_string(file).ok()?;
+    let statements = logical_lines(&content);
+    if statements.iter().any(|statement| defines(statement, name)) {
+        return Some(file.to_path_buf());
+    }
+
+    for statement in &statements {
+        let Some((module, imported)) = parse_from_import(statement) else {
+            continue;
+        };
+        for (original, alias) in imported {
+            if original == "*" {
+                // Star imports re-export the names in `__all__`, or every
+                // public name if the module has none
+                let target = PythonModuleResolver
+                    .resolve_import(&module, file, base_dir, tree)
+                    .ok()
+                    .filter(|target| !target.is_external);
+                let Some(target) = target else {
+                    continue;
+                };
+                let exported = tree
+                    .read_to_string(&target.path)
+                    .ok()
+                    .and_then(|content| dunder_all(&logical_lines(&content)))
+                    .map_or(!name.starts_with('_'), |all| all.iter().any(|n| n == name));
+                if exported {
+                    if let Some(path) =
+                        find_definition(&target.path, name, base_dir, tree, depth + 1)
+                    {
+                        return Some(path);
+                    }
+                }
+            } else if alias == name {
+                let resolved =
+                    resolve_items(&module, &[original], file, base_dir, tree, depth + 1).ok()?;
+                return resolved
+                    .into_iter()
+                    .find(|resolved| !resolved.is_external)
+                    .map(|resolved| resolved.path);
+            }
+        }
+    }
+    None
+}
+
+/// Paths, without extension, a module may be found at
+///
+/// Relative modules are looked up from the package of the importing file.
+/// Absolute modules are looked up in packages mapped by `pyproject.toml`, the
+/// project's source roots and the directory of the importing file.
+fn module_candidates(
+    module_path: &str,
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Vec<PathBuf> {
+    let Some(from_dir) = from_file.parent() else {
+        return Vec::new();
+    };
+
+    let rest = module_path.trim_start_matches('.');
+    let level = module_path.len() - rest.len();
+    let path = PathBuf::from(rest.replace('.', "/"));
+    if level > 0 {
+        // For level=1 (.), stay in the current directory; each further dot
+        // goes up one directory
+        let package = (1..level).try_fold(from_dir, |dir, _| dir.parent());
+        return package.map(|dir| dir.join(path)).into_iter().collect();
+    }
+
+    let project = PythonProject::for_root(tree, base_dir);
+    let mut parts = rest.splitn(2, '.');
+    let mapped = parts
+        .next()
+        .and_then(|package| project.package_dirs.get(package))
+        .map(|dir| dir.join(parts.next().unwrap_or("").replace('.', "/")));
+    mapped
+        .into_iter()
+        .chain(
+            project
+                .source_roots
+                .iter()
+                .map(Path::new)
+                .chain([from_dir])
+                .map(|root| root.join(&path)),
+        )
+        .collect()
+}
+
+/// The file of a module: `<path>.py` or the `__init__.py` of a package
+fn module_file(tree: &SourceTree, candidate: &Path) -> Option<PathBuf> {
+    let file = candidate.with_extension("py");
+    if candidate.file_name().is_some() && tree.is_file(&file) {
+        return Some(file);
+    }
+    let init = candidate.join("__init__.py");
+    tree.is_file(&init).then_some(init)
+}
+
+/// Statements of a Python file, with bracketed and backslash continuations
+/// joined and comments removed
+fn logical_lines(content: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    // The open string's quote character and whether it is triple-quoted
+    let mut quote: Option<(char, bool)> = None;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if let Some((q, triple)) = quote {
+            match c {
+                '\\' => {
+                    current.push(c);
+                    current.extend(chars.next());
+                }
+                '\n' if triple => current.push(' '),
+                '\n' => {
+                    // Unterminated string
+                    quote = None;
+                    depth = 0;
+                    statements.push(std::mem::take(&mut current));
+                }
+                _ if c == q && !triple => {
+                    current.push(c);
+                    quote = None;
+                }
+                _ if c == q && chars.next_if_eq(&q).is_some() => {
+                    if chars.next_if_eq(&q).is_some() {
+                        quote = None;
+                        current.extend([c, q, q]);
+                    } else {
+                        current.extend([c, q]);
+                    }
+                }
+                _ => current.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '#' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '\'' | '"' => {
+                let triple = chars.next_if_eq(&c).is_some();
+                if triple && chars.next_if_eq(&c).is_none() {
+                    // An empty string
+                    current.extend([c, c]);
+                    continue;
+                }
+                quote = Some((c, triple));
+                current.push(c);
+            }
+            '\\' if chars.next_if_eq(&'\n').is_some() => current.push(' '),
+            '(' | '[' | '{' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            '\n' if depth > 0 => current.push(' '),
+            '\n' => {
+                if !current.trim().is_empty() {
+                    statements.push(current.trim_end().to_string());
+                }
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        statements.push(current.trim_end().to_string());
+    }
+    statements
+}
+
+/// Whether a top-level statement defines `name`
+fn defines(statement: &str, name: &str) -> bool {
+    if statement.starts_with(char::is_whitespace) {
+        return false;
+    }
+    let definition = statement
+        .strip_prefix("async def ")
+        .or_else(|| statement.strip_prefix("def "))
+        .or_else(|| statement.strip_prefix("class "));
+    let rest = match definition {
+        Some(rest) => rest,
+        None => statement,
+    };
+    let Some(after) = rest.strip_prefix(name) else {
+        return false;
+    };
+    let after = after.trim_start();
+    if definition.is_some() {
+        after.starts_with(['(', ':', '['])
+    } else {
+        (after.starts_with('=') && !after.starts_with("=="))
+            || after.starts_with(':')
+            || after.starts_with(',')
+    }
+}
+
+/// The module and the `(name, alias)` pairs of a top-level
+/// `from module import ...` statement
+fn parse_from_import(statement: &str) -> Option<(String, Vec<(String, String)>)> {
+    let rest = statement.strip_prefix("from ")?;
+    let (module, names) = rest.split_once(" import ")?;
+    let names = names
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| match name.split_once(" as ") {
+            Some((original, alias)) => (original.trim().to_string(), alias.trim().to_string()),
+            None => (name.to_string(), name.to_string()),
+        })
+        .collect();
+    Some((module.trim().to_string(), names))
+}
+
+/// The names listed in `__all__`, if the module defines it
+fn dunder_all(statements: &[String]) -> Option<Vec<String>> {
+    let mut names = None;
+    for statement in statements {
+        let Some(value) = statement
+            .strip_prefix("__all__")
+            .map(str::trim_start)
+            .and_then(|rest| rest.strip_prefix("+=").or_else(|| rest.strip_prefix('=')))
+        else {
+            continue;
+        };
+        let listed = names.get_or_insert_with(Vec::new);
+        for (index, part) in value.split(['"', '\'']).enumerate() {
+            if index % 2 == 1 {
+                listed.push(part.to_string());
+            }
+        }
+    }
+    names
+}
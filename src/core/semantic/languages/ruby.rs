@@ -1,24 +1,43 @@
 //! Semantic analyzer for Ruby
 
-use crate::core::semantic::analyzer::{
-    AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult,
+use crate::core::semantic::{
+    analyzer::{AnalysisResult, Import, LanguageAnalyzer, SemanticContext, SemanticResult},
+    path_validator::{validate_import_path, validate_module_specifier},
+    query_engine::QueryEngine,
+    resolver::{ModuleResolver, ResolvedPath},
+    zeitwerk,
 };
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
 use std::path::Path;
-use tree_sitter::Parser;
+use tree_sitter::{Language, Node, Parser};
+
+// `tree-sitter-ruby` 0.19 binds its parser to tree-sitter 0.19; the parser
+// itself is compatible with the runtime used here
+#[allow(unused_extern_crates)]
+extern crate tree_sitter_ruby as _;
+
+extern "C" {
+    fn tree_sitter_ruby() -> Language;
+}
+
+/// The tree-sitter grammar of Ruby
+pub fn language() -> Language {
+    // SAFETY: `tree_sitter_ruby` returns a pointer to the statically
+    // allocated grammar, which is all `Language` holds
+    unsafe { tree_sitter_ruby() }
+}
 
 #[allow(clippy::new_without_default)]
 pub struct RubyAnalyzer {
-    #[allow(dead_code)]
-    #[allow(dead_code)]
-    parser: Parser,
+    query_engine: QueryEngine,
 }
 
 impl RubyAnalyzer {
     pub fn new() -> Self {
-        let parser = Parser::new();
-        // Note: tree_sitter_ruby::language() would be used here when the crate is added
-        // parser.set_language(tree_sitter_ruby::language()).unwrap();
-        Self { parser }
+        let query_engine =
+            QueryEngine::new(language(), "ruby").expect("Failed to create Ruby query engine");
+        Self { query_engine }
     }
 }
 
@@ -29,12 +48,55 @@ impl LanguageAnalyzer for RubyAnalyzer {
 
     fn analyze_file(
         &self,
-        _path: &Path,
-        _content: &str,
-        _context: &SemanticContext,
+        path: &Path,
+        content: &str,
+        context: &SemanticContext,
     ) -> SemanticResult<AnalysisResult> {
-        // TODO: Implement Ruby analysis
-        Ok(AnalysisResult::default())
+        let mut parser = Parser::new();
+        parser
+            .set_language(language())
+            .map_err(|e| ContextCreatorError::ParseError(format!("Failed to set language: {e}")))?;
+        let tree = parser.parse(content, None).ok_or_else(|| {
+            ContextCreatorError::ParseError("Failed to parse content".to_string())
+        })?;
+
+        let mut result = self.query_engine.analyze_tree(&tree, content)?;
+
+        // Autoloaded constants are loaded by referencing them
+        for (constant, line) in constant_references(tree.root_node(), content.as_bytes()) {
+            if !result
+                .imports
+                .iter()
+                .any(|import| import.module == constant)
+            {
+                result.imports.push(Import {
+                    module: constant,
+                    items: Vec::new(),
+                    is_relative: false,
+                    is_dynamic: false,
+                    line,
+                });
+            }
+        }
+
+        // Resolve the files defining the constants referenced
+        for type_ref in &mut result.type_references {
+            let constant = match &type_ref.module {
+                Some(module) => format!("{module}::{}", type_ref.name),
+                None => type_ref.name.clone(),
+            };
+            type_ref.definition_path = zeitwerk::resolve_constant(
+                &context.source_tree,
+                &constant,
+                path,
+                &context.base_dir,
+            )
+            .and_then(|file| {
+                validate_import_path(&context.base_dir, &file, &context.source_tree).ok()
+            });
+        }
+
+        Ok(result)
     }
 
     fn can_handle_extension(&self, extension: &str) -> bool {
@@ -45,3 +107,116 @@ impl LanguageAnalyzer for RubyAnalyzer {
         vec!["rb"]
     }
 }
+
+/// Constant paths referenced by a file (`User`, `Admin::UsersController`),
+/// outermost first, without the names of the classes and modules it defines
+fn constant_references(root: Node, source: &[u8]) -> Vec<(String, usize)> {
+    let mut constants: Vec<(String, usize)> = Vec::new();
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let is_constant_path = matches!(node.kind(), "constant" | "scope_resolution");
+        let is_definition_name = node.parent().is_some_and(|parent| {
+            matches!(parent.kind(), "class" | "module")
+                && parent.child_by_field_name("name") == Some(node)
+        });
+        if is_constant_path && !is_definition_name {
+            let text = node.utf8_text(source).unwrap_or("");
+            let constant = text.trim_start_matches("::");
+            let is_constant = constant.split("::").all(|segment| {
+                segment.starts_with(|c: char| c.is_ascii_uppercase())
+                    && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+            });
+            if is_constant {
+                if !constants.iter().any(|(known, _)| known == constant) {
+                    constants.push((constant.to_string(), node.start_position().row + 1));
+                }
+                continue;
+            }
+        }
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.named_children(&mut cursor).collect();
+        stack.extend(children.into_iter().rev());
+    }
+    constants
+}
+
+pub struct RubyModuleResolver;
+
+impl ModuleResolver for RubyModuleResolver {
+    fn resolve_import(
+        &self,
+        module_path: &str,
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<ResolvedPath, ContextCreatorError> {
+        // Validate module name for security; require_relative may climb to
+        // parent directories but must resolve inside the project
+        validate_module_specifier(module_path)?;
+
+        let with_extension = |path: &Path| {
+            if path.extension().is_some_and(|ext| ext == "rb") {
+                path.to_path_buf()
+            } else {
+                path.with_file_name(format!(
+                    "{}.rb",
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .unwrap_or("")
+                ))
+            }
+        };
+        let resolved = |path: &Path| {
+            validate_import_path(base_dir, path, tree)
+                .ok()
+                .map(|validated_path| ResolvedPath {
+                    path: validated_path,
+                    is_external: false,
+                    confidence: 0.9,
+                })
+        };
+
+        // Handle require_relative
+        if module_path.starts_with('.') {
+            if let Some(parent) = from_file.parent() {
+                let path = with_extension(&parent.join(module_path));
+                if let Some(resolved) = tree.is_file(&path).then(|| resolved(&path)).flatten() {
+                    return Ok(resolved);
+                }
+            }
+        }
+
+        // Handle autoloaded constants
+        if module_path.starts_with(|c: char| c.is_ascii_uppercase()) {
+            if let Some(resolved) =
+                zeitwerk::resolve_constant(tree, module_path, from_file, base_dir)
+                    .and_then(|path| resolved(&path))
+            {
+                return Ok(resolved);
+            }
+        }
+
+        // Handle requires of files on the load path: `lib/` of the gem or
+        // application, then the project root
+        let project = zeitwerk::project_dir(tree, from_file, base_dir);
+        for dir in [project.join("lib"), project, base_dir.to_path_buf()] {
+            let path = with_extension(&dir.join(module_path));
+            if tree.is_file(&path) {
+                if let Some(resolved) = resolved(&path) {
+                    return Ok(resolved);
+                }
+            }
+        }
+
+        // Otherwise, a gem or the standard library
+        Ok(ResolvedPath {
+            path: base_dir.join("Gemfile"), // Point to Gemfile as indicator
+            is_external: true,
+            confidence: 0.8,
+        })
+    }
+
+    fn get_file_extensions(&self) -> Vec<&'static str> {
+        vec!["rb"]
+    }
+}
@@ -4,8 +4,10 @@ use crate::core::semantic::{
     analyzer::{AnalysisResult, LanguageAnalyzer, SemanticContext, SemanticResult},
     path_validator::{validate_import_path, validate_module_name},
     query_engine::QueryEngine,
-    resolver::{ModuleResolver, ResolvedPath, ResolverUtils},
+    resolver::{ModuleResolver, ResolvedPath},
+    rust_modules,
 };
+use crate::core::source_tree::SourceTree;
 use crate::utils::error::ContextCreatorError;
 use std::path::Path;
 use tree_sitter::Parser;
@@ -52,6 +54,7 @@ impl LanguageAnalyzer for RustAnalyzer {
             &mut result.type_references,
             path,
             &context.base_dir,
+            &context.source_tree,
         )?;
 
         Ok(result)
@@ -123,6 +126,7 @@ impl ModuleResolver for RustModuleResolver {
         module_path: &str,
         from_file: &Path,
         base_dir: &Path,
+        tree: &SourceTree,
     ) -> Result<ResolvedPath, ContextCreatorError> {
         tracing::debug!(
             "RustModuleResolver::resolve_import - module: '{}', from_file: {}, base_dir: {}",
@@ -131,233 +135,31 @@ impl ModuleResolver for RustModuleResolver {
             base_dir.display()
         );
 
-        // Validate module name for security
-        validate_module_name(module_path)?;
-
-        // Handle current crate imports FIRST (e.g., my_lib::module)
-        // Check if this might be the current crate by looking for Cargo.toml
-        let cargo_path = base_dir.join("Cargo.toml");
-        tracing::debug!(
-            "Checking for Cargo.toml at: {}, exists: {}",
-            cargo_path.display(),
-            cargo_path.exists()
-        );
-        if cargo_path.exists() {
-            // Try to parse crate name from Cargo.toml
-            if let Ok(contents) = std::fs::read_to_string(&cargo_path) {
-                // Simple parsing to find package name
-                for line in contents.lines() {
-                    let trimmed = line.trim();
-                    if trimmed.starts_with("name") && trimmed.contains('=') {
-                        // Extract the crate name from: name = "my_lib"
-                        if let Some(name_part) = trimmed.split('=').nth(1) {
-                            let crate_name = name_part.trim().trim_matches('"').trim_matches('\'');
-                            tracing::debug!(
-                                "Found crate name: '{}', checking against module path: '{}'",
-                                crate_name,
-                                module_path
-                            );
-                            if module_path.starts_with(&format!("{crate_name}::")) {
-                                // This is a reference to the current crate - treat it like crate::
-                                let relative_path = module_path
-                                    .strip_prefix(&format!("{crate_name}::"))
-                                    .unwrap();
-
-                                // IMPORTANT: For crate-level imports, we should also include lib.rs
-                                // as it's the crate root that defines the module structure
-                                // For now, we'll return the most specific module file we can find
-
-                                // For Rust, we need to find the module file, not the item within it
-                                // If importing my_lib::api::handle_api_request, we want to find api.rs
-                                // Split the path and try resolving progressively
-                                let parts: Vec<&str> = relative_path.split("::").collect();
-
-                                // For Rust imports, we need to resolve to the actual module file
-                                // For `crate::utils::helpers::format_output`, we want to find helpers.rs
-                                // But we also need to ensure parent modules (utils/mod.rs) are included
-
-                                // Try to find the module file that contains the imported item
-                                for i in (1..=parts.len()).rev() {
-                                    let module_path = parts[..i].join("::");
-                                    let path = ResolverUtils::module_to_path(&module_path);
-                                    let full_path = base_dir.join("src").join(path);
-
-                                    tracing::debug!(
-                                        "Trying module path '{}' at: {}",
-                                        module_path,
-                                        full_path.display()
-                                    );
-
-                                    // Try as a direct .rs file
-                                    if let Some(resolved) =
-                                        ResolverUtils::find_with_extensions(&full_path, &["rs"])
-                                    {
-                                        tracing::debug!(
-                                            "Resolved crate import to: {}",
-                                            resolved.display()
-                                        );
-                                        let validated_path =
-                                            validate_import_path(base_dir, &resolved)?;
-                                        return Ok(ResolvedPath {
-                                            path: validated_path,
-                                            is_external: false,
-                                            confidence: 0.9,
-                                        });
-                                    }
-
-                                    // Try as a directory module (mod.rs)
-                                    let mod_path = full_path.join("mod.rs");
-                                    if mod_path.exists() {
-                                        let validated_path =
-                                            validate_import_path(base_dir, &mod_path)?;
-                                        // For directory modules, we found the target
-                                        // This is the deepest module file we can find
-                                        return Ok(ResolvedPath {
-                                            path: validated_path,
-                                            is_external: false,
-                                            confidence: 0.9,
-                                        });
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Handle crate-relative imports
-        if module_path.starts_with("crate::") {
-            let relative_path = module_path.strip_prefix("crate::").unwrap();
-            let path = ResolverUtils::module_to_path(relative_path);
-            let full_path = base_dir.join("src").join(path);
-
-            if let Some(resolved) = ResolverUtils::find_with_extensions(&full_path, &["rs"]) {
-                let validated_path = validate_import_path(base_dir, &resolved)?;
-                return Ok(ResolvedPath {
-                    path: validated_path,
-                    is_external: false,
-                    confidence: 0.9,
-                });
-            }
-
-            // Try as a directory module (mod.rs)
-            let mod_path = full_path.join("mod.rs");
-            if mod_path.exists() {
-                let validated_path = validate_import_path(base_dir, &mod_path)?;
-                return Ok(ResolvedPath {
-                    path: validated_path,
-                    is_external: false,
-                    confidence: 0.9,
-                });
-            }
-        }
-
-        // Handle relative imports (self, super)
-        if module_path.starts_with("self::") {
-            // self:: refers to the current module
-            let rest = module_path.strip_prefix("self::").unwrap();
-            if let Some(parent) = from_file.parent() {
-                let path = ResolverUtils::module_to_path(rest);
-                let full_path = parent.join(path);
-                if let Some(resolved) = ResolverUtils::find_with_extensions(&full_path, &["rs"]) {
-                    let validated_path = validate_import_path(base_dir, &resolved)?;
-                    return Ok(ResolvedPath {
-                        path: validated_path,
-                        is_external: false,
-                        confidence: 0.9,
-                    });
-                }
-            }
-        } else if module_path.starts_with("super::") {
-            // super:: refers to the parent module
-            let rest = module_path.strip_prefix("super::").unwrap();
-            if let Some(parent) = from_file.parent() {
-                if let Some(grandparent) = parent.parent() {
-                    // For imports like "super::parent_function", we need to find the module file
-                    // that contains this function. First check if there's a lib.rs or mod.rs
-                    // in the grandparent directory
-
-                    // Try lib.rs first (common for library crates)
-                    let lib_rs = grandparent.join("lib.rs");
-                    if lib_rs.exists() {
-                        let validated_path = validate_import_path(base_dir, &lib_rs)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.9,
-                        });
-                    }
-
-                    // Try mod.rs
-                    let mod_rs = grandparent.join("mod.rs");
-                    if mod_rs.exists() {
-                        let validated_path = validate_import_path(base_dir, &mod_rs)?;
-                        return Ok(ResolvedPath {
-                            path: validated_path,
-                            is_external: false,
-                            confidence: 0.9,
-                        });
-                    }
-
-                    // If the parent directory has a name, try parent_name.rs
-                    if let Some(parent_name) = parent.file_name() {
-                        let parent_rs =
-                            grandparent.join(format!("{}.rs", parent_name.to_string_lossy()));
-                        if parent_rs.exists() {
-                            let validated_path = validate_import_path(base_dir, &parent_rs)?;
-                            return Ok(ResolvedPath {
-                                path: validated_path,
-                                is_external: false,
-                                confidence: 0.9,
-                            });
-                        }
-                    }
-
-                    // If rest is not empty, it might be a submodule path
-                    if !rest.is_empty() {
-                        let path = ResolverUtils::module_to_path(rest);
-                        let full_path = grandparent.join(path);
-                        if let Some(resolved) =
-                            ResolverUtils::find_with_extensions(&full_path, &["rs"])
-                        {
-                            let validated_path = validate_import_path(base_dir, &resolved)?;
-                            return Ok(ResolvedPath {
-                                path: validated_path,
-                                is_external: false,
-                                confidence: 0.9,
-                            });
-                        }
-                    }
-                }
-            }
+        // Handle files pulled in by include!, include_str! and include_bytes!
+        if is_file_reference(module_path) {
+            let parent = from_file.parent().unwrap_or(base_dir);
+            let validated_path = validate_import_path(base_dir, &parent.join(module_path), tree)?;
+            return Ok(ResolvedPath {
+                path: validated_path,
+                is_external: false,
+                confidence: 1.0,
+            });
         }
 
-        // Handle simple module names (e.g., "mod lib;" in same directory)
-        if !module_path.contains("::") {
-            if let Some(parent) = from_file.parent() {
-                // Try as a file
-                let file_path = parent.join(format!("{module_path}.rs"));
-                if file_path.exists() {
-                    let validated_path = validate_import_path(base_dir, &file_path)?;
-                    return Ok(ResolvedPath {
-                        path: validated_path,
-                        is_external: false,
-                        confidence: 0.9,
-                    });
-                }
+        // Validate module name for security
+        validate_module_name(module_path)?;
 
-                // Try as a directory module
-                let mod_path = parent.join(module_path).join("mod.rs");
-                if mod_path.exists() {
-                    let validated_path = validate_import_path(base_dir, &mod_path)?;
-                    return Ok(ResolvedPath {
-                        path: validated_path,
-                        is_external: false,
-                        confidence: 0.9,
-                    });
-                }
-            }
+        // Handle paths into this crate or other crates of the repository:
+        // crate::, self::, super::, mod declarations, the crate's own name,
+        // path dependencies and workspace members
+        if let Some(resolved) = rust_modules::resolve(module_path, from_file, base_dir, tree) {
+            tracing::debug!("Resolved Rust path to: {}", resolved.display());
+            let validated_path = validate_import_path(base_dir, &resolved, tree)?;
+            return Ok(ResolvedPath {
+                path: validated_path,
+                is_external: false,
+                confidence: 0.9,
+            });
         }
 
         // Check if it's a known external module (like stdlib)
@@ -381,6 +183,45 @@ impl ModuleResolver for RustModuleResolver {
         })
     }
 
+    fn resolve_import_items(
+        &self,
+        module_path: &str,
+        items: &[String],
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        let module = self.resolve_import(module_path, from_file, base_dir, tree)?;
+        // Aliases are recorded as `as name` items and name nothing new
+        let names: Vec<&String> = items
+            .iter()
+            .filter(|item| !item.starts_with("as "))
+            .collect();
+        if module.is_external || names.is_empty() {
+            return Ok(vec![module]);
+        }
+        let Some(module_file) = rust_modules::module_file(module_path, from_file, base_dir, tree)
+        else {
+            return Ok(vec![module]);
+        };
+
+        // Follow each item of `use module::{A, b::C}` to the module defining it
+        let mut resolved: Vec<ResolvedPath> = Vec::new();
+        for name in names {
+            let segments: Vec<&str> = name.split("::").filter(|s| *s != "*").collect();
+            let path = rust_modules::resolve_in(&module_file, &segments, base_dir, tree);
+            let validated_path = validate_import_path(base_dir, &path, tree)?;
+            if !resolved.iter().any(|r| r.path == validated_path) {
+                resolved.push(ResolvedPath {
+                    path: validated_path,
+                    is_external: false,
+                    confidence: 0.9,
+                });
+            }
+        }
+        Ok(resolved)
+    }
+
     fn get_file_extensions(&self) -> Vec<&'static str> {
         vec!["rs"]
     }
@@ -415,3 +256,8 @@ impl ModuleResolver for RustModuleResolver {
         true
     }
 }
+
+/// Whether an import is a file path from `include!` rather than a Rust path
+fn is_file_reference(module_path: &str) -> bool {
+    module_path.contains(['/', '\\', '.'])
+}
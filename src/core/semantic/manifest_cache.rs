@@ -0,0 +1,158 @@
+//! Parsed project files shared across analyses
+//!
+//! Resolvers read the same manifests, configuration files and module
+//! summaries for every import. Each is parsed once and reused while all the
+//! files it was read from, such as the files a configuration extends, keep
+//! their modification time, or under `--at` their blob; failed parses are
+//! cached too, so a broken or missing file is not read again until it changes.
+//! Values read from different snapshots are kept apart.
+
+use crate::core::source_tree::{FileVersion, SourceTree};
+use git2::Oid;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Files a cached value was read from, with the versions they had
+type Sources = Vec<(PathBuf, Option<FileVersion>)>;
+
+/// Values keyed by their type, the commit of the snapshot they were read
+/// from and their path
+type Cache =
+    Mutex<HashMap<(TypeId, Option<Oid>, PathBuf), (Sources, Option<Arc<dyn Any + Send + Sync>>)>>;
+
+/// The value `parse` reads from the file at `path` in `tree`, reusing the
+/// previous one while the file is unchanged
+pub fn load_cached<T: Any + Send + Sync>(
+    tree: &SourceTree,
+    path: &Path,
+    parse: impl FnOnce(&Path) -> Option<T>,
+) -> Option<Arc<T>> {
+    cached(tree, path, || {
+        // Versioned before reading, so a concurrent write is noticed later
+        let sources = vec![(path.to_path_buf(), tree.version(path))];
+        (parse(path), sources)
+    })
+}
+
+/// The value `parse` builds for `key`, reusing the previous one while the
+/// files it was read from are unchanged
+///
+/// `parse` returns the value together with every file it read, such as a
+/// configuration and the files it extends, or a workspace root manifest and
+/// those of its members.
+pub fn load_cached_from<T: Any + Send + Sync>(
+    tree: &SourceTree,
+    key: &Path,
+    parse: impl FnOnce() -> (Option<T>, Vec<PathBuf>),
+) -> Option<Arc<T>> {
+    cached(tree, key, || {
+        let (value, files) = parse();
+        let sources = files
+            .into_iter()
+            .map(|path| {
+                let version = tree.version(&path);
+                (path, version)
+            })
+            .collect();
+        (value, sources)
+    })
+}
+
+fn cached<T: Any + Send + Sync>(
+    tree: &SourceTree,
+    key: &Path,
+    parse: impl FnOnce() -> (Option<T>, Sources),
+) -> Option<Arc<T>> {
+    static CACHE: OnceLock<Cache> = OnceLock::new();
+
+    let cache = CACHE.get_or_init(Default::default);
+    let commit = tree.snapshot().map(|snapshot| snapshot.commit());
+    let key = (TypeId::of::<T>(), commit, key.to_path_buf());
+    let previous = cache.lock().ok().and_then(|cache| cache.get(&key).cloned());
+    if let Some((sources, value)) = previous {
+        if sources.iter().all(|(path, v)| tree.version(path) == *v) {
+            return value.and_then(|value| value.downcast().ok());
+        }
+    }
+
+    let (value, sources) = parse();
+    let value = value.map(Arc::new);
+    if let Ok(mut cache) = cache.lock() {
+        let erased = value
+            .clone()
+            .map(|value| value as Arc<dyn Any + Send + Sync>);
+        cache.insert(key, (sources, erased));
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::SystemTime;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_reparses_changed_files_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("manifest.txt");
+        fs::write(&path, "one").unwrap();
+        let parses = AtomicUsize::new(0);
+        let load = || {
+            load_cached(&SourceTree::default(), &path, |path| {
+                parses.fetch_add(1, Ordering::SeqCst);
+                fs::read_to_string(path).ok()
+            })
+        };
+
+        assert_eq!(load().as_deref().map(String::as_str), Some("one"));
+        assert_eq!(load().as_deref().map(String::as_str), Some("one"));
+        assert_eq!(parses.load(Ordering::SeqCst), 1);
+
+        fs::write(&path, "two").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(load().as_deref().map(String::as_str), Some("two"));
+        assert_eq!(parses.load(Ordering::SeqCst), 2);
+
+        // Values of different types read from the same file are kept apart
+        let length = load_cached(&SourceTree::default(), &path, |path| {
+            Some(fs::metadata(path).ok()?.len())
+        });
+        assert_eq!(length.as_deref(), Some(&3));
+    }
+
+    #[test]
+    fn test_reparses_when_any_source_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let leaf = temp_dir.path().join("leaf.txt");
+        let base = temp_dir.path().join("base.txt");
+        fs::write(&leaf, "leaf").unwrap();
+        fs::write(&base, "base").unwrap();
+        let parses = AtomicUsize::new(0);
+        let load = || {
+            load_cached_from(&SourceTree::default(), &leaf, || {
+                parses.fetch_add(1, Ordering::SeqCst);
+                let value =
+                    fs::read_to_string(&leaf).unwrap() + &fs::read_to_string(&base).unwrap();
+                (Some(value), vec![leaf.clone(), base.clone()])
+            })
+        };
+
+        assert_eq!(load().as_deref().map(String::as_str), Some("leafbase"));
+        assert_eq!(load().as_deref().map(String::as_str), Some("leafbase"));
+        assert_eq!(parses.load(Ordering::SeqCst), 1);
+
+        fs::write(&base, "changed").unwrap();
+        let file = fs::File::options().write(true).open(&base).unwrap();
+        file.set_modified(SystemTime::now() + std::time::Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(load().as_deref().map(String::as_str), Some("leafchanged"));
+        assert_eq!(parses.load(Ordering::SeqCst), 2);
+    }
+}
@@ -8,19 +8,32 @@
 #![allow(clippy::new_without_default)]
 
 pub mod analyzer;
+pub mod barrel;
 pub mod cache;
+pub mod cargo_toml;
+pub mod compile_commands;
+pub mod composer_json;
+pub mod csproj;
 pub mod cycle_detector;
 pub mod dependency_types;
 pub mod function_call_index;
 pub mod graph_builder;
 pub mod graph_traverser;
 pub mod languages;
+pub mod manifest_cache;
+pub mod package_json;
 pub mod parallel_analyzer;
 pub mod parser_pool;
 pub mod path_validator;
+pub mod pyproject;
 pub mod query_engine;
 pub mod resolver;
+pub mod rust_modules;
+pub mod tsconfig;
+pub mod type_hierarchy;
 pub mod type_resolver;
+pub mod workspace;
+pub mod zeitwerk;
 
 #[cfg(test)]
 mod rust_function_call_test;
@@ -28,11 +41,15 @@ mod rust_function_call_test;
 // Re-export commonly used types
 pub use cache::AstCacheV2;
 
+#[cfg(test)]
+mod c_test;
 #[cfg(test)]
 mod javascript_test;
 #[cfg(test)]
 mod python_test;
 #[cfg(test)]
+mod ruby_test;
+#[cfg(test)]
 mod test;
 
 pub use analyzer::{LanguageAnalyzer, SemanticContext, SemanticResult};
@@ -87,7 +104,9 @@ pub fn get_analyzer_for_file(
         "ts" | "tsx" => Some(Box::new(languages::typescript::TypeScriptAnalyzer::new())),
         "go" => Some(Box::new(languages::go::GoAnalyzer::new())),
         "java" => Some(Box::new(languages::java::JavaAnalyzer::new())),
-        "cpp" | "cc" | "cxx" | "hpp" | "h" => Some(Box::new(languages::cpp::CppAnalyzer::new())),
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" | "h" => {
+            Some(Box::new(languages::cpp::CppAnalyzer::new()))
+        }
         "c" => Some(Box::new(languages::c::CAnalyzer::new())),
         "cs" => Some(Box::new(languages::csharp::CSharpAnalyzer::new())),
         "rb" => Some(Box::new(languages::ruby::RubyAnalyzer::new())),
@@ -118,6 +137,13 @@ pub fn get_resolver_for_file(
         "py" => Some(Box::new(languages::python::PythonModuleResolver)),
         "js" | "jsx" => Some(Box::new(languages::javascript::JavaScriptModuleResolver)),
         "ts" | "tsx" => Some(Box::new(languages::typescript::TypeScriptModuleResolver)),
+        "c" => Some(Box::new(languages::c::CModuleResolver)),
+        "cs" => Some(Box::new(languages::csharp::CSharpModuleResolver)),
+        "rb" => Some(Box::new(languages::ruby::RubyModuleResolver)),
+        "php" => Some(Box::new(languages::php::PhpModuleResolver)),
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" | "h" => {
+            Some(Box::new(languages::cpp::CppModuleResolver))
+        }
         _ => None,
     };
 
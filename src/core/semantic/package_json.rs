@@ -0,0 +1,490 @@
+//! `package.json` dependencies of JavaScript and TypeScript projects
+//!
+//! A bare import specifier such as `react` or `@nestjs/common/decorators` is
+//! external when the nearest `package.json` declares the package or it is
+//! installed in `node_modules`, and when it names a Node.js built-in module.
+//! Packages of the same repository, declared as workspace members or linked
+//! into `node_modules`, resolve to their source files instead.
+
+use crate::core::semantic::analyzer::{Import, TypeReference};
+use crate::core::semantic::manifest_cache;
+use crate::core::semantic::resolver::ModuleResolver;
+use crate::core::semantic::tsconfig::find_module_file;
+use crate::core::semantic::workspace::{Ecosystem, Workspace};
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Node.js built-in modules, importable with or without the `node:` prefix
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// Manifest fields naming a package's build output, in order of preference
+const ENTRY_FIELDS: [&str; 4] = ["types", "typings", "module", "main"];
+
+/// The parts of a `package.json` used for module resolution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageManifest {
+    /// The manifest file
+    pub path: PathBuf,
+    /// Package name
+    pub name: Option<String>,
+    /// Package version
+    pub version: Option<String>,
+    /// Version ranges of `dependencies`, `devDependencies` and `peerDependencies`
+    pub dependencies: HashMap<String, String>,
+    /// Entry point of the sources, from the `source` field
+    pub source: Option<String>,
+    /// Entry points of the build output, from `types`, `module` and `main`
+    pub entries: Vec<String>,
+}
+
+impl PackageManifest {
+    /// Load a manifest
+    pub fn load(tree: &SourceTree, path: &Path) -> Result<Self, ContextCreatorError> {
+        let invalid = |message: String| {
+            ContextCreatorError::ConfigError(format!("{}: {message}", path.display()))
+        };
+        let content = tree
+            .read_to_string(path)
+            .map_err(|e| invalid(e.to_string()))?;
+        let json: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?;
+
+        let dependencies = ["dependencies", "devDependencies", "peerDependencies"]
+            .iter()
+            .filter_map(|section| json[section].as_object())
+            .flatten()
+            .map(|(name, range)| (name.clone(), range.as_str().unwrap_or("*").to_string()))
+            .collect();
+        let text = |field: &str| json[field].as_str().map(str::to_string);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            name: text("name"),
+            version: text("version"),
+            dependencies,
+            source: text("source"),
+            entries: ENTRY_FIELDS
+                .iter()
+                .filter_map(|field| text(field))
+                .collect(),
+        })
+    }
+}
+
+/// Whether `specifier` names a Node.js built-in module, e.g. `fs`, `fs/promises`
+/// or `node:test`
+pub fn is_node_builtin(specifier: &str) -> bool {
+    if specifier.starts_with("node:") {
+        return true;
+    }
+    let module = specifier.split('/').next().unwrap_or(specifier);
+    NODE_BUILTINS.contains(&module)
+}
+
+/// Split a bare specifier into its package name and subpath
+///
+/// `@scope/pkg/a/b` gives `("@scope/pkg", Some("a/b"))` and `lodash` gives
+/// `("lodash", None)`. Relative and absolute paths, URLs and aliases such as
+/// `@/components` or `~app/utils` are not package names.
+pub fn package_name(specifier: &str) -> Option<(&str, Option<&str>)> {
+    let first = specifier.chars().next()?;
+    if !(first.is_alphanumeric() || first == '@') || specifier.contains(':') {
+        return None;
+    }
+
+    let segments = if first == '@' { 2 } else { 1 };
+    let mut end = 0;
+    for (index, segment) in specifier.split('/').enumerate().take(segments) {
+        if segment.is_empty() || segment == "@" {
+            return None;
+        }
+        end += segment.len() + usize::from(index > 0);
+    }
+    if segments == 2 && end == specifier.split('/').next()?.len() {
+        return None;
+    }
+
+    let subpath = specifier
+        .get(end + 1..)
+        .filter(|subpath| !subpath.is_empty());
+    Some((&specifier[..end], subpath))
+}
+
+/// Resolve a specifier naming a package of this repository
+///
+/// The import resolves to the entry point or subpath of a workspace member
+/// of `root`, or of a package that `node_modules` links to from inside
+/// `root`.
+pub fn resolve_workspace_package(
+    tree: &SourceTree,
+    specifier: &str,
+    from_file: &Path,
+    root: &Path,
+    extensions: &[&str],
+) -> Option<PathBuf> {
+    let (name, subpath) = package_name(specifier)?;
+    let package_dir = workspace_member(tree, name, from_file, root)?;
+
+    if let Some(subpath) = subpath {
+        return find_module_file(tree, &package_dir.join(subpath), extensions).or_else(|| {
+            find_module_file(tree, &package_dir.join("src").join(subpath), extensions)
+        });
+    }
+
+    // Sources are preferred over build output, which may be missing or stale
+    let manifest = load_cached(tree, &package_dir.join("package.json")).unwrap_or_default();
+    let entry = manifest
+        .source
+        .iter()
+        .map(String::as_str)
+        .chain(["src/index"])
+        .chain(manifest.entries.iter().map(String::as_str))
+        .chain(["index"])
+        .find_map(|entry| find_module_file(tree, &package_dir.join(entry), extensions));
+    entry
+}
+
+/// The directory of the package `name` if it belongs to this repository
+///
+/// Besides the declared workspace members, a `node_modules` link that leads
+/// back into `root` outside any `node_modules` directory is a member.
+fn workspace_member(
+    tree: &SourceTree,
+    name: &str,
+    from_file: &Path,
+    root: &Path,
+) -> Option<PathBuf> {
+    if let Some(member) = Workspace::for_root(tree, root).member(Ecosystem::Npm, name) {
+        return Some(member.dir.clone());
+    }
+
+    let package_dir = ancestors_within(from_file, root)
+        .map(|dir| dir.join("node_modules").join(name))
+        .find(|dir| tree.exists(dir))?;
+    let package_dir = tree.canonicalize(&package_dir).ok()?;
+    let is_member = package_dir.starts_with(tree.canonicalize(root).ok()?)
+        && !package_dir
+            .components()
+            .any(|component| component == Component::Normal("node_modules".as_ref()));
+    is_member.then_some(package_dir)
+}
+
+/// The package `specifier` comes from, with its version, if it is a
+/// dependency of the project
+///
+/// The installed version is preferred over the range declared in the
+/// nearest `package.json` that lists the package, e.g. `react v18.2.0` or
+/// `react ^18.0.0`. Imports with the `node:` prefix come from `node`.
+pub fn external_package(
+    tree: &SourceTree,
+    specifier: &str,
+    from_file: &Path,
+    root: &Path,
+) -> Option<String> {
+    if specifier.starts_with("node:") {
+        return Some("node".to_string());
+    }
+    let (name, _) = package_name(specifier)?;
+    if workspace_member(tree, name, from_file, root).is_some() {
+        return None;
+    }
+
+    let installed = ancestors_within(from_file, root)
+        .map(|dir| dir.join("node_modules").join(name).join("package.json"))
+        .find(|manifest| tree.is_file(manifest))
+        .and_then(|manifest| load_cached(tree, &manifest));
+    if let Some(version) = installed.as_ref().and_then(|m| m.version.as_ref()) {
+        return Some(format!("{name} v{version}"));
+    }
+
+    let declared = ancestors_within(from_file, root)
+        .filter_map(|dir| load_cached(tree, &dir.join("package.json")))
+        .find_map(|manifest| manifest.dependencies.get(name).cloned());
+    match (declared, installed) {
+        (Some(range), _) => Some(format!("{name} {range}")),
+        (None, Some(_)) => Some(name.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Mark the type references imported from external packages
+///
+/// Types named in an import of a dependency or a Node.js built-in module are
+/// external, and record the import as their module and the package they
+/// come from. A built-in name that `resolver` resolves to a local module is
+/// shadowed by it and stays internal.
+#[allow(clippy::too_many_arguments)]
+pub fn mark_external_types(
+    tree: &SourceTree,
+    imports: &[Import],
+    type_refs: &mut [TypeReference],
+    from_file: &Path,
+    root: &Path,
+    resolver: &dyn ModuleResolver,
+) {
+    for import in imports.iter().filter(|import| !import.is_relative) {
+        let package = external_package(tree, &import.module, from_file, root).or_else(|| {
+            let builtin = is_node_builtin(&import.module)
+                && !resolver
+                    .resolve_import(&import.module, from_file, root, tree)
+                    .is_ok_and(|resolved| !resolved.is_external);
+            builtin.then(|| "node".to_string())
+        });
+        let Some(package) = package else {
+            continue;
+        };
+
+        for type_ref in type_refs
+            .iter_mut()
+            .filter(|type_ref| import.items.contains(&type_ref.name))
+        {
+            type_ref.is_external = true;
+            type_ref.module = Some(import.module.clone());
+            type_ref.external_package = Some(package.clone());
+        }
+    }
+}
+
+/// The directory of `file` and its parents, up to `root`
+fn ancestors_within<'a>(file: &'a Path, root: &'a Path) -> impl Iterator<Item = &'a Path> {
+    file.parent()
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .take_while(move |dir| dir.starts_with(root))
+}
+
+/// The manifest at `path`, parsed once per modification
+fn load_cached(tree: &SourceTree, path: &Path) -> Option<Arc<PackageManifest>> {
+    manifest_cache::load_cached(tree, path, |path| match PackageManifest::load(tree, path) {
+        Ok(manifest) => Some(manifest),
+        Err(e) => {
+            tracing::debug!("Ignoring {}: {e}", path.display());
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::semantic::languages::typescript::TypeScriptModuleResolver;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_package_name() {
+        assert_eq!(package_name("react"), Some(("react", None)));
+        assert_eq!(package_name("lodash/fp"), Some(("lodash", Some("fp"))));
+        assert_eq!(
+            package_name("@nestjs/common/decorators/core"),
+            Some(("@nestjs/common", Some("decorators/core")))
+        );
+        assert_eq!(package_name("@types/node"), Some(("@types/node", None)));
+        assert_eq!(package_name("./local"), None);
+        assert_eq!(package_name("@/components/Button"), None);
+        assert_eq!(package_name("~app/utils"), None);
+        assert_eq!(package_name("@scope"), None);
+        assert_eq!(package_name("node:fs"), None);
+    }
+
+    #[test]
+    fn test_is_node_builtin() {
+        assert!(is_node_builtin("fs"));
+        assert!(is_node_builtin("fs/promises"));
+        assert!(is_node_builtin("node:test"));
+        assert!(!is_node_builtin("react"));
+    }
+
+    #[test]
+    fn test_external_package_prefers_installed_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join("node_modules/react")).unwrap();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "dependencies": { "react": "^18.0.0" }, "devDependencies": { "vitest": "1.6.0" } }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("node_modules/react/package.json"),
+            r#"{ "name": "react", "version": "18.2.0" }"#,
+        )
+        .unwrap();
+        let file = root.join("src/app.tsx");
+
+        assert_eq!(
+            external_package(&SourceTree::default(), "react/jsx-runtime", &file, root),
+            Some("react v18.2.0".to_string())
+        );
+        assert_eq!(
+            external_package(&SourceTree::default(), "vitest", &file, root),
+            Some("vitest 1.6.0".to_string())
+        );
+        assert_eq!(
+            external_package(&SourceTree::default(), "node:fs", &file, root),
+            Some("node".to_string())
+        );
+        assert_eq!(
+            external_package(&SourceTree::default(), "left-pad", &file, root),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_workspace_package_resolves_to_sources() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("packages/shared/src")).unwrap();
+        fs::create_dir_all(root.join("packages/web")).unwrap();
+        fs::create_dir_all(root.join("node_modules/@acme")).unwrap();
+        fs::write(
+            root.join("packages/shared/package.json"),
+            r#"{ "name": "@acme/shared", "version": "0.1.0", "main": "dist/index.js" }"#,
+        )
+        .unwrap();
+        fs::write(root.join("packages/shared/src/index.ts"), "").unwrap();
+        fs::write(root.join("packages/shared/src/format.ts"), "").unwrap();
+        std::os::unix::fs::symlink(
+            root.join("packages/shared"),
+            root.join("node_modules/@acme/shared"),
+        )
+        .unwrap();
+        let file = root.join("packages/web/app.ts");
+        let extensions = ["ts", "tsx", "js"];
+        let shared = root.canonicalize().unwrap().join("packages/shared/src");
+
+        assert_eq!(
+            resolve_workspace_package(
+                &SourceTree::default(),
+                "@acme/shared",
+                &file,
+                root,
+                &extensions
+            ),
+            Some(shared.join("index.ts"))
+        );
+        assert_eq!(
+            resolve_workspace_package(
+                &SourceTree::default(),
+                "@acme/shared/format",
+                &file,
+                root,
+                &extensions
+            ),
+            Some(shared.join("format.ts"))
+        );
+        assert_eq!(
+            external_package(&SourceTree::default(), "@acme/shared", &file, root),
+            None
+        );
+    }
+
+    #[test]
+    fn test_mark_external_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "dependencies": { "@nestjs/common": "^10.0.0" } }"#,
+        )
+        .unwrap();
+        let import = |module: &str, items: &[&str]| Import {
+            module: module.to_string(),
+            items: items.iter().map(|item| item.to_string()).collect(),
+            is_relative: module.starts_with('.'),
+            is_dynamic: false,
+            line: 1,
+        };
+        let type_ref = |name: &str| TypeReference {
+            name: name.to_string(),
+            module: None,
+            line: 2,
+            definition_path: None,
+            is_external: false,
+            external_package: None,
+        };
+        let imports = [
+            import("@nestjs/common", &["Injectable"]),
+            import("node:events", &["EventEmitter"]),
+            import("./user", &["User"]),
+            import("events", &["Bus"]),
+        ];
+        let mut type_refs = [
+            type_ref("Injectable"),
+            type_ref("EventEmitter"),
+            type_ref("User"),
+            type_ref("Bus"),
+        ];
+        fs::create_dir_all(root.join("events")).unwrap();
+        fs::write(root.join("events/index.ts"), "export interface Bus {}\n").unwrap();
+
+        mark_external_types(
+            &SourceTree::default(),
+            &imports,
+            &mut type_refs,
+            &root.join("app.ts"),
+            root,
+            &TypeScriptModuleResolver,
+        );
+
+        assert_eq!(
+            type_refs[0].external_package.as_deref(),
+            Some("@nestjs/common ^10.0.0")
+        );
+        assert_eq!(type_refs[0].module.as_deref(), Some("@nestjs/common"));
+        assert_eq!(type_refs[1].external_package.as_deref(), Some("node"));
+        assert!(!type_refs[2].is_external);
+        assert!(
+            !type_refs[3].is_external,
+            "A local module shadows the built-in"
+        );
+    }
+}
@@ -4,10 +4,12 @@
 //! It follows the Single Responsibility Principle by focusing solely on parallelization.
 
 use crate::core::cache::FileCache;
+use crate::core::progress::{Phase, ProgressReporter};
 use crate::core::semantic::analyzer::SemanticContext;
 use crate::core::semantic::dependency_types::{DependencyEdgeType, FileAnalysisResult};
 use crate::core::semantic::{get_analyzer_for_file, get_resolver_for_file};
 use crate::core::semantic_cache::SemanticCache;
+use crate::core::source_tree::SourceTree;
 use anyhow::Result;
 use rayon::prelude::*;
 use std::path::{Path, PathBuf};
@@ -25,6 +27,10 @@ pub struct AnalysisOptions {
     pub include_types: bool,
     /// Whether to include function calls
     pub include_functions: bool,
+    /// Directories searched for C and C++ includes
+    pub include_paths: Vec<PathBuf>,
+    /// Receives analysis progress and stops the analysis when cancelled
+    pub reporter: ProgressReporter,
 }
 
 impl Default for AnalysisOptions {
@@ -34,6 +40,8 @@ impl Default for AnalysisOptions {
             trace_imports: true,
             include_types: true,
             include_functions: true,
+            include_paths: Vec::new(),
+            reporter: ProgressReporter::default(),
         }
     }
 }
@@ -77,6 +85,16 @@ impl<'a> ParallelAnalyzer<'a> {
         }
     }
 
+    /// Create a new ParallelAnalyzer that shares a semantic cache across runs
+    pub fn with_semantic_cache(cache: &'a FileCache, semantic_cache: Arc<SemanticCache>) -> Self {
+        Self {
+            cache,
+            semantic_cache,
+            thread_count: None,
+            options: AnalysisOptions::default(),
+        }
+    }
+
     /// Analyze multiple files in parallel
     pub fn analyze_files(
         &self,
@@ -97,11 +115,27 @@ impl<'a> ParallelAnalyzer<'a> {
         let errors = Arc::new(Mutex::new(Vec::new()));
         let errors_ref = &errors;
 
-        // Analyze files in parallel
+        // Analyze files in parallel, skipping the remaining files once cancelled
+        let analyzed = std::sync::atomic::AtomicUsize::new(0);
         let results: Vec<FileAnalysisResult> = files
             .par_iter()
             .enumerate()
             .map(|(index, file_path)| {
+                if options.reporter.is_cancelled() {
+                    return FileAnalysisResult {
+                        file_index: index,
+                        imports: Vec::new(),
+                        function_calls: Vec::new(),
+                        type_references: Vec::new(),
+                        exported_functions: Vec::new(),
+                        content_hash: None,
+                        error: None,
+                    };
+                }
+                options
+                    .reporter
+                    .advance(Phase::Analyze, &analyzed, files.len());
+
                 match self.analyze_single_file(index, file_path, project_root, options, valid_files)
                 {
                     Ok(result) => result,
@@ -123,6 +157,7 @@ impl<'a> ParallelAnalyzer<'a> {
                 }
             })
             .collect();
+        options.reporter.check()?;
 
         // Print collected errors
         let error_list = errors.lock().unwrap();
@@ -172,6 +207,14 @@ impl<'a> ParallelAnalyzer<'a> {
             hasher.finish()
         };
 
+        let context = SemanticContext::new(
+            file_path.to_path_buf(),
+            project_root.to_path_buf(),
+            options.semantic_depth,
+        )
+        .with_include_paths(options.include_paths.clone())
+        .with_source_tree(SourceTree::new(self.cache.snapshot().cloned()));
+
         // Check semantic cache first
         let analysis_result =
             if let Some(cached_result) = self.semantic_cache.get(file_path, content_hash) {
@@ -179,14 +222,6 @@ impl<'a> ParallelAnalyzer<'a> {
                 (*cached_result).clone()
             } else {
                 // Cache miss - perform analysis
-                // Create semantic context
-                let context = SemanticContext::new(
-                    file_path.to_path_buf(),
-                    project_root.to_path_buf(),
-                    options.semantic_depth,
-                );
-
-                // Perform analysis
                 let result = analyzer.analyze_file(file_path, &content, &context)?;
 
                 // Store in cache
@@ -198,12 +233,7 @@ impl<'a> ParallelAnalyzer<'a> {
 
         // Process imports if enabled
         let imports = if options.trace_imports {
-            self.process_imports(
-                file_path,
-                project_root,
-                &analysis_result.imports,
-                valid_files,
-            )?
+            self.process_imports(&context, &analysis_result.imports, valid_files)?
         } else {
             Vec::new()
         };
@@ -241,12 +271,12 @@ impl<'a> ParallelAnalyzer<'a> {
     /// Process imports to create typed edges
     fn process_imports(
         &self,
-        file_path: &Path,
-        project_root: &Path,
+        context: &SemanticContext,
         imports: &[crate::core::semantic::analyzer::Import],
         _valid_files: &std::collections::HashSet<PathBuf>,
     ) -> Result<Vec<(PathBuf, DependencyEdgeType)>> {
         let mut typed_imports = Vec::new();
+        let file_path = &context.current_file;
 
         // Get resolver for the file type
         if let Some(resolver) = get_resolver_for_file(file_path)? {
@@ -260,20 +290,22 @@ impl<'a> ParallelAnalyzer<'a> {
                 );
 
                 // Try to resolve the import
-                match resolver.resolve_import(&import.module, file_path, project_root) {
-                    Ok(resolved) => {
-                        tracing::debug!(
-                            "  Resolved to: {} (external: {})",
-                            resolved.path.display(),
-                            resolved.is_external
-                        );
-                        if !resolved.is_external {
-                            // For trace_imports, we want to track ALL imports,
-                            // not just those in valid_files, to support file expansion
-                            let edge_type = DependencyEdgeType::Import {
-                                symbols: import.items.clone(),
-                            };
-                            typed_imports.push((resolved.path, edge_type));
+                match resolver.resolve_import_in_context(&import.module, &import.items, context) {
+                    Ok(resolved_paths) => {
+                        for resolved in resolved_paths {
+                            tracing::debug!(
+                                "  Resolved to: {} (external: {})",
+                                resolved.path.display(),
+                                resolved.is_external
+                            );
+                            if !resolved.is_external {
+                                // For trace_imports, we want to track ALL imports,
+                                // not just those in valid_files, to support file expansion
+                                let edge_type = DependencyEdgeType::Import {
+                                    symbols: import.items.clone(),
+                                };
+                                typed_imports.push((resolved.path, edge_type));
+                            }
                         }
                     }
                     Err(e) => {
@@ -288,7 +320,7 @@ impl<'a> ParallelAnalyzer<'a> {
                                     let potential_path =
                                         parent.join(format!("{module_base}.{ext}"));
 
-                                    if potential_path.exists() {
+                                    if context.source_tree.exists(&potential_path) {
                                         let edge_type = DependencyEdgeType::Import {
                                             symbols: import.items.clone(),
                                         };
@@ -301,7 +333,9 @@ impl<'a> ParallelAnalyzer<'a> {
                             // Fallback: For trace_imports, track the import even if unresolved
                             // This allows the file expander to attempt resolution later
                             let fallback_path = PathBuf::from(&import.module);
-                            if fallback_path.is_absolute() && fallback_path.exists() {
+                            if fallback_path.is_absolute()
+                                && context.source_tree.exists(&fallback_path)
+                            {
                                 let edge_type = DependencyEdgeType::Import {
                                     symbols: import.items.clone(),
                                 };
@@ -315,7 +349,7 @@ impl<'a> ParallelAnalyzer<'a> {
             // No resolver available - for trace_imports, track absolute paths that exist
             for import in imports {
                 let import_path = PathBuf::from(&import.module);
-                if import_path.is_absolute() && import_path.exists() {
+                if import_path.is_absolute() && context.source_tree.exists(&import_path) {
                     let edge_type = DependencyEdgeType::Import {
                         symbols: import.items.clone(),
                     };
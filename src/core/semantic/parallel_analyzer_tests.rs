@@ -77,6 +77,8 @@ fn test_parallel_file_analysis() {
         trace_imports: true,
         include_types: true,
         include_functions: true,
+        include_paths: Vec::new(),
+        reporter: Default::default(),
     };
 
     let valid_files: HashSet<PathBuf> = files.iter().cloned().collect();
@@ -115,6 +117,8 @@ fn test_thread_pool_management() {
         trace_imports: true,
         include_types: true,
         include_functions: true,
+        include_paths: Vec::new(),
+        reporter: Default::default(),
     };
 
     // Should handle analysis with limited threads
@@ -151,11 +155,13 @@ fn test_analysis_error_handling() {
         trace_imports: true,
         include_types: true,
         include_functions: true,
+        include_paths: Vec::new(),
+        reporter: Default::default(),
     };
 
     let valid_files: HashSet<PathBuf> = [bad_file.clone()].iter().cloned().collect();
     let results = analyzer
-        .analyze_files(&[bad_file.clone()], dir, &options, &valid_files)
+        .analyze_files(std::slice::from_ref(&bad_file), dir, &options, &valid_files)
         .unwrap();
 
     // Should still return a result, but with limited information
@@ -206,6 +212,8 @@ fn test_large_file_set() {
         trace_imports: false,
         include_types: false,
         include_functions: true,
+        include_paths: Vec::new(),
+        reporter: Default::default(),
     };
 
     let start = std::time::Instant::now();
@@ -311,6 +319,8 @@ fn test_selective_analysis_options() {
         trace_imports: true,
         include_types: false,
         include_functions: false,
+        include_paths: Vec::new(),
+        reporter: Default::default(),
     };
 
     let valid_files: HashSet<PathBuf> = files.iter().cloned().collect();
@@ -135,6 +135,24 @@ impl ParserPoolManager {
                 .expect("Failed to create Java parser pool"),
         );
 
+        // C
+        pools.insert(
+            "c",
+            Pool::builder(ParserManager::new(tree_sitter_c::language(), "c"))
+                .config(pool_config)
+                .build()
+                .expect("Failed to create C parser pool"),
+        );
+
+        // C++
+        pools.insert(
+            "cpp",
+            Pool::builder(ParserManager::new(tree_sitter_cpp::language(), "cpp"))
+                .config(pool_config)
+                .build()
+                .expect("Failed to create C++ parser pool"),
+        );
+
         Self { pools }
     }
 
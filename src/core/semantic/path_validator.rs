@@ -6,6 +6,7 @@
 //! 3. Proper URL decoding before validation
 //! 4. No manual path resolution
 
+use crate::core::source_tree::SourceTree;
 use crate::utils::error::ContextCreatorError;
 use std::path::{Path, PathBuf};
 
@@ -13,6 +14,7 @@ use std::path::{Path, PathBuf};
 pub fn validate_import_path(
     base_dir: &Path,
     import_path: &Path,
+    tree: &SourceTree,
 ) -> Result<PathBuf, ContextCreatorError> {
     // 1. Base directory must be absolute
     if !base_dir.is_absolute() {
@@ -44,7 +46,7 @@ pub fn validate_import_path(
 
     // 6. CRITICAL: Only use canonicalize - never fall back to manual resolution
     // If the file doesn't exist, that's a legitimate error, not a security bypass
-    let canonical_path = full_path.canonicalize().map_err(|e| {
+    let canonical_path = tree.canonicalize(&full_path).map_err(|e| {
         ContextCreatorError::InvalidPath(format!(
             "Path does not exist or cannot be resolved: {} ({})",
             full_path.display(),
@@ -52,7 +54,7 @@ pub fn validate_import_path(
         ))
     })?;
 
-    let canonical_base = base_dir.canonicalize().map_err(|e| {
+    let canonical_base = tree.canonicalize(base_dir).map_err(|e| {
         ContextCreatorError::SecurityError(format!("Cannot canonicalize base directory: {e}"))
     })?;
 
@@ -99,8 +101,15 @@ pub fn validate_module_name(module_name: &str) -> Result<(), ContextCreatorError
 
     // Allow only safe characters using a fast check
     let valid_chars = module_name.chars().all(|c| {
-        c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '@' || c == '/' || c == ':'
-        // For scoped packages like @types/node
+        c.is_alphanumeric()
+            || c == '_'
+            || c == '-'
+            || c == '.'
+            || c == '@'
+            || c == '/'
+            || c == ':'
+            || c == '~'
+        // For scoped packages like @types/node and aliases like ~app/utils
     });
 
     if !valid_chars {
@@ -112,6 +121,22 @@ pub fn validate_module_name(module_name: &str) -> Result<(), ContextCreatorError
     Ok(())
 }
 
+/// Validate a JavaScript-style module specifier: a module name, or a path
+/// relative to the importing file that may climb to parent directories.
+/// Where a relative path leads is checked by `validate_import_path` once
+/// it is resolved.
+pub fn validate_module_specifier(specifier: &str) -> Result<(), ContextCreatorError> {
+    let mut name = specifier;
+    while let Some(rest) = name.strip_prefix("../").or_else(|| name.strip_prefix("./")) {
+        name = rest;
+    }
+    // `.`, `..` and `../..` name the index of a directory
+    if matches!(name, "" | "." | "..") && !specifier.is_empty() {
+        return Ok(());
+    }
+    validate_module_name(name)
+}
+
 /// Decode URL-encoded path - handles all encoding variants
 fn decode_url_path(path: &str) -> Result<String, ContextCreatorError> {
     // Fast path - if no % sign, no decoding needed
@@ -159,7 +184,8 @@ mod tests {
         fs::write(base.join("src/lib.rs"), "").unwrap();
 
         // Valid paths should work
-        let result = validate_import_path(base, &PathBuf::from("src/lib.rs"));
+        let result =
+            validate_import_path(base, &PathBuf::from("src/lib.rs"), &SourceTree::default());
         assert!(result.is_ok());
     }
 
@@ -174,7 +200,7 @@ mod tests {
 
         // Try to escape using ../
         let escape_path = base.join("src/../../../etc/passwd");
-        let result = validate_import_path(base, &escape_path);
+        let result = validate_import_path(base, &escape_path, &SourceTree::default());
         assert!(result.is_err());
     }
 
@@ -190,7 +216,7 @@ mod tests {
         ];
 
         for path in encoded_paths {
-            let result = validate_import_path(base, &PathBuf::from(path));
+            let result = validate_import_path(base, &PathBuf::from(path), &SourceTree::default());
             assert!(result.is_err(), "Should block: {path}");
         }
     }
@@ -207,7 +233,7 @@ mod tests {
             let link_path = base.join("evil_link");
             symlink("/etc/passwd", &link_path).unwrap();
 
-            let result = validate_import_path(base, &link_path);
+            let result = validate_import_path(base, &link_path, &SourceTree::default());
             assert!(result.is_err());
         }
 
@@ -225,7 +251,11 @@ mod tests {
         let base = temp_dir.path();
 
         // Nonexistent files should fail (fail closed)
-        let result = validate_import_path(base, &PathBuf::from("does/not/exist.rs"));
+        let result = validate_import_path(
+            base,
+            &PathBuf::from("does/not/exist.rs"),
+            &SourceTree::default(),
+        );
         assert!(result.is_err());
     }
 
@@ -243,4 +273,18 @@ mod tests {
         assert!(validate_module_name(&"a".repeat(256)).is_err());
         assert!(validate_module_name("rm -rf /").is_err());
     }
+
+    #[test]
+    fn test_module_specifier_validation() {
+        assert!(validate_module_specifier("lodash").is_ok());
+        assert!(validate_module_specifier("./utils").is_ok());
+        assert!(validate_module_specifier("../../api/client").is_ok());
+        assert!(validate_module_specifier("..").is_ok());
+        assert!(validate_module_specifier("../..").is_ok());
+
+        assert!(validate_module_specifier("").is_err());
+        assert!(validate_module_specifier("./a/../../etc/passwd").is_err());
+        assert!(validate_module_specifier("../name\0with\0null").is_err());
+        assert!(validate_module_specifier("../rm -rf /").is_err());
+    }
 }
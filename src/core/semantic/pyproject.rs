@@ -0,0 +1,178 @@
+//! Python source roots declared in `pyproject.toml`
+//!
+//! Absolute imports are resolved against the project root, a `src/` layout
+//! directory and the package directories the build backend is told about:
+//! setuptools `package-dir` and `packages.find.where`, poetry `packages` and
+//! hatch wheel `packages`.
+
+use crate::core::semantic::manifest_cache;
+use crate::core::source_tree::SourceTree;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Where the packages of a Python project live
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PythonProject {
+    /// Directories top-level packages are found in, in search order
+    pub source_roots: Vec<PathBuf>,
+    /// Directories of packages mapped by setuptools `package-dir`, by name
+    pub package_dirs: HashMap<String, PathBuf>,
+}
+
+impl PythonProject {
+    /// Read the source roots of the project at `root`
+    pub fn discover(tree: &SourceTree, root: &Path) -> Self {
+        let mut project = Self {
+            source_roots: vec![root.to_path_buf()],
+            package_dirs: HashMap::new(),
+        };
+        if tree.is_dir(&root.join("src")) {
+            project.add_root(root.join("src"));
+        }
+
+        let manifest = tree
+            .read_to_string(&root.join("pyproject.toml"))
+            .ok()
+            .and_then(|content| toml::from_str::<toml::Value>(&content).ok());
+        let Some(tool) = manifest.as_ref().and_then(|manifest| manifest.get("tool")) else {
+            return project;
+        };
+        let relative = |path: &str| (!path.contains("..")).then(|| root.join(path));
+
+        if let Some(setuptools) = tool.get("setuptools") {
+            let package_dir = setuptools
+                .get("package-dir")
+                .and_then(|dirs| dirs.as_table());
+            for (package, dir) in package_dir.into_iter().flatten() {
+                let Some(dir) = dir.as_str().and_then(relative) else {
+                    continue;
+                };
+                if package.is_empty() {
+                    project.add_root(dir);
+                } else {
+                    project.package_dirs.insert(package.clone(), dir);
+                }
+            }
+
+            let find = setuptools
+                .get("packages")
+                .and_then(|packages| packages.get("find"));
+            let wheres = find
+                .and_then(|find| find.get("where"))
+                .and_then(|wheres| wheres.as_array());
+            for dir in wheres.into_iter().flatten() {
+                if let Some(dir) = dir.as_str().and_then(relative) {
+                    project.add_root(dir);
+                }
+            }
+        }
+
+        // poetry: packages = [{ include = "pkg", from = "src" }]
+        let poetry_packages = tool
+            .get("poetry")
+            .and_then(|poetry| poetry.get("packages"))
+            .and_then(|packages| packages.as_array());
+        for package in poetry_packages.into_iter().flatten() {
+            if let Some(dir) = package
+                .get("from")
+                .and_then(|from| from.as_str())
+                .and_then(relative)
+            {
+                project.add_root(dir);
+            }
+        }
+
+        // hatch: packages = ["src/pkg"]
+        let hatch_packages = tool
+            .get("hatch")
+            .and_then(|hatch| hatch.get("build"))
+            .and_then(|build| build.get("targets"))
+            .and_then(|targets| targets.get("wheel"))
+            .and_then(|wheel| wheel.get("packages"))
+            .and_then(|packages| packages.as_array());
+        for package in hatch_packages.into_iter().flatten() {
+            let dir = package.as_str().and_then(relative);
+            if let Some(parent) = dir.as_deref().and_then(Path::parent) {
+                project.add_root(parent.to_path_buf());
+            }
+        }
+
+        project
+    }
+
+    /// The project at `root`, reusing the discovered one while its
+    /// `pyproject.toml` is unchanged
+    pub fn for_root(tree: &SourceTree, root: &Path) -> Arc<PythonProject> {
+        manifest_cache::load_cached(tree, &root.join("pyproject.toml"), |_| {
+            Some(Self::discover(tree, root))
+        })
+        .unwrap_or_else(|| Arc::new(Self::discover(tree, root)))
+    }
+
+    fn add_root(&mut self, dir: PathBuf) {
+        let dir = PathBuf::from_iter(dir.components());
+        if !self.source_roots.contains(&dir) {
+            self.source_roots.push(dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_src_layout_is_a_source_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+
+        let project = PythonProject::discover(&SourceTree::default(), root);
+        assert_eq!(
+            project.source_roots,
+            vec![root.to_path_buf(), root.join("src")]
+        );
+    }
+
+    #[test]
+    fn test_pyproject_package_locations() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("pyproject.toml"),
+            r#"
+[tool.setuptools]
+package-dir = { "" = "lib", "legacy" = "old/legacy_pkg" }
+
+[tool.setuptools.packages.find]
+where = ["python", "../outside"]
+
+[tool.poetry]
+packages = [{ include = "app", from = "source" }, { include = "tools" }]
+
+[tool.hatch.build.targets.wheel]
+packages = ["pkgs/acme"]
+"#,
+        )
+        .unwrap();
+
+        let project = PythonProject::discover(&SourceTree::default(), root);
+        assert_eq!(
+            project.source_roots,
+            vec![
+                root.to_path_buf(),
+                root.join("lib"),
+                root.join("python"),
+                root.join("source"),
+                root.join("pkgs"),
+            ]
+        );
+        assert_eq!(
+            project.package_dirs.get("legacy"),
+            Some(&root.join("old/legacy_pkg"))
+        );
+    }
+}
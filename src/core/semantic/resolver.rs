@@ -1,5 +1,7 @@
 //! Module resolution for converting import strings to file paths
 
+use crate::core::semantic::analyzer::SemanticContext;
+use crate::core::source_tree::SourceTree;
 use crate::utils::error::ContextCreatorError;
 use std::path::{Path, PathBuf};
 
@@ -16,14 +18,53 @@ pub struct ResolvedPath {
 
 /// Trait for language-specific module resolution
 pub trait ModuleResolver: Send + Sync {
-    /// Resolve a module import to a file path
+    /// Resolve a module import to a file path, looking files up in `tree`
     fn resolve_import(
         &self,
         module_path: &str,
         from_file: &Path,
         base_dir: &Path,
+        tree: &SourceTree,
     ) -> Result<ResolvedPath, ContextCreatorError>;
 
+    /// Resolve an import of specific items to the files that define them
+    ///
+    /// Languages whose packages re-export items of other modules override
+    /// this to follow the re-exports; by default the module itself is returned.
+    #[allow(clippy::too_many_arguments)]
+    fn resolve_import_items(
+        &self,
+        module_path: &str,
+        _items: &[String],
+        from_file: &Path,
+        base_dir: &Path,
+        tree: &SourceTree,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        self.resolve_import(module_path, from_file, base_dir, tree)
+            .map(|resolved| vec![resolved])
+    }
+
+    /// Resolve an import of items of `context.current_file` with the settings
+    /// of `context`
+    ///
+    /// Languages searching configured directories, such as the include paths
+    /// of C and C++, override this; by default it is
+    /// [`resolve_import_items`](Self::resolve_import_items).
+    fn resolve_import_in_context(
+        &self,
+        module_path: &str,
+        items: &[String],
+        context: &SemanticContext,
+    ) -> Result<Vec<ResolvedPath>, ContextCreatorError> {
+        self.resolve_import_items(
+            module_path,
+            items,
+            &context.current_file,
+            &context.base_dir,
+            &context.source_tree,
+        )
+    }
+
     /// Get common file extensions for this language
     fn get_file_extensions(&self) -> Vec<&'static str>;
 
@@ -43,26 +84,30 @@ pub struct ResolverUtils;
 
 impl ResolverUtils {
     /// Try to find a file with different extensions
-    pub fn find_with_extensions(base_path: &Path, extensions: &[&str]) -> Option<PathBuf> {
+    pub fn find_with_extensions(
+        tree: &SourceTree,
+        base_path: &Path,
+        extensions: &[&str],
+    ) -> Option<PathBuf> {
         // Try exact path first
-        if base_path.exists() && base_path.is_file() {
+        if tree.exists(base_path) && tree.is_file(base_path) {
             return Some(base_path.to_path_buf());
         }
 
         // Try with each extension
         for ext in extensions {
             let with_ext = base_path.with_extension(ext);
-            if with_ext.exists() && with_ext.is_file() {
+            if tree.exists(&with_ext) && tree.is_file(&with_ext) {
                 return Some(with_ext);
             }
         }
 
         // Try as directory with index file
-        if base_path.exists() && base_path.is_dir() {
+        if tree.exists(base_path) && tree.is_dir(base_path) {
             for index_name in &["index", "mod", "__init__"] {
                 for ext in extensions {
                     let index_path = base_path.join(format!("{index_name}.{ext}"));
-                    if index_path.exists() && index_path.is_file() {
+                    if tree.exists(&index_path) && tree.is_file(&index_path) {
                         return Some(index_path);
                     }
                 }
@@ -79,6 +124,7 @@ impl ResolverUtils {
 
     /// Resolve a relative import path
     pub fn resolve_relative(
+        tree: &SourceTree,
         import_path: &str,
         from_file: &Path,
         extensions: &[&str],
@@ -99,14 +145,14 @@ impl ResolverUtils {
         }
 
         let target = current_dir.join(clean_path);
-        Self::find_with_extensions(&target, extensions)
+        Self::find_with_extensions(tree, &target, extensions)
     }
 
     /// Check if a path is within the project directory
-    pub fn is_within_project(path: &Path, base_dir: &Path) -> bool {
-        path.canonicalize()
+    pub fn is_within_project(tree: &SourceTree, path: &Path, base_dir: &Path) -> bool {
+        tree.canonicalize(path)
             .ok()
-            .and_then(|p| base_dir.canonicalize().ok().map(|b| p.starts_with(b)))
+            .and_then(|p| tree.canonicalize(base_dir).ok().map(|b| p.starts_with(b)))
             .unwrap_or(false)
     }
 }
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use crate::core::semantic::{analyzer::*, languages::ruby::RubyAnalyzer};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_ruby_analysis() {
+        let analyzer = RubyAnalyzer::new();
+        let content = r#"
+require "json"
+require_relative "../support/helpers"
+autoload :Parser, "importers/parser"
+
+module Admin
+  class UsersController < ApplicationController
+    def index
+      @users = User.where(active: true)
+      render json: Reports::Summary.build(@users)
+    end
+
+    def self.policy
+      authorize!(current_user)
+    end
+  end
+end
+"#;
+        let path = PathBuf::from("app/controllers/admin/users_controller.rb");
+        let context = SemanticContext::new(path.clone(), PathBuf::from("."), 3);
+
+        let result = analyzer.analyze_file(&path, content, &context).unwrap();
+
+        let imports: Vec<(&str, bool)> = result
+            .imports
+            .iter()
+            .map(|i| (i.module.as_str(), i.is_relative))
+            .collect();
+        assert_eq!(
+            imports,
+            vec![
+                ("json", false),
+                ("../support/helpers", true),
+                ("importers/parser", false),
+                ("ApplicationController", false),
+                ("User", false),
+                ("Reports::Summary", false),
+            ]
+        );
+
+        let calls: Vec<(&str, Option<&str>)> = result
+            .function_calls
+            .iter()
+            .map(|c| (c.name.as_str(), c.module.as_deref()))
+            .collect();
+        for call in [
+            ("where", Some("User")),
+            ("build", Some("Reports::Summary")),
+            ("render", None),
+            ("authorize!", None),
+        ] {
+            assert!(calls.contains(&call), "Should find call {call:?}");
+        }
+
+        let definitions: Vec<&str> = result
+            .exported_functions
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(definitions, vec!["index", "policy"]);
+
+        let types: Vec<&str> = result
+            .type_references
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        for name in ["ApplicationController", "User", "Summary"] {
+            assert!(types.contains(&name), "Should find constant {name}");
+        }
+    }
+}
@@ -0,0 +1,525 @@
+//! Rust module trees
+//!
+//! Maps Rust paths to the files of the modules they name, the way rustc
+//! loads them: `mod` declarations with `#[path]` attributes, `foo.rs` with
+//! its submodules in `foo/` next to `foo/mod.rs`, `crate::`, `self::` and
+//! `super::` prefixes, and other crates of the repository reached through
+//! `Cargo.toml` path dependencies. Items that a module re-exports with
+//! `pub use` are followed to the module that defines them.
+
+use crate::core::semantic::cargo_toml::CrateManifest;
+use crate::core::semantic::manifest_cache;
+use crate::core::semantic::workspace::{Ecosystem, Workspace};
+use crate::core::source_tree::SourceTree;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tree_sitter::{Node, Parser};
+
+/// Re-export chains longer than this are not followed
+const MAX_REEXPORT_DEPTH: usize = 8;
+
+/// Directories whose files are crate roots of their own targets
+const TARGET_DIRS: [&str; 4] = ["src/bin", "tests", "examples", "benches"];
+
+/// The module-level items of a Rust file
+#[derive(Debug, Default, PartialEq)]
+pub struct ModuleItems {
+    /// Names of the items defined in the file, inline modules included
+    pub defined: Vec<String>,
+    /// Out-of-line `mod` declarations with their `#[path]` attribute, if any
+    pub modules: Vec<(String, Option<String>)>,
+    /// Names re-exported by `pub use`, with the path they refer to
+    pub reexports: Vec<(String, String)>,
+    /// Module paths of `pub use path::*` glob re-exports
+    pub globs: Vec<String>,
+}
+
+impl ModuleItems {
+    /// Read the module-level items of Rust source
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(tree_sitter_rust::language()).ok()?;
+        let tree = parser.parse(content, None)?;
+        let root = tree.root_node();
+        let source = content.as_bytes();
+
+        let mut items = Self::default();
+        let mut path_attribute = None;
+        let mut cursor = root.walk();
+        for item in root.named_children(&mut cursor) {
+            let name = item
+                .child_by_field_name("name")
+                .map(|name| text(name, source).to_string());
+            match (item.kind(), name) {
+                ("attribute_item", _) => {
+                    path_attribute = path_attribute.or_else(|| path_value(text(item, source)));
+                    continue;
+                }
+                ("line_comment" | "block_comment", _) => continue,
+                ("mod_item", Some(name)) => {
+                    if item.child_by_field_name("body").is_some() {
+                        items.defined.push(name);
+                    } else {
+                        items.modules.push((name, path_attribute.take()));
+                    }
+                }
+                ("use_declaration", _) => {
+                    let mut use_cursor = item.walk();
+                    let is_public = item
+                        .children(&mut use_cursor)
+                        .any(|child| child.kind() == "visibility_modifier");
+                    if let (true, Some(argument)) =
+                        (is_public, item.child_by_field_name("argument"))
+                    {
+                        items.add_use_clause(argument, "", source);
+                    }
+                }
+                (_, Some(name))
+                    if item.kind().ends_with("_item") || item.kind() == "macro_definition" =>
+                {
+                    items.defined.push(name);
+                }
+                _ => {}
+            }
+            path_attribute = None;
+        }
+        Some(items)
+    }
+
+    /// The items of the file at `path`, reusing the parsed ones while the
+    /// file is unchanged
+    pub fn for_file(tree: &SourceTree, path: &Path) -> Option<Arc<ModuleItems>> {
+        manifest_cache::load_cached(tree, path, |path| {
+            Self::parse(&tree.read_to_string(path).ok()?)
+        })
+    }
+
+    /// Record the names a `pub use` clause re-exports
+    fn add_use_clause(&mut self, clause: Node, prefix: &str, source: &[u8]) {
+        let join = |path: &str| match prefix {
+            "" => path.to_string(),
+            _ => format!("{prefix}::{path}"),
+        };
+        match clause.kind() {
+            "use_as_clause" => {
+                let path = clause.child_by_field_name("path");
+                let alias = clause.child_by_field_name("alias");
+                if let (Some(path), Some(alias)) = (path, alias) {
+                    self.reexports
+                        .push((text(alias, source).to_string(), join(text(path, source))));
+                }
+            }
+            "scoped_use_list" => {
+                let prefix = clause
+                    .child_by_field_name("path")
+                    .map_or_else(|| prefix.to_string(), |path| join(text(path, source)));
+                if let Some(list) = clause.child_by_field_name("list") {
+                    self.add_use_clause(list, &prefix, source);
+                }
+            }
+            "use_list" => {
+                let mut cursor = clause.walk();
+                for child in clause.named_children(&mut cursor) {
+                    self.add_use_clause(child, prefix, source);
+                }
+            }
+            "use_wildcard" => {
+                let path = text(clause, source).trim_end_matches('*');
+                let path = path.trim_end_matches("::");
+                self.globs.push(if path.is_empty() {
+                    prefix.to_string()
+                } else {
+                    join(path)
+                });
+            }
+            _ => {
+                // `self` in a use list names the module of the prefix
+                let path = text(clause, source);
+                let (name, path) = match path {
+                    "self" => (
+                        prefix.rsplit("::").next().unwrap_or(prefix),
+                        prefix.to_string(),
+                    ),
+                    _ => (path.rsplit("::").next().unwrap_or(path), join(path)),
+                };
+                self.reexports.push((name.to_string(), path));
+            }
+        }
+    }
+
+    /// The `#[path]` of the out-of-line module `name`, if it is declared
+    fn module(&self, name: &str) -> Option<Option<&str>> {
+        self.modules
+            .iter()
+            .find(|(module, _)| module == name)
+            .map(|(_, path)| path.as_deref())
+    }
+}
+
+/// Resolve a Rust path used in `from_file` to the file defining what it
+/// names: the deepest module along the path, or the module a re-exported
+/// item is defined in
+///
+/// Returns `None` for paths into crates outside the repository.
+pub fn resolve(
+    module_path: &str,
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Option<PathBuf> {
+    resolve_path(module_path, from_file, base_dir, tree, 0)
+}
+
+/// Resolve the path segments of `segments` below the module in `module_file`
+pub fn resolve_in(
+    module_file: &Path,
+    segments: &[&str],
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> PathBuf {
+    resolve_segments(module_file, segments, base_dir, tree, 0)
+}
+
+/// The file of the module named by `module_path` in `from_file`, if every
+/// segment of the path is a module
+pub fn module_file(
+    module_path: &str,
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Option<PathBuf> {
+    let segments: Vec<&str> = module_path.split("::").filter(|s| !s.is_empty()).collect();
+    let (first, rest) = segments.split_first()?;
+    let mut current = path_start(first, from_file, base_dir, tree)?;
+    for segment in rest {
+        current = match *segment {
+            "self" => current,
+            "super" => parent_module_file(&current, base_dir, tree)?,
+            name => child_module_file(&current, name, base_dir, tree)?,
+        };
+    }
+    Some(current)
+}
+
+/// The file of the out-of-line module `name` declared in `file`
+pub fn child_module_file(
+    file: &Path,
+    name: &str,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Option<PathBuf> {
+    let items = ModuleItems::for_file(tree, file);
+    if let Some(Some(path)) = items.as_ref().and_then(|items| items.module(name)) {
+        let path = file.parent()?.join(path);
+        return tree.is_file(&path).then_some(path);
+    }
+
+    // Submodules of `foo.rs` live in `foo/`, those of `mod.rs` and crate
+    // roots next to the file. Loose files outside a crate layout fall back
+    // to their siblings.
+    let parent = file.parent()?;
+    let mut dirs = vec![parent.to_path_buf()];
+    if !is_mod_rs(file, base_dir, tree) {
+        dirs.insert(0, parent.join(file.file_stem()?));
+    }
+    dirs.iter()
+        .flat_map(|dir| {
+            [
+                dir.join(format!("{name}.rs")),
+                dir.join(name).join("mod.rs"),
+            ]
+        })
+        .find(|candidate| tree.is_file(candidate))
+}
+
+/// The file of the module that declares the module in `file`
+pub fn parent_module_file(file: &Path, base_dir: &Path, tree: &SourceTree) -> Option<PathBuf> {
+    if is_crate_root(file, base_dir, tree) {
+        return None;
+    }
+    let (dir, name) = if file.file_name()? == "mod.rs" {
+        let module_dir = file.parent()?;
+        (module_dir.parent()?, module_dir.file_name()?.to_str()?)
+    } else {
+        (file.parent()?, file.file_stem()?.to_str()?)
+    };
+
+    let mut candidates = ["mod.rs", "lib.rs", "main.rs"]
+        .iter()
+        .map(|root| dir.join(root))
+        .collect::<Vec<_>>();
+    if let (Some(outer), Some(dir_name)) = (dir.parent(), dir.file_name()) {
+        candidates.push(outer.join(dir_name).with_extension("rs"));
+    }
+    let existing: Vec<PathBuf> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.as_path() != file && tree.is_file(candidate))
+        .collect();
+
+    // Prefer the file that actually declares the module
+    existing
+        .iter()
+        .find(|candidate| {
+            ModuleItems::for_file(tree, candidate).is_some_and(|items| items.module(name).is_some())
+        })
+        .or_else(|| existing.first())
+        .cloned()
+}
+
+/// The root file of the crate `file` belongs to
+pub fn crate_root(file: &Path, base_dir: &Path, tree: &SourceTree) -> PathBuf {
+    let mut current = file.to_path_buf();
+    // Bounded in case of `#[path]` cycles
+    for _ in 0..64 {
+        match parent_module_file(&current, base_dir, tree) {
+            Some(parent) => current = parent,
+            None => break,
+        }
+    }
+    current
+}
+
+fn resolve_path(
+    module_path: &str,
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+    depth: usize,
+) -> Option<PathBuf> {
+    let segments: Vec<&str> = module_path.split("::").filter(|s| !s.is_empty()).collect();
+    let (first, rest) = segments.split_first()?;
+    let start = path_start(first, from_file, base_dir, tree)?;
+    Some(resolve_segments(&start, rest, base_dir, tree, depth))
+}
+
+/// The module file the first segment of a path refers to
+fn path_start(
+    first: &str,
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Option<PathBuf> {
+    match first {
+        "crate" => Some(crate_root(from_file, base_dir, tree)),
+        "self" => Some(from_file.to_path_buf()),
+        "super" => parent_module_file(from_file, base_dir, tree),
+        name => child_module_file(from_file, name, base_dir, tree)
+            .or_else(|| extern_crate_root(name, from_file, base_dir, tree))
+            // 2015 edition paths are relative to the crate root
+            .or_else(|| {
+                child_module_file(&crate_root(from_file, base_dir, tree), name, base_dir, tree)
+            }),
+    }
+}
+
+fn resolve_segments(
+    module_file: &Path,
+    segments: &[&str],
+    base_dir: &Path,
+    tree: &SourceTree,
+    depth: usize,
+) -> PathBuf {
+    let mut current = module_file.to_path_buf();
+    for segment in segments {
+        match *segment {
+            "self" => {}
+            "super" => {
+                if let Some(parent) = parent_module_file(&current, base_dir, tree) {
+                    current = parent;
+                }
+            }
+            name => match child_module_file(&current, name, base_dir, tree) {
+                Some(child) => current = child,
+                None => {
+                    return find_definition(&current, name, base_dir, tree, depth)
+                        .unwrap_or(current)
+                }
+            },
+        }
+    }
+    current
+}
+
+/// The module defining `name`, following the `pub use` re-exports of `file`
+fn find_definition(
+    file: &Path,
+    name: &str,
+    base_dir: &Path,
+    tree: &SourceTree,
+    depth: usize,
+) -> Option<PathBuf> {
+    if depth >= MAX_REEXPORT_DEPTH {
+        return None;
+    }
+    let items = ModuleItems::for_file(tree, file)?;
+    if items.defined.iter().any(|defined| defined == name) {
+        return Some(file.to_path_buf());
+    }
+    if items.module(name).is_some() {
+        return child_module_file(file, name, base_dir, tree);
+    }
+    if let Some((_, path)) = items
+        .reexports
+        .iter()
+        .find(|(exported, _)| exported == name)
+    {
+        return resolve_path(path, file, base_dir, tree, depth + 1);
+    }
+    items.globs.iter().find_map(|glob| {
+        let module = module_file(glob, file, base_dir, tree)?;
+        find_definition(&module, name, base_dir, tree, depth + 1)
+    })
+}
+
+/// The root file of another crate of the repository imported as `name`:
+/// the crate itself, a path dependency or a workspace member
+fn extern_crate_root(
+    name: &str,
+    from_file: &Path,
+    base_dir: &Path,
+    tree: &SourceTree,
+) -> Option<PathBuf> {
+    let manifest = CrateManifest::find(tree, from_file, base_dir);
+    let crate_dir = match &manifest {
+        Some(manifest) if manifest.lib_name == name => manifest.dir.clone(),
+        _ => manifest
+            .as_ref()
+            .and_then(|manifest| manifest.path_dependencies.get(name).cloned())
+            .or_else(|| {
+                let workspace = Workspace::for_root(tree, base_dir);
+                Some(workspace.member(Ecosystem::Cargo, name)?.dir.clone())
+            })?,
+    };
+
+    let root = CrateManifest::load(tree, &crate_dir).map_or_else(
+        || crate_dir.join("src/lib.rs"),
+        |manifest| manifest.lib_root,
+    );
+    let root = tree.canonicalize(&root).ok()?;
+    root.starts_with(tree.canonicalize(base_dir).ok()?)
+        .then_some(root)
+}
+
+/// Whether `file` is the root of a crate target
+fn is_crate_root(file: &Path, base_dir: &Path, tree: &SourceTree) -> bool {
+    let name = file.file_name().and_then(|name| name.to_str());
+    if matches!(name, Some("lib.rs" | "main.rs" | "build.rs")) {
+        return true;
+    }
+    let Some(manifest) = CrateManifest::find(tree, file, base_dir) else {
+        return false;
+    };
+    file == manifest.lib_root
+        || TARGET_DIRS
+            .iter()
+            .any(|dir| file.parent() == Some(manifest.dir.join(dir).as_path()))
+}
+
+/// Whether submodules of `file` live next to it rather than in a directory
+/// named after it
+fn is_mod_rs(file: &Path, base_dir: &Path, tree: &SourceTree) -> bool {
+    file.file_name().is_some_and(|name| name == "mod.rs") || is_crate_root(file, base_dir, tree)
+}
+
+/// The value of a `#[path = "..."]` attribute
+fn path_value(attribute: &str) -> Option<String> {
+    let attribute = attribute.strip_prefix("#[")?.strip_suffix(']')?;
+    let (key, value) = attribute.split_once('=')?;
+    if key.trim() != "path" {
+        return None;
+    }
+    Some(value.trim().trim_matches('"').to_string())
+}
+
+fn text<'a>(node: Node, source: &'a [u8]) -> &'a str {
+    node.utf8_text(source).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_module_items() {
+        let content = r#"
+#[path = "generated/bindings.rs"]
+mod bindings;
+#[cfg(test)]
+mod tests;
+pub mod inline {}
+
+pub use self::model::{Account, db::{self, Pool as DbPool}};
+pub use crate::errors::*;
+use std::collections::HashMap;
+
+pub struct Config;
+pub fn run() {}
+macro_rules! helper { () => {} }
+"#;
+        let items = ModuleItems::parse(content).unwrap();
+
+        assert_eq!(
+            items.modules,
+            vec![
+                (
+                    "bindings".to_string(),
+                    Some("generated/bindings.rs".to_string())
+                ),
+                ("tests".to_string(), None),
+            ]
+        );
+        assert_eq!(items.defined, vec!["inline", "Config", "run", "helper"]);
+        assert_eq!(
+            items.reexports,
+            vec![
+                ("Account".to_string(), "self::model::Account".to_string()),
+                ("db".to_string(), "self::model::db".to_string()),
+                ("DbPool".to_string(), "self::model::db::Pool".to_string()),
+            ]
+        );
+        assert_eq!(items.globs, vec!["crate::errors"]);
+    }
+
+    #[test]
+    fn test_module_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("src/lib.rs", "mod api;\nmod db;\n");
+        // `api.rs` keeps its submodules in `api/`, `db` is a `mod.rs` module
+        write("src/api.rs", "mod routes;\n");
+        write("src/api/routes.rs", "use super::super::db::Pool;\n");
+        write(
+            "src/db/mod.rs",
+            "#[path = \"pool_impl.rs\"]\nmod pool;\npub use pool::Pool;\n",
+        );
+        write("src/db/pool_impl.rs", "pub struct Pool;\n");
+
+        let routes = root.join("src/api/routes.rs");
+        let tree = SourceTree::default();
+        assert_eq!(
+            child_module_file(&root.join("src/api.rs"), "routes", root, &tree),
+            Some(routes.clone())
+        );
+        assert_eq!(
+            parent_module_file(&routes, root, &tree),
+            Some(root.join("src/api.rs"))
+        );
+        assert_eq!(crate_root(&routes, root, &tree), root.join("src/lib.rs"));
+        assert_eq!(
+            resolve("super::super::db::Pool", &routes, root, &tree),
+            Some(root.join("src/db/pool_impl.rs"))
+        );
+        assert_eq!(
+            resolve("crate::db::pool", &routes, root, &tree),
+            Some(root.join("src/db/pool_impl.rs"))
+        );
+        assert_eq!(resolve("serde::Serialize", &routes, root, &tree), None);
+    }
+}
@@ -0,0 +1,554 @@
+//! `tsconfig.json` and `jsconfig.json` module resolution settings
+//!
+//! TypeScript and JavaScript projects map bare import specifiers such as
+//! `@/components/Button` to files with `compilerOptions.baseUrl` and
+//! `compilerOptions.paths`, possibly inherited through `extends`, and split
+//! large codebases into projects linked by `references`.
+
+use crate::core::semantic::manifest_cache;
+use crate::core::source_tree::SourceTree;
+use crate::utils::error::ContextCreatorError;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Configuration file names, in the order they are looked up in a directory
+const CONFIG_NAMES: [&str; 2] = ["tsconfig.json", "jsconfig.json"];
+
+/// Longest `extends` chain followed, which also stops cycles
+const MAX_EXTENDS_DEPTH: usize = 16;
+
+/// Module resolution settings of a TypeScript or JavaScript project
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TsConfig {
+    /// The configuration file
+    pub path: PathBuf,
+    /// Absolute `compilerOptions.baseUrl`
+    pub base_url: Option<PathBuf>,
+    /// `compilerOptions.paths` patterns with their targets, in file order
+    pub paths: Vec<(String, Vec<String>)>,
+    /// Directory the `paths` targets are relative to
+    pub paths_base: PathBuf,
+    /// Configuration files of the referenced projects
+    pub references: Vec<PathBuf>,
+}
+
+impl TsConfig {
+    /// Load a configuration file together with the files it extends
+    pub fn load(tree: &SourceTree, path: &Path) -> Result<Self, ContextCreatorError> {
+        Self::load_with_depth(tree, path, 0, &mut Vec::new())
+    }
+
+    /// Load `path`, adding it and every file it extends to `files`
+    fn load_with_depth(
+        tree: &SourceTree,
+        path: &Path,
+        depth: usize,
+        files: &mut Vec<PathBuf>,
+    ) -> Result<Self, ContextCreatorError> {
+        let invalid = |message: String| {
+            ContextCreatorError::ConfigError(format!("{}: {message}", path.display()))
+        };
+        if depth > MAX_EXTENDS_DEPTH {
+            return Err(invalid("`extends` chain is too long".to_string()));
+        }
+
+        files.push(path.to_path_buf());
+        let content = tree
+            .read_to_string(path)
+            .map_err(|e| invalid(e.to_string()))?;
+        let json: serde_json::Value =
+            serde_json::from_str(&strip_jsonc(&content)).map_err(|e| invalid(e.to_string()))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+
+        // Settings of extended files apply first, in order
+        let mut config = TsConfig::default();
+        let extends = match &json["extends"] {
+            serde_json::Value::String(extends) => vec![extends.as_str()],
+            serde_json::Value::Array(extends) => {
+                extends.iter().filter_map(|e| e.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        for extends in extends {
+            if let Some(base) = find_extended(tree, dir, extends) {
+                let base = Self::load_with_depth(tree, &base, depth + 1, files)?;
+                config.base_url = base.base_url.or(config.base_url);
+                if !base.paths.is_empty() {
+                    config.paths = base.paths;
+                    config.paths_base = base.paths_base;
+                }
+            }
+        }
+
+        let options = &json["compilerOptions"];
+        if let Some(base_url) = options["baseUrl"].as_str() {
+            config.base_url = Some(normalize(&dir.join(base_url)));
+        }
+        if let Some(paths) = options["paths"].as_object() {
+            config.paths = paths
+                .iter()
+                .map(|(pattern, targets)| {
+                    let targets = targets
+                        .as_array()
+                        .map(|targets| {
+                            targets
+                                .iter()
+                                .filter_map(|target| target.as_str().map(str::to_string))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    (pattern.clone(), targets)
+                })
+                .collect();
+            config.paths_base = dir.to_path_buf();
+        }
+        // Targets are relative to `baseUrl` when there is one
+        if let Some(base_url) = &config.base_url {
+            config.paths_base = base_url.clone();
+        }
+
+        // References are not inherited
+        config.references = json["references"]
+            .as_array()
+            .map(|references| {
+                references
+                    .iter()
+                    .filter_map(|reference| reference["path"].as_str())
+                    .map(|reference| {
+                        let reference = normalize(&dir.join(reference));
+                        if tree.is_dir(&reference) {
+                            reference.join(CONFIG_NAMES[0])
+                        } else {
+                            reference
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        config.path = path.to_path_buf();
+
+        Ok(config)
+    }
+
+    /// The configuration that applies to `file`: the nearest one in its
+    /// directory or a parent directory, up to `root`
+    pub fn find(tree: &SourceTree, file: &Path, root: &Path) -> Option<Arc<TsConfig>> {
+        let mut dir = file.parent();
+        while let Some(current) = dir {
+            for name in CONFIG_NAMES {
+                let path = current.join(name);
+                if tree.is_file(&path) {
+                    return load_cached(tree, &path);
+                }
+            }
+            if current == root || !current.starts_with(root) {
+                break;
+            }
+            dir = current.parent();
+        }
+        None
+    }
+
+    /// Paths `specifier` may refer to, most specific first, before trying
+    /// extensions and index files
+    pub fn candidates(&self, specifier: &str) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+        if let Some((matched, targets)) = self.matching_paths(specifier) {
+            candidates.extend(
+                targets
+                    .iter()
+                    .map(|target| normalize(&self.paths_base.join(target.replace('*', matched)))),
+            );
+        }
+        if let Some(base_url) = &self.base_url {
+            candidates.push(normalize(&base_url.join(specifier)));
+        }
+        candidates
+    }
+
+    /// The `paths` entry for `specifier` and the text its wildcard matched
+    ///
+    /// As in TypeScript, an exact pattern wins over wildcards, and among
+    /// wildcards the one with the longest prefix wins.
+    fn matching_paths<'a>(&self, specifier: &'a str) -> Option<(&'a str, &Vec<String>)> {
+        let mut best: Option<(usize, &'a str, &Vec<String>)> = None;
+        for (pattern, targets) in &self.paths {
+            match pattern.split_once('*') {
+                None if pattern == specifier => return Some(("", targets)),
+                None => {}
+                Some((prefix, suffix)) => {
+                    let matched = specifier
+                        .strip_prefix(prefix)
+                        .and_then(|rest| rest.strip_suffix(suffix))
+                        .filter(|_| specifier.len() >= prefix.len() + suffix.len());
+                    let longer = best.map_or(true, |(length, _, _)| prefix.len() > length);
+                    if let (Some(matched), true) = (matched, longer) {
+                        best = Some((prefix.len(), matched, targets));
+                    }
+                }
+            }
+        }
+        best.map(|(_, matched, targets)| (matched, targets))
+    }
+}
+
+/// Resolve a bare specifier with the configuration of `from_file` and the
+/// projects it references
+///
+/// Returns `None` when no configuration applies or no candidate exists.
+pub fn resolve_alias(
+    tree: &SourceTree,
+    specifier: &str,
+    from_file: &Path,
+    root: &Path,
+    extensions: &[&str],
+) -> Option<PathBuf> {
+    let config = TsConfig::find(tree, from_file, root)?;
+    let referenced = config
+        .references
+        .iter()
+        .filter_map(|reference| load_cached(tree, reference));
+
+    std::iter::once(config.clone())
+        .chain(referenced)
+        .flat_map(|config| config.candidates(specifier))
+        .find_map(|candidate| find_module_file(tree, &candidate, extensions))
+}
+
+/// Find the file a module path refers to
+///
+/// Tries the path itself, then the path with each extension appended, then
+/// an `index` file with each extension if it is a directory. A `.js` path
+/// also matches the TypeScript file it is compiled from.
+pub fn find_module_file(tree: &SourceTree, base: &Path, extensions: &[&str]) -> Option<PathBuf> {
+    if tree.is_file(base) {
+        return Some(base.to_path_buf());
+    }
+
+    for ext in extensions {
+        let mut with_ext = OsString::from(base.as_os_str());
+        with_ext.push(format!(".{ext}"));
+        let with_ext = PathBuf::from(with_ext);
+        if tree.is_file(&with_ext) {
+            return Some(with_ext);
+        }
+    }
+
+    if let Some(compiled) = base.extension().and_then(|ext| ext.to_str()) {
+        let sources: &[&str] = match compiled {
+            "js" => &["ts", "tsx"],
+            "jsx" => &["tsx"],
+            "mjs" => &["mts"],
+            "cjs" => &["cts"],
+            _ => &[],
+        };
+        for source in sources {
+            let source = base.with_extension(source);
+            if tree.is_file(&source) {
+                return Some(source);
+            }
+        }
+    }
+
+    if tree.is_dir(base) {
+        for ext in extensions {
+            let index = base.join(format!("index.{ext}"));
+            if tree.is_file(&index) {
+                return Some(index);
+            }
+        }
+    }
+
+    None
+}
+
+/// Load a configuration through the manifest cache, which reloads it when
+/// any file of its `extends` chain changes
+fn load_cached(tree: &SourceTree, path: &Path) -> Option<Arc<TsConfig>> {
+    manifest_cache::load_cached_from(tree, path, || {
+        let mut files = Vec::new();
+        let config = match TsConfig::load_with_depth(tree, path, 0, &mut files) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::debug!("Ignoring {}: {e}", path.display());
+                None
+            }
+        };
+        (config, files)
+    })
+}
+
+/// The file an `extends` entry refers to
+///
+/// Relative entries are resolved against the extending file; others name a
+/// configuration shipped in `node_modules`, e.g. `@tsconfig/node18/tsconfig.json`.
+fn find_extended(tree: &SourceTree, dir: &Path, extends: &str) -> Option<PathBuf> {
+    let with_json = |path: PathBuf| {
+        if tree.is_file(&path) {
+            return Some(path);
+        }
+        let mut json = OsString::from(path.as_os_str());
+        json.push(".json");
+        let json = PathBuf::from(json);
+        if tree.is_file(&json) {
+            return Some(json);
+        }
+        let nested = path.join(CONFIG_NAMES[0]);
+        tree.is_file(&nested).then_some(nested)
+    };
+
+    if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        return with_json(normalize(&dir.join(extends)));
+    }
+    dir.ancestors()
+        .find_map(|ancestor| with_json(ancestor.join("node_modules").join(extends)))
+}
+
+/// Remove `.` and `..` components without touching the file system
+fn normalize(path: &Path) -> PathBuf {
+    use std::path::Component;
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Strip comments and trailing commas, which configuration files allow
+fn strip_jsonc(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (',', _) => {
+                // Drop the comma if only whitespace or comments precede the
+                // closing bracket; comments were already removed up to here,
+                // so look ahead on a copy
+                let rest: String = chars.clone().collect();
+                let next = strip_jsonc_prefix(&rest);
+                if !matches!(next, Some('}') | Some(']')) {
+                    stripped.push(c);
+                }
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+/// The first character after whitespace and comments
+fn strip_jsonc_prefix(rest: &str) -> Option<char> {
+    let mut rest = rest.trim_start();
+    loop {
+        if let Some(line) = rest.strip_prefix("//") {
+            rest = line
+                .split_once('\n')
+                .map_or("", |(_, rest)| rest)
+                .trim_start();
+        } else if let Some(block) = rest.strip_prefix("/*") {
+            rest = block
+                .split_once("*/")
+                .map_or("", |(_, rest)| rest)
+                .trim_start();
+        } else {
+            return rest.chars().next();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let content = r#"{
+            // line comment
+            "a": "http://example.com", /* block */
+            "b": [1, 2,],
+            "c": "quote \" // not a comment",
+        }"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(content)).unwrap();
+        assert_eq!(value["a"], "http://example.com");
+        assert_eq!(value["b"], serde_json::json!([1, 2]));
+        assert_eq!(value["c"], "quote \" // not a comment");
+    }
+
+    #[test]
+    fn test_paths_prefer_exact_then_longest_prefix() {
+        let config = TsConfig {
+            paths: vec![
+                ("*".to_string(), vec!["vendor/*".to_string()]),
+                ("@app/*".to_string(), vec!["src/app/*".to_string()]),
+                ("@app/core/*".to_string(), vec!["src/core/*".to_string()]),
+                ("@app/config".to_string(), vec!["src/config.ts".to_string()]),
+            ],
+            paths_base: PathBuf::from("/project"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.candidates("@app/core/http"),
+            vec![PathBuf::from("/project/src/core/http")]
+        );
+        assert_eq!(
+            config.candidates("@app/button"),
+            vec![PathBuf::from("/project/src/app/button")]
+        );
+        assert_eq!(
+            config.candidates("@app/config"),
+            vec![PathBuf::from("/project/src/config.ts")]
+        );
+        assert_eq!(
+            config.candidates("lodash"),
+            vec![PathBuf::from("/project/vendor/lodash")]
+        );
+    }
+
+    #[test]
+    fn test_extends_chain_resolves_relative_to_each_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("configs")).unwrap();
+        fs::create_dir_all(root.join("app")).unwrap();
+        fs::write(
+            root.join("configs/base.json"),
+            r#"{ "compilerOptions": { "baseUrl": "..", "paths": { "~lib/*": ["lib/*"] } } }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("app/tsconfig.json"),
+            r#"{
+                // Inherit the aliases
+                "extends": "../configs/base",
+                "references": [{ "path": "../shared" }],
+            }"#,
+        )
+        .unwrap();
+
+        let config =
+            TsConfig::load(&SourceTree::default(), &root.join("app/tsconfig.json")).unwrap();
+        assert_eq!(config.base_url, Some(root.to_path_buf()));
+        assert_eq!(config.paths_base, root.to_path_buf());
+        assert_eq!(
+            config.candidates("~lib/math"),
+            vec![root.join("lib/math"), root.join("~lib/math")]
+        );
+        assert_eq!(config.references, vec![root.join("shared")]);
+    }
+
+    #[test]
+    fn test_cached_config_follows_changes_to_extended_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let base = root.join("tsconfig.base.json");
+        fs::write(
+            &base,
+            r#"{ "compilerOptions": { "paths": { "@old/*": ["src/*"] } } }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "extends": "./tsconfig.base.json" }"#,
+        )
+        .unwrap();
+        let file = root.join("src/main.ts");
+        let patterns = || {
+            let config = TsConfig::find(&SourceTree::default(), &file, root).unwrap();
+            config
+                .paths
+                .iter()
+                .map(|(pattern, _)| pattern.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(patterns(), vec!["@old/*"]);
+
+        // Only the extended file changes
+        let modified = fs::metadata(&base).unwrap().modified().unwrap();
+        fs::write(
+            &base,
+            r#"{ "compilerOptions": { "paths": { "@new/*": ["src/*"] } } }"#,
+        )
+        .unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&base)
+            .unwrap()
+            .set_modified(modified + std::time::Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(patterns(), vec!["@new/*"]);
+    }
+
+    #[test]
+    fn test_find_module_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("components")).unwrap();
+        fs::write(root.join("app.service.ts"), "").unwrap();
+        fs::write(root.join("main.ts"), "").unwrap();
+        fs::write(root.join("components/index.tsx"), "").unwrap();
+        let extensions = ["ts", "tsx", "js", "jsx"];
+        let tree = SourceTree::default();
+
+        assert_eq!(
+            find_module_file(&tree, &root.join("app.service"), &extensions),
+            Some(root.join("app.service.ts"))
+        );
+        assert_eq!(
+            find_module_file(&tree, &root.join("main.js"), &extensions),
+            Some(root.join("main.ts"))
+        );
+        assert_eq!(
+            find_module_file(&tree, &root.join("components"), &extensions),
+            Some(root.join("components/index.tsx"))
+        );
+        assert_eq!(
+            find_module_file(&tree, &root.join("missing"), &extensions),
+            None
+        );
+    }
+}
@@ -0,0 +1,346 @@
+//! Type definitions and inheritance relationships
+//!
+//! Extracts the types a file defines together with the supertypes they extend
+//! or implement, for Rust, Python, JavaScript and TypeScript. Rust `impl Trait
+//! for Type` blocks are reported as relationships even when the type is
+//! defined in another file.
+
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser};
+
+/// A type defined in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeDefinition {
+    /// Name of the type
+    pub name: String,
+    /// Kind of definition (struct, enum, trait, class, interface, ...)
+    pub kind: &'static str,
+    /// Line number where the type is defined
+    pub line: usize,
+}
+
+/// How a type relates to its supertype
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelationKind {
+    /// Class inheritance, interface extension or a Rust supertrait
+    Extends,
+    /// Interface or trait implementation
+    Implements,
+}
+
+/// A subtype-supertype relationship declared in a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeRelation {
+    /// The extending or implementing type
+    pub subtype: String,
+    /// The extended or implemented type
+    pub supertype: String,
+    /// Kind of relationship
+    pub kind: RelationKind,
+    /// Line number where the relationship is declared
+    pub line: usize,
+}
+
+/// Type definitions and relationships found in a file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeFacts {
+    pub definitions: Vec<TypeDefinition>,
+    pub relations: Vec<TypeRelation>,
+}
+
+/// Extract type definitions and relationships from a source file
+///
+/// Returns empty facts for unsupported languages and unparsable content.
+pub fn extract_type_facts(path: &Path, content: &str) -> TypeFacts {
+    let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    let language: Language = match extension {
+        "rs" => tree_sitter_rust::language(),
+        "py" => tree_sitter_python::language(),
+        "js" | "jsx" | "mjs" | "cjs" => tree_sitter_javascript::language(),
+        "ts" => tree_sitter_typescript::language_typescript(),
+        "tsx" => tree_sitter_typescript::language_tsx(),
+        _ => return TypeFacts::default(),
+    };
+
+    let mut parser = Parser::new();
+    if parser.set_language(language).is_err() {
+        return TypeFacts::default();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return TypeFacts::default();
+    };
+
+    let mut facts = TypeFacts::default();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        collect_node(node, content.as_bytes(), &mut facts);
+
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+
+    facts
+        .definitions
+        .sort_by(|a, b| a.line.cmp(&b.line).then_with(|| a.name.cmp(&b.name)));
+    facts.relations.sort_by(|a, b| {
+        a.line
+            .cmp(&b.line)
+            .then_with(|| a.supertype.cmp(&b.supertype))
+    });
+    facts
+}
+
+fn collect_node(node: Node, source: &[u8], facts: &mut TypeFacts) {
+    let kind = match node.kind() {
+        "struct_item" => "struct",
+        "enum_item" | "enum_declaration" => "enum",
+        "union_item" => "union",
+        "trait_item" => "trait",
+        "type_item" | "type_alias_declaration" => "type",
+        "class_definition" | "class_declaration" | "abstract_class_declaration" => "class",
+        "interface_declaration" => "interface",
+        "impl_item" => {
+            collect_rust_impl(node, source, facts);
+            return;
+        }
+        _ => return,
+    };
+
+    let Some(name) = node
+        .child_by_field_name("name")
+        .and_then(|name| name.utf8_text(source).ok())
+    else {
+        return;
+    };
+    let line = node.start_position().row + 1;
+    facts.definitions.push(TypeDefinition {
+        name: name.to_string(),
+        kind,
+        line,
+    });
+
+    let mut push = |supertype: Node, relation: RelationKind| {
+        if let Some(supertype) = type_name(supertype, source) {
+            facts.relations.push(TypeRelation {
+                subtype: name.to_string(),
+                supertype,
+                kind: relation,
+                line,
+            });
+        }
+    };
+
+    let mut cursor = node.walk();
+    match node.kind() {
+        // trait Foo: Bar + Baz
+        "trait_item" => {
+            if let Some(bounds) = node.child_by_field_name("bounds") {
+                for bound in bounds.named_children(&mut cursor) {
+                    push(bound, RelationKind::Extends);
+                }
+            }
+        }
+        // class Foo(Bar, metaclass=Meta)
+        "class_definition" => {
+            if let Some(superclasses) = node.child_by_field_name("superclasses") {
+                for superclass in superclasses.named_children(&mut cursor) {
+                    if superclass.kind() != "keyword_argument" {
+                        push(superclass, RelationKind::Extends);
+                    }
+                }
+            }
+        }
+        // class Foo extends Bar implements Baz
+        "class_declaration" | "abstract_class_declaration" => {
+            let heritage = node
+                .named_children(&mut cursor)
+                .find(|child| child.kind() == "class_heritage");
+            if let Some(heritage) = heritage {
+                let mut cursor = heritage.walk();
+                for clause in heritage.named_children(&mut cursor) {
+                    let relation = match clause.kind() {
+                        "implements_clause" => RelationKind::Implements,
+                        "extends_clause" => RelationKind::Extends,
+                        // JavaScript: the heritage holds the expression directly
+                        _ => {
+                            push(clause, RelationKind::Extends);
+                            continue;
+                        }
+                    };
+                    let mut cursor = clause.walk();
+                    for supertype in clause.named_children(&mut cursor) {
+                        if supertype.kind() != "type_arguments" {
+                            push(supertype, relation);
+                        }
+                    }
+                }
+            }
+        }
+        // interface Foo extends Bar, Baz
+        "interface_declaration" => {
+            let clause = node
+                .named_children(&mut cursor)
+                .find(|child| child.kind() == "extends_type_clause");
+            if let Some(clause) = clause {
+                let mut cursor = clause.walk();
+                for supertype in clause.named_children(&mut cursor) {
+                    push(supertype, RelationKind::Extends);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `impl Trait for Type` declares that `Type` implements `Trait`
+fn collect_rust_impl(node: Node, source: &[u8], facts: &mut TypeFacts) {
+    let (Some(trait_node), Some(type_node)) = (
+        node.child_by_field_name("trait"),
+        node.child_by_field_name("type"),
+    ) else {
+        return;
+    };
+    if let (Some(supertype), Some(subtype)) =
+        (type_name(trait_node, source), type_name(type_node, source))
+    {
+        facts.relations.push(TypeRelation {
+            subtype,
+            supertype,
+            kind: RelationKind::Implements,
+            line: node.start_position().row + 1,
+        });
+    }
+}
+
+/// The bare name of a type expression: `a::B<C>` and `a.B` become `B`
+fn type_name(node: Node, source: &[u8]) -> Option<String> {
+    match node.kind() {
+        "generic_type" => node
+            .child_by_field_name("type")
+            .or_else(|| node.named_child(0))
+            .and_then(|inner| type_name(inner, source)),
+        "scoped_type_identifier" | "scoped_identifier" | "nested_type_identifier" => node
+            .child_by_field_name("name")
+            .or_else(|| node.named_child(node.named_child_count().checked_sub(1)?))
+            .and_then(|inner| type_name(inner, source)),
+        "attribute" => node
+            .child_by_field_name("attribute")
+            .and_then(|inner| type_name(inner, source)),
+        "member_expression" => node
+            .child_by_field_name("property")
+            .and_then(|inner| type_name(inner, source)),
+        "identifier" | "type_identifier" | "property_identifier" => {
+            node.utf8_text(source).ok().map(str::to_string)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relations(facts: &TypeFacts) -> Vec<(&str, &str, RelationKind)> {
+        facts
+            .relations
+            .iter()
+            .map(|r| (r.subtype.as_str(), r.supertype.as_str(), r.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_rust_traits_and_impls() {
+        let content = r#"
+pub trait Shape: Debug + std::fmt::Display {}
+
+pub struct Circle;
+
+impl Shape for Circle {}
+impl<T> From<T> for Circle {}
+impl Circle {}
+"#;
+        let facts = extract_type_facts(Path::new("lib.rs"), content);
+
+        assert_eq!(
+            facts.definitions,
+            vec![
+                TypeDefinition {
+                    name: "Shape".to_string(),
+                    kind: "trait",
+                    line: 2,
+                },
+                TypeDefinition {
+                    name: "Circle".to_string(),
+                    kind: "struct",
+                    line: 4,
+                },
+            ]
+        );
+        assert_eq!(
+            relations(&facts),
+            vec![
+                ("Shape", "Debug", RelationKind::Extends),
+                ("Shape", "Display", RelationKind::Extends),
+                ("Circle", "Shape", RelationKind::Implements),
+                ("Circle", "From", RelationKind::Implements),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_python_classes() {
+        let content =
+            "class Base:\n    pass\n\nclass Child(Base, models.Mixin, metaclass=Meta):\n    pass\n";
+        let facts = extract_type_facts(Path::new("models.py"), content);
+
+        assert_eq!(facts.definitions.len(), 2);
+        assert_eq!(
+            relations(&facts),
+            vec![
+                ("Child", "Base", RelationKind::Extends),
+                ("Child", "Mixin", RelationKind::Extends),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typescript_classes_and_interfaces() {
+        let content = r#"
+interface Named { name: string }
+interface Animal extends Named, Comparable<Animal> {}
+export class Dog extends Base<string> implements Animal, ns.Pet {}
+"#;
+        let facts = extract_type_facts(Path::new("dog.ts"), content);
+
+        let kinds: Vec<_> = facts.definitions.iter().map(|d| d.kind).collect();
+        assert_eq!(kinds, vec!["interface", "interface", "class"]);
+        assert_eq!(
+            relations(&facts),
+            vec![
+                ("Animal", "Comparable", RelationKind::Extends),
+                ("Animal", "Named", RelationKind::Extends),
+                ("Dog", "Animal", RelationKind::Implements),
+                ("Dog", "Base", RelationKind::Extends),
+                ("Dog", "Pet", RelationKind::Implements),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_javascript_class_extends() {
+        let content = "class View extends React.Component {}\nclass Plain {}\n";
+        let facts = extract_type_facts(Path::new("view.jsx"), content);
+
+        assert_eq!(facts.definitions.len(), 2);
+        assert_eq!(
+            relations(&facts),
+            vec![("View", "Component", RelationKind::Extends)]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_language_is_empty() {
+        let facts = extract_type_facts(Path::new("main.go"), "type Foo struct{}");
+        assert_eq!(facts, TypeFacts::default());
+    }
+}
@@ -0,0 +1,439 @@
+//! Monorepo workspaces
+//!
+//! A repository may hold several packages that import each other by name:
+//! Cargo `[workspace] members`, npm and yarn `workspaces`, pnpm
+//! `pnpm-workspace.yaml`, uv `[tool.uv.workspace] members` and Python path
+//! dependencies declared in poetry or uv. The workspace maps each member's
+//! package name to its directory so resolvers can follow cross-package imports
+//! instead of treating them as external.
+
+use crate::core::semantic::cargo_toml::CrateManifest;
+use crate::core::semantic::manifest_cache;
+use crate::core::source_tree::SourceTree;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Package ecosystems a workspace member can belong to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    /// Cargo crates
+    Cargo,
+    /// npm, yarn and pnpm packages
+    Npm,
+    /// Python distributions
+    Python,
+}
+
+/// A package of the workspace
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkspaceMember {
+    /// Ecosystem of the package
+    pub ecosystem: Ecosystem,
+    /// Name used to import the package: the crate name with `-` replaced by
+    /// `_`, the npm package name, or the normalized Python distribution name
+    pub name: String,
+    /// Directory of the package manifest
+    pub dir: PathBuf,
+    /// Directory module paths of the package are relative to: `src/` of a
+    /// crate or of a Python src-layout, otherwise the package directory
+    pub source_root: PathBuf,
+}
+
+/// Members of the workspaces declared at a project root
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Workspace {
+    /// The members, in discovery order
+    pub members: Vec<WorkspaceMember>,
+}
+
+impl Workspace {
+    /// Discover the workspace members declared in the manifests at `root`
+    pub fn discover(tree: &SourceTree, root: &Path) -> Self {
+        Self::discover_reading(tree, root, &mut Vec::new())
+    }
+
+    /// Discover the workspace at `root`, adding the manifests of the
+    /// candidate members to `manifests`
+    fn discover_reading(tree: &SourceTree, root: &Path, manifests: &mut Vec<PathBuf>) -> Self {
+        let mut members = Vec::new();
+        members.extend(cargo_members(tree, root, manifests));
+        members.extend(npm_members(tree, root, manifests));
+        members.extend(python_members(tree, root, manifests));
+        Self { members }
+    }
+
+    /// The workspace at `root`, reusing the discovered one while the root
+    /// manifests and those of the members are unchanged
+    pub fn for_root(tree: &SourceTree, root: &Path) -> Arc<Workspace> {
+        manifest_cache::load_cached_from(tree, root, || {
+            let mut manifests = ROOT_MANIFESTS.iter().map(|name| root.join(name)).collect();
+            let workspace = Self::discover_reading(tree, root, &mut manifests);
+            (Some(workspace), manifests)
+        })
+        .unwrap_or_else(|| Arc::new(Self::discover(tree, root)))
+    }
+
+    /// The member of `ecosystem` imported as `name`
+    pub fn member(&self, ecosystem: Ecosystem, name: &str) -> Option<&WorkspaceMember> {
+        self.members
+            .iter()
+            .find(|member| member.ecosystem == ecosystem && member.name == name)
+    }
+}
+
+/// Manifests that declare workspaces, checked to invalidate the cache
+const ROOT_MANIFESTS: [&str; 4] = [
+    "Cargo.toml",
+    "package.json",
+    "pnpm-workspace.yaml",
+    "pyproject.toml",
+];
+
+/// Normalize a Python distribution name to the name its package is imported as
+pub fn python_import_name(name: &str) -> String {
+    name.to_lowercase().replace(['-', '.'], "_")
+}
+
+fn cargo_members(
+    tree: &SourceTree,
+    root: &Path,
+    manifests: &mut Vec<PathBuf>,
+) -> Vec<WorkspaceMember> {
+    let Some(manifest) = read_toml(tree, &root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(workspace) = manifest.get("workspace") else {
+        return Vec::new();
+    };
+    let patterns = string_array(workspace.get("members"));
+    let exclude = string_array(workspace.get("exclude"));
+
+    expand_patterns(tree, root, &patterns, &exclude)
+        .into_iter()
+        .filter_map(|dir| {
+            manifests.push(dir.join("Cargo.toml"));
+            let manifest = CrateManifest::load(tree, &dir)?;
+            let source_root = manifest
+                .lib_root
+                .parent()
+                .map_or_else(|| dir.join("src"), Path::to_path_buf);
+            Some(WorkspaceMember {
+                ecosystem: Ecosystem::Cargo,
+                name: manifest.lib_name,
+                dir,
+                source_root,
+            })
+        })
+        .collect()
+}
+
+fn npm_members(
+    tree: &SourceTree,
+    root: &Path,
+    manifests: &mut Vec<PathBuf>,
+) -> Vec<WorkspaceMember> {
+    // npm and yarn: `"workspaces": [...]` or `"workspaces": { "packages": [...] }`
+    let mut patterns = read_json(tree, &root.join("package.json"))
+        .map(|manifest| {
+            let workspaces = &manifest["workspaces"];
+            let list = workspaces
+                .as_array()
+                .or_else(|| workspaces["packages"].as_array());
+            list.into_iter()
+                .flatten()
+                .filter_map(|pattern| pattern.as_str().map(str::to_string))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    // pnpm: the `packages` list of pnpm-workspace.yaml
+    if let Ok(content) = tree.read_to_string(&root.join("pnpm-workspace.yaml")) {
+        patterns.extend(pnpm_packages(&content));
+    }
+
+    let (exclude, include): (Vec<_>, Vec<_>) = patterns
+        .into_iter()
+        .partition(|pattern| pattern.starts_with('!'));
+    let exclude: Vec<_> = exclude
+        .iter()
+        .map(|pattern| pattern[1..].to_string())
+        .collect();
+
+    expand_patterns(tree, root, &include, &exclude)
+        .into_iter()
+        .filter_map(|dir| {
+            manifests.push(dir.join("package.json"));
+            let manifest = read_json(tree, &dir.join("package.json"))?;
+            let name = manifest["name"].as_str()?.to_string();
+            Some(WorkspaceMember {
+                ecosystem: Ecosystem::Npm,
+                name,
+                source_root: dir.clone(),
+                dir,
+            })
+        })
+        .collect()
+}
+
+fn python_members(
+    tree: &SourceTree,
+    root: &Path,
+    manifests: &mut Vec<PathBuf>,
+) -> Vec<WorkspaceMember> {
+    let Some(manifest) = read_toml(tree, &root.join("pyproject.toml")) else {
+        return Vec::new();
+    };
+    let tool = manifest.get("tool");
+
+    // uv workspaces
+    let uv_workspace = tool
+        .and_then(|tool| tool.get("uv"))
+        .and_then(|uv| uv.get("workspace"));
+    let mut dirs = expand_patterns(
+        tree,
+        root,
+        &string_array(uv_workspace.and_then(|w| w.get("members"))),
+        &string_array(uv_workspace.and_then(|w| w.get("exclude"))),
+    );
+
+    // Path dependencies: poetry `name = { path = "..." }` and uv sources
+    let path_tables = [
+        tool.and_then(|tool| tool.get("poetry"))
+            .and_then(|poetry| poetry.get("dependencies")),
+        tool.and_then(|tool| tool.get("uv"))
+            .and_then(|uv| uv.get("sources")),
+    ];
+    for table in path_tables.into_iter().flatten() {
+        let Some(table) = table.as_table() else {
+            continue;
+        };
+        for dependency in table.values() {
+            if let Some(path) = dependency.get("path").and_then(|path| path.as_str()) {
+                let dir = root.join(path);
+                if !path.contains("..") && tree.is_dir(&dir) && !dirs.contains(&dir) {
+                    dirs.push(dir);
+                }
+            }
+        }
+    }
+
+    dirs.into_iter()
+        .filter_map(|dir| {
+            manifests.push(dir.join("pyproject.toml"));
+            let manifest = read_toml(tree, &dir.join("pyproject.toml"))?;
+            let name = manifest
+                .get("project")
+                .and_then(|project| project.get("name"))
+                .or_else(|| manifest.get("tool")?.get("poetry")?.get("name"))?
+                .as_str()?;
+            let name = python_import_name(name);
+            let source_root = if tree.is_dir(&dir.join("src").join(&name)) {
+                dir.join("src")
+            } else {
+                dir.clone()
+            };
+            Some(WorkspaceMember {
+                ecosystem: Ecosystem::Python,
+                name,
+                dir,
+                source_root,
+            })
+        })
+        .collect()
+}
+
+/// The directories matching member glob patterns relative to `root`, minus
+/// those matching an exclude pattern, without leaving `root`
+fn expand_patterns(
+    tree: &SourceTree,
+    root: &Path,
+    patterns: &[String],
+    exclude: &[String],
+) -> Vec<PathBuf> {
+    let excluded: Vec<_> = exclude
+        .iter()
+        .filter_map(|pattern| glob::Pattern::new(pattern.trim_end_matches('/')).ok())
+        .collect();
+
+    let mut dirs = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_end_matches('/');
+        if pattern.contains("..") {
+            continue;
+        }
+        let Ok(paths) = glob::glob(&root.join(pattern).to_string_lossy()) else {
+            continue;
+        };
+        for dir in paths.flatten().filter(|path| tree.is_dir(path)) {
+            let Ok(relative) = dir.strip_prefix(root) else {
+                continue;
+            };
+            let is_excluded = excluded
+                .iter()
+                .any(|exclude| exclude.matches_path(relative));
+            let in_node_modules = relative
+                .components()
+                .any(|component| component.as_os_str() == "node_modules");
+            if !is_excluded && !in_node_modules && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs
+}
+
+/// The entries of the `packages` list in a pnpm-workspace.yaml
+fn pnpm_packages(content: &str) -> Vec<String> {
+    let mut packages = Vec::new();
+    let mut in_packages = false;
+    for line in content.lines() {
+        let line = line.split(" #").next().unwrap_or(line);
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if let Some(entry) = line.trim().strip_prefix('-').filter(|_| in_packages) {
+            packages.push(entry.trim().trim_matches(['\'', '"']).to_string());
+        }
+    }
+    packages
+}
+
+fn read_toml(tree: &SourceTree, path: &Path) -> Option<toml::Value> {
+    toml::from_str(&tree.read_to_string(path).ok()?).ok()
+}
+
+fn read_json(tree: &SourceTree, path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&tree.read_to_string(path).ok()?).ok()
+}
+
+fn string_array(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|value| value.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item.as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_discover_members_of_every_ecosystem() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let write = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/legacy\"]\n",
+        );
+        write(
+            "crates/core-utils/Cargo.toml",
+            "[package]\nname = \"core-utils\"\n",
+        );
+        write("crates/legacy/Cargo.toml", "[package]\nname = \"legacy\"\n");
+        write(
+            "package.json",
+            r#"{ "workspaces": ["apps/*", "!apps/docs"] }"#,
+        );
+        write("apps/web/package.json", r#"{ "name": "@acme/web" }"#);
+        write("apps/docs/package.json", r#"{ "name": "@acme/docs" }"#);
+        write(
+            "pnpm-workspace.yaml",
+            "# pnpm\npackages:\n  - 'libs/*' # shared\n  - \"!libs/old\"\ncatalog:\n  - ignored\n",
+        );
+        write("libs/shared/package.json", r#"{ "name": "@acme/shared" }"#);
+        write(
+            "pyproject.toml",
+            "[tool.uv.workspace]\nmembers = [\"py/*\"]\n\n[tool.poetry.dependencies]\nacme-ml = { path = \"ml\", develop = true }\n",
+        );
+        write(
+            "py/acme-core/pyproject.toml",
+            "[project]\nname = \"Acme-Core\"\n",
+        );
+        fs::create_dir_all(root.join("py/acme-core/src/acme_core")).unwrap();
+        write("ml/pyproject.toml", "[tool.poetry]\nname = \"acme-ml\"\n");
+
+        let workspace = Workspace::discover(&SourceTree::default(), root);
+        let names: Vec<_> = workspace
+            .members
+            .iter()
+            .map(|member| member.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "core_utils",
+                "@acme/web",
+                "@acme/shared",
+                "acme_core",
+                "acme_ml"
+            ]
+        );
+
+        let core = workspace.member(Ecosystem::Cargo, "core_utils").unwrap();
+        assert_eq!(core.source_root, root.join("crates/core-utils/src"));
+        let python = workspace.member(Ecosystem::Python, "acme_core").unwrap();
+        assert_eq!(python.source_root, root.join("py/acme-core/src"));
+        let ml = workspace.member(Ecosystem::Python, "acme_ml").unwrap();
+        assert_eq!(ml.source_root, root.join("ml"));
+        assert!(workspace.member(Ecosystem::Npm, "core_utils").is_none());
+    }
+
+    #[test]
+    fn test_no_workspace() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"single\"\n",
+        )
+        .unwrap();
+        assert!(Workspace::discover(&SourceTree::default(), temp_dir.path())
+            .members
+            .is_empty());
+    }
+
+    #[test]
+    fn test_cached_workspace_follows_member_manifests() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "workspaces": ["packages/*"] }"#,
+        )
+        .unwrap();
+        fs::create_dir_all(root.join("packages/ui")).unwrap();
+        let member = root.join("packages/ui/package.json");
+        fs::write(&member, r#"{ "name": "@acme/ui" }"#).unwrap();
+        let names = || {
+            Workspace::for_root(&SourceTree::default(), root)
+                .members
+                .iter()
+                .map(|member| member.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(), vec!["@acme/ui"]);
+
+        // Renaming the member leaves the root manifests untouched
+        let modified = fs::metadata(&member).unwrap().modified().unwrap();
+        fs::write(&member, r#"{ "name": "@acme/widgets" }"#).unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&member)
+            .unwrap()
+            .set_modified(modified + std::time::Duration::from_secs(2))
+            .unwrap();
+        assert_eq!(names(), vec!["@acme/widgets"]);
+    }
+}
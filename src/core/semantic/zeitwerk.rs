@@ -0,0 +1,153 @@
+//! Autoloaded Ruby constants
+//!
+//! Rails applications and gems using Zeitwerk do not `require` their own
+//! files: `Admin::UsersController` is loaded from `admin/users_controller.rb`
+//! below an autoload root. Those are the directories of `app/` (and their
+//! `concerns/`) and `lib/` of the Rails application or gem a file belongs to.
+
+use crate::core::source_tree::SourceTree;
+use std::path::{Path, PathBuf};
+
+/// Directories of `app/` holding no Ruby constants
+const NON_AUTOLOADED_APP_DIRS: [&str; 3] = ["assets", "javascript", "views"];
+
+/// The file defining `constant`, as Zeitwerk maps constant paths to files;
+/// constants defined inside their namespace's file resolve to that file
+pub fn resolve_constant(
+    tree: &SourceTree,
+    constant: &str,
+    from_file: &Path,
+    root: &Path,
+) -> Option<PathBuf> {
+    let segments: Vec<String> = constant
+        .trim_start_matches("::")
+        .split("::")
+        .map(underscore)
+        .collect();
+    let roots = autoload_roots(tree, &project_dir(tree, from_file, root));
+    (1..=segments.len()).rev().find_map(|len| {
+        let relative = format!("{}.rb", segments[..len].join("/"));
+        roots
+            .iter()
+            .map(|dir| dir.join(&relative))
+            .find(|candidate| tree.is_file(candidate) && candidate != from_file)
+    })
+}
+
+/// The autoload roots of the Rails application or gem in `project`
+pub fn autoload_roots(tree: &SourceTree, project: &Path) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    if let Ok(entries) = tree.read_dir(&project.join("app")) {
+        let mut app_dirs: Vec<PathBuf> = entries
+            .into_iter()
+            .filter(|dir| tree.is_dir(dir))
+            .filter(|dir| {
+                dir.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| !NON_AUTOLOADED_APP_DIRS.contains(&name))
+            })
+            .collect();
+        app_dirs.sort();
+        for dir in app_dirs {
+            let concerns = dir.join("concerns");
+            roots.push(dir);
+            if tree.is_dir(&concerns) {
+                roots.push(concerns);
+            }
+        }
+    }
+    if tree.is_dir(&project.join("lib")) {
+        roots.push(project.join("lib"));
+    }
+    roots
+}
+
+/// The Rails application or gem `file` belongs to: the nearest directory
+/// with a `Gemfile` or a `.gemspec` between the file and `root`
+pub fn project_dir(tree: &SourceTree, file: &Path, root: &Path) -> PathBuf {
+    file.parent()
+        .into_iter()
+        .flat_map(Path::ancestors)
+        .take_while(|dir| dir.starts_with(root))
+        .find(|dir| tree.is_file(&dir.join("Gemfile")) || has_gemspec(tree, dir))
+        .unwrap_or(root)
+        .to_path_buf()
+}
+
+/// The file name of a constant: `UsersController` is `users_controller`,
+/// `HTMLParser` is `html_parser`
+pub fn underscore(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut underscored = String::with_capacity(name.len() + 4);
+    for (index, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && index > 0 {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|next| next.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                underscored.push('_');
+            }
+        }
+        underscored.extend(c.to_lowercase());
+    }
+    underscored
+}
+
+fn has_gemspec(tree: &SourceTree, dir: &Path) -> bool {
+    tree.read_dir(dir).is_ok_and(|entries| {
+        entries
+            .iter()
+            .any(|path| path.extension().is_some_and(|ext| ext == "gemspec"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_underscore() {
+        assert_eq!(underscore("User"), "user");
+        assert_eq!(underscore("UsersController"), "users_controller");
+        assert_eq!(underscore("HTMLParser"), "html_parser");
+        assert_eq!(underscore("OAuth2Client"), "o_auth2_client");
+    }
+
+    #[test]
+    fn test_resolve_constant() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        for file in [
+            "Gemfile",
+            "app/controllers/admin/users_controller.rb",
+            "app/models/concerns/trackable.rb",
+            "app/models/report.rb",
+            "lib/billing.rb",
+        ] {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), "").unwrap();
+        }
+        let from = root.join("app/controllers/application_controller.rb");
+        let resolve = |constant| resolve_constant(&SourceTree::default(), constant, &from, root);
+
+        assert_eq!(
+            resolve("Admin::UsersController"),
+            Some(root.join("app/controllers/admin/users_controller.rb"))
+        );
+        assert_eq!(
+            resolve("Trackable"),
+            Some(root.join("app/models/concerns/trackable.rb"))
+        );
+        // Constants nested in their namespace's file
+        assert_eq!(
+            resolve("::Report::Row"),
+            Some(root.join("app/models/report.rb"))
+        );
+        assert_eq!(resolve("Billing"), Some(root.join("lib/billing.rb")));
+        assert_eq!(resolve("ActiveRecord::Base"), None);
+    }
+}
@@ -46,6 +46,11 @@ impl SemanticCache {
         self.cache.insert(key, Arc::new(result));
     }
 
+    /// Remove every cached result for a file, whatever its content hash
+    pub fn remove_path(&self, path: &Path) {
+        self.cache.retain(|key, _| key.path != path);
+    }
+
     /// Get cache statistics
     pub fn stats(&self) -> CacheStats {
         CacheStats {
@@ -87,6 +92,7 @@ mod tests {
                 module: "std::collections".to_string(),
                 items: vec!["HashMap".to_string()],
                 is_relative: false,
+                is_dynamic: false,
                 line: 1,
             }],
             function_calls: vec![],
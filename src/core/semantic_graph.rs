@@ -13,10 +13,13 @@ use crate::core::semantic::graph_builder::GraphBuilder;
 use crate::core::semantic::graph_traverser::GraphTraverser;
 use crate::core::semantic::parallel_analyzer::{AnalysisOptions, ParallelAnalyzer};
 use crate::core::semantic::SemanticOptions;
+use crate::core::semantic_cache::SemanticCache;
+use crate::core::source_tree::SourceTree;
 use crate::core::walker::FileInfo;
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 /// Performs sophisticated semantic analysis with proper dependency graph traversal
 /// This is the main entry point that maintains backward compatibility
@@ -24,6 +27,18 @@ pub fn perform_semantic_analysis_graph(
     files: &mut [FileInfo],
     config: &crate::cli::Config,
     cache: &FileCache,
+) -> Result<()> {
+    perform_semantic_analysis_graph_cached(files, config, cache, &Arc::new(SemanticCache::new()))
+}
+
+/// Semantic analysis that reuses parse results from `semantic_cache`
+///
+/// Files whose content hash is already cached are not parsed again.
+pub fn perform_semantic_analysis_graph_cached(
+    files: &mut [FileInfo],
+    config: &crate::cli::Config,
+    cache: &FileCache,
+    semantic_cache: &Arc<SemanticCache>,
 ) -> Result<()> {
     // Skip if no semantic analysis is requested
     if !config.trace_imports && !config.include_callers && !config.include_types {
@@ -31,27 +46,34 @@ pub fn perform_semantic_analysis_graph(
     }
 
     let semantic_options = SemanticOptions::from_config(config);
+    // Under `--at` the cache reads files from the snapshot
+    let tree = SourceTree::new(cache.snapshot().cloned());
 
     // Detect project root from first file
     let project_root = if let Some(first_file) = files.first() {
-        detect_project_root(&first_file.path)
+        detect_project_root(&tree, &first_file.path)
     } else {
         std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
     };
 
     // Step 1: Parallel file analysis
-    let analyzer = ParallelAnalyzer::new(cache);
+    let analyzer = ParallelAnalyzer::with_semantic_cache(cache, semantic_cache.clone());
     let analysis_options = AnalysisOptions {
         semantic_depth: semantic_options.semantic_depth,
         trace_imports: semantic_options.trace_imports,
         include_types: semantic_options.include_types,
         include_functions: semantic_options.include_callers,
+        include_paths: config.include_paths.clone(),
+        reporter: config.reporter.clone(),
     };
 
     let file_paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
     let valid_files: std::collections::HashSet<PathBuf> = files
         .iter()
-        .map(|f| f.path.canonicalize().unwrap_or_else(|_| f.path.clone()))
+        .map(|f| {
+            tree.canonicalize(&f.path)
+                .unwrap_or_else(|_| f.path.clone())
+        })
         .collect();
     let analysis_results =
         analyzer.analyze_files(&file_paths, &project_root, &analysis_options, &valid_files)?;
@@ -140,7 +162,7 @@ pub fn perform_semantic_analysis_graph(
 }
 
 /// Detect the project root directory
-fn detect_project_root(start_path: &std::path::Path) -> PathBuf {
+fn detect_project_root(tree: &SourceTree, start_path: &std::path::Path) -> PathBuf {
     let mut current = start_path.parent().unwrap_or(start_path);
 
     // First try to find git root
@@ -158,10 +180,10 @@ fn detect_project_root(start_path: &std::path::Path) -> PathBuf {
     // Fallback: Look for common project markers
     current = start_path.parent().unwrap_or(start_path);
     loop {
-        if current.join("Cargo.toml").exists()
-            || current.join("package.json").exists()
-            || current.join("pyproject.toml").exists()
-            || current.join("setup.py").exists()
+        if tree.exists(&current.join("Cargo.toml"))
+            || tree.exists(&current.join("package.json"))
+            || tree.exists(&current.join("pyproject.toml"))
+            || tree.exists(&current.join("setup.py"))
         {
             return current.to_path_buf();
         }
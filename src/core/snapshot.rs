@@ -0,0 +1,395 @@
+//! Read a git revision straight from the object database
+//!
+//! A [`GitSnapshot`] lists the blobs in a commit's tree and serves their
+//! contents on demand, so a branch, tag or historical commit can be analyzed
+//! without a checkout. Paths are reported under the repository's working
+//! directory, exactly where the files would live if the revision were checked
+//! out, which keeps the walker, semantic analysis and formatters unaware of
+//! where the bytes come from.
+
+use crate::utils::error::ContextCreatorError;
+use anyhow::Result;
+use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// File mode git uses for symbolic links
+const SYMLINK_MODE: i32 = 0o120000;
+
+/// A blob in the snapshot tree
+#[derive(Debug, Clone, Copy)]
+struct SnapshotEntry {
+    oid: Oid,
+    size: u64,
+}
+
+/// The tree of a single commit, readable without touching the working tree
+pub struct GitSnapshot {
+    repo: Mutex<Repository>,
+    root: PathBuf,
+    common_dir: PathBuf,
+    revision: String,
+    commit: Oid,
+    entries: BTreeMap<PathBuf, SnapshotEntry>,
+}
+
+impl GitSnapshot {
+    /// Open the repository containing `path` and resolve `revision` to a commit
+    ///
+    /// `revision` accepts anything `git rev-parse` does: branches, tags,
+    /// abbreviated or full commit ids and expressions such as `HEAD~3`.
+    pub fn open(path: &Path, revision: &str) -> Result<Self> {
+        let repo = Repository::discover(path).map_err(|e| {
+            ContextCreatorError::InvalidPath(format!(
+                "{} is not inside a git repository: {}",
+                path.display(),
+                e.message()
+            ))
+        })?;
+
+        let (commit, entries) = {
+            let commit = repo
+                .revparse_single(revision)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(|e| {
+                    ContextCreatorError::InvalidConfiguration(format!(
+                        "Cannot resolve '{revision}' to a commit: {}",
+                        e.message()
+                    ))
+                })?;
+            (commit.id(), collect_entries(&repo, &commit.tree()?)?)
+        };
+
+        let root = repo
+            .workdir()
+            .unwrap_or_else(|| repo.path())
+            .canonicalize()?;
+        let common_dir = repo.commondir().to_path_buf();
+
+        Ok(Self {
+            repo: Mutex::new(repo),
+            root,
+            common_dir,
+            revision: revision.to_string(),
+            commit,
+            entries,
+        })
+    }
+
+    /// Working directory of the repository; every snapshot path lives under it
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Git directory shared by the worktrees of the repository, which holds
+    /// the unversioned `info/exclude`
+    pub fn common_dir(&self) -> &Path {
+        &self.common_dir
+    }
+
+    /// The revision as given by the user
+    pub fn revision(&self) -> &str {
+        &self.revision
+    }
+
+    /// The resolved commit
+    pub fn commit(&self) -> Oid {
+        self.commit
+    }
+
+    /// Full id of the resolved commit
+    pub fn commit_id(&self) -> String {
+        self.commit.to_string()
+    }
+
+    /// Paths relative to [`Self::root`] and sizes of all files in the snapshot
+    pub fn files(&self) -> impl Iterator<Item = (&Path, u64)> {
+        self.entries
+            .iter()
+            .map(|(path, entry)| (path.as_path(), entry.size))
+    }
+
+    /// Number of files in the snapshot
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the snapshot tree is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Map a path to its location relative to [`Self::root`]
+    ///
+    /// Returns `None` for paths outside the repository. The path does not
+    /// have to exist in the snapshot.
+    pub fn relative_path(&self, path: &Path) -> Option<PathBuf> {
+        let absolute = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir().ok()?.join(path)
+        };
+
+        if let Some(relative) = strip_root(&absolute, &self.root) {
+            return Some(relative);
+        }
+
+        // The working tree copy may be reachable through a symlinked prefix
+        let canonical = absolute.canonicalize().ok()?;
+        strip_root(&canonical, &self.root)
+    }
+
+    /// Whether `path` is a file in the snapshot
+    pub fn contains(&self, path: &Path) -> bool {
+        self.relative_path(path)
+            .is_some_and(|relative| self.entries.contains_key(&relative))
+    }
+
+    /// Whether `path` is a directory in the snapshot
+    pub fn is_dir(&self, path: &Path) -> bool {
+        match self.relative_path(path) {
+            Some(relative) if relative.as_os_str().is_empty() => true,
+            Some(relative) => {
+                self.entries
+                    .range(relative.clone()..)
+                    .next()
+                    .is_some_and(|(candidate, _)| {
+                        candidate != &relative && candidate.starts_with(&relative)
+                    })
+            }
+            None => false,
+        }
+    }
+
+    /// Files and directories directly inside the directory at `path`
+    pub fn read_dir(&self, path: &Path) -> Option<Vec<PathBuf>> {
+        if !self.is_dir(path) {
+            return None;
+        }
+        let relative = self.relative_path(path)?;
+        let mut children: Vec<PathBuf> = Vec::new();
+        for (candidate, _) in self.entries.range(relative.clone()..) {
+            let Ok(rest) = candidate.strip_prefix(&relative) else {
+                break;
+            };
+            let Some(child) = rest.components().next() else {
+                continue;
+            };
+            let child = self.root.join(&relative).join(child);
+            if children.last() != Some(&child) {
+                children.push(child);
+            }
+        }
+        Some(children)
+    }
+
+    /// Id of the blob of the file at `path`
+    pub fn blob_id(&self, path: &Path) -> Option<Oid> {
+        let relative = self.relative_path(path)?;
+        self.entries.get(&relative).map(|entry| entry.oid)
+    }
+
+    /// Size in bytes of the file at `path`
+    pub fn size(&self, path: &Path) -> Option<u64> {
+        let relative = self.relative_path(path)?;
+        self.entries.get(&relative).map(|entry| entry.size)
+    }
+
+    /// Read the raw contents of the file at `path`
+    pub fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let entry = self
+            .relative_path(path)
+            .and_then(|relative| self.entries.get(&relative).copied())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("{} does not exist at {}", path.display(), self.revision),
+                )
+            })?;
+
+        let repo = self
+            .repo
+            .lock()
+            .map_err(|_| io::Error::other("git repository lock was poisoned"))?;
+        let blob = repo.find_blob(entry.oid).map_err(io::Error::other)?;
+        Ok(blob.content().to_vec())
+    }
+
+    /// Read the file at `path` as UTF-8 text
+    pub fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+impl fmt::Debug for GitSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GitSnapshot")
+            .field("root", &self.root)
+            .field("revision", &self.revision)
+            .field("commit", &self.commit)
+            .field("files", &self.entries.len())
+            .finish()
+    }
+}
+
+/// List every regular file in `tree`, skipping symlinks and submodules
+fn collect_entries(
+    repo: &Repository,
+    tree: &git2::Tree<'_>,
+) -> Result<BTreeMap<PathBuf, SnapshotEntry>> {
+    let odb = repo.odb()?;
+    let mut entries = BTreeMap::new();
+    let mut error = None;
+
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() != Some(ObjectType::Blob) || entry.filemode() == SYMLINK_MODE {
+            return TreeWalkResult::Ok;
+        }
+        let Some(name) = entry.name() else {
+            return TreeWalkResult::Ok;
+        };
+
+        match odb.read_header(entry.id()) {
+            Ok((size, _)) => {
+                entries.insert(
+                    Path::new(dir).join(name),
+                    SnapshotEntry {
+                        oid: entry.id(),
+                        size: size as u64,
+                    },
+                );
+                TreeWalkResult::Ok
+            }
+            Err(e) => {
+                error = Some(e);
+                TreeWalkResult::Abort
+            }
+        }
+    })?;
+
+    match error {
+        Some(e) => Err(e.into()),
+        None => Ok(entries),
+    }
+}
+
+/// Strip `root` from `path`, rejecting any `..` that would escape it
+fn strip_root(path: &Path, root: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut normalized = PathBuf::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return None;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Test User")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test User")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Repository with a `v1` tag whose tree differs from the working tree
+    fn create_repo() -> TempDir {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "pub fn old() {}\n").unwrap();
+        fs::write(root.join("src/removed.rs"), "pub fn gone() {}\n").unwrap();
+
+        git(root, &["init", "-q"]);
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "first"]);
+        git(root, &["tag", "v1"]);
+
+        fs::write(root.join("src/lib.rs"), "pub fn new() {}\n").unwrap();
+        git(root, &["rm", "-q", "src/removed.rs"]);
+        git(root, &["commit", "-q", "-am", "second"]);
+        temp_dir
+    }
+
+    #[test]
+    fn test_reads_blobs_from_revision() {
+        let temp_dir = create_repo();
+        let snapshot = GitSnapshot::open(temp_dir.path(), "v1").unwrap();
+        let root = snapshot.root().to_path_buf();
+
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(
+            snapshot.read_to_string(&root.join("src/lib.rs")).unwrap(),
+            "pub fn old() {}\n"
+        );
+        assert!(snapshot.contains(&root.join("src/removed.rs")));
+        assert!(!root.join("src/removed.rs").exists());
+        assert_eq!(snapshot.size(&root.join("src/lib.rs")), Some(16));
+    }
+
+    #[test]
+    fn test_relative_expressions_resolve() {
+        let temp_dir = create_repo();
+        let head = GitSnapshot::open(temp_dir.path(), "HEAD").unwrap();
+        let parent = GitSnapshot::open(temp_dir.path(), "HEAD~1").unwrap();
+
+        assert_eq!(head.len(), 1);
+        assert_eq!(parent.len(), 2);
+        assert_ne!(head.commit_id(), parent.commit_id());
+    }
+
+    #[test]
+    fn test_missing_file_is_not_found() {
+        let temp_dir = create_repo();
+        let snapshot = GitSnapshot::open(temp_dir.path(), "HEAD").unwrap();
+        let err = snapshot
+            .read(&snapshot.root().join("src/removed.rs"))
+            .unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(snapshot.read(Path::new("/etc/passwd")).is_err());
+    }
+
+    #[test]
+    fn test_directories_and_escapes() {
+        let temp_dir = create_repo();
+        let snapshot = GitSnapshot::open(temp_dir.path(), "v1").unwrap();
+        let root = snapshot.root();
+
+        assert!(snapshot.is_dir(root));
+        assert!(snapshot.is_dir(&root.join("src")));
+        assert!(!snapshot.is_dir(&root.join("src/lib.rs")));
+        assert!(snapshot.relative_path(&root.join("../outside")).is_none());
+    }
+
+    #[test]
+    fn test_unknown_revision_is_rejected() {
+        let temp_dir = create_repo();
+        let err = GitSnapshot::open(temp_dir.path(), "no-such-branch").unwrap_err();
+        assert!(err.to_string().contains("Cannot resolve 'no-such-branch'"));
+    }
+}
@@ -0,0 +1,204 @@
+//! The file tree semantic analysis resolves imports against
+//!
+//! Resolvers check whether candidate files exist, list directories and read
+//! manifests such as `package.json` or `Cargo.toml`. Normally that is the
+//! working tree, but a [`SourceTree`] holding a [`GitSnapshot`] answers paths
+//! inside its repository from the snapshot instead, so `--at` resolves
+//! imports and follows manifests exactly as they were at the revision. Paths
+//! outside the repository are read from disk as usual.
+
+use crate::core::snapshot::GitSnapshot;
+use git2::Oid;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// The files an analysis reads: the working tree, or a git snapshot of it
+#[derive(Debug, Clone, Default)]
+pub struct SourceTree {
+    snapshot: Option<Arc<GitSnapshot>>,
+}
+
+/// Version of a file, telling whether a value parsed from it is outdated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileVersion {
+    /// Modification time of a working tree file
+    Modified(SystemTime),
+    /// Blob of a snapshot file
+    Blob(Oid),
+}
+
+impl SourceTree {
+    /// Read paths inside the repository of `snapshot` from it, if given, and
+    /// everything else from disk
+    pub fn new(snapshot: Option<Arc<GitSnapshot>>) -> Self {
+        Self { snapshot }
+    }
+
+    /// The snapshot paths inside its repository are read from
+    pub fn snapshot(&self) -> Option<&Arc<GitSnapshot>> {
+        self.snapshot.as_ref()
+    }
+
+    /// The snapshot if `path` is inside its repository
+    fn snapshot_for(&self, path: &Path) -> Option<&GitSnapshot> {
+        self.snapshot
+            .as_deref()
+            .filter(|snapshot| snapshot.relative_path(path).is_some())
+    }
+
+    /// Whether `path` is a file
+    pub fn is_file(&self, path: &Path) -> bool {
+        match self.snapshot_for(path) {
+            Some(snapshot) => snapshot.contains(path),
+            None => path.is_file(),
+        }
+    }
+
+    /// Whether `path` is a directory
+    pub fn is_dir(&self, path: &Path) -> bool {
+        match self.snapshot_for(path) {
+            Some(snapshot) => snapshot.is_dir(path),
+            None => path.is_dir(),
+        }
+    }
+
+    /// Whether `path` is a file or a directory
+    pub fn exists(&self, path: &Path) -> bool {
+        match self.snapshot_for(path) {
+            Some(snapshot) => snapshot.contains(path) || snapshot.is_dir(path),
+            None => path.exists(),
+        }
+    }
+
+    /// Read the file at `path` as UTF-8 text
+    pub fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        match self.snapshot_for(path) {
+            Some(snapshot) => snapshot.read_to_string(path),
+            None => std::fs::read_to_string(path),
+        }
+    }
+
+    /// Paths of the files and directories directly inside `dir`
+    pub fn read_dir(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        match self.snapshot_for(dir) {
+            Some(snapshot) => snapshot
+                .read_dir(dir)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, not_found(dir, snapshot))),
+            None => std::fs::read_dir(dir)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect(),
+        }
+    }
+
+    /// Size in bytes of the file at `path`
+    pub fn len(&self, path: &Path) -> io::Result<u64> {
+        match self.snapshot_for(path) {
+            Some(snapshot) => snapshot
+                .size(path)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, not_found(path, snapshot))),
+            None => Ok(std::fs::metadata(path)?.len()),
+        }
+    }
+
+    /// Version of the file at `path`, `None` if it does not exist
+    pub fn version(&self, path: &Path) -> Option<FileVersion> {
+        match self.snapshot_for(path) {
+            Some(snapshot) => snapshot.blob_id(path).map(FileVersion::Blob),
+            None => std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(FileVersion::Modified),
+        }
+    }
+
+    /// Absolute form of `path` with `.` and `..` resolved
+    ///
+    /// Snapshot paths are resolved against the repository root without
+    /// touching the working tree, where they may not exist.
+    pub fn canonicalize(&self, path: &Path) -> io::Result<PathBuf> {
+        match self.snapshot_for(path) {
+            Some(snapshot) if self.exists(path) => snapshot
+                .relative_path(path)
+                .map(|relative| snapshot.root().join(relative))
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, not_found(path, snapshot))),
+            Some(snapshot) => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                not_found(path, snapshot),
+            )),
+            None => path.canonicalize(),
+        }
+    }
+}
+
+fn not_found(path: &Path, snapshot: &GitSnapshot) -> String {
+    format!(
+        "{} does not exist at {}",
+        path.display(),
+        snapshot.revision()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Test User")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test User")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn test_snapshot_tree_answers_paths_in_its_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("src/old.rs"), "old\n").unwrap();
+        fs::write(root.join("src/nested/mod.rs"), "\n").unwrap();
+        git(&root, &["init", "-q"]);
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-q", "-m", "first"]);
+        fs::remove_file(root.join("src/old.rs")).unwrap();
+        fs::write(root.join("src/new.rs"), "new\n").unwrap();
+
+        let old = root.join("src/old.rs");
+        let new = root.join("src/new.rs");
+        let working_tree = SourceTree::default();
+        assert!(!working_tree.is_file(&old));
+        assert!(working_tree.is_file(&new));
+
+        let snapshot = Arc::new(GitSnapshot::open(&root, "HEAD").unwrap());
+        let tree = SourceTree::new(Some(snapshot));
+        assert!(tree.is_file(&old));
+        assert!(!tree.exists(&new));
+        assert!(tree.is_dir(&root.join("src/nested")));
+        assert_eq!(tree.read_to_string(&old).unwrap(), "old\n");
+        assert_eq!(tree.len(&old).unwrap(), 4);
+        assert!(matches!(tree.version(&old), Some(FileVersion::Blob(_))));
+        assert_eq!(
+            tree.canonicalize(&root.join("src/nested/../old.rs"))
+                .unwrap(),
+            old
+        );
+        assert_eq!(
+            tree.read_dir(&root.join("src")).unwrap(),
+            vec![root.join("src/nested"), old.clone()]
+        );
+
+        // Other trees of the same repository are unaffected
+        assert!(!working_tree.is_file(&old));
+        assert!(working_tree.is_file(&new));
+    }
+}
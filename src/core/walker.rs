@@ -1,14 +1,19 @@
 //! Directory walking functionality with .gitignore and .context-creator-ignore support
 
+use crate::core::progress::{Phase, ProgressReporter};
+use crate::core::snapshot::GitSnapshot;
 use crate::utils::error::ContextCreatorError;
 use crate::utils::file_ext::{is_binary_extension, FileType};
 use anyhow::Result;
 use glob::Pattern;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::Override;
 use ignore::{Walk, WalkBuilder};
 use rayon::prelude::*;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tracing::warn;
+use tracing::{debug, warn};
 
 /// Compiled priority rule for efficient pattern matching
 ///
@@ -72,6 +77,10 @@ pub struct WalkOptions {
     pub custom_priorities: Vec<CompiledPriority>,
     /// Filter out binary files by extension
     pub filter_binary_files: bool,
+    /// Walk this git snapshot instead of the working tree
+    pub snapshot: Option<Arc<GitSnapshot>>,
+    /// Receives walk progress and stops the walk when cancelled
+    pub reporter: ProgressReporter,
 }
 
 impl WalkOptions {
@@ -116,6 +125,8 @@ impl WalkOptions {
             include_patterns,
             custom_priorities,
             filter_binary_files: config.get_prompt().is_some(),
+            snapshot: None,
+            reporter: config.reporter.clone(),
         })
     }
 }
@@ -132,6 +143,8 @@ impl Default for WalkOptions {
             include_patterns: vec![],
             custom_priorities: vec![],
             filter_binary_files: false,
+            snapshot: None,
+            reporter: Default::default(),
         }
     }
 }
@@ -164,44 +177,16 @@ pub struct FileInfo {
 impl FileInfo {
     /// Get a display string for the file type
     pub fn file_type_display(&self) -> &'static str {
-        use crate::utils::file_ext::FileType;
-        match self.file_type {
-            FileType::Rust => "Rust",
-            FileType::Python => "Python",
-            FileType::JavaScript => "JavaScript",
-            FileType::TypeScript => "TypeScript",
-            FileType::Go => "Go",
-            FileType::Java => "Java",
-            FileType::Cpp => "C++",
-            FileType::C => "C",
-            FileType::CSharp => "C#",
-            FileType::Ruby => "Ruby",
-            FileType::Php => "PHP",
-            FileType::Swift => "Swift",
-            FileType::Kotlin => "Kotlin",
-            FileType::Scala => "Scala",
-            FileType::Haskell => "Haskell",
-            FileType::Dart => "Dart",
-            FileType::Lua => "Lua",
-            FileType::R => "R",
-            FileType::Julia => "Julia",
-            FileType::Elixir => "Elixir",
-            FileType::Elm => "Elm",
-            FileType::Markdown => "Markdown",
-            FileType::Json => "JSON",
-            FileType::Yaml => "YAML",
-            FileType::Toml => "TOML",
-            FileType::Xml => "XML",
-            FileType::Html => "HTML",
-            FileType::Css => "CSS",
-            FileType::Text => "Text",
-            FileType::Other => "Other",
-        }
+        self.file_type.display_name()
     }
 }
 
 /// Walk a path (file or directory) and collect file information
 pub fn walk_directory(root: &Path, options: WalkOptions) -> Result<Vec<FileInfo>> {
+    if let Some(snapshot) = options.snapshot.clone() {
+        return walk_snapshot(&snapshot, root, &options);
+    }
+
     if !root.exists() {
         return Err(ContextCreatorError::InvalidPath(format!(
             "Path does not exist: {}",
@@ -253,6 +238,248 @@ pub fn walk_directory(root: &Path, options: WalkOptions) -> Result<Vec<FileInfo>
     }
 }
 
+/// Directories a walk of `root` descends into, `root` included
+///
+/// Honours the same ignore files and hidden file setting as
+/// [`walk_directory`], so ignored trees such as build output are left out,
+/// while directories without selected files are listed. Empty if `root` is
+/// not a directory of the working tree.
+pub fn walk_directories(root: &Path, options: &WalkOptions) -> Result<Vec<PathBuf>> {
+    if options.snapshot.is_some() || !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let root = root.canonicalize()?;
+    let mut directories = Vec::new();
+    for entry in build_walker(&root, options)? {
+        options.reporter.check()?;
+        let entry = entry?;
+        if entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_dir())
+        {
+            directories.push(entry.into_path());
+        }
+    }
+
+    Ok(directories)
+}
+
+/// Walk the tree of a git snapshot below `root`
+///
+/// Mirrors the working tree walk: `.gitignore`, `.ignore` and the custom
+/// ignore file are read from the snapshot itself, hidden files are skipped
+/// unless requested, and include/ignore patterns take precedence over both.
+fn walk_snapshot(
+    snapshot: &GitSnapshot,
+    root: &Path,
+    options: &WalkOptions,
+) -> Result<Vec<FileInfo>> {
+    let missing = || {
+        ContextCreatorError::InvalidPath(format!(
+            "Path does not exist at {}: {}",
+            snapshot.revision(),
+            root.display()
+        ))
+    };
+    let prefix = snapshot.relative_path(root).ok_or_else(missing)?;
+    let walk_root = snapshot.root().join(&prefix);
+
+    // Handle individual files
+    if let Some(size) = snapshot.size(&walk_root) {
+        let relative_path = PathBuf::from(
+            walk_root
+                .file_name()
+                .ok_or_else(|| anyhow::anyhow!("Invalid file name"))?,
+        );
+        let file_type = FileType::from_path(&walk_root);
+        let priority = calculate_priority(&file_type, &relative_path, &options.custom_priorities);
+        return Ok(vec![FileInfo {
+            path: walk_root,
+            relative_path,
+            size,
+            file_type,
+            priority,
+            imports: Vec::new(),
+            imported_by: Vec::new(),
+            function_calls: Vec::new(),
+            type_references: Vec::new(),
+            exported_functions: Vec::new(),
+        }]);
+    }
+
+    if !snapshot.is_dir(&walk_root) {
+        return Err(missing().into());
+    }
+
+    let filter = SnapshotFilter::new(snapshot, &walk_root, options)?;
+    let mut files = Vec::new();
+    for (relative, size) in snapshot.files() {
+        let Ok(walk_relative) = relative.strip_prefix(&prefix) else {
+            continue;
+        };
+        if filter.is_ignored(walk_relative) {
+            continue;
+        }
+        let path = snapshot.root().join(relative);
+        if let Some(file_info) = build_file_info(&path, size, &walk_root, options)? {
+            files.push(file_info);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Ignore rules for a snapshot walk, evaluated the way the `ignore` crate does
+struct SnapshotFilter {
+    walk_root: PathBuf,
+    /// Path of `walk_root` relative to the repository root
+    prefix: PathBuf,
+    overrides: Option<Override>,
+    /// Ignore files per repository-relative directory, highest precedence first
+    ignore_files: HashMap<PathBuf, Vec<Gitignore>>,
+    /// `.git/info/exclude` and the global excludes file, which are not
+    /// versioned and rank below every ignore file
+    excludes: Vec<Gitignore>,
+    include_hidden: bool,
+}
+
+impl SnapshotFilter {
+    fn new(snapshot: &GitSnapshot, walk_root: &Path, options: &WalkOptions) -> Result<Self> {
+        let prefix = walk_root
+            .strip_prefix(snapshot.root())
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+
+        // Custom ignore files override .ignore, which overrides .gitignore
+        let ignore_names = [options.ignore_file.as_str(), ".ignore", ".gitignore"];
+        let mut ignore_files: HashMap<PathBuf, Vec<Gitignore>> = HashMap::new();
+        let mut directories: Vec<PathBuf> = snapshot
+            .files()
+            .filter(|(path, _)| {
+                path.file_name()
+                    .is_some_and(|name| ignore_names.iter().any(|ignore| name == *ignore))
+            })
+            .filter_map(|(path, _)| path.parent().map(Path::to_path_buf))
+            .collect();
+        directories.sort();
+        directories.dedup();
+
+        for dir in directories {
+            let mut matchers = Vec::new();
+            for name in ignore_names {
+                let path = snapshot.root().join(&dir).join(name);
+                let Ok(content) = snapshot.read_to_string(&path) else {
+                    continue;
+                };
+                let mut builder = GitignoreBuilder::new(snapshot.root().join(&dir));
+                for line in content.lines() {
+                    if let Err(e) = builder.add_line(Some(path.clone()), line) {
+                        warn!("Skipping invalid ignore rule in {}: {}", path.display(), e);
+                    }
+                }
+                match builder.build() {
+                    Ok(matcher) => matchers.push(matcher),
+                    Err(e) => warn!("Skipping ignore file {}: {}", path.display(), e),
+                }
+            }
+            ignore_files.insert(dir, matchers);
+        }
+
+        Ok(Self {
+            walk_root: walk_root.to_path_buf(),
+            prefix,
+            overrides: build_overrides(walk_root, options)?,
+            ignore_files,
+            excludes: repository_excludes(snapshot),
+            include_hidden: options.include_hidden,
+        })
+    }
+
+    /// Whether a file (relative to the walk root) or any directory above it is ignored
+    fn is_ignored(&self, relative: &Path) -> bool {
+        let mut current = PathBuf::new();
+        let mut components = relative.components().peekable();
+        while let Some(component) = components.next() {
+            current.push(component);
+            let is_dir = components.peek().is_some();
+            if self.matched(&current, is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn matched(&self, relative: &Path, is_dir: bool) -> bool {
+        let path = self.walk_root.join(relative);
+
+        if let Some(overrides) = &self.overrides {
+            let mat = overrides.matched(&path, is_dir);
+            if mat.is_ignore() {
+                return true;
+            }
+            if mat.is_whitelist() {
+                return false;
+            }
+        }
+
+        // Deeper ignore files take precedence over their parents
+        let repo_relative = self.prefix.join(relative);
+        let ignore_files = repo_relative
+            .ancestors()
+            .skip(1)
+            .flat_map(|dir| self.ignore_files.get(dir).into_iter().flatten());
+        for matcher in ignore_files.chain(&self.excludes) {
+            let mat = matcher.matched(&path, is_dir);
+            if mat.is_ignore() {
+                return true;
+            }
+            if mat.is_whitelist() {
+                return false;
+            }
+        }
+
+        !self.include_hidden
+            && relative
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+    }
+}
+
+/// The excludes of the repository of `snapshot` that the working tree walk
+/// honours besides its ignore files, highest precedence first
+///
+/// They are read from disk, as they belong to the clone rather than to any
+/// revision.
+fn repository_excludes(snapshot: &GitSnapshot) -> Vec<Gitignore> {
+    let mut excludes = Vec::new();
+
+    let info_exclude = snapshot.common_dir().join("info").join("exclude");
+    if info_exclude.is_file() {
+        let mut builder = GitignoreBuilder::new(snapshot.root());
+        if let Some(e) = builder.add(&info_exclude) {
+            warn!(
+                "Skipping invalid ignore rule in {}: {}",
+                info_exclude.display(),
+                e
+            );
+        }
+        match builder.build() {
+            Ok(matcher) => excludes.push(matcher),
+            Err(e) => warn!("Skipping ignore file {}: {}", info_exclude.display(), e),
+        }
+    }
+
+    // `core.excludesFile`, or git's default of `$XDG_CONFIG_HOME/git/ignore`
+    let (global, error) = GitignoreBuilder::new(snapshot.root()).build_global();
+    if let Some(e) = error {
+        debug!("Skipping global excludes file: {}", e);
+    }
+    excludes.push(global);
+
+    excludes
+}
+
 /// Sanitize include patterns to prevent security issues
 pub fn sanitize_pattern(pattern: &str) -> Result<String> {
     // Length limit to prevent resource exhaustion
@@ -312,62 +539,70 @@ fn build_walker(root: &Path, options: &WalkOptions) -> Result<Walk> {
         .parents(true)
         .add_custom_ignore_filename(&options.ignore_file);
 
-    // Handle both include and ignore patterns using OverrideBuilder
-    if !options.include_patterns.is_empty() || !options.ignore_patterns.is_empty() {
-        let mut override_builder = ignore::overrides::OverrideBuilder::new(root);
-
-        // If we have no include patterns but have ignore patterns, we need to include everything first
-        if options.include_patterns.is_empty() && !options.ignore_patterns.is_empty() {
-            // Add a pattern to include everything
-            override_builder.add("**/*").map_err(|e| {
-                ContextCreatorError::InvalidConfiguration(format!(
-                    "Failed to add include-all pattern: {e}"
-                ))
-            })?;
-        }
+    if let Some(overrides) = build_overrides(root, options)? {
+        builder.overrides(overrides);
+    }
 
-        // Add include patterns first (without prefix for inclusion)
-        for pattern in &options.include_patterns {
-            if !pattern.trim().is_empty() {
-                // Sanitize pattern for security
-                let sanitized_pattern = sanitize_pattern(pattern)?;
+    Ok(builder.build())
+}
 
-                // Include patterns are added directly (not as negations)
-                override_builder.add(&sanitized_pattern).map_err(|e| {
-                    ContextCreatorError::InvalidConfiguration(format!(
-                        "Invalid include pattern '{pattern}': {e}"
-                    ))
-                })?;
-            }
-        }
+/// Compile include and ignore patterns into overrides rooted at `root`
+///
+/// Returns `None` when no patterns are configured.
+fn build_overrides(root: &Path, options: &WalkOptions) -> Result<Option<Override>> {
+    if options.include_patterns.is_empty() && options.ignore_patterns.is_empty() {
+        return Ok(None);
+    }
 
-        // Add ignore patterns after include patterns (with ! prefix for exclusion)
-        // This ensures ignore patterns take precedence over include patterns
-        for pattern in &options.ignore_patterns {
-            if !pattern.trim().is_empty() {
-                // Sanitize pattern for security
-                let sanitized_pattern = sanitize_pattern(pattern)?;
-
-                // Prefix with ! to make it an ignore pattern
-                let ignore_pattern = format!("!{sanitized_pattern}");
-                override_builder.add(&ignore_pattern).map_err(|e| {
-                    ContextCreatorError::InvalidConfiguration(format!(
-                        "Invalid ignore pattern '{pattern}': {e}"
-                    ))
-                })?;
-            }
-        }
+    let mut override_builder = ignore::overrides::OverrideBuilder::new(root);
 
-        let overrides = override_builder.build().map_err(|e| {
+    // If we have no include patterns but have ignore patterns, we need to include everything first
+    if options.include_patterns.is_empty() && !options.ignore_patterns.is_empty() {
+        // Add a pattern to include everything
+        override_builder.add("**/*").map_err(|e| {
             ContextCreatorError::InvalidConfiguration(format!(
-                "Failed to build pattern overrides: {e}"
+                "Failed to add include-all pattern: {e}"
             ))
         })?;
+    }
 
-        builder.overrides(overrides);
+    // Add include patterns first (without prefix for inclusion)
+    for pattern in &options.include_patterns {
+        if !pattern.trim().is_empty() {
+            // Sanitize pattern for security
+            let sanitized_pattern = sanitize_pattern(pattern)?;
+
+            // Include patterns are added directly (not as negations)
+            override_builder.add(&sanitized_pattern).map_err(|e| {
+                ContextCreatorError::InvalidConfiguration(format!(
+                    "Invalid include pattern '{pattern}': {e}"
+                ))
+            })?;
+        }
     }
 
-    Ok(builder.build())
+    // Add ignore patterns after include patterns (with ! prefix for exclusion)
+    // This ensures ignore patterns take precedence over include patterns
+    for pattern in &options.ignore_patterns {
+        if !pattern.trim().is_empty() {
+            // Sanitize pattern for security
+            let sanitized_pattern = sanitize_pattern(pattern)?;
+
+            // Prefix with ! to make it an ignore pattern
+            let ignore_pattern = format!("!{sanitized_pattern}");
+            override_builder.add(&ignore_pattern).map_err(|e| {
+                ContextCreatorError::InvalidConfiguration(format!(
+                    "Invalid ignore pattern '{pattern}': {e}"
+                ))
+            })?;
+        }
+    }
+
+    let overrides = override_builder.build().map_err(|e| {
+        ContextCreatorError::InvalidConfiguration(format!("Failed to build pattern overrides: {e}"))
+    })?;
+
+    Ok(Some(overrides))
 }
 
 /// Walk directory sequentially
@@ -375,6 +610,7 @@ fn walk_sequential(walker: Walk, root: &Path, options: &WalkOptions) -> Result<V
     let mut files = Vec::new();
 
     for entry in walker {
+        options.reporter.check()?;
         let entry = entry?;
         let path = entry.path();
 
@@ -404,11 +640,20 @@ fn walk_parallel(walker: Walk, root: &Path, options: &WalkOptions) -> Result<Vec
         .filter_map(|e| e.ok())
         .filter(|e| !e.path().is_dir())
         .collect();
+    options.reporter.check()?;
 
     // Process in parallel with proper error collection
+    let total = entries.len();
+    let processed = std::sync::atomic::AtomicUsize::new(0);
     let results: Vec<Result<Option<FileInfo>, ContextCreatorError>> = entries
         .into_par_iter()
         .map(|entry| {
+            // Skip the remaining files once cancelled
+            if options.reporter.is_cancelled() {
+                return Ok(None);
+            }
+            options.reporter.advance(Phase::Walk, &processed, total);
+
             let path = entry.path();
             match process_file(path, &root, &options) {
                 Ok(file_info) => Ok(file_info),
@@ -419,6 +664,7 @@ fn walk_parallel(walker: Walk, root: &Path, options: &WalkOptions) -> Result<Vec
             }
         })
         .collect();
+    options.reporter.check()?;
 
     // Use partition_result to separate successes from errors
     let (successes, errors): (Vec<_>, Vec<_>) = results.into_iter().partition_result();
@@ -462,8 +708,16 @@ fn process_file(path: &Path, root: &Path, options: &WalkOptions) -> Result<Optio
         Err(_) => return Ok(None), // Skip files we can't read
     };
 
-    let size = metadata.len();
+    build_file_info(path, metadata.len(), root, options)
+}
 
+/// Build file information for a file of known size, applying size and binary filters
+fn build_file_info(
+    path: &Path,
+    size: u64,
+    root: &Path,
+    options: &WalkOptions,
+) -> Result<Option<FileInfo>> {
     // Check file size limit
     if let Some(max_size) = options.max_file_size {
         if size > max_size as u64 {
@@ -639,13 +893,11 @@ mod tests {
         assert_eq!(files.len(), 3);
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("main.rs")));
-        assert!(files
-            .iter()
-            .any(|f| f.relative_path == PathBuf::from("lib.rs")));
+            .any(|f| f.relative_path == Path::new("main.rs")));
+        assert!(files.iter().any(|f| f.relative_path == Path::new("lib.rs")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("src/utils.rs")));
+            .any(|f| f.relative_path == Path::new("src/utils.rs")));
     }
 
     #[test]
@@ -753,10 +1005,10 @@ mod tests {
         assert!(files.len() >= 2);
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("main.rs")));
+            .any(|f| f.relative_path == Path::new("main.rs")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("test.rs")));
+            .any(|f| f.relative_path == Path::new("test.rs")));
     }
 
     #[test]
@@ -780,10 +1032,8 @@ mod tests {
         assert!(files.len() >= 2);
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("main.rs")));
-        assert!(files
-            .iter()
-            .any(|f| f.relative_path == PathBuf::from("lib.rs")));
+            .any(|f| f.relative_path == Path::new("main.rs")));
+        assert!(files.iter().any(|f| f.relative_path == Path::new("lib.rs")));
     }
 
     #[test]
@@ -804,13 +1054,33 @@ mod tests {
         assert_eq!(files.len(), 3);
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("main.rs")));
+            .any(|f| f.relative_path == Path::new("main.rs")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("src/lib.rs")));
+            .any(|f| f.relative_path == Path::new("src/lib.rs")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("src/utils/helpers.rs")));
+            .any(|f| f.relative_path == Path::new("src/utils/helpers.rs")));
+    }
+
+    #[test]
+    fn test_walk_directories_lists_directories_without_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().canonicalize().unwrap();
+
+        fs::create_dir_all(root.join("src/empty")).unwrap();
+        fs::create_dir(root.join("target")).unwrap();
+        File::create(root.join("src/lib.rs")).unwrap();
+        File::create(root.join("target/out.rs")).unwrap();
+        fs::write(root.join(".ignore"), "target/\n").unwrap();
+
+        let mut directories = walk_directories(&root, &WalkOptions::default()).unwrap();
+        directories.sort();
+
+        assert_eq!(
+            directories,
+            vec![root.clone(), root.join("src"), root.join("src/empty")]
+        );
     }
 
     #[test]
@@ -1498,6 +1768,8 @@ mod tests {
             include_patterns: vec!["../../../etc/passwd".to_string()], // Should be rejected
             custom_priorities: vec![],
             filter_binary_files: false,
+            snapshot: None,
+            reporter: Default::default(),
         };
 
         // This should fail due to sanitization
@@ -1569,16 +1841,16 @@ mod tests {
         assert_eq!(files.len(), 2);
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("main.rs")));
+            .any(|f| f.relative_path == Path::new("main.rs")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("config.json")));
+            .any(|f| f.relative_path == Path::new("config.json")));
         assert!(!files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("image.jpg")));
+            .any(|f| f.relative_path == Path::new("image.jpg")));
         assert!(!files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("video.mp4")));
+            .any(|f| f.relative_path == Path::new("video.mp4")));
     }
 
     #[test]
@@ -1604,16 +1876,16 @@ mod tests {
         assert_eq!(files.len(), 4);
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("main.rs")));
+            .any(|f| f.relative_path == Path::new("main.rs")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("config.json")));
+            .any(|f| f.relative_path == Path::new("config.json")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("image.jpg")));
+            .any(|f| f.relative_path == Path::new("image.jpg")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("video.mp4")));
+            .any(|f| f.relative_path == Path::new("video.mp4")));
     }
 
     #[test]
@@ -1638,20 +1910,18 @@ mod tests {
 
         // Then: Text files without extensions are kept, binaries are filtered
         assert_eq!(files.len(), 4);
+        assert!(files.iter().any(|f| f.relative_path == Path::new("README")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("README")));
-        assert!(files
-            .iter()
-            .any(|f| f.relative_path == PathBuf::from("LICENSE")));
+            .any(|f| f.relative_path == Path::new("LICENSE")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("Makefile")));
+            .any(|f| f.relative_path == Path::new("Makefile")));
         assert!(files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("Dockerfile")));
+            .any(|f| f.relative_path == Path::new("Dockerfile")));
         assert!(!files
             .iter()
-            .any(|f| f.relative_path == PathBuf::from("binary.exe")));
+            .any(|f| f.relative_path == Path::new("binary.exe")));
     }
 }
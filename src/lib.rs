@@ -25,6 +25,13 @@ pub use utils::error::ContextCreatorError;
 
 /// Main entry point for the context creator library
 pub fn run(mut config: Config) -> Result<()> {
+    // These commands work on the configuration files themselves
+    match &config.command {
+        Some(cli::Commands::Init { .. }) => return commands::run_init(config),
+        Some(cli::Commands::Config { .. }) => return commands::run_config(config),
+        _ => {}
+    }
+
     // Load configuration from file first
     config.load_from_file()?;
 
@@ -40,26 +47,29 @@ pub fn run(mut config: Config) -> Result<()> {
             println!("{}", cli::USAGE_EXAMPLES);
             return Ok(());
         }
+        Some(cli::Commands::Init { .. }) | Some(cli::Commands::Config { .. }) => {
+            unreachable!("handled before loading configuration")
+        }
         None => {} // Continue with normal processing
     }
 
     // Handle remote repository if specified
-    let _temp_dir = if let Some(repo_url) = &config.remote {
+    let _checkout = if let Some(repo) = config.remote_repository()? {
         if config.verbose > 0 {
             debug!(
                 "Starting context-creator with remote repository: {}",
-                repo_url
+                repo.clone_url
             );
         }
 
         // Fetch the repository
-        let temp_dir = crate::remote::fetch_repository(repo_url, config.verbose > 0)?;
-        let repo_path = crate::remote::get_repo_path(&temp_dir, repo_url)?;
+        let cache = config.remote_cache();
+        let checkout = crate::remote::fetch_repository(&repo, cache.as_ref(), config.verbose > 0)?;
 
-        // Update config to use the cloned repository
-        config.paths = Some(vec![repo_path]);
+        // Update config to use the cloned repository (scoped to the subdirectory)
+        config.paths = Some(vec![checkout.path().to_path_buf()]);
 
-        Some(temp_dir) // Keep temp_dir alive until end of function
+        Some(checkout) // Keep the checkout alive until end of function
     } else {
         None
     };
@@ -116,11 +126,31 @@ pub fn run(mut config: Config) -> Result<()> {
             );
         }
 
+        // With --at, read this directory's repository at the requested revision,
+        // and resolve imports against it too
+        let (walk_options, cache) = match &config.at {
+            Some(revision) => {
+                let snapshot = Arc::new(core::snapshot::GitSnapshot::open(directory, revision)?);
+                if config.verbose > 0 {
+                    debug!(
+                        "Reading {} at {} ({})",
+                        directory.display(),
+                        revision,
+                        snapshot.commit_id()
+                    );
+                }
+                let mut walk_options = walk_options.clone();
+                walk_options.snapshot = Some(snapshot.clone());
+                (walk_options, Arc::new(FileCache::with_snapshot(snapshot)))
+            }
+            None => (walk_options.clone(), cache.clone()),
+        };
+
         let output = process_directory(
             directory,
-            walk_options.clone(),
+            walk_options,
             context_options.clone(),
-            cache.clone(),
+            cache,
             &config,
         )?;
         all_outputs.push((directory.clone(), output));
@@ -213,10 +243,92 @@ fn process_directory(
     cache: Arc<FileCache>,
     config: &Config,
 ) -> Result<String> {
+    let files = collect_files(path, &walk_options, &cache, config)?;
+
+    if config.verbose > 0 {
+        debug!("File list:");
+        for file in &files {
+            debug!(
+                "  {} ({})",
+                file.relative_path.display(),
+                file.file_type_display()
+            );
+        }
+    }
+
+    // Prioritize files if needed
+    let prioritized_files = if context_options.max_tokens.is_some() {
+        if config.progress && !config.quiet {
+            info!("Prioritizing files for token limit...");
+        }
+        core::prioritizer::prioritize_files(files, &context_options, cache.clone())?
+    } else {
+        files
+    };
+
+    if config.progress && !config.quiet {
+        info!(
+            "Generating markdown from {} files...",
+            prioritized_files.len()
+        );
+    }
+
+    let output = render_context(
+        prioritized_files,
+        context_options,
+        cache,
+        config.output_format,
+        path,
+    )?;
+
+    if config.progress && !config.quiet {
+        info!("Output generation complete");
+    }
+
+    Ok(output)
+}
+
+/// Format prioritized files in the given output style
+pub fn render_context(
+    files: Vec<core::walker::FileInfo>,
+    context_options: ContextOptions,
+    cache: Arc<FileCache>,
+    format: cli::OutputFormat,
+    base_dir: &Path,
+) -> Result<String> {
+    if format == cli::OutputFormat::Markdown {
+        // Use existing generate_markdown for backward compatibility
+        core::context_builder::generate_markdown(files, context_options, cache)
+    } else {
+        // Use new formatter system
+        core::context_builder::generate_digest(
+            files,
+            context_options,
+            cache,
+            format,
+            &base_dir.display().to_string(),
+        )
+    }
+}
+
+/// Walk a directory and expand the result through semantic analysis
+///
+/// Semantic expansion runs when `config` enables import tracing, callers or
+/// types. The files are returned unprioritized.
+pub fn collect_files(
+    path: &Path,
+    walk_options: &WalkOptions,
+    cache: &Arc<FileCache>,
+    config: &Config,
+) -> Result<Vec<core::walker::FileInfo>> {
     // Walk the directory
     if config.progress && !config.quiet {
         info!("Scanning directory: {}", path.display());
     }
+    config.reporter.start(
+        core::progress::Phase::Walk,
+        format!("Scanning directory: {}", path.display()),
+    );
     let mut files = core::walker::walk_directory(path, walk_options.clone())?;
 
     if config.progress && !config.quiet {
@@ -228,13 +340,17 @@ fn process_directory(
         if config.progress && !config.quiet {
             info!("Analyzing semantic dependencies...");
         }
+        config.reporter.start(
+            core::progress::Phase::Analyze,
+            format!("Analyzing semantic dependencies of {} files", files.len()),
+        );
 
         // Perform single-pass project analysis
         let project_analysis = core::project_analyzer::ProjectAnalysis::analyze_project(
             path,
-            &walk_options,
+            walk_options,
             config,
-            &cache,
+            cache,
         )?;
 
         // Create initial set from our filtered files
@@ -256,11 +372,13 @@ fn process_directory(
         let files_map = core::file_expander::expand_file_list_with_context(
             initial_files_map,
             config,
-            &cache,
-            &walk_options,
+            cache,
+            walk_options,
             &project_analysis.file_map,
         )?;
 
+        config.reporter.check()?;
+
         // Convert back to Vec<FileInfo>
         files = files_map.into_values().collect();
 
@@ -276,54 +394,7 @@ fn process_directory(
         }
     }
 
-    if config.verbose > 0 {
-        debug!("File list:");
-        for file in &files {
-            debug!(
-                "  {} ({})",
-                file.relative_path.display(),
-                file.file_type_display()
-            );
-        }
-    }
-
-    // Prioritize files if needed
-    let prioritized_files = if context_options.max_tokens.is_some() {
-        if config.progress && !config.quiet {
-            info!("Prioritizing files for token limit...");
-        }
-        core::prioritizer::prioritize_files(files, &context_options, cache.clone())?
-    } else {
-        files
-    };
-
-    if config.progress && !config.quiet {
-        info!(
-            "Generating markdown from {} files...",
-            prioritized_files.len()
-        );
-    }
-
-    // Generate output using the appropriate formatter
-    let output = if config.output_format == cli::OutputFormat::Markdown {
-        // Use existing generate_markdown for backward compatibility
-        core::context_builder::generate_markdown(prioritized_files, context_options, cache)?
-    } else {
-        // Use new formatter system
-        core::context_builder::generate_digest(
-            prioritized_files,
-            context_options,
-            cache,
-            config.output_format,
-            &path.display().to_string(),
-        )?
-    };
-
-    if config.progress && !config.quiet {
-        info!("Output generation complete");
-    }
-
-    Ok(output)
+    Ok(files)
 }
 
 /// Execute LLM CLI with the generated context
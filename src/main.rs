@@ -15,8 +15,9 @@ fn run_main() -> Result<()> {
     // Parse command line arguments
     let mut config = Config::parse();
 
-    // Load configuration from file if specified (but not in MCP mode)
-    if !config.mcp {
+    // Load configuration from file if specified (but not in MCP mode, and not
+    // for commands that inspect or create configuration files)
+    if !config.mcp && !config.manages_config_files() {
         config.load_from_file()?;
     }
 
@@ -81,6 +82,11 @@ async fn run_mcp_server(config: Config) -> Result<()> {
                 eprintln!("Starting RMCP MCP server (HTTP/SSE transport) on {addr}");
                 mcp_server::rmcp_server::transport::start_http(&addr).await?;
             }
+            "streamable-http" => {
+                let addr = format!("127.0.0.1:{}", config.mcp_port);
+                eprintln!("Starting RMCP MCP server (streamable HTTP transport) on {addr}/mcp");
+                mcp_server::rmcp_server::transport::start_streamable_http(&addr).await?;
+            }
             _ => {
                 anyhow::bail!("Invalid RMCP transport mode: {}", config.rmcp_transport);
             }
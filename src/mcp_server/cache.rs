@@ -1,9 +1,11 @@
 //! Cache implementation for MCP server
 
+use super::project_index::ProjectIndex;
 use moka::future::Cache;
 use serde::{Deserialize, Serialize};
 use std::hash::Hash;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Cache key for process_local_codebase requests
@@ -16,10 +18,14 @@ pub struct ProcessLocalCacheKey {
     pub include_imports: bool,
     pub max_tokens: Option<u32>,
     pub llm_tool: Option<String>,
+    /// Output style and analysis options, serialized
+    pub settings: String,
+    /// Fingerprint of the project contents, see [`ProjectIndex::fingerprint`]
+    pub fingerprint: u64,
 }
 
 impl ProcessLocalCacheKey {
-    pub fn from_request(request: &super::ProcessLocalRequest) -> Self {
+    pub fn from_request(request: &super::ProcessLocalRequest, fingerprint: u64) -> Self {
         Self {
             prompt: request.prompt.clone(),
             path: request.path.clone(),
@@ -28,6 +34,8 @@ impl ProcessLocalCacheKey {
             include_imports: request.include_imports,
             max_tokens: request.max_tokens,
             llm_tool: request.llm_tool.clone(),
+            settings: settings_key(request.style, &request.options),
+            fingerprint,
         }
     }
 }
@@ -52,10 +60,14 @@ pub struct ProcessRemoteCacheKey {
     pub include_imports: bool,
     pub max_tokens: Option<u32>,
     pub llm_tool: Option<String>,
+    /// Output style and analysis options, serialized
+    pub settings: String,
+    /// Commit the requested ref resolved to when the repository was fetched
+    pub commit: String,
 }
 
 impl ProcessRemoteCacheKey {
-    pub fn from_request(request: &super::ProcessRemoteRequest) -> Self {
+    pub fn from_request(request: &super::ProcessRemoteRequest, commit: String) -> Self {
         Self {
             prompt: request.prompt.clone(),
             repo_url: request.repo_url.clone(),
@@ -64,10 +76,20 @@ impl ProcessRemoteCacheKey {
             include_imports: request.include_imports,
             max_tokens: request.max_tokens,
             llm_tool: request.llm_tool.clone(),
+            settings: settings_key(request.style, &request.options),
+            commit,
         }
     }
 }
 
+/// Priorities carry floats, so the options are keyed by their JSON form
+fn settings_key(
+    style: Option<crate::cli::OutputFormat>,
+    options: &super::AnalysisOptions,
+) -> String {
+    serde_json::to_string(&(style, options)).unwrap_or_default()
+}
+
 /// Cached response for process_remote_repo
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessRemoteCacheValue {
@@ -84,6 +106,7 @@ pub struct ProcessRemoteCacheValue {
 pub struct McpCache {
     process_local_cache: Cache<ProcessLocalCacheKey, ProcessLocalCacheValue>,
     process_remote_cache: Cache<ProcessRemoteCacheKey, ProcessRemoteCacheValue>,
+    projects: Arc<ProjectIndex>,
 }
 
 impl McpCache {
@@ -102,9 +125,27 @@ impl McpCache {
         Self {
             process_local_cache,
             process_remote_cache,
+            projects: Arc::new(ProjectIndex::new()),
         }
     }
 
+    /// The per-root project index shared by all tools
+    pub fn projects(&self) -> &Arc<ProjectIndex> {
+        &self.projects
+    }
+
+    /// Fingerprint of the files `config` selects in the project at `path`,
+    /// polled on the blocking pool
+    pub async fn project_fingerprint(
+        &self,
+        path: &Path,
+        config: crate::cli::Config,
+    ) -> anyhow::Result<u64> {
+        let projects = self.projects.clone();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || projects.fingerprint(&path, &config)).await?
+    }
+
     /// Get a cached process_local_codebase response
     pub async fn get_process_local(
         &self,
@@ -143,6 +184,7 @@ impl McpCache {
     pub fn clear_all(&self) {
         self.process_local_cache.invalidate_all();
         self.process_remote_cache.invalidate_all();
+        self.projects.clear();
     }
 }
 
@@ -151,3 +193,52 @@ impl Default for McpCache {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_includes_analysis_options() {
+        let mut request: crate::mcp_server::ProcessLocalRequest =
+            serde_json::from_value(serde_json::json!({
+                "prompt": "Explain",
+                "path": "/project",
+                "include_patterns": [],
+                "ignore_patterns": [],
+                "include_imports": false,
+            }))
+            .unwrap();
+        let key = ProcessLocalCacheKey::from_request(&request, 0);
+
+        request.options.include_callers = true;
+        assert_ne!(key, ProcessLocalCacheKey::from_request(&request, 0));
+
+        request.options.include_callers = false;
+        request.style = Some(crate::cli::OutputFormat::Xml);
+        assert_ne!(key, ProcessLocalCacheKey::from_request(&request, 0));
+    }
+
+    #[test]
+    fn test_remote_cache_key_includes_commit() {
+        let request: crate::mcp_server::ProcessRemoteRequest =
+            serde_json::from_value(serde_json::json!({
+                "prompt": "Explain",
+                "repo_url": "https://github.com/owner/repo",
+                "include_patterns": [],
+                "ignore_patterns": [],
+                "include_imports": false,
+            }))
+            .unwrap();
+        let key = ProcessRemoteCacheKey::from_request(&request, "a".repeat(40));
+
+        assert_eq!(
+            key,
+            ProcessRemoteCacheKey::from_request(&request, "a".repeat(40))
+        );
+        assert_ne!(
+            key,
+            ProcessRemoteCacheKey::from_request(&request, "b".repeat(40))
+        );
+    }
+}
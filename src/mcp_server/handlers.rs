@@ -1,12 +1,23 @@
 //! RPC method handlers for the MCP server
+//!
+//! The JSON-RPC methods of the tools are registered from the
+//! [`ToolRegistry`], and the synchronous implementations below do the work of
+//! the tools on both servers.
 
+use super::project_index::ProjectIndex;
+use super::tools::ToolRegistry;
 use super::{
-    CodebaseRpcServer, HealthResponse, HealthRpcServer, ProcessLocalRequest, ProcessLocalResponse,
+    HealthResponse, HealthRpcServer, ProcessLocalRequest, ProcessLocalResponse,
     ProcessRemoteRequest, ProcessRemoteResponse,
 };
+use crate::core::progress::{Phase, ProgressReporter};
+use crate::remote::ClonedRepository;
 use anyhow::Result;
 use jsonrpsee::core::RpcResult;
+use jsonrpsee::types::{ErrorObjectOwned, Params};
+use jsonrpsee::RpcModule;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::{Instant, SystemTime};
 
 /// Implementation of health check RPC methods
@@ -30,255 +41,92 @@ impl HealthRpcServer for HealthRpcImpl {
     }
 }
 
-/// Implementation of codebase processing RPC handlers
-pub struct CodebaseRpcImpl {
-    cache: std::sync::Arc<crate::mcp_server::cache::McpCache>,
-}
-
-impl CodebaseRpcImpl {
-    pub fn new(cache: std::sync::Arc<crate::mcp_server::cache::McpCache>) -> Self {
-        Self { cache }
+/// JSON-RPC methods calling the tools of `tools`, named by their `rpc_method`
+pub fn tool_methods(tools: Arc<ToolRegistry>) -> Result<RpcModule<ToolRegistry>> {
+    let methods: Vec<_> = tools
+        .tools()
+        .iter()
+        .map(|tool| (tool.rpc_method, tool.name))
+        .collect();
+
+    let mut module = RpcModule::from_arc(tools);
+    for (method, name) in methods {
+        module.register_async_method(method, move |params, tools| async move {
+            let arguments = rpc_arguments(&params);
+            // The tool stops its work if this future is dropped because the
+            // client went away
+            tools
+                .call(name, arguments, ProgressReporter::default())
+                .await
+                .map_err(|e| ErrorObjectOwned::owned(e.code(), e.to_string(), None::<()>))
+        })?;
     }
+    Ok(module)
 }
 
-#[jsonrpsee::core::async_trait]
-impl CodebaseRpcServer for CodebaseRpcImpl {
-    async fn process_local_codebase(
-        &self,
-        request: ProcessLocalRequest,
-    ) -> RpcResult<ProcessLocalResponse> {
-        let start = Instant::now();
-
-        // Validate path security
-        validate_path(&request.path)?;
-
-        // Check cache first
-        let cache_key = crate::mcp_server::cache::ProcessLocalCacheKey::from_request(&request);
-        if let Some(cached) = self.cache.get_process_local(&cache_key).await {
-            let processing_time_ms = start.elapsed().as_millis() as u64;
-            return Ok(ProcessLocalResponse {
-                answer: cached.answer,
-                context: if request.include_context.unwrap_or(false) {
-                    Some(cached.markdown)
-                } else {
-                    None
-                },
-                file_count: cached.file_count,
-                token_count: cached.token_count,
-                processing_time_ms,
-                llm_tool: cached.llm_tool,
-            });
+/// The request of a JSON-RPC call, passed as `[request]` or `{"request": request}`
+fn rpc_arguments(params: &Params) -> serde_json::Value {
+    use serde_json::Value;
+
+    let params = params
+        .as_str()
+        .and_then(|params| serde_json::from_str(params).ok())
+        .unwrap_or(Value::Null);
+    match params {
+        Value::Array(mut values) if values.len() == 1 => values.remove(0),
+        Value::Object(mut object) if object.len() == 1 && object.contains_key("request") => {
+            object.remove("request").unwrap_or(Value::Null)
         }
-
-        // Use blocking task for file I/O
-        let cache = self.cache.clone();
-        let response = tokio::task::spawn_blocking(move || process_codebase_sync(request, start))
-            .await
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Internal error", Some(e.to_string()))
-            })?
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(
-                    -32603,
-                    "Processing error",
-                    Some(e.to_string()),
-                )
-            })?;
-
-        // Cache the response
-        let cache_value = crate::mcp_server::cache::ProcessLocalCacheValue {
-            answer: response.answer.clone(),
-            markdown: response.context.clone().unwrap_or_default(),
-            file_count: response.file_count,
-            token_count: response.token_count,
-            llm_tool: response.llm_tool.clone(),
-        };
-        cache.set_process_local(cache_key, cache_value).await;
-
-        Ok(response)
-    }
-
-    async fn process_remote_repo(
-        &self,
-        request: ProcessRemoteRequest,
-    ) -> RpcResult<ProcessRemoteResponse> {
-        let start = Instant::now();
-
-        // Validate URL
-        validate_url(&request.repo_url)?;
-
-        // Clone the repository and process it
-        let response = tokio::task::spawn_blocking(move || process_remote_sync(request, start))
-            .await
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Internal error", Some(e.to_string()))
-            })?
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(
-                    -32603,
-                    "Processing error",
-                    Some(e.to_string()),
-                )
-            })?;
-
-        Ok(response)
-    }
-
-    async fn get_file_metadata(
-        &self,
-        request: super::GetFileMetadataRequest,
-    ) -> RpcResult<super::GetFileMetadataResponse> {
-        // Validate path security
-        validate_path(&request.file_path)?;
-
-        // Use blocking task for file I/O
-        let response = tokio::task::spawn_blocking(move || get_file_metadata_sync(request))
-            .await
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Internal error", Some(e.to_string()))
-            })?
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(
-                    -32603,
-                    "Processing error",
-                    Some(e.to_string()),
-                )
-            })?;
-
-        Ok(response)
-    }
-
-    async fn search_codebase(
-        &self,
-        request: super::SearchCodebaseRequest,
-    ) -> RpcResult<super::SearchCodebaseResponse> {
-        use std::time::Instant;
-        let start = Instant::now();
-
-        // Validate path security
-        validate_path(&request.path)?;
-
-        // Use blocking task for file I/O
-        let response = tokio::task::spawn_blocking(move || search_codebase_sync(request, start))
-            .await
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Internal error", Some(e.to_string()))
-            })?
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Search error", Some(e.to_string()))
-            })?;
-
-        Ok(response)
-    }
-
-    async fn diff_files(
-        &self,
-        request: super::DiffFilesRequest,
-    ) -> RpcResult<super::DiffFilesResponse> {
-        // Validate paths security
-        validate_path(&request.file1_path)?;
-        validate_path(&request.file2_path)?;
-
-        // Use blocking task for file I/O
-        let response = tokio::task::spawn_blocking(move || diff_files_sync(request))
-            .await
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Internal error", Some(e.to_string()))
-            })?
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Diff error", Some(e.to_string()))
-            })?;
-
-        Ok(response)
-    }
-
-    async fn semantic_search(
-        &self,
-        request: super::SemanticSearchRequest,
-    ) -> RpcResult<super::SemanticSearchResponse> {
-        use std::time::Instant;
-        let start = Instant::now();
-
-        // Validate path security
-        validate_path(&request.path)?;
-
-        // Use blocking task for file I/O and AST parsing
-        let response = tokio::task::spawn_blocking(move || semantic_search_sync(request, start))
-            .await
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(-32603, "Internal error", Some(e.to_string()))
-            })?
-            .map_err(|e| {
-                jsonrpsee::types::ErrorObject::owned(
-                    -32603,
-                    "Semantic search error",
-                    Some(e.to_string()),
-                )
-            })?;
-
-        Ok(response)
+        params => params,
     }
 }
 
-/// Validate path for security issues
-pub(super) fn validate_path(path: &Path) -> RpcResult<()> {
-    // Check for path traversal attempts
-    let path_str = path.to_string_lossy();
-    if path_str.contains("..") || path_str.contains('~') {
-        return Err(jsonrpsee::types::ErrorObject::owned(
-            -32602,
-            "Invalid path: potential security risk",
-            None::<String>,
-        ));
-    }
+/// Synchronous implementation of codebase processing
+pub(super) fn process_codebase_sync(
+    request: ProcessLocalRequest,
+    start: Instant,
+    reporter: ProgressReporter,
+) -> Result<ProcessLocalResponse> {
+    let mut config = local_config(&request, reporter)?;
+    reserve_prompt_tokens(&mut config, &request.prompt);
 
-    // Ensure path is absolute or relative to current directory
-    if !path.is_absolute() && !path.starts_with(".") && !path.exists() {
-        return Err(jsonrpsee::types::ErrorObject::owned(
-            -32602,
-            "Invalid path: must be absolute or relative to current directory",
-            None::<String>,
-        ));
-    }
+    let (output, file_count) = build_context(&request.path, &config)?;
 
-    Ok(())
-}
+    // Count tokens
+    let token_counter = crate::core::token::TokenCounter::new()?;
+    let token_count = token_counter.count_tokens(&output)?;
 
-/// Validate URL for remote repositories
-fn validate_url(url: &str) -> RpcResult<()> {
-    // Basic URL validation
-    if !url.starts_with("https://") && !url.starts_with("http://") {
-        return Err(jsonrpsee::types::ErrorObject::owned(
-            -32602,
-            "Invalid URL: must start with http:// or https://",
-            None::<String>,
-        ));
-    }
+    // Execute LLM with prompt and context
+    let answer = execute_llm_sync(
+        &request.prompt,
+        &output,
+        request.llm_tool.as_deref(),
+        &config.reporter,
+    )?;
 
-    // GitHub URL validation
-    if url.contains("github.com") && !url.contains("/") {
-        return Err(jsonrpsee::types::ErrorObject::owned(
-            -32602,
-            "Invalid GitHub URL format",
-            None::<String>,
-        ));
-    }
+    let processing_time_ms = start.elapsed().as_millis() as u64;
 
-    Ok(())
+    Ok(ProcessLocalResponse {
+        answer,
+        context: if request.include_context.unwrap_or(false) {
+            Some(output)
+        } else {
+            None
+        },
+        file_count,
+        token_count,
+        processing_time_ms,
+        llm_tool: request.llm_tool.unwrap_or_else(|| "gemini".to_string()),
+    })
 }
 
-/// Synchronous implementation of codebase processing
-pub(super) fn process_codebase_sync(
-    request: ProcessLocalRequest,
-    start: Instant,
-) -> Result<ProcessLocalResponse> {
+/// The configuration an `analyze_local` request is processed with, before
+/// the prompt's tokens are reserved
+pub(super) fn local_config(
+    request: &ProcessLocalRequest,
+    reporter: ProgressReporter,
+) -> Result<crate::cli::Config> {
     use crate::cli::{Config, LlmTool};
-    use crate::core::cache::FileCache;
-    use crate::core::context_builder::{generate_markdown, ContextOptions};
-    use crate::core::prioritizer::prioritize_files;
-    use crate::core::token::TokenCounter;
-    use crate::core::walker::{walk_directory, WalkOptions};
-    use std::sync::Arc;
 
     // Determine LLM tool
     let llm_tool = if let Some(tool_str) = &request.llm_tool {
@@ -291,28 +139,8 @@ pub(super) fn process_codebase_sync(
         LlmTool::Gemini
     };
 
-    // Calculate effective token limit considering prompt
-    let effective_max_tokens = if let Some(max_tokens) = request.max_tokens {
-        max_tokens as usize
-    } else {
-        // Use LLM default if not specified
-        llm_tool.default_max_tokens()
-    };
-
-    // Reserve tokens for prompt and response
-    let prompt_tokens = if let Ok(counter) = TokenCounter::new() {
-        counter
-            .count_tokens(&request.prompt)
-            .unwrap_or(request.prompt.len() / 4)
-    } else {
-        request.prompt.len() / 4 // Rough estimate
-    };
-
-    let safety_buffer = 1000; // For LLM response
-    let context_tokens = effective_max_tokens.saturating_sub(prompt_tokens + safety_buffer);
-
     // Create a Config from the request
-    let config = Config {
+    let mut config = Config {
         paths: Some(vec![request.path.clone()]),
         include: if request.include_patterns.is_empty() {
             None
@@ -325,8 +153,9 @@ pub(super) fn process_codebase_sync(
             Some(request.ignore_patterns.clone())
         },
         trace_imports: request.include_imports,
-        max_tokens: Some(context_tokens),
+        max_tokens: request.max_tokens.map(|max_tokens| max_tokens as usize),
         llm_tool,
+        output_format: request.style.unwrap_or_default(),
         // Enable prompt for proper context calculation
         prompt: Some(request.prompt.clone()),
         // Disable other options
@@ -334,52 +163,101 @@ pub(super) fn process_codebase_sync(
         copy: false,
         verbose: 0,
         quiet: true,
+        reporter,
         ..Default::default()
     };
+    apply_analysis_options(&mut config, &request.options, &request.path)?;
+    Ok(config)
+}
 
-    // Create walker options
-    let walk_options = WalkOptions::from_config(&config)?;
+/// Fetch and check out the repository of a remote request
+///
+/// Returns the repository name and the checkout, whose commit keys the
+/// response cache.
+pub(super) fn fetch_remote_sync(
+    request: &ProcessRemoteRequest,
+    reporter: &ProgressReporter,
+) -> Result<(String, ClonedRepository)> {
+    use crate::remote;
 
-    // Create context options
-    let context_options = ContextOptions::from_config(&config)?;
+    let repo = remote::parse_remote_url(&request.repo_url)?;
+    let cache = remote::RemoteCache::at_default_location();
+    reporter.start(Phase::Fetch, format!("Fetching {}", request.repo_url));
+    let checkout = remote::fetch_repository(&repo, cache.as_ref(), false)?;
+    reporter.check()?;
+    Ok((repo.name, checkout))
+}
 
-    // Create file cache
-    let cache = Arc::new(FileCache::new());
+/// Synchronous implementation of remote repository processing, on the
+/// checkout made by [`fetch_remote_sync`]
+pub(super) fn process_remote_sync(
+    request: ProcessRemoteRequest,
+    repo_name: String,
+    checkout: ClonedRepository,
+    start: Instant,
+    reporter: ProgressReporter,
+) -> Result<ProcessRemoteResponse> {
+    use crate::cli::Config;
 
-    // Walk the directory
-    let files = walk_directory(&request.path, walk_options)?;
+    let repo_path = checkout.path().to_path_buf();
 
-    // Prioritize files if max tokens is set
-    let prioritized_files = if context_options.max_tokens.is_some() {
-        prioritize_files(files, &context_options, cache.clone())?
+    // Determine LLM tool
+    let llm_tool = if let Some(tool_str) = &request.llm_tool {
+        match tool_str.as_str() {
+            "gemini" => crate::cli::LlmTool::Gemini,
+            "codex" => crate::cli::LlmTool::Codex,
+            _ => crate::cli::LlmTool::Gemini,
+        }
     } else {
-        files
+        crate::cli::LlmTool::Gemini
     };
 
-    // Generate markdown
-    let output = generate_markdown(prioritized_files, context_options, cache)?;
+    // Create a Config from the request
+    let mut config = Config {
+        paths: Some(vec![repo_path.clone()]),
+        include: if request.include_patterns.is_empty() {
+            None
+        } else {
+            Some(request.include_patterns.clone())
+        },
+        ignore: if request.ignore_patterns.is_empty() {
+            None
+        } else {
+            Some(request.ignore_patterns.clone())
+        },
+        trace_imports: request.include_imports,
+        max_tokens: request.max_tokens.map(|max_tokens| max_tokens as usize),
+        llm_tool,
+        output_format: request.style.unwrap_or_default(),
+        prompt: Some(request.prompt.clone()),
+        // Disable other options
+        output_file: None,
+        copy: false,
+        verbose: 0,
+        quiet: true,
+        reporter,
+        ..Default::default()
+    };
+    apply_analysis_options(&mut config, &request.options, &repo_path)?;
+    reserve_prompt_tokens(&mut config, &request.prompt);
+
+    let (output, file_count) = build_context(&repo_path, &config)?;
 
     // Count tokens
     let token_counter = crate::core::token::TokenCounter::new()?;
     let token_count = token_counter.count_tokens(&output)?;
 
-    // Count files (count markdown headers starting with ##)
-    let file_count = output
-        .lines()
-        .filter(|line| {
-            line.starts_with("## ")
-                && !line.starts_with("## Table of")
-                && !line.starts_with("## Statistics")
-                && !line.starts_with("## File Structure")
-        })
-        .count();
-
     // Execute LLM with prompt and context
-    let answer = execute_llm_sync(&request.prompt, &output, request.llm_tool.as_deref())?;
+    let answer = execute_llm_sync(
+        &request.prompt,
+        &output,
+        request.llm_tool.as_deref(),
+        &config.reporter,
+    )?;
 
     let processing_time_ms = start.elapsed().as_millis() as u64;
 
-    Ok(ProcessLocalResponse {
+    Ok(ProcessRemoteResponse {
         answer,
         context: if request.include_context.unwrap_or(false) {
             Some(output)
@@ -389,69 +267,137 @@ pub(super) fn process_codebase_sync(
         file_count,
         token_count,
         processing_time_ms,
+        repo_name,
         llm_tool: request.llm_tool.unwrap_or_else(|| "gemini".to_string()),
     })
 }
 
-/// Synchronous implementation of remote repository processing
-pub(super) fn process_remote_sync(
-    request: ProcessRemoteRequest,
-    start: Instant,
-) -> Result<ProcessRemoteResponse> {
-    use crate::cli::Config;
-    use crate::core::cache::FileCache;
-    use crate::core::context_builder::{generate_markdown, ContextOptions};
-    use crate::core::prioritizer::prioritize_files;
-    use crate::core::walker::{walk_directory, WalkOptions};
-    use crate::remote;
-    use std::sync::Arc;
+/// Apply the analysis options shared by the context-building requests
+///
+/// The configuration files found at `root`, and the named profile if one is
+/// given, fill in values the request left at their defaults, as on the
+/// command line. Request priorities are checked before the files' ones.
+fn apply_analysis_options(
+    config: &mut crate::cli::Config,
+    options: &super::AnalysisOptions,
+    root: &Path,
+) -> Result<()> {
+    use crate::config::ConfigFile;
+    use crate::utils::error::ContextCreatorError;
 
-    // Clone the repository
-    let temp_dir = remote::fetch_repository(&request.repo_url, false)?;
-    let repo_path = remote::get_repo_path(&temp_dir, &request.repo_url)?;
+    config.include_callers = options.include_callers;
+    config.include_types = options.include_types;
+    if let Some(depth) = options.semantic_depth {
+        config.semantic_depth = depth;
+    }
+    config.git_context = options.git_context;
+    if let Some(depth) = options.git_context_depth {
+        config.git_context_depth = depth;
+    }
+    config.enhanced_context = options.enhanced_context;
+
+    let layers = ConfigFile::discover_layers(root)?;
+    if let Some(name) = &options.profile {
+        if layers.is_empty() {
+            return Err(ContextCreatorError::ConfigError(format!(
+                "Profile '{name}' requested but no configuration file was found for {}",
+                root.display()
+            ))
+            .into());
+        }
+        config.profile = Some(name.clone());
+    }
+    if !layers.is_empty() {
+        config.apply_config_file(ConfigFile::merge_layers(
+            layers.into_iter().map(|layer| layer.config),
+        )?)?;
+    }
 
-    // Extract repo name from URL
-    let repo_name = request
-        .repo_url
-        .split('/')
-        .next_back()
-        .unwrap_or("unknown")
-        .trim_end_matches(".git")
-        .to_string();
+    config
+        .custom_priorities
+        .splice(0..0, options.priorities.iter().cloned());
+    Ok(())
+}
 
-    // Determine LLM tool
-    let llm_tool = if let Some(tool_str) = &request.llm_tool {
-        match tool_str.as_str() {
-            "gemini" => crate::cli::LlmTool::Gemini,
-            "codex" => crate::cli::LlmTool::Codex,
-            _ => crate::cli::LlmTool::Gemini,
-        }
-    } else {
-        crate::cli::LlmTool::Gemini
-    };
+/// Shrink the token budget so the prompt and the LLM's response still fit
+fn reserve_prompt_tokens(config: &mut crate::cli::Config, prompt: &str) {
+    use crate::core::token::TokenCounter;
+
+    let effective_max_tokens = config
+        .get_effective_max_tokens()
+        .unwrap_or_else(|| config.llm_tool.default_max_tokens());
 
-    // Calculate effective token limit considering prompt
-    let effective_max_tokens = if let Some(max_tokens) = request.max_tokens {
-        max_tokens as usize
+    // Reserve tokens for prompt and response
+    let prompt_tokens = if let Ok(counter) = TokenCounter::new() {
+        counter.count_tokens(prompt).unwrap_or(prompt.len() / 4)
     } else {
-        llm_tool.default_max_tokens()
+        prompt.len() / 4 // Rough estimate
     };
 
-    // Reserve tokens for prompt and response
-    let prompt_tokens = if let Ok(counter) = crate::core::token::TokenCounter::new() {
-        counter
-            .count_tokens(&request.prompt)
-            .unwrap_or(request.prompt.len() / 4)
+    let safety_buffer = 1000; // For LLM response
+    config.max_tokens = Some(effective_max_tokens.saturating_sub(prompt_tokens + safety_buffer));
+}
+
+/// Walk, expand, prioritize and format a directory the way the CLI does
+///
+/// Returns the formatted context and the number of files it contains.
+fn build_context(root: &Path, config: &crate::cli::Config) -> Result<(String, usize)> {
+    use crate::core::cache::FileCache;
+    use crate::core::context_builder::ContextOptions;
+    use crate::core::prioritizer::prioritize_files;
+    use crate::core::walker::WalkOptions;
+    use std::sync::Arc;
+
+    let walk_options = WalkOptions::from_config(config)?;
+    let context_options = ContextOptions::from_config(config)?;
+    let cache = Arc::new(FileCache::new());
+
+    let files = crate::collect_files(root, &walk_options, &cache, config)?;
+
+    // Prioritize files if max tokens is set
+    let prioritized_files = if context_options.max_tokens.is_some() {
+        prioritize_files(files, &context_options, cache.clone())?
     } else {
-        request.prompt.len() / 4
+        files
     };
+    let file_count = prioritized_files.len();
+
+    config.reporter.check()?;
+    config
+        .reporter
+        .start(Phase::Render, format!("Formatting {file_count} files"));
+    let output = crate::render_context(
+        prioritized_files,
+        context_options,
+        cache,
+        config.output_format,
+        root,
+    )?;
+    Ok((output, file_count))
+}
 
-    let safety_buffer = 1000;
-    let context_tokens = effective_max_tokens.saturating_sub(prompt_tokens + safety_buffer);
+/// Synchronous implementation of context assembly
+///
+/// Runs the same walker, semantic expansion, prioritizer and formatters as
+/// the CLI and returns the result instead of handing it to an LLM.
+pub(super) fn get_context_sync(
+    request: super::GetContextRequest,
+    start: Instant,
+    reporter: ProgressReporter,
+) -> Result<super::GetContextResponse> {
+    use super::{ContextFile, ContextFormat, GetContextResponse, TruncationInfo};
+    use crate::cli::{Config, OutputFormat};
+    use crate::core::cache::FileCache;
+    use crate::core::context_builder::ContextOptions;
+    use crate::core::prioritizer::prioritize_files;
+    use crate::core::token::TokenCounter;
+    use crate::core::walker::WalkOptions;
+    use std::collections::HashSet;
+    use std::sync::Arc;
 
     // Create a Config from the request
-    let config = Config {
-        paths: Some(vec![repo_path.clone()]),
+    let mut config = Config {
+        paths: Some(vec![request.path.clone()]),
         include: if request.include_patterns.is_empty() {
             None
         } else {
@@ -463,71 +409,179 @@ pub(super) fn process_remote_sync(
             Some(request.ignore_patterns.clone())
         },
         trace_imports: request.include_imports,
-        max_tokens: Some(context_tokens),
-        llm_tool,
-        prompt: Some(request.prompt.clone()),
+        max_tokens: request.max_tokens.map(|max_tokens| max_tokens as usize),
+        output_format: match request.format {
+            ContextFormat::Markdown | ContextFormat::Json => OutputFormat::Markdown,
+            ContextFormat::Xml => OutputFormat::Xml,
+            ContextFormat::Plain => OutputFormat::Plain,
+            ContextFormat::Paths => OutputFormat::Paths,
+        },
         // Disable other options
         output_file: None,
         copy: false,
         verbose: 0,
         quiet: true,
+        reporter,
         ..Default::default()
     };
+    apply_analysis_options(&mut config, &request.options, &request.path)?;
+    config.max_tokens = config.get_effective_max_tokens();
 
-    // Create walker options
     let walk_options = WalkOptions::from_config(&config)?;
-
-    // Create context options
     let context_options = ContextOptions::from_config(&config)?;
-
-    // Create file cache
     let cache = Arc::new(FileCache::new());
 
-    // Walk the directory
-    let files = walk_directory(&repo_path, walk_options)?;
-
-    // Prioritize files if max tokens is set
-    let prioritized_files = if context_options.max_tokens.is_some() {
-        prioritize_files(files, &context_options, cache.clone())?
+    // Walk and expand, then apply the token budget
+    let candidates = crate::collect_files(&request.path, &walk_options, &cache, &config)?;
+    let candidate_paths: Vec<_> = candidates
+        .iter()
+        .map(|file| file.relative_path.clone())
+        .collect();
+    let selected = if context_options.max_tokens.is_some() {
+        prioritize_files(candidates, &context_options, cache.clone())?
     } else {
-        files
+        candidates
     };
 
-    // Generate markdown
-    let output = generate_markdown(prioritized_files, context_options, cache)?;
-
-    // Count tokens
-    let token_counter = crate::core::token::TokenCounter::new()?;
-    let token_count = token_counter.count_tokens(&output)?;
+    config.reporter.check()?;
+    config.reporter.start(
+        Phase::Render,
+        format!("Formatting {} files", selected.len()),
+    );
+
+    // Per-file token counts use the same measure as the prioritizer
+    let counter = TokenCounter::new()?;
+    let mut files = Vec::with_capacity(selected.len());
+    for file in &selected {
+        let content = cache.get_or_load(&file.path)?;
+        let token_count = counter
+            .count_file_tokens(&content, &file.relative_path.to_string_lossy())?
+            .total_tokens;
+        files.push(ContextFile {
+            path: file.relative_path.clone(),
+            language: file.file_type_display().to_string(),
+            token_count,
+        });
+    }
 
-    // Count files (count markdown headers starting with ##)
-    let file_count = output
-        .lines()
-        .filter(|line| {
-            line.starts_with("## ")
-                && !line.starts_with("## Table of")
-                && !line.starts_with("## Statistics")
-                && !line.starts_with("## File Structure")
-        })
-        .count();
+    let included: HashSet<_> = files.iter().map(|file| file.path.clone()).collect();
+    let mut omitted_files: Vec<_> = candidate_paths
+        .into_iter()
+        .filter(|path| !included.contains(path))
+        .collect();
+    omitted_files.sort();
+    let truncation = TruncationInfo {
+        truncated: !omitted_files.is_empty(),
+        max_tokens: context_options.max_tokens,
+        candidate_count: included.len() + omitted_files.len(),
+        omitted_files,
+    };
 
-    // Execute LLM with prompt and context
-    let answer = execute_llm_sync(&request.prompt, &output, request.llm_tool.as_deref())?;
+    let context = match request.format {
+        ContextFormat::Json => {
+            let mut entries = Vec::with_capacity(selected.len());
+            for (file, info) in selected.iter().zip(&files) {
+                entries.push(serde_json::json!({
+                    "path": info.path,
+                    "language": info.language,
+                    "content": &*cache.get_or_load(&file.path)?,
+                }));
+            }
+            serde_json::to_string_pretty(&serde_json::json!({
+                "root": request.path,
+                "files": entries,
+            }))?
+        }
+        _ => crate::render_context(
+            selected,
+            context_options,
+            cache,
+            config.output_format,
+            &request.path,
+        )?,
+    };
 
+    let token_count = counter.count_tokens(&context)?;
     let processing_time_ms = start.elapsed().as_millis() as u64;
 
-    Ok(ProcessRemoteResponse {
-        answer,
-        context: if request.include_context.unwrap_or(false) {
-            Some(output)
-        } else {
-            None
-        },
-        file_count,
+    Ok(GetContextResponse {
+        context,
+        format: request.format,
+        file_count: files.len(),
+        files,
         token_count,
+        truncation,
         processing_time_ms,
-        repo_name,
-        llm_tool: request.llm_tool.unwrap_or_else(|| "gemini".to_string()),
+    })
+}
+
+/// Synchronous implementation of get_imports, or get_importers if `reverse`
+pub(super) fn get_imports_sync(
+    projects: &ProjectIndex,
+    request: super::FileGraphRequest,
+    start: Instant,
+    reverse: bool,
+) -> Result<super::ImportGraphResponse> {
+    let graph = projects.graph(&request.path)?;
+    let depth = request.depth.unwrap_or(1);
+    let edges = if reverse {
+        graph.importers(&request.file, depth)?
+    } else {
+        graph.imports(&request.file, depth)?
+    };
+
+    Ok(super::ImportGraphResponse {
+        edges,
+        files_analyzed: graph.file_count(),
+        processing_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Synchronous implementation of find_callers
+pub(super) fn find_callers_sync(
+    projects: &ProjectIndex,
+    request: super::SymbolGraphRequest,
+    start: Instant,
+) -> Result<super::FindCallersResponse> {
+    let graph = projects.graph(&request.path)?;
+    let callers = graph.callers(&request.symbol, request.depth.unwrap_or(1));
+
+    Ok(super::FindCallersResponse {
+        callers,
+        files_analyzed: graph.file_count(),
+        processing_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Synchronous implementation of find_definition
+pub(super) fn find_definition_sync(
+    projects: &ProjectIndex,
+    request: super::FindDefinitionRequest,
+    start: Instant,
+) -> Result<super::FindDefinitionResponse> {
+    let graph = projects.graph(&request.path)?;
+    let definitions = graph.definitions(&request.symbol);
+
+    Ok(super::FindDefinitionResponse {
+        definitions,
+        files_analyzed: graph.file_count(),
+        processing_time_ms: start.elapsed().as_millis() as u64,
+    })
+}
+
+/// Synchronous implementation of get_type_hierarchy
+pub(super) fn get_type_hierarchy_sync(
+    projects: &ProjectIndex,
+    request: super::SymbolGraphRequest,
+    start: Instant,
+) -> Result<super::TypeHierarchyResponse> {
+    let graph = projects.graph(&request.path)?;
+    let hierarchy = graph.type_hierarchy(&request.symbol, request.depth.unwrap_or(1));
+
+    Ok(super::TypeHierarchyResponse {
+        hierarchy,
+        files_analyzed: graph.file_count(),
+        processing_time_ms: start.elapsed().as_millis() as u64,
     })
 }
 
@@ -1230,10 +1284,16 @@ fn is_supported_language_file(ext: &str) -> bool {
 }
 
 /// Execute LLM with prompt and context
-fn execute_llm_sync(prompt: &str, context: &str, llm_tool: Option<&str>) -> Result<String> {
+///
+/// The subprocess is killed if `reporter` is cancelled while it runs.
+fn execute_llm_sync(
+    prompt: &str,
+    context: &str,
+    llm_tool: Option<&str>,
+    reporter: &ProgressReporter,
+) -> Result<String> {
     use crate::cli::LlmTool;
     use crate::utils::error::ContextCreatorError;
-    use std::io::Write;
     use std::process::{Command, Stdio};
 
     // Determine which LLM tool to use
@@ -1250,7 +1310,10 @@ fn execute_llm_sync(prompt: &str, context: &str, llm_tool: Option<&str>) -> Resu
     let full_input = format!("{prompt}\n\n{context}");
     let tool_command = tool.command();
 
-    let mut child = Command::new(tool_command)
+    reporter.check()?;
+    reporter.start(Phase::Llm, format!("Waiting for {tool_command}"));
+
+    let child = Command::new(tool_command)
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -1266,15 +1329,7 @@ fn execute_llm_sync(prompt: &str, context: &str, llm_tool: Option<&str>) -> Resu
             }
         })?;
 
-    // Write input to stdin
-    if let Some(mut stdin) = child.stdin.take() {
-        stdin.write_all(full_input.as_bytes())?;
-        stdin.flush()?;
-    }
-
-    // Capture output
-    let output = child.wait_with_output()?;
-
+    let output = wait_cancellable(child, full_input, reporter)?;
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(ContextCreatorError::SubprocessError(format!(
@@ -1285,3 +1340,116 @@ fn execute_llm_sync(prompt: &str, context: &str, llm_tool: Option<&str>) -> Resu
 
     Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
+
+/// Write `input` to a child's stdin and wait for it to exit
+///
+/// The child is killed if `reporter` is cancelled before it exits.
+fn wait_cancellable(
+    mut child: std::process::Child,
+    input: String,
+    reporter: &ProgressReporter,
+) -> Result<std::process::Output> {
+    use crate::utils::error::ContextCreatorError;
+    use std::io::{Read, Write};
+    use std::time::Duration;
+
+    // Feed stdin and drain the output on their own threads so this one can
+    // watch for cancellation
+    let stdin = child.stdin.take().map(|mut stdin| {
+        std::thread::spawn(move || {
+            stdin.write_all(input.as_bytes())?;
+            stdin.flush()
+        })
+    });
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        pipe.map(|mut pipe| {
+            std::thread::spawn(move || {
+                let mut buffer = Vec::new();
+                pipe.read_to_end(&mut buffer).map(|_| buffer)
+            })
+        })
+    };
+    let stdout = drain(child.stdout.take().map(|pipe| Box::new(pipe) as _));
+    let stderr = drain(child.stderr.take().map(|pipe| Box::new(pipe) as _));
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if reporter.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(ContextCreatorError::Cancelled.into());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    let join = |handle: Option<std::thread::JoinHandle<std::io::Result<Vec<u8>>>>| {
+        handle
+            .map(|handle| {
+                handle.join().map_err(|_| {
+                    ContextCreatorError::SubprocessError("Output reader panicked".into())
+                })
+            })
+            .transpose()
+    };
+    let stdout = join(stdout)?.transpose()?.unwrap_or_default();
+    let stderr = join(stderr)?.transpose()?.unwrap_or_default();
+    if let Some(stdin) = stdin {
+        stdin
+            .join()
+            .map_err(|_| ContextCreatorError::SubprocessError("Input writer panicked".into()))??;
+    }
+
+    Ok(std::process::Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    fn spawn(program: &str, args: &[&str]) -> std::process::Child {
+        Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_wait_cancellable_returns_output() {
+        let reporter = ProgressReporter::default();
+        let output = wait_cancellable(spawn("cat", &[]), "hello".to_string(), &reporter).unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello");
+    }
+
+    #[test]
+    fn test_wait_cancellable_kills_child_on_cancel() {
+        let reporter = ProgressReporter::default();
+        let canceller = reporter.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            canceller.cancel();
+        });
+
+        let start = Instant::now();
+        let error =
+            wait_cancellable(spawn("sleep", &["30"]), String::new(), &reporter).unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(crate::utils::error::ContextCreatorError::Cancelled)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -1,7 +1,8 @@
 //! MCP (Model Context Protocol) server implementation for context-creator
 //!
-//! This module provides a JSON-RPC server that allows AI agents to
-//! analyze codebases programmatically.
+//! This module provides a JSON-RPC server and an MCP server that allow AI
+//! agents to analyze codebases programmatically. Both serve the tools of
+//! [`tools::ToolRegistry`].
 
 use anyhow::Result;
 use jsonrpsee::{
@@ -15,8 +16,11 @@ use std::net::SocketAddr;
 
 pub mod cache;
 pub mod handlers;
-pub mod rmcp_handlers;
+pub mod project_index;
+pub mod prompts;
+pub mod resources;
 pub mod rmcp_server;
+pub mod tools;
 
 /// Health check response structure
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
@@ -34,6 +38,36 @@ pub trait HealthRpc {
     async fn health_check(&self) -> RpcResult<HealthResponse>;
 }
 
+/// Context selection options shared by the requests that build a context
+///
+/// These mirror the CLI flags of the same name and are applied to the same
+/// [`Config`](crate::cli::Config).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct AnalysisOptions {
+    /// Optional: include files containing callers of functions in the selected files
+    #[serde(default)]
+    pub include_callers: bool,
+    /// Optional: include type definitions used by the selected files
+    #[serde(default)]
+    pub include_types: bool,
+    /// Optional: depth limit for dependency traversal (default: 5)
+    pub semantic_depth: Option<usize>,
+    /// Optional: include git commit history in file headers
+    #[serde(default)]
+    pub git_context: bool,
+    /// Optional: number of git commits to show per file (default: 3)
+    pub git_context_depth: Option<usize>,
+    /// Optional: include file metadata in file headers
+    #[serde(default)]
+    pub enhanced_context: bool,
+    /// Optional: priority rules, checked before those of the configuration files
+    #[serde(default)]
+    pub priorities: Vec<crate::config::Priority>,
+    /// Optional: profile from the `.context-creator.toml` files of the analyzed
+    /// directory; the files always apply, a profile is selected on top of them
+    pub profile: Option<String>,
+}
+
 /// Request structure for process_local_codebase
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct ProcessLocalRequest {
@@ -51,6 +85,11 @@ pub struct ProcessLocalRequest {
     pub max_tokens: Option<u32>,
     /// Optional: LLM tool to use (default: gemini)
     pub llm_tool: Option<String>,
+    /// Optional: output style of the context sent to the LLM (default: markdown)
+    pub style: Option<crate::cli::OutputFormat>,
+    /// Optional: semantic analysis, git context, priorities and profile
+    #[serde(flatten)]
+    pub options: AnalysisOptions,
     /// Optional: return markdown context along with answer
     pub include_context: Option<bool>,
 }
@@ -89,6 +128,11 @@ pub struct ProcessRemoteRequest {
     pub max_tokens: Option<u32>,
     /// Optional: LLM tool to use (default: gemini)
     pub llm_tool: Option<String>,
+    /// Optional: output style of the context sent to the LLM (default: markdown)
+    pub style: Option<crate::cli::OutputFormat>,
+    /// Optional: semantic analysis, git context, priorities and profile
+    #[serde(flatten)]
+    pub options: AnalysisOptions,
     /// Optional: return markdown context along with answer
     pub include_context: Option<bool>,
 }
@@ -112,6 +156,90 @@ pub struct ProcessRemoteResponse {
     pub llm_tool: String,
 }
 
+/// Format of the context returned by get_context
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextFormat {
+    /// Markdown document, as printed by the CLI (default)
+    #[default]
+    Markdown,
+    /// XML document with one element per file
+    Xml,
+    /// JSON document with the path, language and content of each file
+    Json,
+    /// Plain text with file separators
+    Plain,
+    /// List of file paths only
+    Paths,
+}
+
+/// Request structure for get_context
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct GetContextRequest {
+    /// Path to the codebase to analyze
+    pub path: std::path::PathBuf,
+    /// Optional: specific file patterns to include
+    #[serde(default)]
+    pub include_patterns: Vec<String>,
+    /// Optional: patterns to ignore
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,
+    /// Optional: whether to trace imports
+    #[serde(default)]
+    pub include_imports: bool,
+    /// Optional: token budget for the context (all matching files if not specified)
+    pub max_tokens: Option<u32>,
+    /// Optional: format of the returned context (default: markdown)
+    #[serde(default)]
+    pub format: ContextFormat,
+    /// Optional: semantic analysis, git context, priorities and profile
+    #[serde(flatten)]
+    pub options: AnalysisOptions,
+}
+
+/// A file included in the context returned by get_context
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ContextFile {
+    /// Path relative to the analyzed directory
+    pub path: std::path::PathBuf,
+    /// Language or file type
+    pub language: String,
+    /// Tokens the file contributes, including its header
+    pub token_count: usize,
+}
+
+/// How the token budget shaped the context returned by get_context
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TruncationInfo {
+    /// Whether files were left out to stay within `max_tokens`
+    pub truncated: bool,
+    /// The token budget that was applied, if any
+    pub max_tokens: Option<usize>,
+    /// Number of files selected before the budget was applied
+    pub candidate_count: usize,
+    /// Files left out, relative to the analyzed directory
+    pub omitted_files: Vec<std::path::PathBuf>,
+}
+
+/// Response structure for get_context
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct GetContextResponse {
+    /// The assembled context
+    pub context: String,
+    /// Format of `context`
+    pub format: ContextFormat,
+    /// Files included in the context, in output order
+    pub files: Vec<ContextFile>,
+    /// Number of files included
+    pub file_count: usize,
+    /// Token count of the context
+    pub token_count: usize,
+    /// Files dropped to fit the token budget
+    pub truncation: TruncationInfo,
+    /// Processing time in milliseconds
+    pub processing_time_ms: u64,
+}
+
 /// Request structure for get_file_metadata
 #[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
 pub struct GetFileMetadataRequest {
@@ -226,47 +354,80 @@ pub struct SemanticSearchResponse {
     pub search_time_ms: u64,
 }
 
-/// RPC trait for codebase processing
-#[rpc(server)]
-pub trait CodebaseRpc {
-    /// Process a local codebase directory
-    #[method(name = "process_local_codebase")]
-    async fn process_local_codebase(
-        &self,
-        request: ProcessLocalRequest,
-    ) -> RpcResult<ProcessLocalResponse>;
-
-    /// Process a remote repository
-    #[method(name = "process_remote_repo")]
-    async fn process_remote_repo(
-        &self,
-        request: ProcessRemoteRequest,
-    ) -> RpcResult<ProcessRemoteResponse>;
-
-    /// Get metadata for a specific file
-    #[method(name = "get_file_metadata")]
-    async fn get_file_metadata(
-        &self,
-        request: GetFileMetadataRequest,
-    ) -> RpcResult<GetFileMetadataResponse>;
-
-    /// Search codebase for a query string
-    #[method(name = "search_codebase")]
-    async fn search_codebase(
-        &self,
-        request: SearchCodebaseRequest,
-    ) -> RpcResult<SearchCodebaseResponse>;
-
-    /// Get diff between two files
-    #[method(name = "diff_files")]
-    async fn diff_files(&self, request: DiffFilesRequest) -> RpcResult<DiffFilesResponse>;
-
-    /// Perform semantic search across codebase
-    #[method(name = "semantic_search")]
-    async fn semantic_search(
-        &self,
-        request: SemanticSearchRequest,
-    ) -> RpcResult<SemanticSearchResponse>;
+/// Request structure for get_imports and get_importers
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct FileGraphRequest {
+    /// Root of the project to analyze
+    pub path: std::path::PathBuf,
+    /// File to start from, relative to `path` or absolute
+    pub file: std::path::PathBuf,
+    /// Optional: number of import levels to follow (default: 1)
+    pub depth: Option<usize>,
+}
+
+/// Response structure for get_imports and get_importers
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct ImportGraphResponse {
+    /// Import edges in breadth-first order
+    pub edges: Vec<crate::core::code_graph::ImportEdge>,
+    /// Number of files analyzed
+    pub files_analyzed: usize,
+    /// Processing time in milliseconds
+    pub processing_time_ms: u64,
+}
+
+/// Request structure for find_callers and get_type_hierarchy
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct SymbolGraphRequest {
+    /// Root of the project to analyze
+    pub path: std::path::PathBuf,
+    /// Function or type name
+    pub symbol: String,
+    /// Optional: number of levels to follow (default: 1)
+    pub depth: Option<usize>,
+}
+
+/// Response structure for find_callers
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct FindCallersResponse {
+    /// Call sites ordered by depth, file and line
+    pub callers: Vec<crate::core::code_graph::CallSite>,
+    /// Number of files analyzed
+    pub files_analyzed: usize,
+    /// Processing time in milliseconds
+    pub processing_time_ms: u64,
+}
+
+/// Request structure for find_definition
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct FindDefinitionRequest {
+    /// Root of the project to analyze
+    pub path: std::path::PathBuf,
+    /// Function or type name
+    pub symbol: String,
+}
+
+/// Response structure for find_definition
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct FindDefinitionResponse {
+    /// Definitions ordered by file and line
+    pub definitions: Vec<crate::core::code_graph::Definition>,
+    /// Number of files analyzed
+    pub files_analyzed: usize,
+    /// Processing time in milliseconds
+    pub processing_time_ms: u64,
+}
+
+/// Response structure for get_type_hierarchy
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct TypeHierarchyResponse {
+    /// Definitions, supertypes and subtypes of the type
+    #[serde(flatten)]
+    pub hierarchy: crate::core::code_graph::TypeHierarchy,
+    /// Number of files analyzed
+    pub files_analyzed: usize,
+    /// Processing time in milliseconds
+    pub processing_time_ms: u64,
 }
 
 /// Server handle wrapper for managing the MCP server lifecycle
@@ -298,16 +459,13 @@ pub async fn start_server(addr: &str) -> Result<ServerHandle> {
     // Get the actual address (in case port 0 was used)
     let local_addr = server.local_addr()?;
 
-    // Create shared cache
+    // Create the tools, sharing one cache
     let cache = std::sync::Arc::new(cache::McpCache::new());
-
-    // Create and register the handlers
-    let health_impl = handlers::HealthRpcImpl;
-    let codebase_impl = handlers::CodebaseRpcImpl::new(cache);
+    let tools = std::sync::Arc::new(tools::ToolRegistry::new(cache));
 
     // Merge RPC modules
-    let mut rpc_module = health_impl.into_rpc();
-    rpc_module.merge(codebase_impl.into_rpc())?;
+    let mut rpc_module = handlers::HealthRpcImpl.into_rpc();
+    rpc_module.merge(handlers::tool_methods(tools)?)?;
 
     // Start the server in the background
     let handle = server.start(rpc_module);
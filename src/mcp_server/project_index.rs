@@ -0,0 +1,567 @@
+//! Per-root project index for the MCP server
+//!
+//! Each analyzed root keeps its code graph, file contents and parse results
+//! between calls. A file watcher notes when the directories walked for the
+//! indexed files change; only then are the files polled again, and only files whose
+//! modification time or size changed are read. Those whose content hash
+//! changed are dropped from the caches and parsed again when the graph is
+//! rebuilt. Roots that cannot be watched are polled on every call.
+//!
+//! The content hashes also give each root a fingerprint that response caches
+//! use as part of their key. It covers the files a request's configuration
+//! selects and the configuration files applying to the root, whose settings
+//! and profiles change the responses.
+
+use crate::cli::Config;
+use crate::config::ConfigFile;
+use crate::core::cache::FileCache;
+use crate::core::code_graph::CodeGraph;
+use crate::core::semantic_cache::SemanticCache;
+use crate::core::walker::{walk_directories, walk_directory, WalkOptions};
+use anyhow::Result;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// What a file looked like when it was last indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    content_hash: u64,
+}
+
+/// The files one walk configuration selected when they were last polled
+struct PolledWalk {
+    /// Watcher generation the walk was polled at, `None` for unwatched roots
+    generation: Option<u64>,
+    /// Configuration files applying to the root
+    layers: Vec<PathBuf>,
+    /// Sorted paths of the selected files and the configuration files
+    paths: Vec<PathBuf>,
+    /// Directories the walk descended into
+    directories: Vec<PathBuf>,
+    fingerprint: u64,
+}
+
+/// Counts the changes in the watched directories of a root
+///
+/// Only the directories the polled walks descended into are watched, each on
+/// its own, so ignored trees such as build output cost nothing, while
+/// directories without selected files still notice new ones. A file or
+/// directory created in a watched directory is a change, after which the next
+/// walk watches any new directory.
+struct ProjectWatcher {
+    generation: Arc<AtomicU64>,
+    watcher: RecommendedWatcher,
+    directories: HashSet<PathBuf>,
+}
+
+impl ProjectWatcher {
+    /// Create a watcher, `None` if the platform has none
+    fn new() -> Option<Self> {
+        let generation = Arc::new(AtomicU64::new(0));
+        let counter = generation.clone();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            // Reads, including the index's own, change nothing
+            if event.map_or(true, |event| !matches!(event.kind, EventKind::Access(_))) {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .ok()?;
+        Some(Self {
+            generation,
+            watcher,
+            directories: HashSet::new(),
+        })
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Watch exactly `directories`, returning whether all of them could be
+    ///
+    /// Directories are watched again even if they already were, since one
+    /// may have been deleted and created anew.
+    fn watch(&mut self, directories: HashSet<PathBuf>) -> bool {
+        for directory in self.directories.difference(&directories) {
+            let _ = self.watcher.unwatch(directory);
+        }
+        let watched = directories.iter().all(|directory| {
+            self.watcher
+                .watch(directory, RecursiveMode::NonRecursive)
+                .is_ok()
+        });
+        self.directories = directories;
+        watched
+    }
+}
+
+/// Index state of one project root
+struct IndexedProject {
+    stamps: HashMap<PathBuf, FileStamp>,
+    /// Polled walks keyed by the hash of their walk settings
+    walks: HashMap<u64, PolledWalk>,
+    watcher: Option<ProjectWatcher>,
+    file_cache: Arc<FileCache>,
+    semantic_cache: Arc<SemanticCache>,
+    /// Built on first use and dropped when a file changes
+    graph: Option<Arc<CodeGraph>>,
+}
+
+/// Project indexes keyed by root
+#[derive(Default)]
+pub struct ProjectIndex {
+    projects: Mutex<HashMap<PathBuf, Arc<Mutex<IndexedProject>>>>,
+}
+
+impl std::fmt::Debug for ProjectIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let roots = self.projects.lock().map(|p| p.len()).unwrap_or_default();
+        f.debug_struct("ProjectIndex")
+            .field("roots", &roots)
+            .finish()
+    }
+}
+
+impl ProjectIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The code graph of `root`, rebuilt only if files changed since the last call
+    pub fn graph(&self, root: &Path) -> Result<Arc<CodeGraph>> {
+        let project = self.project(root)?;
+        let mut project = project
+            .lock()
+            .map_err(|_| crate::utils::error::ContextCreatorError::MutexPoisoned)?;
+        let config = index_config(root);
+        project.refresh(root, &config)?;
+
+        if let Some(graph) = &project.graph {
+            return Ok(graph.clone());
+        }
+        let graph = Arc::new(CodeGraph::build_cached(
+            root,
+            &config,
+            project.file_cache.clone(),
+            &project.semantic_cache,
+        )?);
+        project.graph = Some(graph.clone());
+        Ok(graph)
+    }
+
+    /// Hash of the paths and contents of the files `config` selects under
+    /// `root` and of the configuration files applying to it
+    ///
+    /// `config` is the effective configuration of a request. When it expands
+    /// the selection semantically, any file that is not ignored may be pulled
+    /// in, so all of them are covered.
+    pub fn fingerprint(&self, root: &Path, config: &Config) -> Result<u64> {
+        let project = self.project(root)?;
+        let mut project = project
+            .lock()
+            .map_err(|_| crate::utils::error::ContextCreatorError::MutexPoisoned)?;
+
+        let mut config = config.clone();
+        if config.trace_imports || config.include_callers || config.include_types {
+            config.include = None;
+        }
+        project.refresh(root, &config)
+    }
+
+    /// Forget every indexed root
+    pub fn clear(&self) {
+        if let Ok(mut projects) = self.projects.lock() {
+            projects.clear();
+        }
+    }
+
+    fn project(&self, root: &Path) -> Result<Arc<Mutex<IndexedProject>>> {
+        let key = root.canonicalize()?;
+        let mut projects = self
+            .projects
+            .lock()
+            .map_err(|_| crate::utils::error::ContextCreatorError::MutexPoisoned)?;
+        Ok(projects
+            .entry(key)
+            .or_insert_with(|| {
+                Arc::new(Mutex::new(IndexedProject {
+                    stamps: HashMap::new(),
+                    walks: HashMap::new(),
+                    watcher: ProjectWatcher::new(),
+                    file_cache: Arc::new(FileCache::new()),
+                    semantic_cache: Arc::new(SemanticCache::new()),
+                    graph: None,
+                }))
+            })
+            .clone())
+    }
+}
+
+impl IndexedProject {
+    /// Poll the files `config` selects under `root`, invalidate what changed
+    /// and return their fingerprint
+    fn refresh(&mut self, root: &Path, config: &Config) -> Result<u64> {
+        let walk_options = WalkOptions::from_config(config)?;
+        let key = walk_key(&walk_options);
+        let generation = self.watcher.as_ref().map(ProjectWatcher::generation);
+        // Configuration files may live outside the watched root, so they are
+        // always polled
+        let layers = ConfigFile::discover_layer_paths(root)?;
+
+        if let Some(walk) = self.walks.get(&key) {
+            if walk.generation.is_some()
+                && walk.generation == generation
+                && walk.layers == layers
+                && layers.iter().all(|path| self.is_unchanged(path))
+            {
+                return Ok(walk.fingerprint);
+            }
+        }
+
+        let directories = walk_directories(root, &walk_options)?;
+        let listed: Vec<PathBuf> = walk_directory(root, walk_options)?
+            .into_iter()
+            .map(|file| file.path)
+            // The walk skips hidden files such as `.context-creator.toml`
+            .chain(layers.iter().cloned())
+            .collect();
+
+        let mut changed = Vec::new();
+        let mut paths = Vec::with_capacity(listed.len());
+        for path in listed {
+            match self.poll(&path) {
+                Ok(true) => changed.push(path.clone()),
+                Ok(false) => {}
+                // A file deleted since the walk listed it is a change like any other
+                Err(e) if is_not_found(&e) => {
+                    self.stamps.remove(&path);
+                    changed.push(path);
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+            paths.push(path);
+        }
+        paths.sort();
+
+        if let Some(previous) = self.walks.remove(&key) {
+            for path in previous.paths {
+                if paths.binary_search(&path).is_err() && self.stamps.remove(&path).is_some() {
+                    changed.push(path);
+                }
+            }
+        }
+
+        if !changed.is_empty() {
+            for path in &changed {
+                self.file_cache.invalidate(path);
+                self.semantic_cache.remove_path(path);
+            }
+            self.graph = None;
+            // Other walks may cover the changed files too
+            self.walks.clear();
+        }
+
+        let mut hasher = DefaultHasher::new();
+        for path in &paths {
+            path.hash(&mut hasher);
+            self.stamps[path].content_hash.hash(&mut hasher);
+        }
+        let fingerprint = hasher.finish();
+
+        self.walks.insert(
+            key,
+            PolledWalk {
+                generation,
+                layers,
+                paths,
+                directories,
+                fingerprint,
+            },
+        );
+        self.watch_walks(root);
+        Ok(fingerprint)
+    }
+
+    /// Watch `root` and the directories the polled walks descended into,
+    /// falling back to polling on every call if that fails
+    fn watch_walks(&mut self, root: &Path) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        let mut directories = HashSet::from([root.to_path_buf()]);
+        for walk in self.walks.values() {
+            directories.extend(walk.directories.iter().cloned());
+        }
+        if !watcher.watch(directories) {
+            self.watcher = None;
+            self.walks.clear();
+        }
+    }
+
+    /// Whether the file at `path` still has the size and modification time
+    /// it was stamped with
+    fn is_unchanged(&self, path: &Path) -> bool {
+        let (Some(stamp), Ok(metadata)) = (self.stamps.get(path), std::fs::metadata(path)) else {
+            return false;
+        };
+        stamp.modified == metadata.modified().ok() && stamp.len == metadata.len()
+    }
+
+    /// Stamp the file at `path`, returning whether its content changed
+    fn poll(&mut self, path: &Path) -> Result<bool> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata.modified().ok();
+        let len = metadata.len();
+
+        let previous = self.stamps.get(path).copied();
+        if let Some(stamp) = previous {
+            if stamp.modified == modified && stamp.len == len {
+                return Ok(false);
+            }
+        }
+        let stamp = FileStamp {
+            modified,
+            len,
+            content_hash: hash_file(path)?,
+        };
+        self.stamps.insert(path.to_path_buf(), stamp);
+        Ok(previous.map(|p| p.content_hash) != Some(stamp.content_hash))
+    }
+}
+
+/// Configuration the index walks and analyzes a root with
+fn index_config(root: &Path) -> Config {
+    Config {
+        paths: Some(vec![root.to_path_buf()]),
+        quiet: true,
+        ..Default::default()
+    }
+}
+
+/// Hash of the settings that decide which files a walk selects
+fn walk_key(options: &WalkOptions) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    options.include_patterns.hash(&mut hasher);
+    options.ignore_patterns.hash(&mut hasher);
+    options.filter_binary_files.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn is_not_found(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::NotFound)
+}
+
+fn hash_file(path: &Path) -> Result<u64> {
+    let content = std::fs::read(path)?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    /// Change a file and make sure its modification time moves
+    fn rewrite(path: &Path, content: &str) {
+        let modified = fs::metadata(path).unwrap().modified().unwrap();
+        fs::write(path, content).unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(modified + Duration::from_secs(2))
+            .unwrap();
+    }
+
+    /// Wait for the watcher to deliver a change until `check` holds
+    fn eventually(mut check: impl FnMut() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if check() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn test_graph_is_reused_until_a_file_changes() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("lib.py"), "def helper():\n    pass\n").unwrap();
+        fs::write(
+            dir.path().join("app.py"),
+            "from lib import helper\n\nhelper()\n",
+        )
+        .unwrap();
+        let index = ProjectIndex::new();
+
+        let first = index.graph(dir.path()).unwrap();
+        let second = index.graph(dir.path()).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.callers("helper", 1).len(), 1);
+
+        rewrite(
+            &dir.path().join("app.py"),
+            "from lib import helper\n\nhelper()\nhelper()\n",
+        );
+        assert!(eventually(|| {
+            let third = index.graph(dir.path()).unwrap();
+            !Arc::ptr_eq(&second, &third) && third.callers("helper", 1).len() == 2
+        }));
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_content() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(&file, "fn main() {}\n").unwrap();
+        let index = ProjectIndex::new();
+        let config = index_config(dir.path());
+        let fingerprint = || index.fingerprint(dir.path(), &config).unwrap();
+
+        let before = fingerprint();
+        assert_eq!(before, fingerprint());
+
+        // A new modification time with the same content keeps the fingerprint
+        rewrite(&file, "fn main() {}\n");
+        assert_eq!(before, fingerprint());
+
+        rewrite(&file, "fn main() { println!(); }\n");
+        assert!(eventually(|| fingerprint() != before));
+
+        let edited = fingerprint();
+        fs::write(dir.path().join("extra.rs"), "").unwrap();
+        assert!(eventually(|| fingerprint() != edited));
+        fs::remove_file(dir.path().join("extra.rs")).unwrap();
+        assert!(eventually(|| fingerprint() == edited));
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_configuration_files() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        let config = dir.path().join(".context-creator.toml");
+        fs::write(&config, "[profiles.review]\nignore = [\"tests/**\"]\n").unwrap();
+        let index = ProjectIndex::new();
+        let request = index_config(dir.path());
+
+        let before = index.fingerprint(dir.path(), &request).unwrap();
+        rewrite(&config, "[profiles.review]\nignore = [\"docs/**\"]\n");
+        assert!(eventually(|| index
+            .fingerprint(dir.path(), &request)
+            .unwrap()
+            != before));
+    }
+
+    #[test]
+    fn test_fingerprint_follows_request_configuration() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("generated")).unwrap();
+        fs::write(dir.path().join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.path().join("generated/out.rs"), "fn out() {}\n").unwrap();
+        let index = ProjectIndex::new();
+
+        let ignoring = Config {
+            ignore: Some(vec!["generated/**".to_string()]),
+            ..index_config(dir.path())
+        };
+        let everything = index_config(dir.path());
+        let before_ignoring = index.fingerprint(dir.path(), &ignoring).unwrap();
+        let before_everything = index.fingerprint(dir.path(), &everything).unwrap();
+        assert_ne!(before_ignoring, before_everything);
+
+        // A change to an ignored file only affects requests that select it
+        rewrite(&dir.path().join("generated/out.rs"), "fn out() { 1; }\n");
+        assert!(eventually(|| index
+            .fingerprint(dir.path(), &everything)
+            .unwrap()
+            != before_everything));
+        assert_eq!(
+            index.fingerprint(dir.path(), &ignoring).unwrap(),
+            before_ignoring
+        );
+
+        // Include patterns narrow the files unless imports may be traced
+        let included = Config {
+            include: Some(vec!["src/**".to_string()]),
+            ..index_config(dir.path())
+        };
+        let tracing = Config {
+            trace_imports: true,
+            ..included.clone()
+        };
+        let before_included = index.fingerprint(dir.path(), &included).unwrap();
+        let before_tracing = index.fingerprint(dir.path(), &tracing).unwrap();
+        rewrite(&dir.path().join("generated/out.rs"), "fn out() { 2; }\n");
+        assert!(eventually(|| index
+            .fingerprint(dir.path(), &tracing)
+            .unwrap()
+            != before_tracing));
+        assert_eq!(
+            index.fingerprint(dir.path(), &included).unwrap(),
+            before_included
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_tracks_directories_without_selected_files() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        fs::create_dir(dir.path().join("empty")).unwrap();
+        fs::create_dir(dir.path().join("logs")).unwrap();
+        fs::write(dir.path().join(".ignore"), "*.log\n").unwrap();
+        fs::write(dir.path().join("logs/run.log"), "started\n").unwrap();
+        let index = ProjectIndex::new();
+        let config = index_config(dir.path());
+        let fingerprint = || index.fingerprint(dir.path(), &config).unwrap();
+
+        let before = fingerprint();
+        assert_eq!(before, fingerprint());
+
+        fs::write(dir.path().join("empty/new.rs"), "fn new() {}\n").unwrap();
+        assert!(eventually(|| fingerprint() != before));
+
+        let with_new = fingerprint();
+        fs::write(dir.path().join("logs/parse.rs"), "fn parse() {}\n").unwrap();
+        assert!(eventually(|| fingerprint() != with_new));
+    }
+
+    #[test]
+    fn test_vanished_files_are_changes() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("gone.rs");
+        fs::write(&file, "fn gone() {}\n").unwrap();
+        let mut project = IndexedProject {
+            stamps: HashMap::new(),
+            walks: HashMap::new(),
+            watcher: None,
+            file_cache: Arc::new(FileCache::new()),
+            semantic_cache: Arc::new(SemanticCache::new()),
+            graph: None,
+        };
+        let config = index_config(dir.path());
+
+        let before = project.refresh(dir.path(), &config).unwrap();
+        assert!(project.poll(&file).is_ok());
+        fs::remove_file(&file).unwrap();
+        assert!(is_not_found(&project.poll(&file).unwrap_err()));
+        assert_ne!(before, project.refresh(dir.path(), &config).unwrap());
+        assert!(project.stamps.is_empty());
+    }
+}
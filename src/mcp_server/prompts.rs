@@ -0,0 +1,288 @@
+//! MCP prompts for the RMCP server
+//!
+//! Each prompt assembles its context on the server, so the client receives a
+//! ready-to-use message with the relevant diff, files or call sites inlined.
+
+use rmcp::model::{
+    ErrorData, GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage,
+    PromptMessageRole,
+};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::project_index::ProjectIndex;
+use super::{ContextFormat, GetContextRequest};
+use crate::utils::git;
+
+/// The prompts the server offers
+pub fn list_prompts() -> Vec<Prompt> {
+    vec![
+        Prompt::new(
+            "review_diff",
+            Some("Review the changes since a git reference, with the changed files as context"),
+            Some(vec![
+                argument(
+                    "base",
+                    "Git reference to compare against (default: HEAD)",
+                    false,
+                ),
+                argument(
+                    "target",
+                    "Git reference to compare to (default: the working tree)",
+                    false,
+                ),
+            ]),
+        ),
+        Prompt::new(
+            "explain_module",
+            Some("Explain a file or directory of the project, with the files it imports"),
+            Some(vec![
+                argument(
+                    "module",
+                    "File or directory, relative to the project root",
+                    true,
+                ),
+                argument(
+                    "include_imports",
+                    "Whether to include imported files (default: true)",
+                    false,
+                ),
+            ]),
+        ),
+        Prompt::new(
+            "find_callers",
+            Some("Find and explain the callers of a function, with the calling files"),
+            Some(vec![
+                argument("symbol", "Name of the function", true),
+                argument("depth", "Levels of callers to follow (default: 1)", false),
+            ]),
+        ),
+    ]
+}
+
+/// Assemble the message of a prompt
+pub fn get_prompt(
+    root: &Path,
+    projects: &ProjectIndex,
+    name: &str,
+    arguments: Option<&JsonObject>,
+) -> Result<GetPromptResult, ErrorData> {
+    let arguments = Arguments(arguments);
+    let (description, text) = match name {
+        "review_diff" => review_diff(root, &arguments)?,
+        "explain_module" => explain_module(root, &arguments)?,
+        "find_callers" => find_callers(root, projects, &arguments)?,
+        _ => {
+            return Err(ErrorData::invalid_params(
+                format!("Unknown prompt: {name}"),
+                None,
+            ))
+        }
+    };
+
+    Ok(GetPromptResult {
+        description: Some(description),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+fn review_diff(root: &Path, arguments: &Arguments) -> Result<(String, String), ErrorData> {
+    let base = arguments
+        .string("base")
+        .unwrap_or_else(|| "HEAD".to_string());
+    let target = arguments.string("target");
+
+    let diff = git::get_diff(root, &base, target.as_deref()).map_err(internal_error)?;
+    let compared = match &target {
+        Some(target) => format!("{base}..{target}"),
+        None => format!("{base} and the working tree"),
+    };
+    if diff.trim().is_empty() {
+        return Err(ErrorData::invalid_params(
+            format!("No changes between {compared}"),
+            None,
+        ));
+    }
+
+    // Deleted files have no content to show
+    let changed = git::get_diff_files(root, &base, target.as_deref())
+        .map_err(internal_error)?
+        .into_iter()
+        .filter(|path| path.is_file())
+        .filter_map(|path| path.strip_prefix(root).ok().map(Path::to_path_buf))
+        .collect::<Vec<_>>();
+
+    let mut text = String::from(
+        "Review the following changes. Point out bugs, risky or unclear changes and missing \
+         tests, and suggest concrete improvements.\n\n",
+    );
+    let _ = write!(text, "## Diff\n\n```diff\n{}\n```\n", diff.trim_end());
+    if !changed.is_empty() {
+        let _ = write!(
+            text,
+            "\n## Changed files\n\n{}",
+            file_context(root, &changed, false)?
+        );
+    }
+
+    Ok((format!("Review of the changes between {compared}"), text))
+}
+
+fn explain_module(root: &Path, arguments: &Arguments) -> Result<(String, String), ErrorData> {
+    let module = arguments.required("module")?;
+    let include_imports = arguments.boolean("include_imports")?.unwrap_or(true);
+
+    let relative = PathBuf::from(module.trim_end_matches('/'));
+    if relative.is_absolute() || module.contains("..") || !root.join(&relative).exists() {
+        return Err(ErrorData::invalid_params(
+            format!("Not a file or directory of the project: {module}"),
+            None,
+        ));
+    }
+    let pattern = if root.join(&relative).is_dir() {
+        relative.join("**")
+    } else {
+        relative
+    };
+
+    let context = file_context(root, &[pattern], include_imports)?;
+    let text = format!(
+        "Explain what `{module}` does: its purpose, its main types and functions, how it fits \
+         into the rest of the project and anything surprising about it.\n\n{context}"
+    );
+
+    Ok((format!("Explanation of {module}"), text))
+}
+
+fn find_callers(
+    root: &Path,
+    projects: &ProjectIndex,
+    arguments: &Arguments,
+) -> Result<(String, String), ErrorData> {
+    let symbol = arguments.required("symbol")?;
+    let depth = arguments.number("depth")?.unwrap_or(1);
+
+    let graph = projects.graph(root).map_err(internal_error)?;
+    let definitions = graph.definitions(&symbol);
+    let callers = graph.callers(&symbol, depth);
+
+    let mut text = format!(
+        "Explain how `{symbol}` is used: summarize each caller, what it passes and expects \
+         back, and what would break if the signature or behavior of `{symbol}` changed.\n\n"
+    );
+    let _ = writeln!(text, "## Definitions\n");
+    if definitions.is_empty() {
+        let _ = writeln!(text, "No definition found in the project.");
+    }
+    for definition in &definitions {
+        let _ = writeln!(
+            text,
+            "- {} `{}` at {}:{}",
+            definition.kind,
+            definition.name,
+            definition.file.display(),
+            definition.line
+        );
+    }
+    let _ = writeln!(text, "\n## Call sites\n");
+    if callers.is_empty() {
+        let _ = writeln!(text, "No call sites found in the project.");
+    }
+    for caller in &callers {
+        let _ = writeln!(
+            text,
+            "- {}:{} calls `{}` (depth {})",
+            caller.file.display(),
+            caller.line,
+            caller.function,
+            caller.depth
+        );
+    }
+
+    let files: BTreeSet<_> = definitions
+        .iter()
+        .map(|definition| definition.file.clone())
+        .chain(callers.iter().map(|caller| caller.file.clone()))
+        .collect();
+    if !files.is_empty() {
+        let files: Vec<_> = files.into_iter().collect();
+        let _ = write!(text, "\n## Files\n\n{}", file_context(root, &files, false)?);
+    }
+
+    Ok((format!("Callers of {symbol}"), text))
+}
+
+/// Markdown context of the given project files or glob patterns
+fn file_context(
+    root: &Path,
+    patterns: &[PathBuf],
+    include_imports: bool,
+) -> Result<String, ErrorData> {
+    let request = GetContextRequest {
+        path: root.to_path_buf(),
+        include_patterns: patterns
+            .iter()
+            .map(|pattern| pattern.to_string_lossy().into_owned())
+            .collect(),
+        ignore_patterns: Vec::new(),
+        include_imports,
+        max_tokens: None,
+        format: ContextFormat::Markdown,
+        options: Default::default(),
+    };
+    super::handlers::get_context_sync(request, Instant::now(), Default::default())
+        .map(|response| response.context)
+        .map_err(internal_error)
+}
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        required: Some(required),
+    }
+}
+
+fn internal_error(error: anyhow::Error) -> ErrorData {
+    ErrorData::internal_error(error.to_string(), None)
+}
+
+/// Prompt arguments, which clients send as strings
+struct Arguments<'a>(Option<&'a JsonObject>);
+
+impl Arguments<'_> {
+    fn string(&self, name: &str) -> Option<String> {
+        match self.0?.get(name)? {
+            serde_json::Value::String(value) if !value.is_empty() => Some(value.clone()),
+            serde_json::Value::Number(value) => Some(value.to_string()),
+            serde_json::Value::Bool(value) => Some(value.to_string()),
+            _ => None,
+        }
+    }
+
+    fn required(&self, name: &str) -> Result<String, ErrorData> {
+        self.string(name).ok_or_else(|| {
+            ErrorData::invalid_params(format!("Missing required argument: {name}"), None)
+        })
+    }
+
+    fn boolean(&self, name: &str) -> Result<Option<bool>, ErrorData> {
+        self.parsed(name)
+    }
+
+    fn number(&self, name: &str) -> Result<Option<usize>, ErrorData> {
+        self.parsed(name)
+    }
+
+    fn parsed<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, ErrorData> {
+        self.string(name)
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    ErrorData::invalid_params(format!("Invalid value for `{name}`: {value}"), None)
+                })
+            })
+            .transpose()
+    }
+}
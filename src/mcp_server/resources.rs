@@ -0,0 +1,220 @@
+//! MCP resources for the RMCP server
+//!
+//! The walked files of the project, respecting ignore rules, are listed as
+//! `file://` resources. Generated context bundles are read from
+//! `context://project`, optionally narrowed with query parameters, e.g.
+//! `context://project?include=src/**/*.rs&max_tokens=20000&format=xml`.
+
+use rmcp::model::{
+    AnnotateAble, ErrorData, ListResourcesResult, RawResource, RawResourceTemplate,
+    ReadResourceResult, ResourceContents, ResourceTemplate,
+};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::{ContextFormat, GetContextRequest};
+use crate::cli::Config;
+use crate::core::walker::{walk_directory, FileInfo, WalkOptions};
+
+/// URI of the context bundle for the whole project
+pub const PROJECT_BUNDLE_URI: &str = "context://project";
+
+/// Number of resources returned per `resources/list` page
+const PAGE_SIZE: usize = 500;
+
+/// List the context bundle and the project files, a page at a time
+pub fn list_resources(root: &Path, cursor: Option<&str>) -> Result<ListResourcesResult, ErrorData> {
+    let offset = match cursor {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .map_err(|_| ErrorData::invalid_params(format!("Invalid cursor: {cursor}"), None))?,
+        None => 0,
+    };
+
+    let bundle = RawResource {
+        description: Some("All project files assembled as a markdown context".to_string()),
+        mime_type: Some("text/markdown".to_string()),
+        ..RawResource::new(PROJECT_BUNDLE_URI, "Project context")
+    };
+    let files = project_files(root)?.into_iter().map(|file| RawResource {
+        description: Some(file.file_type_display().to_string()),
+        mime_type: Some("text/plain".to_string()),
+        size: u32::try_from(file.size).ok(),
+        ..RawResource::new(
+            file_uri(&file.path),
+            file.relative_path.to_string_lossy().into_owned(),
+        )
+    });
+
+    let mut resources: Vec<_> = std::iter::once(bundle)
+        .chain(files)
+        .skip(offset)
+        .take(PAGE_SIZE + 1)
+        .map(|resource| resource.no_annotation())
+        .collect();
+    let next_cursor = (resources.len() > PAGE_SIZE).then(|| {
+        resources.truncate(PAGE_SIZE);
+        (offset + PAGE_SIZE).to_string()
+    });
+
+    Ok(ListResourcesResult {
+        next_cursor,
+        resources,
+    })
+}
+
+/// Templates for parameterized context bundles
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    vec![RawResourceTemplate {
+        uri_template: format!(
+            "{PROJECT_BUNDLE_URI}{{?include,ignore,max_tokens,format,include_imports}}"
+        ),
+        name: "Project context".to_string(),
+        description: Some(
+            "Project files assembled as a context. `include` and `ignore` take comma-separated \
+             glob patterns, `format` is markdown, xml, json, plain or paths"
+                .to_string(),
+        ),
+        mime_type: None,
+    }
+    .no_annotation()]
+}
+
+/// Read a project file or generate a context bundle
+pub fn read_resource(root: &Path, uri: &str) -> Result<ReadResourceResult, ErrorData> {
+    let (text, mime_type) = if let Some(query) = bundle_query(uri) {
+        let request = bundle_request(root, query)?;
+        let mime_type = match request.format {
+            ContextFormat::Markdown => "text/markdown",
+            ContextFormat::Xml => "application/xml",
+            ContextFormat::Json => "application/json",
+            ContextFormat::Plain | ContextFormat::Paths => "text/plain",
+        };
+        let response =
+            super::handlers::get_context_sync(request, Instant::now(), Default::default())
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+        (response.context, mime_type)
+    } else if let Some(path) = uri.strip_prefix("file://") {
+        (read_project_file(root, Path::new(path))?, "text/plain")
+    } else {
+        return Err(ErrorData::resource_not_found(
+            format!("Unknown resource: {uri}"),
+            None,
+        ));
+    };
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type.to_string()),
+            text,
+        }],
+    })
+}
+
+/// The files of the project, walked with its ignore rules
+fn project_files(root: &Path) -> Result<Vec<FileInfo>, ErrorData> {
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        quiet: true,
+        ..Default::default()
+    };
+    WalkOptions::from_config(&config)
+        .and_then(|options| walk_directory(root, options))
+        .map_err(|e| ErrorData::internal_error(e.to_string(), None))
+}
+
+/// Read a file, provided the walk of the project includes it
+fn read_project_file(root: &Path, path: &Path) -> Result<String, ErrorData> {
+    let not_found =
+        || ErrorData::resource_not_found(format!("Not a project file: {}", path.display()), None);
+    let path = path.canonicalize().map_err(|_| not_found())?;
+    if !path.starts_with(root) || !project_files(root)?.iter().any(|file| file.path == path) {
+        return Err(not_found());
+    }
+    std::fs::read_to_string(&path).map_err(|e| ErrorData::internal_error(e.to_string(), None))
+}
+
+fn file_uri(path: &Path) -> String {
+    format!("file://{}", path.display())
+}
+
+/// The query string of a `context://project` URI, empty if it has none
+fn bundle_query(uri: &str) -> Option<&str> {
+    let rest = uri.strip_prefix(PROJECT_BUNDLE_URI)?;
+    match rest.strip_prefix('?') {
+        Some(query) => Some(query),
+        None if rest.is_empty() || rest == "/" => Some(""),
+        None => None,
+    }
+}
+
+/// Build the get_context request a bundle URI describes
+fn bundle_request(root: &Path, query: &str) -> Result<GetContextRequest, ErrorData> {
+    let mut request = GetContextRequest {
+        path: PathBuf::from(root),
+        include_patterns: Vec::new(),
+        ignore_patterns: Vec::new(),
+        include_imports: false,
+        max_tokens: None,
+        format: ContextFormat::Markdown,
+        options: Default::default(),
+    };
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value);
+        let invalid =
+            || ErrorData::invalid_params(format!("Invalid value for `{key}`: {value}"), None);
+        match key {
+            "include" => request.include_patterns.extend(split_patterns(&value)),
+            "ignore" => request.ignore_patterns.extend(split_patterns(&value)),
+            "max_tokens" => request.max_tokens = Some(value.parse().map_err(|_| invalid())?),
+            "include_imports" => request.include_imports = value.parse().map_err(|_| invalid())?,
+            "format" => {
+                request.format = serde_json::from_value(serde_json::Value::String(value.clone()))
+                    .map_err(|_| invalid())?
+            }
+            _ => {
+                return Err(ErrorData::invalid_params(
+                    format!("Unknown context parameter: {key}"),
+                    None,
+                ))
+            }
+        }
+    }
+
+    Ok(request)
+}
+
+fn split_patterns(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(str::to_string)
+}
+
+/// Decode `%XX` escapes and `+` in a query value
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+            (b'+', _) => decoded.push(b' '),
+            (byte, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -1,39 +1,60 @@
 //! RMCP-based MCP server implementation for context-creator
 //!
 //! This module provides an MCP-compliant server using the rmcp library
-//! that allows AI agents to analyze codebases programmatically.
+//! that allows AI agents to analyze codebases programmatically. Its tools
+//! come from the [`ToolRegistry`] shared with the JSON-RPC server.
 
 use anyhow::Result;
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, tool::Parameters, wrapper::Json},
-    model::{ErrorCode, ErrorData, ServerCapabilities, ServerInfo},
-    tool, tool_handler, tool_router, ServerHandler,
+    model::{
+        CallToolRequestParam, CallToolResult, Content, ErrorCode, ErrorData, GetPromptRequestParam,
+        GetPromptResult, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        ListToolsResult, PaginatedRequestParam, ProgressNotificationParam,
+        ReadResourceRequestParam, ReadResourceResult, ServerCapabilities, ServerInfo, Tool,
+    },
+    service::RequestContext,
+    RoleServer, ServerHandler,
 };
-use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
 
-use super::{
-    DiffFilesRequest, DiffFilesResponse, GetFileMetadataRequest, GetFileMetadataResponse,
-    ProcessLocalRequest, ProcessLocalResponse, ProcessRemoteRequest, ProcessRemoteResponse,
-    SearchCodebaseRequest, SearchCodebaseResponse, SemanticSearchRequest, SemanticSearchResponse,
-};
+use super::tools::{ToolError, ToolRegistry};
+use crate::core::progress::{ProgressEvent, ProgressReporter};
 
 /// Context Creator MCP Server implementation
 #[derive(Debug, Clone)]
 pub struct ContextCreatorServer {
-    cache: Arc<crate::mcp_server::cache::McpCache>,
-    tool_router: ToolRouter<Self>,
+    tools: Arc<ToolRegistry>,
+    /// Project served as resources and used by prompts
+    root: PathBuf,
 }
 
-#[tool_router]
 impl ContextCreatorServer {
+    /// Create a server for the project in the current directory
     pub fn new() -> Self {
+        Self::with_root(std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
+    }
+
+    /// Create a server for the project at `root`
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
         Self {
-            cache: Arc::new(crate::mcp_server::cache::McpCache::new()),
-            tool_router: Self::tool_router(),
+            tools: Arc::new(ToolRegistry::new(Arc::new(
+                crate::mcp_server::cache::McpCache::new(),
+            ))),
+            root: root.canonicalize().unwrap_or(root),
         }
     }
+
+    /// The project served as resources and used by prompts
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The tools the server serves
+    pub fn tools(&self) -> &ToolRegistry {
+        &self.tools
+    }
 }
 
 impl Default for ContextCreatorServer {
@@ -42,164 +63,56 @@ impl Default for ContextCreatorServer {
     }
 }
 
-impl ContextCreatorServer {
-    #[tool(description = "Analyze a local codebase directory and answer questions about it")]
-    pub async fn analyze_local(
-        &self,
-        Parameters(request): Parameters<ProcessLocalRequest>,
-    ) -> Result<Json<ProcessLocalResponse>, ErrorData> {
-        let start = Instant::now();
-
-        // Validate path security
-        super::rmcp_handlers::validate_path(&request.path).map_err(|e| {
-            ErrorData::new(
-                ErrorCode::INVALID_PARAMS,
-                format!("Invalid path: {e}"),
-                None,
-            )
-        })?;
-
-        // Check cache first
-        let cache_key = crate::mcp_server::cache::ProcessLocalCacheKey::from_request(&request);
-        if let Some(cached) = self.cache.get_process_local(&cache_key).await {
-            let processing_time_ms = start.elapsed().as_millis() as u64;
-            return Ok(Json(ProcessLocalResponse {
-                answer: cached.answer,
-                context: if request.include_context.unwrap_or(false) {
-                    Some(cached.markdown)
-                } else {
-                    None
-                },
-                file_count: cached.file_count,
-                token_count: cached.token_count,
-                processing_time_ms,
-                llm_tool: cached.llm_tool,
-            }));
-        }
+/// Scale of the `progress` values sent in progress notifications
+const PROGRESS_TOTAL: u32 = 1000;
 
-        // Use blocking task for file I/O
-        let cache = self.cache.clone();
-        let response = tokio::task::spawn_blocking(move || {
-            super::handlers::process_codebase_sync(request, start)
-        })
-        .await
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
-
-        // Cache the response
-        let cache_value = crate::mcp_server::cache::ProcessLocalCacheValue {
-            answer: response.answer.clone(),
-            markdown: response.context.clone().unwrap_or_default(),
-            file_count: response.file_count,
-            token_count: response.token_count,
-            llm_tool: response.llm_tool.clone(),
-        };
-        cache.set_process_local(cache_key, cache_value).await;
-
-        Ok(Json(response))
-    }
+/// A reporter forwarding progress to the client as `notifications/progress`
+///
+/// Reports are only forwarded when the client asked for them with a progress
+/// token; the returned task finishes once every clone of the reporter is
+/// dropped.
+fn progress_reporter(
+    context: &RequestContext<RoleServer>,
+) -> (ProgressReporter, Option<tokio::task::JoinHandle<()>>) {
+    let Some(progress_token) = context.meta.get_progress_token() else {
+        return (ProgressReporter::default(), None);
+    };
 
-    #[tool(description = "Analyze a remote Git repository and answer questions about it")]
-    pub async fn analyze_remote(
-        &self,
-        Parameters(request): Parameters<ProcessRemoteRequest>,
-    ) -> Result<Json<ProcessRemoteResponse>, ErrorData> {
-        let start = Instant::now();
-
-        // Check cache first
-        let cache_key = crate::mcp_server::cache::ProcessRemoteCacheKey::from_request(&request);
-        if let Some(cached) = self.cache.get_process_remote(&cache_key).await {
-            let processing_time_ms = start.elapsed().as_millis() as u64;
-            return Ok(Json(ProcessRemoteResponse {
-                answer: cached.answer,
-                context: if request.include_context.unwrap_or(false) {
-                    Some(cached.markdown)
-                } else {
-                    None
-                },
-                file_count: cached.file_count,
-                token_count: cached.token_count,
-                processing_time_ms,
-                repo_name: cached.repo_name,
-                llm_tool: cached.llm_tool,
-            }));
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<ProgressEvent>();
+    let peer = context.peer.clone();
+    let forwarder = tokio::spawn(async move {
+        let mut last = None;
+        while let Some(event) = receiver.recv().await {
+            let progress = (event.fraction() * f64::from(PROGRESS_TOTAL)) as u32;
+            // Clients expect progress to increase with every notification
+            if last.is_some_and(|last| progress <= last) {
+                continue;
+            }
+            last = Some(progress);
+            let _ = peer
+                .notify_progress(ProgressNotificationParam {
+                    progress_token: progress_token.clone(),
+                    progress,
+                    total: Some(PROGRESS_TOTAL),
+                    message: Some(format!("{}: {}", event.phase.name(), event.message)),
+                })
+                .await;
         }
+    });
+    let reporter = ProgressReporter::new(move |event| {
+        let _ = sender.send(event.clone());
+    });
+    (reporter, Some(forwarder))
+}
 
-        // Use blocking task for file I/O and git operations
-        let cache = self.cache.clone();
-        let response = tokio::task::spawn_blocking(move || {
-            super::handlers::process_remote_sync(request, start)
-        })
-        .await
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
-        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?;
-
-        // Cache the response
-        let cache_value = crate::mcp_server::cache::ProcessRemoteCacheValue {
-            answer: response.answer.clone(),
-            markdown: response.context.clone().unwrap_or_default(),
-            file_count: response.file_count,
-            token_count: response.token_count,
-            repo_name: response.repo_name.clone(),
-            llm_tool: response.llm_tool.clone(),
-        };
-        cache.set_process_remote(cache_key, cache_value).await;
-
-        Ok(Json(response))
-    }
-
-    #[tool(description = "Get metadata information about a specific file")]
-    pub async fn file_metadata(
-        &self,
-        Parameters(request): Parameters<GetFileMetadataRequest>,
-    ) -> Result<Json<GetFileMetadataResponse>, ErrorData> {
-        tokio::task::spawn_blocking(move || super::handlers::get_file_metadata_sync(request))
-            .await
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
-            .map(Json)
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))
-    }
-
-    #[tool(description = "Search for text patterns across the codebase")]
-    pub async fn search(
-        &self,
-        Parameters(request): Parameters<SearchCodebaseRequest>,
-    ) -> Result<Json<SearchCodebaseResponse>, ErrorData> {
-        let start = Instant::now();
-        tokio::task::spawn_blocking(move || super::handlers::search_codebase_sync(request, start))
-            .await
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
-            .map(Json)
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))
-    }
-
-    #[tool(description = "Generate a diff between two files")]
-    pub async fn diff(
-        &self,
-        Parameters(request): Parameters<DiffFilesRequest>,
-    ) -> Result<Json<DiffFilesResponse>, ErrorData> {
-        tokio::task::spawn_blocking(move || super::handlers::diff_files_sync(request))
-            .await
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
-            .map(Json)
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))
-    }
-
-    #[tool(description = "Perform semantic search for code symbols (functions, types, imports)")]
-    pub async fn semantic_search(
-        &self,
-        Parameters(request): Parameters<SemanticSearchRequest>,
-    ) -> Result<Json<SemanticSearchResponse>, ErrorData> {
-        let start = Instant::now();
-        tokio::task::spawn_blocking(move || super::handlers::semantic_search_sync(request, start))
-            .await
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
-            .map(Json)
-            .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))
-    }
+fn tool_error(error: ToolError) -> ErrorData {
+    let code = match error {
+        ToolError::UnknownTool(_) | ToolError::InvalidParams(_) => ErrorCode::INVALID_PARAMS,
+        ToolError::Internal(_) => ErrorCode::INTERNAL_ERROR,
+    };
+    ErrorData::new(code, error.to_string(), None)
 }
 
-#[tool_handler]
 impl ServerHandler for ContextCreatorServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -208,18 +121,132 @@ impl ServerHandler for ContextCreatorServer {
                 "This server provides tools to:\n",
                 "- Analyze local directories with 'analyze_local'\n",
                 "- Analyze remote Git repositories with 'analyze_remote'\n",
+                "- Assemble context for your own use, without an LLM call, with 'get_context'\n",
+                "- Explore the dependency graph with 'get_imports', 'get_importers', 'find_callers', 'find_definition' and 'get_type_hierarchy'\n",
                 "- Get file metadata with 'file_metadata'\n",
                 "- Search codebases with 'search'\n",
                 "- Generate diffs with 'diff'\n",
                 "- Perform semantic searches with 'semantic_search'\n\n",
-                "All analysis tools use LLM integration to provide intelligent answers about code."
+                "Project files are exposed as resources, and 'context://project' returns them assembled as a context.\n",
+                "The 'review_diff', 'explain_module' and 'find_callers' prompts assemble their context on the server.\n\n",
+                "'analyze_local' and 'analyze_remote' send the context to an LLM CLI to answer the prompt."
             ).into()),
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
+                .enable_resources()
+                .enable_prompts()
                 .build(),
             ..Default::default()
         }
     }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        Ok(ListToolsResult::with_all_items(
+            self.tools
+                .tools()
+                .iter()
+                .map(|tool| Tool::new(tool.name, tool.description, tool.input_schema.clone()))
+                .collect(),
+        ))
+    }
+
+    /// Call a tool, reporting its progress and stopping it when the client
+    /// cancels the request
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let (reporter, forwarder) = progress_reporter(&context);
+        let arguments = serde_json::Value::Object(request.arguments.unwrap_or_default());
+
+        let cancel = reporter.cancel_on_drop();
+        let call = self.tools.call(&request.name, arguments, reporter.clone());
+        tokio::pin!(call);
+        let result = tokio::select! {
+            result = &mut call => result,
+            () = context.ct.cancelled() => {
+                reporter.cancel();
+                call.await
+            }
+        };
+
+        // Send the remaining notifications before the response
+        drop((cancel, reporter));
+        if let Some(forwarder) = forwarder {
+            let _ = forwarder.await;
+        }
+
+        let response = result.map_err(tool_error)?;
+        Ok(CallToolResult::success(vec![Content::json(response)?]))
+    }
+
+    async fn list_resources(
+        &self,
+        request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let root = self.root.clone();
+        let cursor = request.and_then(|request| request.cursor);
+        run_blocking(move || super::resources::list_resources(&root, cursor.as_deref())).await
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, ErrorData> {
+        Ok(ListResourceTemplatesResult::with_all_items(
+            super::resources::resource_templates(),
+        ))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let root = self.root.clone();
+        run_blocking(move || super::resources::read_resource(&root, &request.uri)).await
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, ErrorData> {
+        Ok(ListPromptsResult::with_all_items(
+            super::prompts::list_prompts(),
+        ))
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, ErrorData> {
+        let root = self.root.clone();
+        let projects = self.tools.cache().projects().clone();
+        run_blocking(move || {
+            super::prompts::get_prompt(&root, &projects, &request.name, request.arguments.as_ref())
+        })
+        .await
+    }
+}
+
+/// Run a resource or prompt request on the blocking pool
+async fn run_blocking<T, F>(work: F) -> Result<T, ErrorData>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ErrorData> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| ErrorData::new(ErrorCode::INTERNAL_ERROR, e.to_string(), None))?
 }
 
 /// Start the RMCP server with specified transport
@@ -227,6 +254,9 @@ pub mod transport {
     use super::*;
     use rmcp::{transport::stdio, ServiceExt};
 
+    /// Path the streamable HTTP transport is served at
+    pub const STREAMABLE_HTTP_PATH: &str = "/mcp";
+
     /// Start server with stdio transport (for MCP CLI usage)
     pub async fn start_stdio() -> Result<()> {
         tracing::info!("Starting Context Creator MCP server (stdio mode)");
@@ -247,9 +277,11 @@ pub mod transport {
             addr
         );
 
+        // Connections share the tools, and with them the cache
+        let server = ContextCreatorServer::new();
         let ct = SseServer::serve(addr.parse()?)
             .await?
-            .with_service_directly(ContextCreatorServer::new);
+            .with_service_directly(move || server.clone());
 
         // Wait for shutdown signal
         tokio::signal::ctrl_c().await?;
@@ -257,4 +289,40 @@ pub mod transport {
 
         Ok(())
     }
+
+    /// Router serving `server` with the streamable HTTP transport
+    ///
+    /// Sessions share the tools, and with them the cache.
+    pub fn streamable_http_router(server: ContextCreatorServer) -> axum::Router {
+        use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+        use rmcp::transport::{StreamableHttpServerConfig, StreamableHttpService};
+
+        let service = StreamableHttpService::new(
+            move || Ok(server.clone()),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        axum::Router::new().nest_service(STREAMABLE_HTTP_PATH, service)
+    }
+
+    /// Start server with the streamable HTTP transport
+    pub async fn start_streamable_http(addr: &str) -> Result<()> {
+        tracing::info!(
+            "Starting Context Creator MCP server (streamable HTTP mode) on {}{}",
+            addr,
+            STREAMABLE_HTTP_PATH
+        );
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(
+            listener,
+            streamable_http_router(ContextCreatorServer::new()),
+        )
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,594 @@
+//! Transport-agnostic MCP tools
+//!
+//! Every tool is declared once here, with its name, description, request
+//! schema, validation and execution. The JSON-RPC server (`--mcp`) registers
+//! each tool under its method name, and the RMCP server (`--rmcp`) lists and
+//! calls them through `tools/list` and `tools/call`, so a tool added to
+//! [`ToolRegistry::new`] is served by both.
+
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::cache::{
+    McpCache, ProcessLocalCacheKey, ProcessLocalCacheValue, ProcessRemoteCacheKey,
+    ProcessRemoteCacheValue,
+};
+use super::handlers;
+use super::{
+    DiffFilesRequest, DiffFilesResponse, FileGraphRequest, FindCallersResponse,
+    FindDefinitionRequest, FindDefinitionResponse, GetContextRequest, GetContextResponse,
+    GetFileMetadataRequest, GetFileMetadataResponse, ImportGraphResponse, ProcessLocalRequest,
+    ProcessLocalResponse, ProcessRemoteRequest, ProcessRemoteResponse, SearchCodebaseRequest,
+    SearchCodebaseResponse, SemanticSearchRequest, SemanticSearchResponse, SymbolGraphRequest,
+    TypeHierarchyResponse,
+};
+use crate::core::progress::ProgressReporter;
+
+/// A JSON object, as used for tool arguments and schemas
+pub type JsonObject = serde_json::Map<String, Value>;
+
+/// Error of a tool call
+#[derive(Debug, thiserror::Error)]
+pub enum ToolError {
+    /// No tool has the requested name
+    #[error("Unknown tool: {0}")]
+    UnknownTool(String),
+
+    /// The arguments are malformed or failed validation
+    #[error("{0}")]
+    InvalidParams(String),
+
+    /// The tool failed while running
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl ToolError {
+    /// JSON-RPC error code of the error
+    pub fn code(&self) -> i32 {
+        match self {
+            ToolError::UnknownTool(_) => -32601,
+            ToolError::InvalidParams(_) => -32602,
+            ToolError::Internal(_) => -32603,
+        }
+    }
+}
+
+impl From<anyhow::Error> for ToolError {
+    fn from(error: anyhow::Error) -> Self {
+        ToolError::Internal(error.to_string())
+    }
+}
+
+/// What a tool call runs with
+#[derive(Debug, Clone)]
+pub struct ToolContext {
+    /// Response cache and project index shared by all calls
+    pub cache: Arc<McpCache>,
+    /// Receives the call's progress and carries its cancellation
+    pub reporter: ProgressReporter,
+}
+
+type ToolFuture = Pin<Box<dyn Future<Output = Result<Value, ToolError>> + Send>>;
+type Handler = Box<dyn Fn(ToolContext, Value) -> ToolFuture + Send + Sync>;
+
+/// A tool and how to call it
+pub struct ToolSpec {
+    /// Name of the tool in `tools/list` and `tools/call`
+    pub name: &'static str,
+    /// Method name on the JSON-RPC server
+    pub rpc_method: &'static str,
+    /// What the tool does
+    pub description: &'static str,
+    /// JSON Schema of the arguments
+    pub input_schema: Arc<JsonObject>,
+    handler: Handler,
+}
+
+impl std::fmt::Debug for ToolSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ToolSpec")
+            .field("name", &self.name)
+            .field("rpc_method", &self.rpc_method)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ToolSpec {
+    fn new<Req, Resp, Fut>(
+        name: &'static str,
+        rpc_method: &'static str,
+        description: &'static str,
+        run: fn(ToolContext, Req) -> Fut,
+    ) -> Self
+    where
+        Req: DeserializeOwned + JsonSchema + Send + 'static,
+        Resp: Serialize + 'static,
+        Fut: Future<Output = Result<Resp, ToolError>> + Send + 'static,
+    {
+        Self {
+            name,
+            rpc_method,
+            description,
+            input_schema: Arc::new(schema_for::<Req>()),
+            handler: Box::new(move |context, arguments| {
+                Box::pin(async move {
+                    let request = serde_json::from_value(arguments)
+                        .map_err(|e| ToolError::InvalidParams(format!("Invalid arguments: {e}")))?;
+                    let response = run(context, request).await?;
+                    serde_json::to_value(response).map_err(|e| ToolError::Internal(e.to_string()))
+                })
+            }),
+        }
+    }
+
+    /// Validate and run the tool with JSON arguments
+    pub fn call(&self, context: ToolContext, arguments: Value) -> ToolFuture {
+        (self.handler)(context, arguments)
+    }
+}
+
+/// The tools served over every transport
+#[derive(Debug)]
+pub struct ToolRegistry {
+    tools: Vec<ToolSpec>,
+    cache: Arc<McpCache>,
+}
+
+impl ToolRegistry {
+    /// Create the registry, sharing `cache` between all tool calls
+    pub fn new(cache: Arc<McpCache>) -> Self {
+        let tools = vec![
+            ToolSpec::new(
+                "analyze_local",
+                "process_local_codebase",
+                "Analyze a local codebase directory and answer questions about it",
+                analyze_local,
+            ),
+            ToolSpec::new(
+                "analyze_remote",
+                "process_remote_repo",
+                "Analyze a remote Git repository and answer questions about it",
+                analyze_remote,
+            ),
+            ToolSpec::new(
+                "get_context",
+                "get_context",
+                "Assemble the context for a local codebase (markdown, xml or json) without calling an LLM",
+                get_context,
+            ),
+            ToolSpec::new(
+                "file_metadata",
+                "get_file_metadata",
+                "Get metadata information about a specific file",
+                file_metadata,
+            ),
+            ToolSpec::new(
+                "search",
+                "search_codebase",
+                "Search for text patterns across the codebase",
+                search,
+            ),
+            ToolSpec::new(
+                "diff",
+                "diff_files",
+                "Generate a diff between two files",
+                diff,
+            ),
+            ToolSpec::new(
+                "semantic_search",
+                "semantic_search",
+                "Perform semantic search for code symbols (functions, types, imports)",
+                semantic_search,
+            ),
+            ToolSpec::new(
+                "get_imports",
+                "get_imports",
+                "List the files a file imports, following imports up to `depth` levels",
+                get_imports,
+            ),
+            ToolSpec::new(
+                "get_importers",
+                "get_importers",
+                "List the files importing a file, up to `depth` levels",
+                get_importers,
+            ),
+            ToolSpec::new(
+                "find_callers",
+                "find_callers",
+                "Find the call sites of a function; deeper levels follow callers of the calling files' exports",
+                find_callers,
+            ),
+            ToolSpec::new(
+                "find_definition",
+                "find_definition",
+                "Find where a function or type is defined",
+                find_definition,
+            ),
+            ToolSpec::new(
+                "get_type_hierarchy",
+                "get_type_hierarchy",
+                "List the supertypes and subtypes of a type, up to `depth` levels",
+                get_type_hierarchy,
+            ),
+        ];
+        Self { tools, cache }
+    }
+
+    /// All tools, in the order they are listed
+    pub fn tools(&self) -> &[ToolSpec] {
+        &self.tools
+    }
+
+    /// The tool named `name`
+    pub fn get(&self, name: &str) -> Option<&ToolSpec> {
+        self.tools.iter().find(|tool| tool.name == name)
+    }
+
+    /// Cache shared by the tool calls
+    pub fn cache(&self) -> &Arc<McpCache> {
+        &self.cache
+    }
+
+    /// Call the tool named `name` with JSON arguments
+    pub async fn call(
+        &self,
+        name: &str,
+        arguments: Value,
+        reporter: ProgressReporter,
+    ) -> Result<Value, ToolError> {
+        let tool = self
+            .get(name)
+            .ok_or_else(|| ToolError::UnknownTool(name.to_string()))?;
+        let context = ToolContext {
+            cache: self.cache.clone(),
+            reporter,
+        };
+        tool.call(context, arguments).await
+    }
+}
+
+/// JSON Schema of a request type, in the draft the MCP specification uses
+fn schema_for<T: JsonSchema>() -> JsonObject {
+    let mut settings = schemars::gen::SchemaSettings::draft07();
+    settings.option_nullable = true;
+    settings.option_add_null_type = false;
+    settings.visitors = Vec::new();
+    let schema = settings.into_generator().into_root_schema_for::<T>();
+    match serde_json::to_value(schema) {
+        Ok(Value::Object(object)) => object,
+        _ => JsonObject::new(),
+    }
+}
+
+/// Validate a path argument for security issues
+pub fn validate_path(path: &Path) -> Result<(), ToolError> {
+    // Check for path traversal attempts
+    let path_str = path.to_string_lossy();
+    if path_str.contains("..") || path_str.contains('~') {
+        return Err(ToolError::InvalidParams(
+            "Invalid path: potential security risk".to_string(),
+        ));
+    }
+
+    if !path.exists() {
+        return Err(ToolError::InvalidParams(format!(
+            "Invalid path: {} does not exist",
+            path.display()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Validate a repository URL argument
+pub fn validate_url(url: &str) -> Result<(), ToolError> {
+    crate::remote::parse_remote_url(url)
+        .map_err(|e| ToolError::InvalidParams(format!("Invalid URL: {e}")))?;
+    Ok(())
+}
+
+/// Run a tool's work on the blocking pool
+///
+/// Dropping the returned future, as transports do when a client goes away,
+/// cancels the work.
+async fn run_blocking<T, F>(reporter: ProgressReporter, work: F) -> Result<T, ToolError>
+where
+    T: Send + 'static,
+    F: FnOnce(ProgressReporter) -> anyhow::Result<T> + Send + 'static,
+{
+    let _cancel = reporter.cancel_on_drop();
+    run_blocking_step(reporter, work).await
+}
+
+/// Run one step of a tool's work on the blocking pool, leaving cancellation
+/// on drop to the caller
+async fn run_blocking_step<T, F>(reporter: ProgressReporter, work: F) -> Result<T, ToolError>
+where
+    T: Send + 'static,
+    F: FnOnce(ProgressReporter) -> anyhow::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || work(reporter))
+        .await
+        .map_err(|e| ToolError::Internal(e.to_string()))?
+        .map_err(ToolError::from)
+}
+
+async fn analyze_local(
+    context: ToolContext,
+    request: ProcessLocalRequest,
+) -> Result<ProcessLocalResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+
+    // Check cache first, keyed on the current contents of the files the
+    // request selects
+    let config = handlers::local_config(&request, ProgressReporter::default())?;
+    let fingerprint = context
+        .cache
+        .project_fingerprint(&request.path, config)
+        .await?;
+    let cache_key = ProcessLocalCacheKey::from_request(&request, fingerprint);
+    if let Some(cached) = context.cache.get_process_local(&cache_key).await {
+        return Ok(ProcessLocalResponse {
+            answer: cached.answer,
+            context: if request.include_context.unwrap_or(false) {
+                Some(cached.markdown)
+            } else {
+                None
+            },
+            file_count: cached.file_count,
+            token_count: cached.token_count,
+            processing_time_ms: start.elapsed().as_millis() as u64,
+            llm_tool: cached.llm_tool,
+        });
+    }
+
+    let response = run_blocking(context.reporter, move |reporter| {
+        handlers::process_codebase_sync(request, start, reporter)
+    })
+    .await?;
+
+    let cache_value = ProcessLocalCacheValue {
+        answer: response.answer.clone(),
+        markdown: response.context.clone().unwrap_or_default(),
+        file_count: response.file_count,
+        token_count: response.token_count,
+        llm_tool: response.llm_tool.clone(),
+    };
+    context
+        .cache
+        .set_process_local(cache_key, cache_value)
+        .await;
+
+    Ok(response)
+}
+
+async fn analyze_remote(
+    context: ToolContext,
+    request: ProcessRemoteRequest,
+) -> Result<ProcessRemoteResponse, ToolError> {
+    let start = Instant::now();
+    validate_url(&request.repo_url)?;
+    let _cancel = context.reporter.cancel_on_drop();
+
+    // Check cache first, keyed on the commit the request's ref resolves to
+    let fetch_request = request.clone();
+    let (repo_name, checkout) = run_blocking_step(context.reporter.clone(), move |reporter| {
+        handlers::fetch_remote_sync(&fetch_request, &reporter)
+    })
+    .await?;
+    let cache_key = ProcessRemoteCacheKey::from_request(&request, checkout.commit_id());
+    if let Some(cached) = context.cache.get_process_remote(&cache_key).await {
+        return Ok(ProcessRemoteResponse {
+            answer: cached.answer,
+            context: if request.include_context.unwrap_or(false) {
+                Some(cached.markdown)
+            } else {
+                None
+            },
+            file_count: cached.file_count,
+            token_count: cached.token_count,
+            processing_time_ms: start.elapsed().as_millis() as u64,
+            repo_name: cached.repo_name,
+            llm_tool: cached.llm_tool,
+        });
+    }
+
+    let response = run_blocking_step(context.reporter, move |reporter| {
+        handlers::process_remote_sync(request, repo_name, checkout, start, reporter)
+    })
+    .await?;
+
+    let cache_value = ProcessRemoteCacheValue {
+        answer: response.answer.clone(),
+        markdown: response.context.clone().unwrap_or_default(),
+        file_count: response.file_count,
+        token_count: response.token_count,
+        repo_name: response.repo_name.clone(),
+        llm_tool: response.llm_tool.clone(),
+    };
+    context
+        .cache
+        .set_process_remote(cache_key, cache_value)
+        .await;
+
+    Ok(response)
+}
+
+async fn get_context(
+    context: ToolContext,
+    request: GetContextRequest,
+) -> Result<GetContextResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    run_blocking(context.reporter, move |reporter| {
+        handlers::get_context_sync(request, start, reporter)
+    })
+    .await
+}
+
+async fn file_metadata(
+    context: ToolContext,
+    request: GetFileMetadataRequest,
+) -> Result<GetFileMetadataResponse, ToolError> {
+    validate_path(&request.file_path)?;
+    run_blocking(context.reporter, move |_| {
+        handlers::get_file_metadata_sync(request)
+    })
+    .await
+}
+
+async fn search(
+    context: ToolContext,
+    request: SearchCodebaseRequest,
+) -> Result<SearchCodebaseResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    run_blocking(context.reporter, move |_| {
+        handlers::search_codebase_sync(request, start)
+    })
+    .await
+}
+
+async fn diff(
+    context: ToolContext,
+    request: DiffFilesRequest,
+) -> Result<DiffFilesResponse, ToolError> {
+    validate_path(&request.file1_path)?;
+    validate_path(&request.file2_path)?;
+    run_blocking(context.reporter, move |_| {
+        handlers::diff_files_sync(request)
+    })
+    .await
+}
+
+async fn semantic_search(
+    context: ToolContext,
+    request: SemanticSearchRequest,
+) -> Result<SemanticSearchResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    run_blocking(context.reporter, move |_| {
+        handlers::semantic_search_sync(request, start)
+    })
+    .await
+}
+
+async fn get_imports(
+    context: ToolContext,
+    request: FileGraphRequest,
+) -> Result<ImportGraphResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    let projects = context.cache.projects().clone();
+    run_blocking(context.reporter, move |_| {
+        handlers::get_imports_sync(&projects, request, start, false)
+    })
+    .await
+}
+
+async fn get_importers(
+    context: ToolContext,
+    request: FileGraphRequest,
+) -> Result<ImportGraphResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    let projects = context.cache.projects().clone();
+    run_blocking(context.reporter, move |_| {
+        handlers::get_imports_sync(&projects, request, start, true)
+    })
+    .await
+}
+
+async fn find_callers(
+    context: ToolContext,
+    request: SymbolGraphRequest,
+) -> Result<FindCallersResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    let projects = context.cache.projects().clone();
+    run_blocking(context.reporter, move |_| {
+        handlers::find_callers_sync(&projects, request, start)
+    })
+    .await
+}
+
+async fn find_definition(
+    context: ToolContext,
+    request: FindDefinitionRequest,
+) -> Result<FindDefinitionResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    let projects = context.cache.projects().clone();
+    run_blocking(context.reporter, move |_| {
+        handlers::find_definition_sync(&projects, request, start)
+    })
+    .await
+}
+
+async fn get_type_hierarchy(
+    context: ToolContext,
+    request: SymbolGraphRequest,
+) -> Result<TypeHierarchyResponse, ToolError> {
+    let start = Instant::now();
+    validate_path(&request.path)?;
+    let projects = context.cache.projects().clone();
+    run_blocking(context.reporter, move |_| {
+        handlers::get_type_hierarchy_sync(&projects, request, start)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_tool_has_an_object_schema() {
+        let registry = ToolRegistry::new(Arc::new(McpCache::new()));
+        for tool in registry.tools() {
+            assert_eq!(
+                tool.input_schema.get("type"),
+                Some(&Value::from("object")),
+                "{}",
+                tool.name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_call_validates_arguments() {
+        let registry = ToolRegistry::new(Arc::new(McpCache::new()));
+
+        let unknown = registry
+            .call("missing", Value::Null, ProgressReporter::default())
+            .await;
+        assert!(matches!(unknown, Err(ToolError::UnknownTool(_))));
+
+        let malformed = registry
+            .call(
+                "get_context",
+                serde_json::json!({"path": 1}),
+                ProgressReporter::default(),
+            )
+            .await;
+        assert!(matches!(malformed, Err(ToolError::InvalidParams(_))));
+
+        let traversal = registry
+            .call(
+                "get_imports",
+                serde_json::json!({"path": "../outside", "file": "a.rs"}),
+                ProgressReporter::default(),
+            )
+            .await;
+        assert!(matches!(traversal, Err(ToolError::InvalidParams(_))));
+    }
+}
@@ -0,0 +1,780 @@
+//! Persistent cache of cloned remote repositories
+//!
+//! Every repository URL gets a bare clone under the user cache directory
+//! (`~/.cache/context-creator/repos` on Linux). Later runs fetch only the objects
+//! they are missing, offline runs resolve refs from what was fetched before, and
+//! the least recently used entries are evicted once the cache exceeds its size cap.
+//!
+//! Processes and tasks sharing the cache take an entry's lock file while they
+//! fetch into or copy out of it; eviction skips locked entries. Holders keep
+//! refreshing the lock's modification time, so only locks left behind by a
+//! process that died go stale. Evicted entries are first renamed to a tombstone,
+//! so no process can take the lock of an entry that is being deleted.
+
+use super::{
+    copy_objects, fetch_commit, fetch_error, resolve_local_commit, set_private_permissions,
+    RemoteRepository,
+};
+use crate::utils::error::ContextCreatorError;
+use git2::{ObjectType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+/// Default size cap of the clone cache (2 GiB)
+pub const DEFAULT_MAX_SIZE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Bare repository inside a cache entry
+const GIT_DIR: &str = "repo.git";
+
+/// Metadata file inside a cache entry
+const METADATA_FILE: &str = "entry.json";
+
+/// Lock file inside a cache entry, present while the entry is in use
+const LOCK_FILE: &str = "entry.lock";
+
+/// Locks older than this were left behind by a process that died
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30 * 60);
+
+/// How often a holder refreshes its lock, well within `STALE_LOCK_AGE`
+const LOCK_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Suffix of entry directories renamed for deletion
+const TOMBSTONE_SUFFIX: &str = ".evicted";
+
+/// How long to wait for another process to release an entry
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// Delay between attempts to take a held lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// On-disk cache of bare clones, keyed by repository URL
+#[derive(Debug, Clone)]
+pub struct RemoteCache {
+    root: PathBuf,
+    max_size_bytes: u64,
+    offline: bool,
+}
+
+/// A commit available in the cache
+///
+/// The entry stays locked until this is dropped, so `git_dir` is not changed
+/// or evicted by other processes while it is read. Other fetches of the same
+/// repository wait for it meanwhile.
+#[derive(Debug)]
+pub struct CachedCommit {
+    /// Bare repository holding the commit's objects
+    pub git_dir: PathBuf,
+    /// The resolved commit
+    pub commit: Oid,
+    _lock: EntryLock,
+}
+
+/// Exclusive use of a cache entry, released on drop
+#[derive(Debug)]
+struct EntryLock {
+    path: PathBuf,
+    /// Refreshes the lock until the sender is dropped
+    heartbeat: Option<(Sender<()>, JoinHandle<()>)>,
+}
+
+impl EntryLock {
+    /// Take the lock of `entry`, waiting for other processes to release it
+    fn acquire(entry: &Path) -> Result<Self, ContextCreatorError> {
+        let started = Instant::now();
+        loop {
+            if let Some(lock) = Self::try_acquire(entry)? {
+                return Ok(lock);
+            }
+            if started.elapsed() > LOCK_TIMEOUT {
+                return Err(ContextCreatorError::RemoteFetchError(format!(
+                    "Timed out waiting for the clone cache entry {}; remove {} if no other process is using it",
+                    entry.display(),
+                    entry.join(LOCK_FILE).display()
+                )));
+            }
+            std::thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+
+    /// Take the lock of `entry` if no one holds it
+    fn try_acquire(entry: &Path) -> Result<Option<Self>, ContextCreatorError> {
+        Self::try_acquire_refreshing(entry, LOCK_REFRESH_INTERVAL)
+    }
+
+    /// Like [`try_acquire`](Self::try_acquire), refreshing the lock every
+    /// `interval` while it is held
+    fn try_acquire_refreshing(
+        entry: &Path,
+        interval: Duration,
+    ) -> Result<Option<Self>, ContextCreatorError> {
+        let path = entry.join(LOCK_FILE);
+        // The entry may have been evicted while waiting
+        fs::create_dir_all(entry)?;
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let _ = write!(file, "{}", std::process::id());
+                drop(file);
+                let heartbeat = Self::start_heartbeat(path.clone(), interval);
+                Ok(Some(Self { path, heartbeat }))
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                let is_stale = fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .is_some_and(|age| age > STALE_LOCK_AGE);
+                if is_stale {
+                    warn!("Removing stale clone cache lock {}", path.display());
+                    let _ = fs::remove_file(&path);
+                }
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Refresh the lock at `path` every `interval` in the background while
+    /// it is held
+    fn start_heartbeat(path: PathBuf, interval: Duration) -> Option<(Sender<()>, JoinHandle<()>)> {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let handle = std::thread::Builder::new()
+            .name("clone-cache-lock".to_string())
+            .spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(interval) {
+                    if refresh_lock(&path).is_err() {
+                        warn!("Failed to refresh clone cache lock {}", path.display());
+                    }
+                }
+            })
+            .ok()?;
+        Some((sender, handle))
+    }
+
+    fn stop_heartbeat(&mut self) {
+        if let Some((sender, handle)) = self.heartbeat.take() {
+            drop(sender);
+            let _ = handle.join();
+        }
+    }
+
+    /// Delete the locked entry `entry`
+    ///
+    /// The entry is renamed to a tombstone first: a process waiting for the
+    /// lock recreates an empty entry instead of locking one that is deleted
+    /// under it.
+    fn remove_entry(mut self, entry: &Path) -> Result<(), ContextCreatorError> {
+        self.stop_heartbeat();
+        let tombstone = tombstone_path(entry);
+        fs::rename(entry, &tombstone)?;
+        self.path = tombstone.join(LOCK_FILE);
+        fs::remove_dir_all(&tombstone)?;
+        Ok(())
+    }
+}
+
+impl Drop for EntryLock {
+    fn drop(&mut self) {
+        self.stop_heartbeat();
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Move the modification time of the lock at `path` to now by writing the
+/// holder's process id again
+fn refresh_lock(path: &Path) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().write(true).truncate(true).open(path)?;
+    write!(file, "{}", std::process::id())
+}
+
+/// A unique path next to `entry` to rename it to before deleting it
+fn tombstone_path(entry: &Path) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos())
+        .unwrap_or(0);
+    let mut name = entry.file_name().unwrap_or_default().to_os_string();
+    name.push(format!("-{}-{nanos}{TOMBSTONE_SUFFIX}", std::process::id()));
+    entry.with_file_name(name)
+}
+
+fn is_tombstone(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with(TOMBSTONE_SUFFIX))
+}
+
+/// Bookkeeping stored next to each cached clone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EntryMetadata {
+    url: String,
+    /// Seconds since the Unix epoch
+    last_used: u64,
+}
+
+/// A cache entry considered for eviction
+struct Entry {
+    path: PathBuf,
+    last_used: u64,
+    size: u64,
+}
+
+impl RemoteCache {
+    /// Create a cache rooted at `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            max_size_bytes: DEFAULT_MAX_SIZE_BYTES,
+            offline: false,
+        }
+    }
+
+    /// Default cache location under the user cache directory
+    pub fn default_root() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("context-creator").join("repos"))
+    }
+
+    /// Create a cache at the default location, if the platform has one
+    pub fn at_default_location() -> Option<Self> {
+        Self::default_root().map(Self::new)
+    }
+
+    /// Set the size cap; least recently used entries are evicted above it
+    pub fn with_max_size(mut self, max_size_bytes: u64) -> Self {
+        self.max_size_bytes = max_size_bytes;
+        self
+    }
+
+    /// Only use what is already cached, never touch the network
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Root directory of the cache
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether the cache is in offline mode
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Directory of the cache entry for `repo`
+    ///
+    /// The key is derived from the clone URL, so a ref or subdirectory selection
+    /// shares the entry of its repository.
+    pub fn entry_dir(&self, repo: &RemoteRepository) -> PathBuf {
+        let url = repo.clone_url.trim_end_matches('/');
+        let url = url.strip_suffix(".git").unwrap_or(url);
+
+        // git's object hash is stable across toolchains, unlike `DefaultHasher`
+        let key = Oid::hash_object(ObjectType::Blob, url.as_bytes())
+            .map(|oid| oid.to_string()[..16].to_string())
+            .unwrap_or_default();
+
+        let name: String = repo
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+        self.root.join(format!("{name}-{key}"))
+    }
+
+    /// Make the commit selected by `repo.git_ref` available in the cache
+    ///
+    /// Online, the remote is asked for the ref and only missing objects are
+    /// fetched; if the remote cannot be reached a previously fetched ref is used
+    /// instead. Offline, the ref must already be cached.
+    pub fn fetch(
+        &self,
+        repo: &RemoteRepository,
+        verbose: bool,
+    ) -> Result<CachedCommit, ContextCreatorError> {
+        let entry = self.entry_dir(repo);
+        let git_dir = entry.join(GIT_DIR);
+        let wanted = repo.git_ref.as_deref().unwrap_or("HEAD");
+
+        if self.offline && !git_dir.exists() {
+            return Err(ContextCreatorError::RemoteFetchError(format!(
+                "{} is not in the clone cache; run once without --offline to cache it",
+                repo.display_name()
+            )));
+        }
+        fs::create_dir_all(&entry)?;
+        set_private_permissions(&self.root)?;
+        let lock = EntryLock::acquire(&entry)?;
+
+        let created = !git_dir.exists();
+        let git_repo = if !created {
+            if verbose {
+                eprintln!("📦 Using cached clone: {}", entry.display());
+            }
+            let git_repo = Repository::open_bare(&git_dir).map_err(|e| fetch_error(repo, e))?;
+            // The URL may differ in form (e.g. a trailing `.git`) from the one cached
+            git_repo
+                .remote_set_url("origin", &repo.clone_url)
+                .map_err(|e| fetch_error(repo, e))?;
+            git_repo
+        } else {
+            let git_repo = Repository::init_bare(&git_dir).map_err(|e| fetch_error(repo, e))?;
+            git_repo
+                .remote("origin", &repo.clone_url)
+                .map_err(|e| fetch_error(repo, e))?;
+            git_repo
+        };
+
+        let commit = if self.offline {
+            resolve_local_commit(&git_repo, wanted).ok_or_else(|| {
+                ContextCreatorError::RemoteFetchError(format!(
+                    "'{wanted}' of {} is not in the clone cache; run once without --offline to fetch it",
+                    repo.display_name()
+                ))
+            })?
+        } else if let Some(oid) = cached_commit_id(&git_repo, wanted) {
+            // Commit ids never move, so there is nothing to ask the remote
+            oid
+        } else {
+            let mut remote = git_repo
+                .find_remote("origin")
+                .map_err(|e| fetch_error(repo, e))?;
+            match fetch_commit(&git_repo, &mut remote, repo) {
+                Ok(oid) => oid,
+                Err(e) => match resolve_local_commit(&git_repo, wanted) {
+                    Some(oid) => {
+                        warn!(
+                            "Could not update {} ({}); using cached '{wanted}'",
+                            repo.display_name(),
+                            e.message()
+                        );
+                        oid
+                    }
+                    None => {
+                        // Don't keep an empty entry for a repository that never fetched
+                        if created {
+                            let _ = lock.remove_entry(&entry);
+                        }
+                        return Err(fetch_error(repo, e));
+                    }
+                },
+            }
+        };
+
+        self.touch(&entry, repo)?;
+        if let Err(e) = self.evict(Some(&entry)) {
+            warn!("Failed to evict old clone cache entries: {}", e);
+        }
+
+        Ok(CachedCommit {
+            git_dir,
+            commit,
+            _lock: lock,
+        })
+    }
+
+    /// Like [`fetch`](Self::fetch), then copy the cached objects into
+    /// `target` before other processes may change or evict the entry
+    pub fn fetch_into(
+        &self,
+        repo: &RemoteRepository,
+        target: &Repository,
+        verbose: bool,
+    ) -> Result<Oid, ContextCreatorError> {
+        let cached = self.fetch(repo, verbose)?;
+        copy_objects(target, &cached.git_dir).map_err(|e| fetch_error(repo, e))?;
+        Ok(cached.commit)
+    }
+
+    /// Record that an entry was just used
+    fn touch(&self, entry: &Path, repo: &RemoteRepository) -> Result<(), ContextCreatorError> {
+        let metadata = EntryMetadata {
+            url: repo.clone_url.clone(),
+            last_used: now_secs(),
+        };
+        let json = serde_json::to_string_pretty(&metadata).map_err(|e| {
+            ContextCreatorError::RemoteFetchError(format!("Failed to write cache metadata: {e}"))
+        })?;
+        fs::write(entry.join(METADATA_FILE), json)?;
+        Ok(())
+    }
+
+    /// Total size of all cache entries in bytes
+    pub fn size(&self) -> u64 {
+        self.entries().iter().map(|entry| entry.size).sum()
+    }
+
+    /// Remove least recently used entries until the cache fits its size cap
+    ///
+    /// `keep` is never evicted, so the entry in use survives even if it alone
+    /// exceeds the cap, and neither are entries other processes hold locked.
+    /// Returns the removed entry directories.
+    pub fn evict(&self, keep: Option<&Path>) -> Result<Vec<PathBuf>, ContextCreatorError> {
+        self.remove_tombstones();
+        let mut entries = self.entries();
+        let mut total: u64 = entries.iter().map(|entry| entry.size).sum();
+        entries.sort_by_key(|entry| entry.last_used);
+
+        let mut evicted = Vec::new();
+        for entry in entries {
+            if total <= self.max_size_bytes {
+                break;
+            }
+            if Some(entry.path.as_path()) == keep {
+                continue;
+            }
+            let Ok(Some(lock)) = EntryLock::try_acquire(&entry.path) else {
+                debug!("Clone cache entry {} is in use", entry.path.display());
+                continue;
+            };
+
+            debug!("Evicting clone cache entry {}", entry.path.display());
+            lock.remove_entry(&entry.path)?;
+            total = total.saturating_sub(entry.size);
+            evicted.push(entry.path);
+        }
+
+        Ok(evicted)
+    }
+
+    /// Delete tombstones left behind by evictions that were interrupted
+    fn remove_tombstones(&self) {
+        let Ok(read_dir) = fs::read_dir(&self.root) else {
+            return;
+        };
+        for path in read_dir.filter_map(|dir_entry| dir_entry.ok().map(|e| e.path())) {
+            if is_tombstone(&path) {
+                let _ = fs::remove_dir_all(&path);
+            }
+        }
+    }
+
+    /// All entries currently in the cache
+    fn entries(&self) -> Vec<Entry> {
+        let Ok(read_dir) = fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+
+        read_dir
+            .filter_map(|dir_entry| dir_entry.ok())
+            .map(|dir_entry| dir_entry.path())
+            .filter(|path| !is_tombstone(path) && path.join(GIT_DIR).is_dir())
+            .map(|path| {
+                // Entries without readable metadata are the first to go
+                let last_used = fs::read_to_string(path.join(METADATA_FILE))
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<EntryMetadata>(&json).ok())
+                    .map_or(0, |metadata| metadata.last_used);
+                let size = dir_size(&path);
+                Entry {
+                    path,
+                    last_used,
+                    size,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A full commit id that is already present in the repository
+fn cached_commit_id(git_repo: &Repository, wanted: &str) -> Option<Oid> {
+    if wanted.len() != 40 {
+        return None;
+    }
+    let oid = Oid::from_str(wanted).ok()?;
+    git_repo.find_commit(oid).ok().map(|commit| commit.id())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::remote::parse_remote_url;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    /// Create a working repository with one commit and a bare clone of it
+    fn create_upstream(root: &Path) -> (PathBuf, PathBuf) {
+        let work = root.join("work");
+        fs::create_dir_all(&work).unwrap();
+        fs::write(work.join("lib.rs"), "pub fn one() {}\n").unwrap();
+        git(&work, &["init", "-q", "-b", "main"]);
+        git(&work, &["add", "."]);
+        git(&work, &["commit", "-q", "-m", "one"]);
+
+        let bare = root.join("upstream.git");
+        git(
+            root,
+            &["clone", "-q", "--bare", "work", bare.to_str().unwrap()],
+        );
+        (work, bare)
+    }
+
+    fn head(dir: &Path) -> String {
+        let output = Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    #[test]
+    fn test_entry_dir_is_stable_per_url() {
+        let cache = RemoteCache::new("/cache");
+        let a = parse_remote_url("https://github.com/owner/repo").unwrap();
+        let b = parse_remote_url("https://github.com/owner/repo.git").unwrap();
+        let c = parse_remote_url("https://gitlab.com/owner/repo").unwrap();
+        let with_ref = a.clone().with_ref("v1");
+
+        assert_eq!(cache.entry_dir(&a), cache.entry_dir(&b));
+        assert_eq!(cache.entry_dir(&a), cache.entry_dir(&with_ref));
+        assert_ne!(cache.entry_dir(&a), cache.entry_dir(&c));
+        assert!(cache
+            .entry_dir(&a)
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("repo-"));
+    }
+
+    #[test]
+    fn test_fetch_reuses_entry_and_fetches_new_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let (work, bare) = create_upstream(temp_dir.path());
+        let cache = RemoteCache::new(temp_dir.path().join("cache"));
+        let repo = parse_remote_url(bare.to_str().unwrap()).unwrap();
+
+        let first = cache.fetch(&repo, false).unwrap();
+        assert_eq!(first.commit.to_string(), head(&work));
+        assert!(cache.entry_dir(&repo).join(METADATA_FILE).exists());
+        let first_git_dir = first.git_dir.clone();
+        drop(first);
+
+        // A new upstream commit is picked up by the next fetch
+        fs::write(work.join("two.rs"), "pub fn two() {}\n").unwrap();
+        git(&work, &["add", "."]);
+        git(&work, &["commit", "-q", "-m", "two"]);
+        git(&work, &["push", "-q", bare.to_str().unwrap(), "main"]);
+
+        let second = cache.fetch(&repo, false).unwrap();
+        assert_eq!(second.git_dir, first_git_dir);
+        assert_eq!(second.commit.to_string(), head(&work));
+    }
+
+    #[test]
+    fn test_offline_uses_cached_refs_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let (_work, bare) = create_upstream(temp_dir.path());
+        let cache_root = temp_dir.path().join("cache");
+        let repo = parse_remote_url(bare.to_str().unwrap()).unwrap();
+
+        let offline = RemoteCache::new(&cache_root).offline(true);
+        assert!(offline.fetch(&repo, false).is_err());
+
+        let online = RemoteCache::new(&cache_root)
+            .fetch(&repo, false)
+            .unwrap()
+            .commit;
+
+        // The upstream can disappear once it is cached
+        fs::remove_dir_all(&bare).unwrap();
+        let cached = offline.fetch(&repo, false).unwrap();
+        assert_eq!(cached.commit, online);
+        drop(cached);
+
+        let main = offline
+            .fetch(&repo.clone().with_ref("main"), false)
+            .unwrap();
+        assert_eq!(main.commit, online);
+        drop(main);
+        assert!(offline.fetch(&repo.with_ref("missing"), false).is_err());
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = RemoteCache::new(temp_dir.path()).with_max_size(300);
+
+        for (name, last_used) in [("old", 1), ("mid", 2), ("new", 3)] {
+            let entry = temp_dir.path().join(name);
+            fs::create_dir_all(entry.join(GIT_DIR)).unwrap();
+            fs::write(entry.join(GIT_DIR).join("pack"), vec![0u8; 100]).unwrap();
+            let metadata = EntryMetadata {
+                url: name.to_string(),
+                last_used,
+            };
+            fs::write(
+                entry.join(METADATA_FILE),
+                serde_json::to_string(&metadata).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let keep = temp_dir.path().join("old");
+        let evicted = cache.evict(Some(&keep)).unwrap();
+        assert_eq!(evicted, vec![temp_dir.path().join("mid")]);
+        assert!(keep.exists());
+        assert!(temp_dir.path().join("new").exists());
+        assert!(cache.size() <= 300);
+    }
+
+    #[test]
+    fn test_evict_skips_locked_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = RemoteCache::new(temp_dir.path()).with_max_size(300);
+
+        for (name, last_used) in [("old", 1), ("mid", 2), ("new", 3)] {
+            let entry = temp_dir.path().join(name);
+            fs::create_dir_all(entry.join(GIT_DIR)).unwrap();
+            fs::write(entry.join(GIT_DIR).join("pack"), vec![0u8; 100]).unwrap();
+            let metadata = EntryMetadata {
+                url: name.to_string(),
+                last_used,
+            };
+            fs::write(
+                entry.join(METADATA_FILE),
+                serde_json::to_string(&metadata).unwrap(),
+            )
+            .unwrap();
+        }
+
+        // Another process is reading the least recently used entry
+        let lock = EntryLock::try_acquire(&temp_dir.path().join("old"))
+            .unwrap()
+            .unwrap();
+        assert!(EntryLock::try_acquire(&temp_dir.path().join("old"))
+            .unwrap()
+            .is_none());
+
+        let evicted = cache.evict(None).unwrap();
+        assert_eq!(evicted, vec![temp_dir.path().join("mid")]);
+        assert!(temp_dir.path().join("old").exists());
+
+        drop(lock);
+        assert!(!temp_dir.path().join("old").join(LOCK_FILE).exists());
+    }
+
+    #[test]
+    fn test_evict_deletes_through_tombstones() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = RemoteCache::new(temp_dir.path()).with_max_size(0);
+
+        let entry = temp_dir.path().join("repo");
+        fs::create_dir_all(entry.join(GIT_DIR)).unwrap();
+        fs::write(entry.join(GIT_DIR).join("pack"), vec![0u8; 100]).unwrap();
+        // An eviction that was interrupted after the rename
+        let leftover = tombstone_path(&temp_dir.path().join("gone"));
+        fs::create_dir_all(leftover.join(GIT_DIR)).unwrap();
+
+        assert_eq!(cache.evict(None).unwrap(), vec![entry.clone()]);
+        assert!(!entry.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+
+        // Waiters recreate the entry and lock it afresh
+        let lock = EntryLock::try_acquire(&entry).unwrap().unwrap();
+        assert!(entry.join(LOCK_FILE).exists());
+        drop(lock);
+    }
+
+    #[test]
+    fn test_held_lock_is_refreshed_not_stale() {
+        let temp_dir = TempDir::new().unwrap();
+        let entry = temp_dir.path().join("repo");
+        let lock = EntryLock::try_acquire_refreshing(&entry, Duration::from_millis(20))
+            .unwrap()
+            .unwrap();
+        let path = entry.join(LOCK_FILE);
+
+        // A holder that has been running for longer than the stale age
+        let long_ago = SystemTime::now() - STALE_LOCK_AGE * 2;
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(long_ago)
+            .unwrap();
+        let age = || {
+            fs::metadata(&path)
+                .unwrap()
+                .modified()
+                .unwrap()
+                .elapsed()
+                .unwrap_or_default()
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while age() > STALE_LOCK_AGE && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(
+            age() < STALE_LOCK_AGE,
+            "the heartbeat did not refresh the lock"
+        );
+
+        assert!(EntryLock::try_acquire(&entry).unwrap().is_none());
+        assert!(path.exists());
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_concurrent_fetches_share_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let (work, bare) = create_upstream(temp_dir.path());
+        let cache = RemoteCache::new(temp_dir.path().join("cache"));
+        let repo = parse_remote_url(bare.to_str().unwrap()).unwrap();
+
+        let commits: Vec<Oid> = std::thread::scope(|scope| {
+            let fetches: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| cache.fetch(&repo, false).unwrap().commit))
+                .collect();
+            fetches.into_iter().map(|f| f.join().unwrap()).collect()
+        });
+
+        assert!(commits
+            .iter()
+            .all(|commit| commit.to_string() == head(&work)));
+        assert!(!cache.entry_dir(&repo).join(LOCK_FILE).exists());
+    }
+}
@@ -0,0 +1,1137 @@
+//! Remote repository fetching functionality
+//!
+//! Repositories are cloned with `git2`, so no external `gh` or `git` binary is
+//! required. Any git host reachable over HTTP(S) or SSH is supported, as well as
+//! `file://` URLs and paths to local (bare or non-bare) repositories.
+//!
+//! A branch, tag or commit can be selected together with a subdirectory, either
+//! explicitly or from a pasted web URL such as `.../tree/<ref>/<subdir>`. Only
+//! the selected commit is fetched and only the subdirectory is checked out.
+//!
+//! Fetched objects are kept in a persistent [`RemoteCache`] so that repeated runs
+//! against the same repository only fetch what changed.
+
+pub mod cache;
+
+pub use cache::RemoteCache;
+
+use crate::utils::error::ContextCreatorError;
+use git2::build::CheckoutBuilder;
+use git2::{
+    Cred, CredentialType, Direction, FetchOptions, Oid, Remote, RemoteCallbacks, Repository,
+};
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+use std::fs;
+
+/// Hosts whose repository URLs always have the form `<owner>/<repo>`
+const TWO_SEGMENT_HOSTS: &[&str] = &["github.com", "bitbucket.org"];
+
+/// Path segments followed by a ref in web URLs (e.g. `/tree/main`, GitLab's
+/// `/-/tree/main`); Gitea's `/src/branch/main` names the kind of ref first.
+/// GitLab's `/-/` ends the repository path whatever follows it.
+const WEB_PATH_MARKERS: &[&str] = &["tree", "blob", "commit", "commits", "raw"];
+
+/// Maximum number of times the credentials callback is invoked before giving up
+const MAX_CREDENTIAL_ATTEMPTS: usize = 3;
+
+/// How a remote repository is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteKind {
+    /// `http://` or `https://` URL
+    Http,
+    /// `ssh://`, `git://` or scp-like `git@host:owner/repo.git` URL
+    Ssh,
+    /// `file://` URL
+    File,
+    /// Path to a repository on the local filesystem
+    Local,
+}
+
+/// A parsed remote repository location
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRepository {
+    /// Transport used to reach the repository
+    pub kind: RemoteKind,
+    /// URL or path handed to git2 for cloning
+    pub clone_url: String,
+    /// Host name, if the repository is not local
+    pub host: Option<String>,
+    /// Owner or namespace (may contain `/` for nested GitLab groups)
+    pub owner: Option<String>,
+    /// Repository name without a trailing `.git`
+    pub name: String,
+    /// Branch, tag or commit to check out (default branch when `None`)
+    pub git_ref: Option<String>,
+    /// Subdirectory of the repository to analyze
+    pub subdir: Option<PathBuf>,
+}
+
+impl RemoteRepository {
+    /// Human readable `owner/name` identifier
+    pub fn display_name(&self) -> String {
+        match &self.owner {
+            Some(owner) => format!("{owner}/{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Whether the repository is reached over the network
+    pub fn is_network(&self) -> bool {
+        matches!(self.kind, RemoteKind::Http | RemoteKind::Ssh)
+    }
+
+    /// Select the branch, tag or commit to check out
+    pub fn with_ref(mut self, git_ref: impl Into<String>) -> Self {
+        self.git_ref = Some(git_ref.into());
+        self
+    }
+
+    /// Restrict the analysis to a subdirectory of the repository
+    pub fn with_subdir(mut self, subdir: impl Into<PathBuf>) -> Result<Self, ContextCreatorError> {
+        let subdir = subdir.into();
+        validate_subdir(&subdir)?;
+        self.subdir = Some(subdir).filter(|s| !s.as_os_str().is_empty());
+        Ok(self)
+    }
+}
+
+/// A repository checked out for analysis
+///
+/// The checkout is removed when this value is dropped.
+#[derive(Debug)]
+pub struct ClonedRepository {
+    temp_dir: TempDir,
+    checkout_dir: PathBuf,
+    root: PathBuf,
+    commit: Oid,
+}
+
+impl ClonedRepository {
+    /// Directory to analyze (the checkout, scoped to the requested subdirectory)
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// Root of the checked out working tree
+    pub fn checkout_dir(&self) -> &Path {
+        &self.checkout_dir
+    }
+
+    /// Commit that was checked out
+    pub fn commit_id(&self) -> String {
+        self.commit.to_string()
+    }
+
+    /// Temporary directory holding the checkout
+    pub fn temp_dir(&self) -> &TempDir {
+        &self.temp_dir
+    }
+}
+
+/// Subdirectories must stay inside the repository
+fn validate_subdir(subdir: &Path) -> Result<(), ContextCreatorError> {
+    let escapes = subdir
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return Err(ContextCreatorError::SecurityError(format!(
+            "Repository subdirectory must be a relative path inside the repository: {}",
+            subdir.display()
+        )));
+    }
+    Ok(())
+}
+
+fn unsupported_url(url: &str) -> ContextCreatorError {
+    ContextCreatorError::InvalidConfiguration(format!(
+        "Unsupported repository URL: {url}. Expected an http(s) or ssh URL, \
+         git@host:owner/repo, a file:// URL or a path to a local git repository"
+    ))
+}
+
+/// Strip a trailing `.git` from a repository name
+fn strip_git_suffix(name: &str) -> &str {
+    name.strip_suffix(".git").unwrap_or(name)
+}
+
+/// Parse a repository URL or local path
+///
+/// Accepted forms:
+/// - `https://github.com/owner/repo`, `https://gitlab.com/group/sub/repo.git`, ...
+/// - `ssh://git@host[:port]/owner/repo.git` and `git@host:owner/repo.git`
+/// - `file:///path/to/repo.git`
+/// - a path to an existing local repository (bare or non-bare)
+pub fn parse_remote_url(url: &str) -> Result<RemoteRepository, ContextCreatorError> {
+    let url = url.trim().trim_end_matches('/');
+    if url.is_empty() {
+        return Err(unsupported_url(url));
+    }
+
+    if let Some((scheme, rest)) = url.split_once("://") {
+        return match scheme.to_ascii_lowercase().as_str() {
+            "http" | "https" => parse_network_url(url, scheme, rest, RemoteKind::Http),
+            "ssh" | "git" | "git+ssh" | "ssh+git" => {
+                parse_network_url(url, scheme, rest, RemoteKind::Ssh)
+            }
+            "file" => parse_local_path(url, Path::new(rest), RemoteKind::File),
+            _ => Err(unsupported_url(url)),
+        };
+    }
+
+    let path = Path::new(url);
+    if path.exists() {
+        return parse_local_path(url, path, RemoteKind::Local);
+    }
+
+    parse_scp_like_url(url)
+}
+
+/// Parse `<scheme>://[user@]host[:port]/path` URLs
+fn parse_network_url(
+    url: &str,
+    scheme: &str,
+    rest: &str,
+    kind: RemoteKind,
+) -> Result<RemoteRepository, ContextCreatorError> {
+    // Drop query strings and fragments pasted from a browser
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    let (authority, path) = rest.split_once('/').ok_or_else(|| unsupported_url(url))?;
+
+    let host = authority
+        .rsplit('@')
+        .next()
+        .and_then(|h| h.split(':').next())
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| unsupported_url(url))?
+        .to_ascii_lowercase();
+
+    let (segments, web_path) = split_web_path(&host, path);
+    if segments.len() < 2 {
+        return Err(ContextCreatorError::InvalidConfiguration(format!(
+            "Repository URL must contain owner and repository name: {url}"
+        )));
+    }
+
+    let (name, owner) = segments.split_last().expect("at least two segments");
+    let repo = RemoteRepository {
+        kind,
+        clone_url: format!("{scheme}://{authority}/{}", segments.join("/")),
+        host: Some(host),
+        owner: Some(owner.join("/")),
+        name: strip_git_suffix(name).to_string(),
+        git_ref: None,
+        subdir: None,
+    };
+    apply_web_path(repo, &web_path)
+}
+
+/// Parse scp-like SSH URLs such as `git@gitlab.com:group/repo.git`
+fn parse_scp_like_url(url: &str) -> Result<RemoteRepository, ContextCreatorError> {
+    let (authority, path) = url.split_once(':').ok_or_else(|| unsupported_url(url))?;
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+
+    // A single letter before the colon is a Windows drive, not a host
+    if host.len() < 2 || host.contains('/') || host.contains('\\') {
+        return Err(unsupported_url(url));
+    }
+
+    let host = host.to_ascii_lowercase();
+    let (segments, web_path) = split_web_path(&host, path);
+    if segments.len() < 2 {
+        return Err(ContextCreatorError::InvalidConfiguration(format!(
+            "Repository URL must contain owner and repository name: {url}"
+        )));
+    }
+
+    let (name, owner) = segments.split_last().expect("at least two segments");
+    let repo = RemoteRepository {
+        kind: RemoteKind::Ssh,
+        clone_url: format!("{authority}:{}", segments.join("/")),
+        host: Some(host),
+        owner: Some(owner.join("/")),
+        name: strip_git_suffix(name).to_string(),
+        git_ref: None,
+        subdir: None,
+    };
+    apply_web_path(repo, &web_path)
+}
+
+/// Parse a `file://` URL or a local repository path
+fn parse_local_path(
+    url: &str,
+    path: &Path,
+    kind: RemoteKind,
+) -> Result<RemoteRepository, ContextCreatorError> {
+    if !path.exists() {
+        return Err(ContextCreatorError::InvalidPath(format!(
+            "Repository path does not exist: {}",
+            path.display()
+        )));
+    }
+
+    let path = path.canonicalize().map_err(|e| {
+        ContextCreatorError::InvalidPath(format!("Invalid repository path {url}: {e}"))
+    })?;
+
+    // For `repo/.git` use the name of the working directory
+    let name_path = if path.file_name().is_some_and(|n| n == ".git") {
+        path.parent().unwrap_or(&path)
+    } else {
+        &path
+    };
+    let name = name_path
+        .file_name()
+        .map(|n| strip_git_suffix(&n.to_string_lossy()).to_string())
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| unsupported_url(url))?;
+
+    Ok(RemoteRepository {
+        kind,
+        clone_url: path.to_string_lossy().to_string(),
+        host: None,
+        owner: None,
+        name,
+        git_ref: None,
+        subdir: None,
+    })
+}
+
+/// Split a URL path into the repository segments and the trailing web path
+fn split_web_path<'a>(host: &str, path: &'a str) -> (Vec<&'a str>, Vec<&'a str>) {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let repo_len = if TWO_SEGMENT_HOSTS.contains(&host) {
+        segments.len().min(2)
+    } else if let Some(git_suffix) = segments.iter().position(|s| s.ends_with(".git")) {
+        // A `.git` suffix ends the repository path
+        git_suffix + 1
+    } else {
+        // Repositories have an owner and a name, so a web path starts at the
+        // third segment at the earliest
+        (2..segments.len())
+            .find(|&start| starts_web_path(&segments[start..]))
+            .unwrap_or(segments.len())
+    };
+
+    let (repo, rest) = segments.split_at(repo_len);
+    (repo.to_vec(), rest.to_vec())
+}
+
+/// Whether `segments` are a web path rather than the rest of the repository
+/// path: a marker followed by a ref, or anything after GitLab's `/-/`
+fn starts_web_path(segments: &[&str]) -> bool {
+    match segments {
+        ["-", ..] => true,
+        [marker, _, ..] if WEB_PATH_MARKERS.contains(marker) => true,
+        ["src", "branch" | "tag" | "commit", _, ..] => true,
+        _ => false,
+    }
+}
+
+/// Extract the ref and subdirectory from a web path
+///
+/// Handles `tree/<ref>/<dir>` and `blob/<ref>/<dir>` (GitHub), `-/tree/<ref>/<dir>`
+/// (GitLab), `src/<ref>/<dir>` (Bitbucket), `src/branch/<ref>/<dir>` (Gitea) and
+/// `commit/<sha>`. Other GitLab pages such as `-/merge_requests/12` name no ref
+/// and are ignored. Refs containing `/` cannot be told apart from the
+/// subdirectory here; pass them with `--remote-ref` instead.
+fn apply_web_path(
+    repo: RemoteRepository,
+    web_path: &[&str],
+) -> Result<RemoteRepository, ContextCreatorError> {
+    let mut rest = web_path.iter().copied().skip_while(|s| *s == "-");
+
+    let git_ref = match rest.next() {
+        Some("tree" | "blob" | "commit" | "commits" | "raw") => rest.next(),
+        Some("src") => match rest.next() {
+            Some("branch" | "tag" | "commit") => rest.next(),
+            other => other,
+        },
+        _ => None,
+    };
+
+    let Some(git_ref) = git_ref else {
+        return Ok(repo);
+    };
+
+    let subdir: PathBuf = rest.collect();
+    repo.with_ref(git_ref).with_subdir(subdir)
+}
+
+/// Restrict a directory to the current user (0700)
+fn set_private_permissions(path: &Path) -> Result<(), ContextCreatorError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let metadata = fs::metadata(path).map_err(|e| {
+            ContextCreatorError::RemoteFetchError(format!(
+                "Failed to get directory metadata for {}: {e}",
+                path.display()
+            ))
+        })?;
+        let mut perms = metadata.permissions();
+        perms.set_mode(0o700);
+        fs::set_permissions(path, perms).map_err(|e| {
+            ContextCreatorError::RemoteFetchError(format!(
+                "Failed to set permissions on {}: {e}",
+                path.display()
+            ))
+        })?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// Fetch a remote repository and check it out into a new temporary directory
+///
+/// With a `cache`, objects are fetched into (or, offline, read from) the cached
+/// clone and copied into the checkout, so that evicting the cache entry leaves
+/// the checkout's history intact. Without one, the
+/// repository is fetched straight into the temporary checkout.
+pub fn fetch_repository(
+    repo: &RemoteRepository,
+    cache: Option<&RemoteCache>,
+    verbose: bool,
+) -> Result<ClonedRepository, ContextCreatorError> {
+    let temp_dir = TempDir::new().map_err(|e| {
+        ContextCreatorError::RemoteFetchError(format!("Failed to create temp directory: {e}"))
+    })?;
+
+    // Set secure permissions on temp directory (0700)
+    set_private_permissions(temp_dir.path())?;
+
+    if verbose {
+        eprintln!("📥 Fetching repository: {}", repo.display_name());
+    }
+
+    let checkout_dir = temp_dir.path().join(&repo.name);
+    let commit = clone_repository(repo, cache, &checkout_dir, verbose)?;
+
+    let root = match &repo.subdir {
+        Some(subdir) => checkout_dir.join(subdir),
+        None => checkout_dir.clone(),
+    };
+
+    if verbose {
+        eprintln!("✅ Repository fetched successfully ({commit})");
+    }
+
+    Ok(ClonedRepository {
+        temp_dir,
+        checkout_dir,
+        root,
+        commit,
+    })
+}
+
+/// Build credential callbacks for authenticated clones
+///
+/// SSH remotes use the running ssh-agent; HTTP(S) remotes use the configured
+/// git credential helper. Attempts are capped so a rejected credential does not
+/// loop forever.
+fn remote_callbacks<'a>() -> RemoteCallbacks<'a> {
+    let mut attempts = 0;
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed| {
+        attempts += 1;
+        if attempts > MAX_CREDENTIAL_ATTEMPTS {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            let config = git2::Config::open_default()?;
+            return Cred::credential_helper(&config, url, username_from_url);
+        }
+        Cred::default()
+    });
+    callbacks
+}
+
+fn fetch_error(repo: &RemoteRepository, e: git2::Error) -> ContextCreatorError {
+    ContextCreatorError::RemoteFetchError(format!(
+        "Failed to clone {}: {}",
+        repo.display_name(),
+        e.message()
+    ))
+}
+
+/// Fetch options shared by every fetch of `repo`
+fn fetch_options<'a>(repo: &RemoteRepository, shallow: bool) -> FetchOptions<'a> {
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(remote_callbacks());
+
+    // libgit2 only supports shallow fetches over network transports
+    if shallow && repo.is_network() {
+        options.depth(1);
+    }
+    options
+}
+
+/// Clone a repository into `target_dir` using git2
+///
+/// Only the requested commit is fetched (shallow over the network), and only the
+/// requested subdirectory is written to the working tree.
+fn clone_repository(
+    repo: &RemoteRepository,
+    cache: Option<&RemoteCache>,
+    target_dir: &Path,
+    verbose: bool,
+) -> Result<Oid, ContextCreatorError> {
+    if verbose {
+        let git_ref = repo.git_ref.as_deref().unwrap_or("HEAD");
+        eprintln!("🔄 Cloning {} at {git_ref}", repo.clone_url);
+    }
+
+    let git_repo = Repository::init(target_dir).map_err(|e| fetch_error(repo, e))?;
+    git_repo
+        .remote("origin", &repo.clone_url)
+        .map_err(|e| fetch_error(repo, e))?;
+
+    let (git_repo, commit) = match cache {
+        Some(cache) => {
+            let commit = cache.fetch_into(repo, &git_repo, verbose)?;
+            // Re-open so the object database picks up the copied packs
+            let git_repo = Repository::open(target_dir).map_err(|e| fetch_error(repo, e))?;
+            (git_repo, commit)
+        }
+        None => {
+            let mut remote = git_repo
+                .find_remote("origin")
+                .map_err(|e| fetch_error(repo, e))?;
+            let commit =
+                fetch_commit(&git_repo, &mut remote, repo).map_err(|e| fetch_error(repo, e))?;
+            drop(remote);
+            (git_repo, commit)
+        }
+    };
+
+    checkout_commit(&git_repo, commit, repo.subdir.as_deref())?;
+
+    Ok(commit)
+}
+
+/// Copy the objects of a cached clone, hard-linking them where possible
+/// (the equivalent of `git clone --local`)
+fn copy_objects(git_repo: &Repository, cached_git_dir: &Path) -> Result<(), git2::Error> {
+    let to_error = |e: std::io::Error| git2::Error::from_str(&e.to_string());
+
+    let source = cached_git_dir.join("objects");
+    let target = git_repo.path().join("objects");
+    for entry in WalkDir::new(&source)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+    {
+        let Ok(relative) = entry.path().strip_prefix(&source) else {
+            continue;
+        };
+        // `info/` would carry the cache's own alternates and pack lists
+        if relative.starts_with("info") {
+            continue;
+        }
+        let destination = target.join(relative);
+        if entry.file_type().is_dir() {
+            fs::create_dir_all(&destination).map_err(to_error)?;
+        } else if fs::hard_link(entry.path(), &destination).is_err() {
+            // Across file systems
+            fs::copy(entry.path(), &destination).map_err(to_error)?;
+        }
+    }
+
+    // A shallow cache needs the same boundary, or history walks hit missing parents
+    let shallow = cached_git_dir.join("shallow");
+    if shallow.exists() {
+        fs::copy(&shallow, git_repo.path().join("shallow")).map_err(to_error)?;
+    }
+
+    Ok(())
+}
+
+/// Local ref that stores a fetched remote ref, so it can be resolved offline
+fn local_ref_name(remote_ref: &str) -> String {
+    if remote_ref == "HEAD" {
+        "refs/remotes/origin/HEAD".to_string()
+    } else if let Some(branch) = remote_ref.strip_prefix("refs/heads/") {
+        format!("refs/remotes/origin/{branch}")
+    } else if remote_ref.starts_with("refs/tags/") {
+        remote_ref.to_string()
+    } else {
+        format!(
+            "refs/remotes/origin/{}",
+            remote_ref.strip_prefix("refs/").unwrap_or(remote_ref)
+        )
+    }
+}
+
+/// Resolve a ref against what has already been fetched
+fn resolve_local_commit(git_repo: &Repository, wanted: &str) -> Option<Oid> {
+    let candidates = [
+        local_ref_name(wanted),
+        format!("refs/remotes/origin/{wanted}"),
+        format!("refs/tags/{wanted}"),
+    ];
+
+    candidates
+        .iter()
+        .find_map(|name| git_repo.revparse_single(name).ok())
+        .or_else(|| {
+            // Bare names other than HEAD were covered above; what remains are commit ids
+            (wanted != "HEAD")
+                .then(|| git_repo.revparse_single(wanted).ok())
+                .flatten()
+        })
+        .and_then(|object| object.peel_to_commit().ok())
+        .map(|commit| commit.id())
+}
+
+/// Fetch the commit selected by `repo.git_ref` and return its id
+fn fetch_commit(
+    git_repo: &Repository,
+    remote: &mut Remote,
+    repo: &RemoteRepository,
+) -> Result<Oid, git2::Error> {
+    let wanted = repo.git_ref.as_deref().unwrap_or("HEAD");
+
+    // Resolve branch and tag names against the refs the remote advertises
+    let advertised: Vec<(String, Oid)> = {
+        let connection = remote.connect_auth(Direction::Fetch, Some(remote_callbacks()), None)?;
+        connection
+            .list()?
+            .iter()
+            .map(|head| (head.name().to_string(), head.oid()))
+            .collect()
+    };
+
+    let candidates = [
+        wanted.to_string(),
+        format!("refs/heads/{wanted}"),
+        format!("refs/tags/{wanted}"),
+    ];
+    let matched = candidates
+        .iter()
+        .find_map(|name| advertised.iter().find(|(advertised, _)| advertised == name));
+
+    if let Some((name, oid)) = matched {
+        // Also record the branches HEAD points at, so they resolve offline later
+        let refspecs: Vec<String> = advertised
+            .iter()
+            .filter(|(other, other_oid)| {
+                other == name
+                    || (name == "HEAD" && other.starts_with("refs/heads/") && other_oid == oid)
+            })
+            .map(|(other, _)| format!("+{other}:{}", local_ref_name(other)))
+            .collect();
+        remote.fetch(&refspecs, Some(&mut fetch_options(repo, true)), None)?;
+        return Ok(git_repo.find_object(*oid, None)?.peel_to_commit()?.id());
+    }
+
+    // Not a ref name: try the commit id directly, which most hosts allow
+    if let Ok(oid) = Oid::from_str(wanted) {
+        if wanted.len() == 40
+            && remote
+                .fetch(&[wanted], Some(&mut fetch_options(repo, true)), None)
+                .is_ok()
+        {
+            if let Ok(commit) = git_repo.find_commit(oid) {
+                return Ok(commit.id());
+            }
+        }
+    }
+
+    // Anything but a commit id is a ref the remote does not have; failing
+    // here spares a full fetch of every branch and tag to report a typo
+    if !is_commit_id(wanted) {
+        let mut message = format!("ref '{wanted}' not found");
+        let closest = closest_branches(wanted, &advertised);
+        if !closest.is_empty() {
+            message.push_str(&format!("; did you mean {}?", closest.join(", ")));
+        }
+        return Err(git2::Error::from_str(&message));
+    }
+
+    // Fall back to a full fetch so abbreviated ids resolve too
+    remote.fetch(
+        &[
+            "+refs/heads/*:refs/remotes/origin/*",
+            "+refs/tags/*:refs/tags/*",
+        ],
+        Some(&mut fetch_options(repo, false)),
+        None,
+    )?;
+    let commit = git_repo
+        .revparse_single(wanted)
+        .map_err(|_| git2::Error::from_str(&format!("ref '{wanted}' not found")))?
+        .peel_to_commit()?;
+    Ok(commit.id())
+}
+
+/// Whether `wanted` may be a commit id, full or abbreviated
+fn is_commit_id(wanted: &str) -> bool {
+    (4..=40).contains(&wanted.len()) && wanted.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The advertised branches whose names are closest to `wanted`, for typos
+fn closest_branches(wanted: &str, advertised: &[(String, Oid)]) -> Vec<String> {
+    const MAX_SUGGESTIONS: usize = 3;
+
+    let max_distance = (wanted.chars().count() / 3).max(2);
+    let mut branches: Vec<(usize, &str)> = advertised
+        .iter()
+        .filter_map(|(name, _)| name.strip_prefix("refs/heads/"))
+        .map(|branch| (edit_distance(wanted, branch), branch))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    branches.sort();
+    branches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, branch)| branch.to_string())
+        .collect()
+}
+
+/// Levenshtein distance between `a` and `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Check out `commit`, limited to `subdir` when given
+fn checkout_commit(
+    git_repo: &Repository,
+    commit: Oid,
+    subdir: Option<&Path>,
+) -> Result<(), ContextCreatorError> {
+    let to_error = |e: git2::Error| {
+        ContextCreatorError::RemoteFetchError(format!(
+            "Failed to check out {commit}: {}",
+            e.message()
+        ))
+    };
+
+    let commit_obj = git_repo.find_commit(commit).map_err(to_error)?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+
+    if let Some(subdir) = subdir {
+        let tree = commit_obj.tree().map_err(to_error)?;
+        if tree.get_path(subdir).is_err() {
+            return Err(ContextCreatorError::RemoteFetchError(format!(
+                "Subdirectory '{}' does not exist at {commit}",
+                subdir.display()
+            )));
+        }
+        // Sparse checkout: only materialize the requested subtree
+        checkout.path(subdir.to_string_lossy().replace('\\', "/"));
+    }
+
+    git_repo
+        .checkout_tree(commit_obj.as_object(), Some(&mut checkout))
+        .map_err(to_error)?;
+    git_repo.set_head_detached(commit).map_err(to_error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Test")
+            .env("GIT_AUTHOR_EMAIL", "test@example.com")
+            .env("GIT_COMMITTER_NAME", "Test")
+            .env("GIT_COMMITTER_EMAIL", "test@example.com")
+            .status()
+            .expect("Failed to run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn commit(work: &Path, message: &str) {
+        git(work, &["add", "."]);
+        git(work, &["commit", "-q", "-m", message]);
+    }
+
+    /// Create a bare repository with a tagged first commit, a second commit on
+    /// the default branch and a `feature` branch
+    fn create_bare_repo(root: &Path) -> PathBuf {
+        let work = root.join("work");
+        fs::create_dir_all(work.join("src")).unwrap();
+        fs::create_dir_all(work.join("packages/api")).unwrap();
+        fs::write(work.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(work.join("README.md"), "# Fixture\n").unwrap();
+        fs::write(work.join("packages/api/lib.rs"), "pub fn api() {}\n").unwrap();
+
+        git(&work, &["init", "-q", "-b", "main"]);
+        commit(&work, "initial");
+        git(&work, &["tag", "-a", "v1.0.0", "-m", "release"]);
+
+        fs::write(work.join("src/new.rs"), "pub fn new() {}\n").unwrap();
+        commit(&work, "second");
+
+        git(&work, &["checkout", "-q", "-b", "feature"]);
+        fs::write(work.join("src/feature.rs"), "pub fn feature() {}\n").unwrap();
+        commit(&work, "feature");
+        git(&work, &["checkout", "-q", "main"]);
+
+        let bare = root.join("fixture.git");
+        git(
+            root,
+            &["clone", "-q", "--bare", "work", bare.to_str().unwrap()],
+        );
+        bare
+    }
+
+    fn rev_parse(dir: &Path, rev: &str) -> String {
+        let output = Command::new("git")
+            .args(["rev-parse", rev])
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    #[test]
+    fn test_parse_github_url_https() {
+        let repo = parse_remote_url("https://github.com/rust-lang/rust").unwrap();
+        assert_eq!(repo.kind, RemoteKind::Http);
+        assert_eq!(repo.host.as_deref(), Some("github.com"));
+        assert_eq!(repo.owner.as_deref(), Some("rust-lang"));
+        assert_eq!(repo.name, "rust");
+        assert_eq!(repo.clone_url, "https://github.com/rust-lang/rust");
+    }
+
+    #[test]
+    fn test_parse_github_url_http() {
+        let repo = parse_remote_url("http://github.com/rust-lang/rust").unwrap();
+        assert_eq!(repo.owner.as_deref(), Some("rust-lang"));
+        assert_eq!(repo.name, "rust");
+    }
+
+    #[test]
+    fn test_parse_github_url_trailing_slash() {
+        let repo = parse_remote_url("https://github.com/rust-lang/rust/").unwrap();
+        assert_eq!(repo.owner.as_deref(), Some("rust-lang"));
+        assert_eq!(repo.name, "rust");
+    }
+
+    #[test]
+    fn test_parse_github_url_with_web_path() {
+        let repo = parse_remote_url("https://github.com/rust-lang/rust/tree/master/src").unwrap();
+        assert_eq!(repo.clone_url, "https://github.com/rust-lang/rust");
+        assert_eq!(repo.name, "rust");
+    }
+
+    #[test]
+    fn test_parse_gitlab_nested_groups() {
+        let repo = parse_remote_url("https://gitlab.com/group/subgroup/project.git").unwrap();
+        assert_eq!(repo.host.as_deref(), Some("gitlab.com"));
+        assert_eq!(repo.owner.as_deref(), Some("group/subgroup"));
+        assert_eq!(repo.name, "project");
+        assert_eq!(
+            repo.clone_url,
+            "https://gitlab.com/group/subgroup/project.git"
+        );
+
+        let repo = parse_remote_url("https://gitlab.com/group/project/-/tree/main").unwrap();
+        assert_eq!(repo.clone_url, "https://gitlab.com/group/project");
+    }
+
+    #[test]
+    fn test_parse_repository_paths_with_marker_segments() {
+        // Groups named like web path markers are part of the repository path
+        let repo = parse_remote_url("https://gitlab.example.com/platform/src/api.git").unwrap();
+        assert_eq!(repo.owner.as_deref(), Some("platform/src"));
+        assert_eq!(repo.name, "api");
+        assert_eq!(repo.git_ref, None);
+
+        let repo = parse_remote_url("https://git.example.com/tree/raw/tools").unwrap();
+        assert_eq!(repo.owner.as_deref(), Some("tree/raw"));
+        assert_eq!(repo.name, "tools");
+
+        let repo = parse_remote_url("https://git.example.com/team/commits").unwrap();
+        assert_eq!(repo.owner.as_deref(), Some("team"));
+        assert_eq!(repo.name, "commits");
+
+        // Nothing after a `.git` suffix belongs to the repository path
+        let repo =
+            parse_remote_url("https://gitlab.example.com/platform/api.git/-/tree/main").unwrap();
+        assert_eq!(
+            repo.clone_url,
+            "https://gitlab.example.com/platform/api.git"
+        );
+        assert_eq!(repo.git_ref.as_deref(), Some("main"));
+
+        let repo = parse_remote_url("git@gitlab.example.com:platform/src/api.git").unwrap();
+        assert_eq!(repo.owner.as_deref(), Some("platform/src"));
+        assert_eq!(repo.name, "api");
+    }
+
+    #[test]
+    fn test_parse_bitbucket_and_gitea_urls() {
+        let repo = parse_remote_url("https://bitbucket.org/team/service").unwrap();
+        assert_eq!(repo.owner.as_deref(), Some("team"));
+        assert_eq!(repo.name, "service");
+
+        let repo = parse_remote_url("https://gitea.example.com/org/tool/src/branch/main").unwrap();
+        assert_eq!(repo.host.as_deref(), Some("gitea.example.com"));
+        assert_eq!(repo.clone_url, "https://gitea.example.com/org/tool");
+    }
+
+    #[test]
+    fn test_parse_ssh_urls() {
+        let repo = parse_remote_url("git@gitlab.com:group/project.git").unwrap();
+        assert_eq!(repo.kind, RemoteKind::Ssh);
+        assert_eq!(repo.host.as_deref(), Some("gitlab.com"));
+        assert_eq!(repo.owner.as_deref(), Some("group"));
+        assert_eq!(repo.name, "project");
+        assert_eq!(repo.clone_url, "git@gitlab.com:group/project.git");
+
+        let repo = parse_remote_url("ssh://git@example.com:2222/owner/repo.git").unwrap();
+        assert_eq!(repo.kind, RemoteKind::Ssh);
+        assert_eq!(repo.host.as_deref(), Some("example.com"));
+        assert_eq!(repo.clone_url, "ssh://git@example.com:2222/owner/repo.git");
+    }
+
+    #[test]
+    fn test_parse_local_and_file_urls() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare = temp_dir.path().join("project.git");
+        fs::create_dir(&bare).unwrap();
+
+        let repo = parse_remote_url(bare.to_str().unwrap()).unwrap();
+        assert_eq!(repo.kind, RemoteKind::Local);
+        assert_eq!(repo.name, "project");
+        assert!(repo.host.is_none());
+
+        let repo = parse_remote_url(&format!("file://{}", bare.display())).unwrap();
+        assert_eq!(repo.kind, RemoteKind::File);
+        assert_eq!(repo.name, "project");
+    }
+
+    #[test]
+    fn test_parse_remote_url_invalid() {
+        assert!(parse_remote_url("not-a-url").is_err());
+        assert!(parse_remote_url("ftp://example.com/owner/repo").is_err());
+        assert!(parse_remote_url("https://github.com/").is_err());
+        assert!(parse_remote_url("https://github.com/rust-lang").is_err());
+        assert!(parse_remote_url("file:///definitely/not/here.git").is_err());
+        assert!(parse_remote_url("C:\\repos\\missing").is_err());
+    }
+
+    #[test]
+    fn test_parse_web_url_ref_and_subdir() {
+        let repo =
+            parse_remote_url("https://github.com/owner/repo/tree/v1.2.0/packages/api").unwrap();
+        assert_eq!(repo.clone_url, "https://github.com/owner/repo");
+        assert_eq!(repo.git_ref.as_deref(), Some("v1.2.0"));
+        assert_eq!(repo.subdir, Some(PathBuf::from("packages/api")));
+
+        let repo = parse_remote_url("https://github.com/owner/repo/tree/main").unwrap();
+        assert_eq!(repo.git_ref.as_deref(), Some("main"));
+        assert_eq!(repo.subdir, None);
+
+        let repo =
+            parse_remote_url("https://gitlab.com/group/sub/project/-/tree/develop/src").unwrap();
+        assert_eq!(repo.clone_url, "https://gitlab.com/group/sub/project");
+        assert_eq!(repo.git_ref.as_deref(), Some("develop"));
+        assert_eq!(repo.subdir, Some(PathBuf::from("src")));
+
+        let repo = parse_remote_url("https://bitbucket.org/team/service/src/release/docs").unwrap();
+        assert_eq!(repo.git_ref.as_deref(), Some("release"));
+        assert_eq!(repo.subdir, Some(PathBuf::from("docs")));
+
+        let repo = parse_remote_url("https://gitea.example.com/org/tool/src/tag/v2/cmd").unwrap();
+        assert_eq!(repo.git_ref.as_deref(), Some("v2"));
+        assert_eq!(repo.subdir, Some(PathBuf::from("cmd")));
+
+        let repo = parse_remote_url("https://github.com/owner/repo/commit/abc123").unwrap();
+        assert_eq!(repo.git_ref.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_parse_gitlab_pages_without_ref() {
+        for url in [
+            "https://gitlab.com/group/project/-/merge_requests/12",
+            "https://gitlab.com/group/project/-/issues/3",
+            "https://gitlab.com/group/project/-/pipelines",
+            "https://gitlab.com/group/project/-/tree",
+        ] {
+            let repo = parse_remote_url(url).unwrap();
+            assert_eq!(repo.clone_url, "https://gitlab.com/group/project", "{url}");
+            assert_eq!(repo.git_ref, None, "{url}");
+            assert_eq!(repo.subdir, None, "{url}");
+        }
+
+        let repo = parse_remote_url("https://gitlab.com/group/sub/project/-/issues/3").unwrap();
+        assert_eq!(repo.clone_url, "https://gitlab.com/group/sub/project");
+        assert_eq!(repo.owner.as_deref(), Some("group/sub"));
+
+        let repo = parse_remote_url("git@gitlab.com:group/project/-/merge_requests/12").unwrap();
+        assert_eq!(repo.clone_url, "git@gitlab.com:group/project");
+        assert_eq!(repo.git_ref, None);
+    }
+
+    #[test]
+    fn test_parse_scp_like_url_ref_and_subdir() {
+        let repo = parse_remote_url("git@github.com:owner/repo/tree/v1.2.0/packages/api").unwrap();
+        assert_eq!(repo.clone_url, "git@github.com:owner/repo");
+        assert_eq!(repo.git_ref.as_deref(), Some("v1.2.0"));
+        assert_eq!(repo.subdir, Some(PathBuf::from("packages/api")));
+
+        let repo = parse_remote_url("git@gitlab.example.com:group/sub/project/-/blob/develop/src")
+            .unwrap();
+        assert_eq!(repo.clone_url, "git@gitlab.example.com:group/sub/project");
+        assert_eq!(repo.owner.as_deref(), Some("group/sub"));
+        assert_eq!(repo.git_ref.as_deref(), Some("develop"));
+        assert_eq!(repo.subdir, Some(PathBuf::from("src")));
+
+        assert!(parse_remote_url("git@github.com:owner/repo/tree/main/../../etc").is_err());
+    }
+
+    #[test]
+    fn test_subdir_must_stay_inside_repository() {
+        let repo = parse_remote_url("https://github.com/owner/repo").unwrap();
+        assert!(repo.clone().with_subdir("../etc").is_err());
+        assert!(repo.clone().with_subdir("/etc").is_err());
+        assert!(repo.with_subdir("packages/api").is_ok());
+    }
+
+    #[test]
+    fn test_fetch_local_bare_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare = create_bare_repo(temp_dir.path());
+        let repo = parse_remote_url(bare.to_str().unwrap()).unwrap();
+
+        let cloned = fetch_repository(&repo, None, false).unwrap();
+        assert_eq!(cloned.path(), cloned.checkout_dir());
+        assert_eq!(cloned.commit_id(), rev_parse(&bare, "main"));
+        assert!(cloned.path().join("src/main.rs").exists());
+        assert!(cloned.path().join("src/new.rs").exists());
+        assert!(!cloned.path().join("src/feature.rs").exists());
+    }
+
+    #[test]
+    fn test_fetch_branch_tag_and_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare = create_bare_repo(temp_dir.path());
+        let repo = parse_remote_url(bare.to_str().unwrap()).unwrap();
+
+        let cloned = fetch_repository(&repo.clone().with_ref("feature"), None, false).unwrap();
+        assert!(cloned.path().join("src/feature.rs").exists());
+
+        let cloned = fetch_repository(&repo.clone().with_ref("v1.0.0"), None, false).unwrap();
+        assert_eq!(cloned.commit_id(), rev_parse(&bare, "v1.0.0^{commit}"));
+        assert!(!cloned.path().join("src/new.rs").exists());
+
+        let first = rev_parse(&bare, "main~1");
+        let cloned = fetch_repository(&repo.clone().with_ref(&first[..10]), None, false).unwrap();
+        assert_eq!(cloned.commit_id(), first);
+
+        let result = fetch_repository(&repo.with_ref("does-not-exist"), None, false);
+        assert!(matches!(
+            result,
+            Err(ContextCreatorError::RemoteFetchError(_))
+        ));
+    }
+
+    #[test]
+    fn test_misspelled_ref_fails_without_full_fetch() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare = create_bare_repo(temp_dir.path());
+        let repo = parse_remote_url(bare.to_str().unwrap())
+            .unwrap()
+            .with_ref("mian");
+        let git_repo = Repository::init(temp_dir.path().join("clone")).unwrap();
+        let mut remote = git_repo.remote("origin", &repo.clone_url).unwrap();
+
+        let error = fetch_commit(&git_repo, &mut remote, &repo).unwrap_err();
+        assert_eq!(error.message(), "ref 'mian' not found; did you mean main?");
+        // Nothing was fetched
+        assert_eq!(git_repo.references().unwrap().count(), 0);
+
+        assert!(is_commit_id("abc1"));
+        assert!(!is_commit_id("abc"));
+        assert!(!is_commit_id("deadbeefs"));
+        assert_eq!(edit_distance("mian", "main"), 2);
+        assert_eq!(edit_distance("feture", "feature"), 1);
+    }
+
+    #[test]
+    fn test_fetch_subdirectory_sparse_checkout() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare = create_bare_repo(temp_dir.path());
+        let repo = parse_remote_url(bare.to_str().unwrap())
+            .unwrap()
+            .with_subdir("packages/api")
+            .unwrap();
+
+        let cloned = fetch_repository(&repo, None, false).unwrap();
+        assert_eq!(cloned.path(), cloned.checkout_dir().join("packages/api"));
+        assert!(cloned.path().join("lib.rs").exists());
+        assert!(!cloned.checkout_dir().join("src/main.rs").exists());
+
+        let missing = parse_remote_url(bare.to_str().unwrap())
+            .unwrap()
+            .with_subdir("packages/web")
+            .unwrap();
+        assert!(fetch_repository(&missing, None, false).is_err());
+    }
+
+    #[test]
+    fn test_fetch_with_cache_copies_objects() {
+        let temp_dir = TempDir::new().unwrap();
+        let bare = create_bare_repo(temp_dir.path());
+        let repo = parse_remote_url(bare.to_str().unwrap()).unwrap();
+        let cache = RemoteCache::new(temp_dir.path().join("cache"));
+
+        let cloned = fetch_repository(&repo, Some(&cache), false).unwrap();
+        assert!(cloned.path().join("src/main.rs").exists());
+        assert!(cache.entry_dir(&repo).exists());
+
+        // The checkout keeps its history when the cache entry is evicted
+        fs::remove_dir_all(cache.entry_dir(&repo)).unwrap();
+        let checkout = Repository::open(cloned.checkout_dir()).unwrap();
+        let head = checkout.head().unwrap().target().unwrap();
+        assert_eq!(head, cloned.commit);
+        assert!(checkout.find_commit(head).unwrap().parent(0).is_ok());
+        assert!(!cloned
+            .checkout_dir()
+            .join(".git/objects/info/alternates")
+            .exists());
+    }
+
+    #[test]
+    fn test_fetch_missing_repository_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let empty = temp_dir.path().join("empty");
+        fs::create_dir(&empty).unwrap();
+
+        let repo = parse_remote_url(empty.to_str().unwrap()).unwrap();
+        let result = fetch_repository(&repo, None, false);
+        assert!(matches!(
+            result,
+            Err(ContextCreatorError::RemoteFetchError(_))
+        ));
+    }
+}
@@ -86,6 +86,10 @@ pub enum ContextCreatorError {
     #[error("Parallel processing errors: {error_count} files failed")]
     ParallelProcessingErrors { error_count: usize },
 
+    /// The operation was cancelled by its caller
+    #[error("Operation cancelled")]
+    Cancelled,
+
     /// General I/O errors
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
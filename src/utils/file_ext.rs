@@ -43,6 +43,42 @@ pub enum FileType {
 }
 
 impl FileType {
+    /// Human-readable name of the file type
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FileType::Rust => "Rust",
+            FileType::Python => "Python",
+            FileType::JavaScript => "JavaScript",
+            FileType::TypeScript => "TypeScript",
+            FileType::Go => "Go",
+            FileType::Java => "Java",
+            FileType::Cpp => "C++",
+            FileType::C => "C",
+            FileType::CSharp => "C#",
+            FileType::Ruby => "Ruby",
+            FileType::Php => "PHP",
+            FileType::Swift => "Swift",
+            FileType::Kotlin => "Kotlin",
+            FileType::Scala => "Scala",
+            FileType::Haskell => "Haskell",
+            FileType::Dart => "Dart",
+            FileType::Lua => "Lua",
+            FileType::R => "R",
+            FileType::Julia => "Julia",
+            FileType::Elixir => "Elixir",
+            FileType::Elm => "Elm",
+            FileType::Markdown => "Markdown",
+            FileType::Json => "JSON",
+            FileType::Yaml => "YAML",
+            FileType::Toml => "TOML",
+            FileType::Xml => "XML",
+            FileType::Html => "HTML",
+            FileType::Css => "CSS",
+            FileType::Text => "Text",
+            FileType::Other => "Other",
+        }
+    }
+
     /// Determine file type from path
     pub fn from_path(path: &Path) -> Self {
         let extension = path
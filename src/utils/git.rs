@@ -116,6 +116,66 @@ pub fn get_changed_files<P: AsRef<Path>>(
     Ok(files)
 }
 
+/// Get the files changed between a git reference and another reference or the working tree
+///
+/// Unlike [`get_changed_files`], paths are resolved against `repo_path` rather than
+/// the repository root, and files outside `repo_path` are left out.
+pub fn get_diff_files<P: AsRef<Path>>(
+    repo_path: P,
+    from: &str,
+    to: Option<&str>,
+) -> Result<Vec<PathBuf>> {
+    validate_git_reference(from)?;
+    if let Some(to) = to {
+        validate_git_reference(to)?;
+    }
+
+    let mut args = vec!["--name-only", from];
+    args.extend(to);
+    let stdout = run_git_diff(repo_path.as_ref(), &args)?;
+    let mut files = Vec::new();
+    for line in stdout.lines() {
+        let line = line.trim();
+        if !line.is_empty() {
+            let safe_path = validate_file_path(line)?;
+            files.push(repo_path.as_ref().join(safe_path));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Get a unified diff between a git reference and another reference or the working tree
+///
+/// Only changes under `repo_path` are included, with paths relative to it.
+pub fn get_diff<P: AsRef<Path>>(repo_path: P, from: &str, to: Option<&str>) -> Result<String> {
+    validate_git_reference(from)?;
+    if let Some(to) = to {
+        validate_git_reference(to)?;
+    }
+
+    let mut args = vec![from];
+    args.extend(to);
+    run_git_diff(repo_path.as_ref(), &args)
+}
+
+/// Run `git diff --relative` in `repo_path` and return its output
+fn run_git_diff(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(["diff", "--relative", "--no-color"])
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| anyhow!("Failed to execute git command: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("{}", sanitize_git_error(&stderr)));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 /// Get diff statistics between two git references
 pub fn get_diff_stats<P: AsRef<Path>>(repo_path: P, from: &str, to: &str) -> Result<DiffStats> {
     // Validate git references to prevent command injection
@@ -16,6 +16,8 @@ mod binary_filtering_integration_test;
 mod binary_name_test;
 #[path = "modules/cache_integration_test.rs"]
 mod cache_integration_test;
+#[path = "modules/config_commands_test.rs"]
+mod config_commands_test;
 #[path = "modules/config_precedence_test.rs"]
 mod config_precedence_test;
 #[path = "modules/config_rename_test.rs"]
@@ -147,6 +149,10 @@ mod token_limits_integration_test;
 #[path = "modules/remote_parsing_test.rs"]
 mod remote_parsing_test;
 
+// Git snapshot tests
+#[path = "modules/git_snapshot_test.rs"]
+mod git_snapshot_test;
+
 // Security tests
 #[path = "modules/security_vulnerability_test.rs"]
 mod security_vulnerability_test;
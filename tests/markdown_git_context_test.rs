@@ -99,6 +99,7 @@ fn test_markdown_with_git_context_enabled() {
         enhanced_context: false,
         git_context: true,
         git_context_depth: 3,
+        reporter: Default::default(),
     };
 
     let cache = Arc::new(FileCache::new());
@@ -147,6 +148,7 @@ fn test_markdown_without_git_context() {
         enhanced_context: false,
         git_context: false,
         git_context_depth: 3,
+        reporter: Default::default(),
     };
 
     let cache = Arc::new(FileCache::new());
@@ -194,6 +196,7 @@ fn test_markdown_with_git_context_and_enhanced_context() {
         enhanced_context: true,
         git_context: true,
         git_context_depth: 3,
+        reporter: Default::default(),
     };
 
     let cache = Arc::new(FileCache::new());
@@ -242,6 +245,7 @@ fn test_markdown_git_context_non_git_directory() {
         enhanced_context: false,
         git_context: true,
         git_context_depth: 3,
+        reporter: Default::default(),
     };
 
     let cache = Arc::new(FileCache::new());
@@ -339,6 +339,303 @@ async fn test_process_remote_repo_invalid_url() -> Result<()> {
     Ok(())
 }
 
+/// Test get_context RPC method
+#[tokio::test]
+async fn test_get_context_handler() -> Result<()> {
+    use tempfile::TempDir;
+
+    // Given: A running MCP server and a small codebase
+    let server_handle = start_test_server("127.0.0.1:0").await?;
+    let addr = server_handle.local_addr()?;
+    let temp_dir = TempDir::new()?;
+    std::fs::create_dir_all(temp_dir.path().join("src"))?;
+    std::fs::write(
+        temp_dir.path().join("src/main.rs"),
+        "mod utils;\n\nfn main() {\n    utils::greet();\n}\n",
+    )?;
+    std::fs::write(
+        temp_dir.path().join("src/utils.rs"),
+        "pub fn greet() {\n    println!(\"Hello!\");\n}\n",
+    )?;
+
+    // When: We call get_context without a token budget
+    let client = HttpClientBuilder::default().build(format!("http://{addr}"))?;
+    let request = serde_json::json!({ "path": temp_dir.path() });
+    let response: serde_json::Value = client.request("get_context", rpc_params![request]).await?;
+
+    // Then: The markdown context contains every file, without an LLM call
+    assert_eq!(response["format"], "markdown");
+    assert_eq!(response["file_count"], 2);
+    assert_eq!(response["truncation"]["truncated"], false);
+    let context = response["context"].as_str().unwrap();
+    assert!(context.contains("pub fn greet()"));
+    assert!(context.contains("utils::greet();"));
+    for file in response["files"].as_array().unwrap() {
+        assert_eq!(file["language"], "Rust");
+        assert!(file["token_count"].as_u64().unwrap() > 0);
+    }
+    assert!(response["token_count"].as_u64().unwrap() > 0);
+
+    Ok(())
+}
+
+/// Test get_context reports the files dropped by the token budget
+#[tokio::test]
+async fn test_get_context_truncation() -> Result<()> {
+    use tempfile::TempDir;
+
+    // Given: A running MCP server and a codebase larger than the budget
+    let server_handle = start_test_server("127.0.0.1:0").await?;
+    let addr = server_handle.local_addr()?;
+    let temp_dir = TempDir::new()?;
+    for i in 0..5 {
+        let body = format!("pub fn function_{i}() {{}}\n").repeat(200);
+        std::fs::write(temp_dir.path().join(format!("module_{i}.rs")), body)?;
+    }
+
+    // When: We call get_context with a small budget
+    let client = HttpClientBuilder::default().build(format!("http://{addr}"))?;
+    let request = serde_json::json!({
+        "path": temp_dir.path(),
+        "max_tokens": 3000,
+        "format": "xml",
+    });
+    let response: serde_json::Value = client.request("get_context", rpc_params![request]).await?;
+
+    // Then: The omitted files are listed alongside the selected ones
+    let truncation = &response["truncation"];
+    assert_eq!(truncation["truncated"], true);
+    assert_eq!(truncation["max_tokens"], 3000);
+    assert_eq!(truncation["candidate_count"], 5);
+    let omitted = truncation["omitted_files"].as_array().unwrap().len() as u64;
+    assert!(omitted > 0);
+    assert_eq!(response["file_count"].as_u64().unwrap() + omitted, 5);
+    assert!(response["context"].as_str().unwrap().starts_with('<'));
+
+    Ok(())
+}
+
+/// Test get_context applies semantic options, priorities and a profile
+#[tokio::test]
+async fn test_get_context_semantic_options_and_profile() -> Result<()> {
+    use tempfile::TempDir;
+
+    // Given: A codebase whose configuration file defines a profile
+    let server_handle = start_test_server("127.0.0.1:0").await?;
+    let addr = server_handle.local_addr()?;
+    let temp_dir = TempDir::new()?;
+    std::fs::create_dir_all(temp_dir.path().join("src"))?;
+    std::fs::create_dir_all(temp_dir.path().join("generated"))?;
+    std::fs::write(
+        temp_dir.path().join("src/main.rs"),
+        "mod utils;\n\nfn main() {\n    utils::greet();\n}\n",
+    )?;
+    std::fs::write(
+        temp_dir.path().join("src/utils.rs"),
+        "pub fn greet() {\n    println!(\"Hello!\");\n}\n",
+    )?;
+    std::fs::write(temp_dir.path().join("generated/out.rs"), "fn out() {}\n")?;
+    std::fs::write(
+        temp_dir.path().join(".context-creator.toml"),
+        "[profiles.app]\nignore = [\"generated/**\"]\n",
+    )?;
+
+    // When: We request the imports of main.rs with the profile and a priority
+    let client = HttpClientBuilder::default().build(format!("http://{addr}"))?;
+    let request = serde_json::json!({
+        "path": temp_dir.path(),
+        "include_patterns": ["src/main.rs"],
+        "include_imports": true,
+        "semantic_depth": 2,
+        "priorities": [{ "pattern": "src/utils.rs", "weight": 500.0 }],
+        "profile": "app",
+        "format": "paths",
+    });
+    let response: serde_json::Value = client.request("get_context", rpc_params![request]).await?;
+
+    // Then: The imported module is pulled in and the profile's ignore applies
+    let paths: Vec<&str> = response["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| file["path"].as_str().unwrap())
+        .collect();
+    assert!(paths.contains(&"src/main.rs"));
+    assert!(paths.contains(&"src/utils.rs"));
+    assert!(!paths.iter().any(|path| path.starts_with("generated")));
+    assert!(response["context"]
+        .as_str()
+        .unwrap()
+        .contains("src/utils.rs"));
+
+    // And: An unknown profile is an error
+    let request = serde_json::json!({ "path": temp_dir.path(), "profile": "missing" });
+    let result: Result<serde_json::Value, _> =
+        client.request("get_context", rpc_params![request]).await;
+    assert!(result.unwrap_err().to_string().contains("missing"));
+
+    Ok(())
+}
+
+/// Test that get_context applies the configuration file without a profile
+#[tokio::test]
+async fn test_get_context_applies_config_file_defaults() -> Result<()> {
+    use tempfile::TempDir;
+
+    // Given: A codebase whose configuration file ignores a directory
+    let server_handle = start_test_server("127.0.0.1:0").await?;
+    let addr = server_handle.local_addr()?;
+    let temp_dir = TempDir::new()?;
+    std::fs::create_dir_all(temp_dir.path().join("src"))?;
+    std::fs::create_dir_all(temp_dir.path().join("vendor"))?;
+    std::fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}\n")?;
+    std::fs::write(temp_dir.path().join("vendor/dep.rs"), "fn dep() {}\n")?;
+    std::fs::write(
+        temp_dir.path().join(".context-creator.toml"),
+        "ignore = [\"vendor/**\"]\n\n[profiles.all]\nignore = []\n",
+    )?;
+    let client = HttpClientBuilder::default().build(format!("http://{addr}"))?;
+    let paths = |response: &serde_json::Value| -> Vec<String> {
+        response["files"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|file| file["path"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // When: We request the context without naming a profile
+    let request = serde_json::json!({ "path": temp_dir.path(), "format": "paths" });
+    let response: serde_json::Value = client.request("get_context", rpc_params![request]).await?;
+
+    // Then: The file's ignore patterns apply, as they do on the command line
+    let files = paths(&response);
+    assert!(files.contains(&"src/main.rs".to_string()));
+    assert!(!files.iter().any(|path| path.starts_with("vendor")));
+
+    // And: A profile still replaces the top-level patterns
+    let request =
+        serde_json::json!({ "path": temp_dir.path(), "format": "paths", "profile": "all" });
+    let response: serde_json::Value = client.request("get_context", rpc_params![request]).await?;
+    assert!(paths(&response).contains(&"vendor/dep.rs".to_string()));
+
+    Ok(())
+}
+
+/// Test the dependency-graph RPC methods
+#[tokio::test]
+async fn test_dependency_graph_methods() -> Result<()> {
+    use tempfile::TempDir;
+
+    // Given: A running MCP server and a small TypeScript project
+    let server_handle = start_test_server("127.0.0.1:0").await?;
+    let addr = server_handle.local_addr()?;
+    let temp_dir = TempDir::new()?;
+    std::fs::write(
+        temp_dir.path().join("shapes.ts"),
+        "export interface Shape {\n  area(): number;\n}\n\nexport class Square implements Shape {\n  area() { return 1; }\n}\n",
+    )?;
+    std::fs::write(
+        temp_dir.path().join("index.ts"),
+        "import { Square } from './shapes';\n\nexport function total() {\n  return new Square().area();\n}\n",
+    )?;
+    let client = HttpClientBuilder::default().build(format!("http://{addr}"))?;
+
+    // When: We ask what index.ts imports
+    let request = serde_json::json!({ "path": temp_dir.path(), "file": "index.ts" });
+    let response: serde_json::Value = client.request("get_imports", rpc_params![request]).await?;
+
+    // Then: The edge carries the line and the edge type
+    let edges = response["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0]["to"], "shapes.ts");
+    assert_eq!(edges[0]["kind"], "import");
+    assert_eq!(edges[0]["line"], 1);
+
+    // When: We ask who imports shapes.ts
+    let request = serde_json::json!({ "path": temp_dir.path(), "file": "shapes.ts" });
+    let response: serde_json::Value = client
+        .request("get_importers", rpc_params![request])
+        .await?;
+    assert_eq!(response["edges"][0]["from"], "index.ts");
+
+    // When: We look up a definition and a type hierarchy
+    let request = serde_json::json!({ "path": temp_dir.path(), "symbol": "total" });
+    let response: serde_json::Value = client
+        .request("find_definition", rpc_params![request])
+        .await?;
+    assert_eq!(response["definitions"][0]["file"], "index.ts");
+    assert_eq!(response["definitions"][0]["line"], 3);
+
+    let request = serde_json::json!({ "path": temp_dir.path(), "symbol": "Shape" });
+    let response: serde_json::Value = client
+        .request("get_type_hierarchy", rpc_params![request])
+        .await?;
+    assert_eq!(response["definitions"][0]["kind"], "interface");
+    assert_eq!(response["subtypes"][0]["subtype"], "Square");
+    assert_eq!(response["subtypes"][0]["kind"], "implements");
+
+    // And: An unknown file is an error
+    let request = serde_json::json!({ "path": temp_dir.path(), "file": "missing.ts" });
+    let result: Result<serde_json::Value, _> =
+        client.request("get_imports", rpc_params![request]).await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+/// Test that the project index picks up edits between calls
+#[tokio::test]
+async fn test_graph_methods_see_file_changes() -> Result<()> {
+    use tempfile::TempDir;
+
+    // Given: A running MCP server and a project with a single import
+    let server_handle = start_test_server("127.0.0.1:0").await?;
+    let addr = server_handle.local_addr()?;
+    let temp_dir = TempDir::new()?;
+    std::fs::write(temp_dir.path().join("a.ts"), "export const a = 1;\n")?;
+    std::fs::write(temp_dir.path().join("b.ts"), "export const b = 2;\n")?;
+    let main_path = temp_dir.path().join("main.ts");
+    std::fs::write(&main_path, "import { a } from './a';\n")?;
+    let client = HttpClientBuilder::default().build(format!("http://{addr}"))?;
+    let request = serde_json::json!({ "path": temp_dir.path(), "file": "main.ts" });
+
+    let response: serde_json::Value = client
+        .request("get_imports", rpc_params![request.clone()])
+        .await?;
+    assert_eq!(response["edges"].as_array().unwrap().len(), 1);
+
+    // When: main.ts gains a second import
+    let modified = std::fs::metadata(&main_path)?.modified()?;
+    std::fs::write(
+        &main_path,
+        "import { a } from './a';\nimport { b } from './b';\n",
+    )?;
+    std::fs::File::options()
+        .write(true)
+        .open(&main_path)?
+        .set_modified(modified + std::time::Duration::from_secs(2))?;
+
+    // Then: Queries reflect the edit once the file watcher has seen it
+    let deadline = std::time::Instant::now() + Duration::from_secs(5);
+    let response = loop {
+        let response: serde_json::Value = client
+            .request("get_imports", rpc_params![request.clone()])
+            .await?;
+        if response["edges"].as_array().unwrap().len() == 2 || std::time::Instant::now() > deadline
+        {
+            break response;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    };
+    let edges = response["edges"].as_array().unwrap();
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edges[1]["to"], "b.ts");
+    assert_eq!(edges[1]["line"], 2);
+
+    Ok(())
+}
+
 /// Test get_file_metadata RPC method
 #[tokio::test]
 async fn test_get_file_metadata_handler() -> Result<()> {
@@ -980,6 +1277,31 @@ fn main() {
     Ok(())
 }
 
+/// Test that every tool of the registry is served as a JSON-RPC method
+#[tokio::test]
+async fn test_every_tool_is_a_method() -> Result<()> {
+    use context_creator::mcp_server::cache::McpCache;
+    use context_creator::mcp_server::tools::ToolRegistry;
+    use jsonrpsee::core::ClientError;
+
+    let server = start_test_server("127.0.0.1:0").await?;
+    let client = HttpClientBuilder::default().build(format!("http://{}", server.local_addr()?))?;
+
+    let registry = ToolRegistry::new(std::sync::Arc::new(McpCache::new()));
+    for tool in registry.tools() {
+        // Missing arguments are rejected by the tool, not as an unknown method
+        let result: Result<serde_json::Value, _> = client
+            .request(tool.rpc_method, rpc_params![serde_json::json!({})])
+            .await;
+        match result {
+            Err(ClientError::Call(error)) => assert_eq!(error.code(), -32602, "{}", tool.name),
+            other => panic!("{}: unexpected result {other:?}", tool.name),
+        }
+    }
+
+    Ok(())
+}
+
 // Helper function to start test server
 async fn start_test_server(addr: &str) -> Result<context_creator::mcp_server::ServerHandle> {
     context_creator::mcp_server::start_server(addr).await
@@ -764,12 +764,12 @@ fn test_error_message_quality_for_invalid_combinations() {
     assert!(error_msg3.contains("--prompt, paths, --include, --remote, or --stdin"));
 
     // Test 4: Invalid repo URL (should fail with clear message)
-    let config4 = Config::parse_from(["context-creator", "--remote", "not-a-github-url"]);
+    let config4 = Config::parse_from(["context-creator", "--remote", "not-a-repo-url"]);
 
     let result4 = config4.validate();
     assert!(result4.is_err());
     let error_msg4 = result4.unwrap_err().to_string();
-    assert!(error_msg4.contains("Repository URL must be a GitHub URL"));
+    assert!(error_msg4.contains("Unsupported repository URL"));
 
     // Test 5: Nonexistent path (should fail with clear message)
     let config5 = Config::parse_from([
@@ -0,0 +1,168 @@
+#![cfg(test)]
+
+use assert_cmd::prelude::*;
+use predicates::prelude::*;
+use std::fs;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn context_creator(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.current_dir(dir.path()).env("HOME", dir.path());
+    cmd
+}
+
+#[test]
+fn test_init_writes_tailored_config() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    fs::create_dir_all(temp_dir.path().join("node_modules/lib")).unwrap();
+    fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+    fs::write(temp_dir.path().join("src/index.ts"), "export {};\n").unwrap();
+
+    context_creator(&temp_dir)
+        .arg("init")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Wrote"));
+
+    let written = fs::read_to_string(temp_dir.path().join(".context-creator.toml")).unwrap();
+    assert!(written.contains("# Languages: TypeScript (1 file)"));
+    assert!(written.contains("node_modules/**"));
+    assert!(written.contains("src/index.ts"));
+    assert!(!written.contains("target/**"));
+}
+
+#[test]
+fn test_init_refuses_to_overwrite_without_force() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join(".context-creator.toml");
+    fs::write(&config_path, "# mine\n").unwrap();
+
+    context_creator(&temp_dir)
+        .arg("init")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), "# mine\n");
+
+    context_creator(&temp_dir)
+        .args(["init", "--force"])
+        .assert()
+        .success();
+    assert!(fs::read_to_string(&config_path)
+        .unwrap()
+        .contains("context-creator init"));
+}
+
+#[test]
+fn test_config_show_reports_sources() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(".context-creator.toml"),
+        "ignore = [\"build/**\"]\n\n[defaults]\nmax_tokens = 12345\n",
+    )
+    .unwrap();
+
+    context_creator(&temp_dir)
+        .args(["--max-tokens", "999", "config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            ".context-creator.toml (local file)",
+        ))
+        .stdout(predicate::str::is_match(r"max_tokens\s+999\s+# CLI").unwrap())
+        .stdout(predicate::str::is_match(r"ignore\s+\[build/\*\*\]\s+# local file").unwrap())
+        .stdout(predicate::str::is_match(r"quiet\s+false\s+# built-in default").unwrap());
+}
+
+#[test]
+fn test_config_show_without_config_file() {
+    let temp_dir = TempDir::new().unwrap();
+
+    context_creator(&temp_dir)
+        .args(["config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("# Config file: none"))
+        .stdout(predicate::str::is_match(r"llm_tool\s+gemini\s+# built-in default").unwrap());
+}
+
+#[test]
+fn test_config_show_layers_home_repo_and_nested_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().join("home");
+    let repo = temp_dir.path().join("repo");
+    let app = repo.join("app");
+    fs::create_dir_all(&home).unwrap();
+    fs::create_dir_all(repo.join(".git")).unwrap();
+    fs::create_dir_all(&app).unwrap();
+
+    fs::write(
+        home.join(".context-creator.toml"),
+        "ignore = [\"*.log\"]\n\n[defaults]\nmax_tokens = 100000\nstyle = \"xml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        repo.join(".context-creator.toml"),
+        "ignore = [\"target/**\"]\n\n[defaults]\nmax_tokens = 50000\n",
+    )
+    .unwrap();
+    fs::write(
+        app.join(".context-creator.toml"),
+        "reset = [\"defaults.style\"]\n\n[defaults]\ntrace_imports = true\n",
+    )
+    .unwrap();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.current_dir(&app)
+        .env("HOME", &home)
+        .args(["config", "show"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "# Config files, later ones take precedence:",
+        ))
+        .stdout(
+            predicate::str::is_match(r"max_tokens\s+50000\s+# .*repo/\.context-creator\.toml")
+                .unwrap(),
+        )
+        .stdout(
+            predicate::str::is_match(r"ignore\s+\[\*\.log, target/\*\*\]\s+# home file \+ ")
+                .unwrap(),
+        )
+        .stdout(predicate::str::is_match(r"trace_imports\s+true\s+# local file").unwrap())
+        .stdout(predicate::str::is_match(r"style\s+markdown\s+# built-in default").unwrap());
+}
+
+#[test]
+fn test_config_schema_is_json_schema() {
+    let temp_dir = TempDir::new().unwrap();
+
+    let output = context_creator(&temp_dir)
+        .args(["config", "schema"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(schema["title"], "context-creator configuration");
+    assert!(schema["properties"]["priorities"].is_object());
+}
+
+#[test]
+fn test_misspelled_key_fails_with_location() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(".context-creator.toml"),
+        "[defaults]\nmax_tokens = 1000\nprogres = true\n",
+    )
+    .unwrap();
+
+    context_creator(&temp_dir)
+        .arg(".")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("at line 3, column 1"))
+        .stderr(predicate::str::contains("unknown field `progres`"));
+}
@@ -2,13 +2,14 @@
 
 use clap::Parser;
 use context_creator::cli::Config;
+use context_creator::config::ConfigFile;
 use context_creator::core::walker::WalkOptions;
 use std::fs;
 use tempfile::TempDir;
 
-/// Test that CLI ignore patterns override config file patterns
+/// Test that CLI ignore patterns are appended to config file patterns
 #[test]
-fn test_cli_ignore_patterns_override_config() {
+fn test_cli_ignore_patterns_extend_config() {
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("test-config.toml");
 
@@ -32,21 +33,23 @@ ignore = ["config_*.rs", "config_target/**"]
     // Load configuration from file
     config.load_from_file().unwrap();
 
-    // CLI ignore patterns should take precedence
-    let ignore_patterns = config.get_ignore_patterns();
-    assert_eq!(ignore_patterns, vec!["cli_*.rs", "cli_target/**"]);
+    // CLI ignore patterns come after the config file's
+    let expected = vec![
+        "config_*.rs",
+        "config_target/**",
+        "cli_*.rs",
+        "cli_target/**",
+    ];
+    assert_eq!(config.get_ignore_patterns(), expected);
 
     // Verify in WalkOptions as well
     let walk_options = WalkOptions::from_config(&config).unwrap();
-    assert_eq!(
-        walk_options.ignore_patterns,
-        vec!["cli_*.rs", "cli_target/**"]
-    );
+    assert_eq!(walk_options.ignore_patterns, expected);
 }
 
-/// Test that CLI include patterns override config file patterns
+/// Test that CLI include patterns are appended to config file patterns
 #[test]
-fn test_cli_include_patterns_override_config() {
+fn test_cli_include_patterns_extend_config() {
     let temp_dir = TempDir::new().unwrap();
     let config_path = temp_dir.path().join("test-config.toml");
 
@@ -70,16 +73,13 @@ include = ["config_*.rs", "config_src/**"]
     // Load configuration from file
     config.load_from_file().unwrap();
 
-    // CLI include patterns should take precedence
-    let include_patterns = config.get_include_patterns();
-    assert_eq!(include_patterns, vec!["cli_*.rs", "cli_src/**"]);
+    // CLI include patterns come after the config file's
+    let expected = vec!["config_*.rs", "config_src/**", "cli_*.rs", "cli_src/**"];
+    assert_eq!(config.get_include_patterns(), expected);
 
     // Verify in WalkOptions as well
     let walk_options = WalkOptions::from_config(&config).unwrap();
-    assert_eq!(
-        walk_options.include_patterns,
-        vec!["cli_*.rs", "cli_src/**"]
-    );
+    assert_eq!(walk_options.include_patterns, expected);
 }
 
 /// Test that when no CLI patterns are provided, config file patterns are used
@@ -111,7 +111,7 @@ include = ["config_src/**/*.rs"]
     assert_eq!(include_patterns, vec!["config_src/**/*.rs"]);
 }
 
-/// Test that empty CLI patterns don't override config file patterns
+/// Test that empty CLI patterns don't hide config file patterns
 #[test]
 fn test_empty_cli_patterns_dont_override_config() {
     let temp_dir = TempDir::new().unwrap();
@@ -137,13 +137,19 @@ ignore = ["config_*.rs", "config_target/**"]
     // Load configuration from file
     config.load_from_file().unwrap();
 
-    // CLI ignore patterns are present but empty - this should override config
+    // Empty CLI ignore patterns are appended like any other
     let ignore_patterns = config.get_ignore_patterns();
-    assert_eq!(ignore_patterns, vec!["", "   "]);
+    assert_eq!(
+        ignore_patterns,
+        vec!["config_*.rs", "config_target/**", "", "   "]
+    );
 
     // But WalkOptions should filter out empty patterns
     let walk_options = WalkOptions::from_config(&config).unwrap();
-    assert_eq!(walk_options.ignore_patterns, Vec::<String>::new());
+    assert_eq!(
+        walk_options.ignore_patterns,
+        vec!["config_*.rs", "config_target/**"]
+    );
 }
 
 /// Test precedence with mixed CLI and config patterns
@@ -171,9 +177,12 @@ include = ["config_src/**/*.rs"]
     // Load configuration from file
     config.load_from_file().unwrap();
 
-    // CLI ignore patterns should override config ignore patterns
+    // CLI ignore patterns should extend config ignore patterns
     let ignore_patterns = config.get_ignore_patterns();
-    assert_eq!(ignore_patterns, vec!["cli_*.rs"]);
+    assert_eq!(
+        ignore_patterns,
+        vec!["config_*.rs", "config_target/**", "cli_*.rs"]
+    );
 
     // Config include patterns should be used since no CLI include patterns were provided
     let include_patterns = config.get_include_patterns();
@@ -274,13 +283,25 @@ include = ["config_src/**/*.rs"]
 
     // All CLI options should work together
     assert_eq!(config.get_prompt(), Some("Analyze security".to_string()));
-    assert_eq!(config.get_include_patterns(), vec!["cli_src/**/*.rs"]);
-    assert_eq!(config.get_ignore_patterns(), vec!["cli_*.rs"]);
+    assert_eq!(
+        config.get_include_patterns(),
+        vec!["config_src/**/*.rs", "cli_src/**/*.rs"]
+    );
+    assert_eq!(
+        config.get_ignore_patterns(),
+        vec!["config_*.rs", "cli_*.rs"]
+    );
 
     // Verify in WalkOptions as well
     let walk_options = WalkOptions::from_config(&config).unwrap();
-    assert_eq!(walk_options.include_patterns, vec!["cli_src/**/*.rs"]);
-    assert_eq!(walk_options.ignore_patterns, vec!["cli_*.rs"]);
+    assert_eq!(
+        walk_options.include_patterns,
+        vec!["config_src/**/*.rs", "cli_src/**/*.rs"]
+    );
+    assert_eq!(
+        walk_options.ignore_patterns,
+        vec!["config_*.rs", "cli_*.rs"]
+    );
 }
 
 /// Test that config file patterns work with repo argument
@@ -309,12 +330,15 @@ ignore = ["config_*.rs"]
     // Load configuration from file
     config.load_from_file().unwrap();
 
-    // CLI patterns should take precedence
+    // CLI patterns should extend the config file's
     assert_eq!(
         config.remote,
         Some("https://github.com/owner/repo".to_string())
     );
-    assert_eq!(config.get_ignore_patterns(), vec!["cli_*.rs"]);
+    assert_eq!(
+        config.get_ignore_patterns(),
+        vec!["config_*.rs", "cli_*.rs"]
+    );
 }
 
 /// Test that precedence works with multiple CLI invocations
@@ -348,12 +372,21 @@ include = ["config_src/**/*.rs"]
     // Load configuration from file
     config.load_from_file().unwrap();
 
-    // All CLI patterns should override config patterns
+    // All CLI patterns should be appended to config patterns
     assert_eq!(
         config.get_ignore_patterns(),
-        vec!["cli1_*.rs", "cli2_*.rs", "cli3_*.rs"]
+        vec![
+            "config1_*.rs",
+            "config2_*.rs",
+            "cli1_*.rs",
+            "cli2_*.rs",
+            "cli3_*.rs"
+        ]
+    );
+    assert_eq!(
+        config.get_include_patterns(),
+        vec!["config_src/**/*.rs", "cli_src/**/*.rs"]
     );
-    assert_eq!(config.get_include_patterns(), vec!["cli_src/**/*.rs"]);
 }
 
 /// Test that config file loading doesn't interfere with CLI patterns
@@ -364,7 +397,7 @@ fn test_config_loading_doesnt_interfere_with_cli() {
 
     // Create a config file with different token limits (not patterns)
     let config_content = r#"
-[token_limits]
+[tokens]
 gemini = 500000
 "#;
     fs::write(&config_path, config_content).unwrap();
@@ -387,3 +420,78 @@ gemini = 500000
     assert_eq!(config.get_ignore_patterns(), vec!["cli_*.rs"]);
     assert_eq!(config.get_include_patterns(), vec!["cli_src/**/*.rs"]);
 }
+
+/// Test that --profile selects a profile's patterns and semantic flags
+#[test]
+fn test_profile_selected_from_cli() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("test-config.toml");
+
+    let config_content = r#"
+include = ["**/*"]
+
+[profiles.frontend]
+include = ["web/**/*.ts"]
+ignore = ["web/generated/**"]
+trace_imports = true
+"#;
+    fs::write(&config_path, config_content).unwrap();
+
+    let mut config = Config::parse_from([
+        "context-creator",
+        "--config",
+        config_path.to_str().unwrap(),
+        "--profile",
+        "frontend",
+    ]);
+    config.load_from_file().unwrap();
+
+    assert_eq!(config.get_include_patterns(), vec!["web/**/*.ts"]);
+    assert_eq!(config.get_ignore_patterns(), vec!["web/generated/**"]);
+    assert!(config.trace_imports);
+}
+
+/// Test that an unknown profile is an error rather than silently ignored
+#[test]
+fn test_unknown_profile_is_an_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("test-config.toml");
+    fs::write(&config_path, "[profiles.docs]\ninclude = [\"**/*.md\"]\n").unwrap();
+
+    let mut config = Config::parse_from([
+        "context-creator",
+        "--config",
+        config_path.to_str().unwrap(),
+        "--profile",
+        "backend",
+    ]);
+
+    let err = config.load_from_file().unwrap_err().to_string();
+    assert!(err.contains("Unknown profile 'backend'"));
+}
+
+/// Test that `reset` in a config file drops inherited patterns, not CLI ones
+#[test]
+fn test_reset_keeps_cli_patterns() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::create_dir_all(root.join("service")).unwrap();
+    fs::write(
+        root.join(".context-creator.toml"),
+        "ignore = [\"target/**\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("service/.context-creator.toml"),
+        "reset = [\"ignore\"]\nignore = [\"fixtures/**\"]\n",
+    )
+    .unwrap();
+
+    let mut config = Config::parse_from(["context-creator", "--ignore", "*.snap"]);
+    let layers = ConfigFile::discover_layers(&root.join("service")).unwrap();
+    let merged = ConfigFile::merge_layers(layers.into_iter().map(|layer| layer.config)).unwrap();
+    config.apply_config_file(merged).unwrap();
+
+    assert_eq!(config.get_ignore_patterns(), vec!["fixtures/**", "*.snap"]);
+}
@@ -33,6 +33,8 @@ fn test_cycle_detection_no_cycle() {
         include_patterns: vec![],
         custom_priorities: vec![],
         filter_binary_files: false,
+        snapshot: None,
+        reporter: Default::default(),
     };
 
     let mut files = walk_directory(base_path, walk_options).unwrap();
@@ -78,6 +80,8 @@ fn test_cycle_detection_with_cycle() {
         include_patterns: vec![],
         custom_priorities: vec![],
         filter_binary_files: false,
+        snapshot: None,
+        reporter: Default::default(),
     };
 
     let mut files = walk_directory(base_path, walk_options).unwrap();
@@ -129,6 +133,8 @@ fn test_self_referential_cycle() {
         include_patterns: vec![],
         custom_priorities: vec![],
         filter_binary_files: false,
+        snapshot: None,
+        reporter: Default::default(),
     };
 
     let mut files = walk_directory(base_path, walk_options).unwrap();
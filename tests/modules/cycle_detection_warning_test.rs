@@ -79,6 +79,8 @@ fn test_cycle_detection_warning_output() {
         include_patterns: vec![],
         custom_priorities: vec![],
         filter_binary_files: false,
+        snapshot: None,
+        reporter: Default::default(),
     };
 
     let mut files = walk_directory(base_path, walk_options).unwrap();
@@ -0,0 +1,329 @@
+#![cfg(test)]
+
+use assert_cmd::prelude::*;
+use context_creator::core::semantic::languages::typescript::TypeScriptModuleResolver;
+use context_creator::core::semantic::resolver::ModuleResolver;
+use context_creator::core::snapshot::GitSnapshot;
+use context_creator::core::source_tree::SourceTree;
+use context_creator::core::walker::{walk_directory, WalkOptions};
+use predicates::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", "Test User")
+        .env("GIT_AUTHOR_EMAIL", "test@example.com")
+        .env("GIT_COMMITTER_NAME", "Test User")
+        .env("GIT_COMMITTER_EMAIL", "test@example.com")
+        .status()
+        .expect("Failed to run git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// A repository tagged `v1`, then changed in the working tree and in a second commit
+fn create_repo_with_history() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(
+        root.join("src/main.rs"),
+        "mod utils;\n\nfn main() {\n    utils::helper();\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/utils.rs"),
+        "pub fn helper() {\n    // version one\n}\n",
+    )
+    .unwrap();
+    fs::write(root.join("src/legacy.rs"), "pub fn legacy() {}\n").unwrap();
+    fs::write(root.join("notes.txt"), "old notes\n").unwrap();
+    fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+    fs::write(root.join(".context-creator-ignore"), "notes.txt\n").unwrap();
+
+    git(root, &["init", "-q"]);
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "first"]);
+    git(root, &["tag", "v1"]);
+
+    fs::write(
+        root.join("src/utils.rs"),
+        "pub fn helper() {\n    // version two\n}\n",
+    )
+    .unwrap();
+    git(root, &["rm", "-q", "src/legacy.rs"]);
+    git(root, &["commit", "-q", "-am", "second"]);
+
+    // Uncommitted work must not leak into a snapshot either
+    fs::write(root.join("src/draft.rs"), "pub fn draft() {}\n").unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_at_reads_contents_from_the_revision() {
+    let temp_dir = create_repo_with_history();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(temp_dir.path()).arg("--at").arg("v1");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("version one"))
+        .stdout(predicate::str::contains("version two").not())
+        .stdout(predicate::str::contains("legacy.rs"))
+        .stdout(predicate::str::contains("draft.rs").not());
+}
+
+#[test]
+fn test_at_applies_ignore_files_from_the_snapshot() {
+    let temp_dir = create_repo_with_history();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(temp_dir.path()).arg("--at").arg("v1");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("old notes").not())
+        .stdout(predicate::str::contains("main.rs"));
+}
+
+#[test]
+fn test_at_walk_honours_info_exclude_like_the_working_tree_walk() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("scratch")).unwrap();
+    fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(root.join("src/local.env"), "TOKEN=1\n").unwrap();
+    fs::write(root.join("scratch/notes.md"), "# Notes\n").unwrap();
+    git(&root, &["init", "-q"]);
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "first"]);
+    fs::write(root.join(".git/info/exclude"), "*.env\nscratch/\n").unwrap();
+
+    let walk = |snapshot: Option<Arc<GitSnapshot>>| {
+        let options = WalkOptions {
+            snapshot,
+            ..Default::default()
+        };
+        let mut files: Vec<PathBuf> = walk_directory(&root, options)
+            .unwrap()
+            .into_iter()
+            .map(|file| file.relative_path)
+            .collect();
+        files.sort();
+        files
+    };
+
+    let working_tree = walk(None);
+    assert_eq!(working_tree, vec![PathBuf::from("src/main.rs")]);
+    let snapshot = Arc::new(GitSnapshot::open(&root, "HEAD").unwrap());
+    assert_eq!(walk(Some(snapshot)), working_tree);
+}
+
+#[test]
+fn test_at_with_include_pattern_and_subdirectory() {
+    let temp_dir = create_repo_with_history();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(temp_dir.path().join("src"))
+        .arg("--at")
+        .arg("HEAD~1")
+        .arg("--include")
+        .arg("legacy.rs");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("pub fn legacy()"))
+        .stdout(predicate::str::contains("main.rs").not());
+}
+
+#[test]
+fn test_at_supports_trace_imports() {
+    let temp_dir = create_repo_with_history();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(temp_dir.path())
+        .arg("--at")
+        .arg("v1")
+        .arg("--include")
+        .arg("src/main.rs")
+        .arg("--trace-imports");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("version one"))
+        .stdout(predicate::str::contains("version two").not());
+}
+
+#[test]
+fn test_at_traces_imports_of_files_deleted_from_the_working_tree() {
+    let temp_dir = create_repo_with_history();
+    let root = temp_dir.path();
+    fs::write(root.join("src/main.rs"), "mod utils;\n\nfn main() {}\n").unwrap();
+    fs::remove_file(root.join("src/utils.rs")).unwrap();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(root)
+        .arg("--at")
+        .arg("HEAD")
+        .arg("--include")
+        .arg("src/main.rs")
+        .arg("--trace-imports");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("utils.rs"))
+        .stdout(predicate::str::contains("version two"));
+}
+
+#[test]
+fn test_at_resolves_imports_with_manifests_from_the_revision() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::write(
+        root.join("tsconfig.json"),
+        r#"{ "compilerOptions": { "baseUrl": ".", "paths": { "@lib/*": ["lib/*"] } } }"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("src/app.ts"),
+        "import { format } from '@lib/format';\n\nformat();\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("lib/format.ts"),
+        "export function format() {\n  // committed formatter\n}\n",
+    )
+    .unwrap();
+    git(root, &["init", "-q"]);
+    git(root, &["add", "."]);
+    git(root, &["commit", "-q", "-m", "first"]);
+
+    // Neither the alias nor its target exist in the working tree any more
+    fs::remove_file(root.join("tsconfig.json")).unwrap();
+    fs::remove_file(root.join("lib/format.ts")).unwrap();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(root)
+        .arg("--at")
+        .arg("HEAD")
+        .arg("--include")
+        .arg("src/app.ts")
+        .arg("--trace-imports");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("committed formatter"));
+}
+
+#[test]
+fn test_snapshot_and_working_tree_resolve_imports_independently() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("lib")).unwrap();
+    fs::create_dir_all(root.join("shared")).unwrap();
+    let tsconfig = |target: &str| {
+        format!(
+            r#"{{ "compilerOptions": {{ "baseUrl": ".", "paths": {{ "@lib/*": ["{target}/*"] }} }} }}"#
+        )
+    };
+    fs::write(root.join("tsconfig.json"), tsconfig("lib")).unwrap();
+    fs::write(
+        root.join("src/app.ts"),
+        "import { format } from '@lib/format';\n",
+    )
+    .unwrap();
+    fs::write(root.join("lib/format.ts"), "export function format() {}\n").unwrap();
+    git(&root, &["init", "-q"]);
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "first"]);
+
+    // The working tree maps the alias elsewhere
+    fs::write(root.join("tsconfig.json"), tsconfig("shared")).unwrap();
+    fs::write(
+        root.join("shared/format.ts"),
+        "export function format() {}\n",
+    )
+    .unwrap();
+
+    let snapshot = Arc::new(GitSnapshot::open(&root, "HEAD").unwrap());
+    let at_head = SourceTree::new(Some(snapshot));
+    let working_tree = SourceTree::default();
+    let app = root.join("src/app.ts");
+    let resolve = |tree: &SourceTree| {
+        TypeScriptModuleResolver
+            .resolve_import("@lib/format", &app, &root, tree)
+            .unwrap()
+            .path
+    };
+
+    // Alternating analyses in one process each see their own tree
+    for _ in 0..2 {
+        assert_eq!(resolve(&at_head), root.join("lib/format.ts"));
+        assert_eq!(resolve(&working_tree), root.join("shared/format.ts"));
+    }
+}
+
+#[test]
+fn test_at_with_xml_output() {
+    let temp_dir = create_repo_with_history();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(temp_dir.path())
+        .arg("--at")
+        .arg("v1")
+        .arg("--style")
+        .arg("xml");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("version one"))
+        .stdout(predicate::str::contains("legacy.rs"));
+}
+
+#[test]
+fn test_at_unknown_revision_fails() {
+    let temp_dir = create_repo_with_history();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(temp_dir.path()).arg("--at").arg("does-not-exist");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot resolve 'does-not-exist'"));
+}
+
+#[test]
+fn test_at_outside_repository_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("file.rs"), "fn main() {}\n").unwrap();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg(temp_dir.path()).arg("--at").arg("HEAD");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("not inside a git repository"));
+}
+
+#[test]
+fn test_at_conflicts_with_remote() {
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg("https://github.com/fake/repo")
+        .arg("--at")
+        .arg("main");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "--at cannot be combined with --remote",
+    ));
+}
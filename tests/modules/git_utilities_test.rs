@@ -161,6 +161,35 @@ fn test_get_diff_stats_identical_refs() {
     assert_eq!(stats.deletions, 0, "Should report zero deletions");
 }
 
+/// Test git utility: diff against the working tree
+#[test]
+fn test_get_diff_and_diff_files() {
+    let repo = setup_git_repo_with_history();
+    fs::write(repo.path().join("file2.txt"), "edited content\n").unwrap();
+
+    let diff = context_creator::utils::git::get_diff(repo.path(), "HEAD", None)
+        .expect("Should diff the working tree");
+    assert!(diff.contains("--- a/file2.txt"));
+    assert!(diff.contains("+edited content"));
+
+    let files = context_creator::utils::git::get_diff_files(repo.path(), "HEAD", None)
+        .expect("Should list working tree changes");
+    assert_eq!(files, vec![repo.path().join("file2.txt")]);
+
+    let diff = context_creator::utils::git::get_diff(repo.path(), "HEAD~1", Some("HEAD"))
+        .expect("Should diff two commits");
+    assert!(diff.contains("+modified line"));
+    assert!(!diff.contains("edited content"));
+}
+
+#[test]
+fn test_get_diff_rejects_unsafe_reference() {
+    let repo = setup_git_repo_with_history();
+
+    let result = context_creator::utils::git::get_diff(repo.path(), "HEAD; rm -rf /", None);
+    assert!(result.is_err(), "Should reject unsafe git reference");
+}
+
 /// Test git utility: get repository root
 #[test]
 fn test_get_repository_root_from_root() {
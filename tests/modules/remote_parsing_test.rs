@@ -3,165 +3,102 @@
 use assert_cmd::prelude::*;
 use predicates::prelude::*;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tempfile::TempDir;
 
+fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .expect("Failed to run git");
+    assert!(status.success(), "git {args:?} failed");
+}
+
+/// Create a bare repository that stands in for a remote host
+fn create_bare_repo(root: &Path) -> PathBuf {
+    let work = root.join("work");
+    fs::create_dir_all(work.join("src")).unwrap();
+    fs::write(work.join("src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(work.join("README.md"), "# Mock Repo\n").unwrap();
+    fs::write(work.join("Cargo.toml"), "name = \"mock-repo\"\n").unwrap();
+
+    git(&work, &["init", "-q"]);
+    git(&work, &["add", "."]);
+    git(
+        &work,
+        &[
+            "-c",
+            "user.name=Test User",
+            "-c",
+            "user.email=test@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "initial commit",
+        ],
+    );
+
+    let bare = root.join("mock-repo.git");
+    git(
+        root,
+        &["clone", "-q", "--bare", "work", bare.to_str().unwrap()],
+    );
+    bare
+}
+
 #[test]
-#[cfg_attr(windows, ignore = "Mock gh command has issues on Windows CI")]
-fn test_github_repo_parsing_with_gh() {
+fn test_local_bare_repo_end_to_end() {
     let temp_dir = TempDir::new().unwrap();
-    let mock_bin_dir = temp_dir.path().join("bin");
-    fs::create_dir(&mock_bin_dir).unwrap();
-
-    // Create the mock gh script
-    let _mock_repo_path = temp_dir.path().join("repo");
-
-    #[cfg(unix)]
-    {
-        let mock_gh_path = mock_bin_dir.join("gh");
-        let script = r#"#!/bin/sh
-# Mock gh command
-if [ "$1" = "repo" ] && [ "$2" = "clone" ]; then
-    # The 4th argument is the target directory path (e.g., /tmp/xyz/repo)
-    target_dir="$4"
-    # Simulate cloning by creating the directory structure
-    mkdir -p "$target_dir/src"
-    echo 'fn main() {{}}' > "$target_dir/src/main.rs"
-    echo '# Mock Repo' > "$target_dir/README.md"
-    echo 'name = "mock-repo"' > "$target_dir/Cargo.toml"
-    echo "Cloned successfully"
-    exit 0
-fi
-if [ "$1" = "--version" ]; then
-    echo "gh version 2.40.0"
-    exit 0
-fi
-exit 1
-"#;
-        fs::write(&mock_gh_path, script).unwrap();
-
-        // Make the mock script executable
-        use std::os::unix::fs::PermissionsExt;
-        fs::set_permissions(&mock_gh_path, fs::Permissions::from_mode(0o755)).unwrap();
-    }
-
-    #[cfg(windows)]
-    {
-        // On Windows, create gh.cmd which will be found by Command::new("gh")
-        let script = r#"@echo off
-echo Arguments: %* >> gh_debug.log
-if "%1" == "--version" (
-    echo gh version 2.40.0
-    exit /b 0
-)
-if "%1" == "repo" if "%2" == "clone" (
-    rem Extract the target directory from arguments
-    rem Arguments are: repo clone fake/repo C:\path\to\temp\repo -- --depth 1
-    set "target_dir=%~4"
-    echo Target dir: %target_dir% >> gh_debug.log
-    
-    rem Create the directory structure
-    if not exist "%target_dir%" mkdir "%target_dir%"
-    if not exist "%target_dir%\src" mkdir "%target_dir%\src"
-    
-    rem Create mock files
-    echo fn main() {} > "%target_dir%\src\main.rs"
-    echo # Mock Repo > "%target_dir%\README.md"
-    echo name = "mock-repo" > "%target_dir%\Cargo.toml"
-    
-    echo Cloned successfully
-    exit /b 0
-)
-echo Command not recognized >> gh_debug.log
-exit /b 1
-"#;
-        fs::write(mock_bin_dir.join("gh.cmd"), script).unwrap();
-    }
+    let bare = create_bare_repo(temp_dir.path());
 
     let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg(&bare)
+        .arg("--remote-cache-dir")
+        .arg(temp_dir.path().join("cache"));
 
-    // Prepend the mock bin directory to the PATH
-    let original_path = std::env::var("PATH").unwrap_or_default();
-    #[cfg(windows)]
-    let new_path = format!("{};{}", mock_bin_dir.display(), original_path);
-    #[cfg(not(windows))]
-    let new_path = format!("{}:{}", mock_bin_dir.display(), original_path);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("main.rs"))
+        .stdout(predicate::str::contains("README.md"));
+}
 
-    cmd.env("PATH", new_path);
-    cmd.arg("--remote").arg("https://github.com/fake/repo");
+#[test]
+fn test_file_url_end_to_end() {
+    let temp_dir = TempDir::new().unwrap();
+    let bare = create_bare_repo(temp_dir.path());
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg(format!("file://{}", bare.display()))
+        .arg("--remote-cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .arg("--include")
+        .arg("**/*.rs");
 
     cmd.assert()
         .success()
         .stdout(predicate::str::contains("main.rs"))
-        .stdout(predicate::str::contains("README.md"));
+        .stdout(predicate::str::contains("README.md").not());
 }
 
 #[test]
-#[ignore = "Git fallback test has issues with mock script"]
-fn test_github_repo_parsing_fallback_to_git() {
+fn test_clone_does_not_need_git_or_gh_binaries() {
     let temp_dir = TempDir::new().unwrap();
-    let mock_bin_dir = temp_dir.path().join("bin");
-    fs::create_dir(&mock_bin_dir).unwrap();
-
-    // Create mock git script (no gh available)
-    let mock_git_path = mock_bin_dir.join("git");
-    let _mock_repo_path = temp_dir.path().join("repo");
-
-    #[cfg(unix)]
-    {
-        let script = r#"#!/bin/sh
-# Mock git command
-if [ "$1" = "clone" ]; then
-    # For git clone, the last argument is the target directory
-    # Find the last argument
-    for last; do true; done
-    target_dir="$last"
-    # Simulate cloning by creating a directory with files
-    mkdir -p "$target_dir/src"
-    echo 'fn main() {{}}' > "$target_dir/src/main.rs"
-    echo '# Mock Repo' > "$target_dir/README.md"
-    echo Cloned successfully
-    exit 0
-fi
-if [ "$1" = "--version" ]; then
-    echo "git version 2.40.0"
-    exit 0
-fi
-exit 1
-"#;
-        fs::write(&mock_git_path, script).unwrap();
-
-        use std::os::unix::fs::PermissionsExt;
-        fs::set_permissions(&mock_git_path, fs::Permissions::from_mode(0o755)).unwrap();
-    }
-
-    #[cfg(windows)]
-    {
-        let script = r#"@echo off
-if "%1" == "clone" (
-    rem For git clone, the last argument is the target directory
-    rem Get the last argument using a simple approach
-    for %%a in (%*) do set "target_dir=%%a"
-    mkdir "%target_dir%\src" 2>nul
-    echo fn main() {} > "%target_dir%\src\main.rs"
-    echo # Mock Repo > "%target_dir%\README.md"
-    echo Cloned successfully
-    exit /b 0
-)
-if "%1" == "--version" (
-    echo git version 2.40.0
-    exit /b 0
-)
-exit /b 1
-"#;
-        fs::write(mock_git_path.with_extension("bat"), script).unwrap();
-    }
+    let bare = create_bare_repo(temp_dir.path());
+    let empty_bin_dir = temp_dir.path().join("bin");
+    fs::create_dir(&empty_bin_dir).unwrap();
 
     let mut cmd = Command::cargo_bin("context-creator").unwrap();
 
-    // Set PATH with only our mock bin (no gh available)
-    cmd.env("PATH", mock_bin_dir.display().to_string());
-    cmd.arg("--remote").arg("https://github.com/fake/repo");
+    // Set PATH to empty directory (no commands available)
+    cmd.env("PATH", empty_bin_dir.display().to_string());
+    cmd.arg("--remote")
+        .arg(&bare)
+        .arg("--remote-cache-dir")
+        .arg(temp_dir.path().join("cache"));
 
     cmd.assert()
         .success()
@@ -169,48 +106,136 @@ exit /b 1
 }
 
 #[test]
-fn test_invalid_repo_url() {
+fn test_remote_ref_and_subdir() {
+    let temp_dir = TempDir::new().unwrap();
+    let bare = create_bare_repo(temp_dir.path());
+    git(
+        temp_dir.path(),
+        &["--git-dir", bare.to_str().unwrap(), "tag", "v1.0.0", "HEAD"],
+    );
+
     let mut cmd = Command::cargo_bin("context-creator").unwrap();
-    cmd.arg("--remote").arg("https://gitlab.com/fake/repo");
+    cmd.arg("--remote")
+        .arg(&bare)
+        .arg("--remote-cache-dir")
+        .arg(temp_dir.path().join("cache"))
+        .arg("--remote-ref")
+        .arg("v1.0.0")
+        .arg("--remote-subdir")
+        .arg("src");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("main.rs"))
+        .stdout(predicate::str::contains("README.md").not())
+        .stdout(predicate::str::contains("Cargo.toml").not());
+}
+
+#[test]
+fn test_remote_ref_requires_remote() {
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote-ref").arg("main").arg(".");
 
     cmd.assert().failure().stderr(predicate::str::contains(
-        "Repository URL must be a GitHub URL",
+        "--remote-ref and --remote-subdir require --remote",
     ));
 }
 
 #[test]
-fn test_repo_and_directory_mutually_exclusive_cli() {
+fn test_offline_uses_cached_clone() {
+    let temp_dir = TempDir::new().unwrap();
+    let bare = create_bare_repo(temp_dir.path());
+    let cache_dir = temp_dir.path().join("cache");
+
+    // Offline before anything was cached fails
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg(&bare)
+        .arg("--remote-cache-dir")
+        .arg(&cache_dir)
+        .arg("--offline");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("not in the clone cache"));
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg(&bare)
+        .arg("--remote-cache-dir")
+        .arg(&cache_dir);
+    cmd.assert().success();
+
+    // Once cached, the upstream is no longer needed
+    fs::rename(&bare, temp_dir.path().join("moved.git")).unwrap();
+    fs::create_dir(&bare).unwrap();
+
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg(&bare)
+        .arg("--remote-cache-dir")
+        .arg(&cache_dir)
+        .arg("--offline");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("main.rs"));
+}
+
+#[test]
+fn test_offline_conflicts_with_no_remote_cache() {
     let mut cmd = Command::cargo_bin("context-creator").unwrap();
     cmd.arg("--remote")
         .arg("https://github.com/fake/repo")
-        .arg(".");
+        .arg("--offline")
+        .arg("--no-remote-cache");
 
     cmd.assert().failure().stderr(predicate::str::contains(
-        "Cannot specify both --remote and local paths",
+        "--offline requires the clone cache",
     ));
 }
 
 #[test]
-fn test_no_git_or_gh_available() {
+fn test_invalid_repo_url() {
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote").arg("ftp://example.com/fake/repo");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unsupported repository URL"));
+}
+
+#[test]
+fn test_missing_local_repo_fails() {
     let temp_dir = TempDir::new().unwrap();
-    let empty_bin_dir = temp_dir.path().join("bin");
-    fs::create_dir(&empty_bin_dir).unwrap();
+    let not_a_repo = temp_dir.path().join("plain-dir");
+    fs::create_dir(&not_a_repo).unwrap();
 
     let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg(&not_a_repo)
+        .arg("--remote-cache-dir")
+        .arg(temp_dir.path().join("cache"));
 
-    // Set PATH to empty directory (no commands available)
-    cmd.env("PATH", empty_bin_dir.display().to_string());
-    cmd.arg("--remote").arg("https://github.com/fake/repo");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to clone"));
+}
+
+#[test]
+fn test_repo_and_directory_mutually_exclusive_cli() {
+    let mut cmd = Command::cargo_bin("context-creator").unwrap();
+    cmd.arg("--remote")
+        .arg("https://github.com/fake/repo")
+        .arg(".");
 
     cmd.assert().failure().stderr(predicate::str::contains(
-        "Neither gh CLI nor git is available",
+        "Cannot specify both --remote and local paths",
     ));
 }
 
 #[test]
-#[ignore = "Real repository test - requires network and git/gh CLI"]
+#[ignore = "Real repository test - requires network"]
 fn test_parse_own_repository() {
-    // This test requires gh or git to be available and network access
+    // This test requires network access
     // Use our own repository as the test case
     let mut cmd = Command::cargo_bin("context-creator").unwrap();
     cmd.arg("--remote")
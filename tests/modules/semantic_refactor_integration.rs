@@ -163,6 +163,8 @@ fn test_new_modular_architecture_works_together() {
         trace_imports: true,
         include_types: true,
         include_functions: true,
+        include_paths: Vec::new(),
+        reporter: Default::default(),
     };
 
     let file_paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
@@ -432,6 +434,8 @@ fn test_modules_work_together_seamlessly() {
                 trace_imports: true,
                 include_types: true,
                 include_functions: true,
+                include_paths: Vec::new(),
+                reporter: Default::default(),
             },
             &valid_files,
         )
@@ -125,6 +125,7 @@ fn test_cache_with_parser_pool() {
 #[test]
 fn test_path_validation() {
     use context_creator::core::semantic::path_validator::validate_import_path;
+    use context_creator::core::source_tree::SourceTree;
     use std::fs;
     use tempfile::TempDir;
 
@@ -138,24 +139,55 @@ fn test_path_validation() {
     fs::write(base_dir.join("tests/test.rs"), "").unwrap();
 
     // Valid paths within project
-    assert!(validate_import_path(base_dir, &base_dir.join("src/lib.rs")).is_ok());
-    assert!(validate_import_path(base_dir, &base_dir.join("tests/test.rs")).is_ok());
+    assert!(validate_import_path(
+        base_dir,
+        &base_dir.join("src/lib.rs"),
+        &SourceTree::default()
+    )
+    .is_ok());
+    assert!(validate_import_path(
+        base_dir,
+        &base_dir.join("tests/test.rs"),
+        &SourceTree::default()
+    )
+    .is_ok());
 
     // Invalid paths - outside project
-    assert!(validate_import_path(base_dir, std::path::Path::new("/etc/passwd")).is_err());
-    assert!(validate_import_path(base_dir, &base_dir.join("../../../etc/passwd")).is_err());
+    assert!(validate_import_path(
+        base_dir,
+        std::path::Path::new("/etc/passwd"),
+        &SourceTree::default()
+    )
+    .is_err());
+    assert!(validate_import_path(
+        base_dir,
+        &base_dir.join("../../../etc/passwd"),
+        &SourceTree::default()
+    )
+    .is_err());
 
     // Invalid paths - absolute paths outside project
-    assert!(validate_import_path(base_dir, std::path::Path::new("/tmp/file.rs")).is_err());
+    assert!(validate_import_path(
+        base_dir,
+        std::path::Path::new("/tmp/file.rs"),
+        &SourceTree::default()
+    )
+    .is_err());
 
     // Edge case - symlinks should be resolved
     fs::write(base_dir.join("lib.rs"), "").unwrap();
-    assert!(validate_import_path(base_dir, &base_dir.join("./src/../lib.rs")).is_ok());
+    assert!(validate_import_path(
+        base_dir,
+        &base_dir.join("./src/../lib.rs"),
+        &SourceTree::default()
+    )
+    .is_ok());
 }
 
 #[test]
 fn test_path_traversal_attack_prevention() {
     use context_creator::core::semantic::path_validator::validate_import_path;
+    use context_creator::core::source_tree::SourceTree;
     use std::fs;
 
     let temp_dir = TempDir::new().unwrap();
@@ -178,7 +210,7 @@ fn test_path_traversal_attack_prevention() {
     for attack in attacks {
         let path = base_dir.join(attack);
         assert!(
-            validate_import_path(base_dir, &path).is_err(),
+            validate_import_path(base_dir, &path, &SourceTree::default()).is_err(),
             "Path traversal attack should be blocked: {attack}"
         );
     }
@@ -7,6 +7,7 @@
 
 use context_creator::cli::Config;
 use context_creator::core::cache::FileCache;
+use context_creator::core::semantic::{get_analyzer_for_file, SemanticContext};
 use context_creator::core::walker::{walk_directory, WalkOptions};
 use std::collections::HashMap;
 use std::fs;
@@ -815,6 +816,856 @@ export default {
     assert!(file_paths.iter().any(|p| p.ends_with("config.ts")));
 }
 
+#[test]
+fn test_typescript_tsconfig_path_aliases() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::create_dir_all(root.join("app/src/components")).unwrap();
+    fs::create_dir_all(root.join("app/src/utils")).unwrap();
+    fs::create_dir_all(root.join("shared/src")).unwrap();
+
+    // Aliases come from a base configuration the app extends
+    fs::write(
+        root.join("tsconfig.base.json"),
+        r#"{
+    "compilerOptions": {
+        // Relative to this file
+        "baseUrl": ".",
+        "paths": {
+            "@/*": ["app/src/*"],
+            "~app/*": ["app/src/utils/*"],
+        },
+    },
+}"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("app/tsconfig.json"),
+        r#"{
+    "extends": "../tsconfig.base.json",
+    "references": [{ "path": "../shared" }]
+}"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("shared/tsconfig.json"),
+        r#"{ "compilerOptions": { "baseUrl": "src" } }"#,
+    )
+    .unwrap();
+
+    fs::write(
+        root.join("app/src/page.tsx"),
+        r#"
+import { Button } from '@/components/Button';
+import { format } from '~app/format';
+import { AppService } from './app.service';
+import { money } from 'currency';
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("app/src/components/Button.tsx"),
+        "export const Button = () => null;\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("app/src/utils/format.ts"),
+        "export const format = (s: string) => s;\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("app/src/app.service.ts"),
+        "export class AppService {}\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("shared/src/currency.ts"),
+        "export const money = 1;\n",
+    )
+    .unwrap();
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["app/src/page.tsx".to_string()]),
+        trace_imports: true,
+        semantic_depth: 2,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "app/src/components/Button.tsx",
+        "app/src/utils/format.ts",
+        "app/src/app.service.ts",
+        "shared/src/currency.ts",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+}
+
+#[test]
+fn test_javascript_local_module_shadowing_node_builtin() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    fs::create_dir_all(root.join("events")).unwrap();
+    fs::create_dir_all(root.join("node_modules/@tanstack/query")).unwrap();
+
+    fs::write(
+        root.join("package.json"),
+        r#"{ "dependencies": { "@tanstack/query": "^5.0.0" } }"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("node_modules/@tanstack/query/index.js"),
+        "module.exports = {};\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("app.js"),
+        r#"
+import { bus } from 'events';
+import { readFile } from 'node:fs';
+import { useQuery } from '@tanstack/query';
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("service.ts"),
+        r#"
+import { Bus } from 'events';
+
+export function publish(bus: Bus) {}
+"#,
+    )
+    .unwrap();
+    fs::write(
+        root.join("events/index.js"),
+        "export const bus = {};\nexport class Bus {}\n",
+    )
+    .unwrap();
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["app.js".to_string()]),
+        trace_imports: true,
+        semantic_depth: 2,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    assert!(
+        expanded_files.contains_key(&root.join("events/index.js")),
+        "The local events module should be traced, got {:?}",
+        expanded_files.keys()
+    );
+    assert!(
+        !expanded_files
+            .keys()
+            .any(|path| path.components().any(|c| c.as_os_str() == "node_modules")),
+        "Dependencies should stay external"
+    );
+    let service = root.join("service.ts");
+    let analyzer = get_analyzer_for_file(&service).unwrap().unwrap();
+    let context = SemanticContext::new(service.clone(), root.to_path_buf(), 2);
+    let content = fs::read_to_string(&service).unwrap();
+    let analysis = analyzer.analyze_file(&service, &content, &context).unwrap();
+    let bus = analysis
+        .type_references
+        .iter()
+        .find(|type_ref| type_ref.name == "Bus")
+        .expect("Bus should be referenced");
+    assert!(
+        !bus.is_external && bus.external_package.is_none(),
+        "Types from the local events module should stay internal, got {bus:?}"
+    );
+}
+
+#[test]
+fn test_workspace_cross_package_imports() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+
+    // Cargo workspace
+    write("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n");
+    write("crates/app/Cargo.toml", "[package]\nname = \"app\"\n");
+    write(
+        "crates/app/src/main.rs",
+        "use shared_core::models::User;\n\nfn main() {\n    let _user = User::default();\n}\n",
+    );
+    write(
+        "crates/shared-core/Cargo.toml",
+        "[package]\nname = \"shared-core\"\n",
+    );
+    write("crates/shared-core/src/lib.rs", "pub mod models;\n");
+    write(
+        "crates/shared-core/src/models.rs",
+        "#[derive(Default)]\npub struct User;\n",
+    );
+
+    // npm workspace
+    write(
+        "package.json",
+        r#"{ "private": true, "workspaces": ["packages/*"] }"#,
+    );
+    write(
+        "packages/web/package.json",
+        r#"{ "name": "@acme/web", "dependencies": { "@acme/ui": "*" } }"#,
+    );
+    write(
+        "packages/web/index.ts",
+        "import { Button } from '@acme/ui';\n\nButton();\n",
+    );
+    write(
+        "packages/ui/package.json",
+        r#"{ "name": "@acme/ui", "main": "dist/index.js" }"#,
+    );
+    write("packages/ui/src/index.ts", "export function Button() {}\n");
+
+    // uv workspace with a src-layout member
+    write(
+        "pyproject.toml",
+        "[tool.uv.workspace]\nmembers = [\"py/*\"]\n",
+    );
+    write(
+        "py/service/pyproject.toml",
+        "[project]\nname = \"service\"\n",
+    );
+    write(
+        "py/service/app.py",
+        "from acme_shared.models import Thing\n\nThing()\n",
+    );
+    write(
+        "py/shared/pyproject.toml",
+        "[project]\nname = \"acme-shared\"\n",
+    );
+    write("py/shared/src/acme_shared/__init__.py", "");
+    write(
+        "py/shared/src/acme_shared/models.py",
+        "class Thing:\n    pass\n",
+    );
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec![
+            "crates/app/src/main.rs".to_string(),
+            "packages/web/index.ts".to_string(),
+            "py/service/app.py".to_string(),
+        ]),
+        trace_imports: true,
+        semantic_depth: 2,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "crates/shared-core/src/models.rs",
+        "packages/ui/src/index.ts",
+        "py/shared/src/acme_shared/models.py",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected} across the workspace, got {:?}",
+            expanded_files.keys()
+        );
+    }
+}
+
+#[test]
+fn test_python_src_layout_namespace_packages_and_reexports() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(
+        "pyproject.toml",
+        "[project]\nname = \"shop\"\n\n[tool.setuptools.packages.find]\nwhere = [\"src\"]\n",
+    );
+    write(
+        "app.py",
+        "from shop import Order, charge\nfrom plugins import stripe\n\ncharge(Order())\n",
+    );
+    // The package re-exports its API from submodules
+    write(
+        "src/shop/__init__.py",
+        r#""""Shop (with a docstring mentioning brackets: [)"""
+from .models import *
+from .services.billing import (
+    charge as charge,
+)
+
+__version__ = "1.0"
+"#,
+    );
+    write(
+        "src/shop/models.py",
+        "__all__ = [\"Order\"]\n\n\nclass Order:\n    pass\n",
+    );
+    write("src/shop/unused.py", "class Unused:\n    pass\n");
+    // `services` and `plugins` are namespace packages without __init__.py
+    write(
+        "src/shop/services/billing.py",
+        "def charge(order):\n    pass\n",
+    );
+    write("src/plugins/stripe.py", "def pay():\n    pass\n");
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["app.py".to_string()]),
+        trace_imports: true,
+        semantic_depth: 2,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "src/shop/models.py",
+        "src/shop/services/billing.py",
+        "src/plugins/stripe.py",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+    assert!(
+        !expanded_files.contains_key(&root.join("src/shop/unused.py")),
+        "Only the modules defining the imported names should be traced"
+    );
+}
+
+#[test]
+fn test_typescript_barrel_reexports() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(
+        "src/App.tsx",
+        "import { Button, Header, Avatar } from './components';\n\nexport const App = () => Button(Header(Avatar()));\n",
+    );
+    // The barrel re-exports its components, one of them through a nested barrel
+    write(
+        "src/components/index.ts",
+        r#"export * from './Button';
+export * from './Unused';
+export { CardHeader as Header } from './Card';
+export { default as Avatar } from './Avatar';
+"#,
+    );
+    write(
+        "src/components/Button.tsx",
+        "export function Button() {\n  return null;\n}\n",
+    );
+    write(
+        "src/components/Unused.tsx",
+        "export const Unused = () => null;\n",
+    );
+    write(
+        "src/components/Avatar.tsx",
+        "export default function Avatar() {\n  return null;\n}\n",
+    );
+    write(
+        "src/components/Card/index.ts",
+        "export * from './CardHeader';\n",
+    );
+    write(
+        "src/components/Card/CardHeader.tsx",
+        "export const CardHeader = () => null;\n",
+    );
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["src/App.tsx".to_string()]),
+        trace_imports: true,
+        semantic_depth: 1,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "src/components/Button.tsx",
+        "src/components/Avatar.tsx",
+        "src/components/Card/CardHeader.tsx",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+    for unexpected in ["src/components/index.ts", "src/components/Unused.tsx"] {
+        assert!(
+            !expanded_files.contains_key(&root.join(unexpected)),
+            "Only the modules defining the imported names should be traced, got {unexpected}"
+        );
+    }
+}
+
+#[test]
+fn test_javascript_dynamic_imports() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(
+        "src/routes.tsx",
+        r#"import React from 'react';
+
+const Home = React.lazy(() => import('./pages/Home'));
+const worker = new Worker(require.resolve('./workers/sync'));
+
+export const routes = [{ path: '/', element: Home }];
+"#,
+    );
+    write(
+        "src/pages/Home.tsx",
+        "import { Banner } from '../components/Banner';\n\nexport default function Home() {\n  return Banner();\n}\n",
+    );
+    write(
+        "src/components/Banner.tsx",
+        "export function Banner() {\n  return null;\n}\n",
+    );
+    write("src/workers/sync.ts", "export {};\n");
+    write(
+        "src/routes.test.ts",
+        "jest.mock('./pages/Home');\n\ntest('routes', () => {});\n",
+    );
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["src/routes.tsx".to_string()]),
+        trace_imports: true,
+        semantic_depth: 3,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "src/pages/Home.tsx",
+        "src/components/Banner.tsx",
+        "src/workers/sync.ts",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+}
+
+#[test]
+fn test_rust_module_tree_path_dependencies_and_includes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write("Cargo.toml", "[workspace]\nmembers = [\"app\"]\n");
+    // `billing-core` is a path dependency, not a workspace member
+    write(
+        "app/Cargo.toml",
+        "[package]\nname = \"app\"\n\n[dependencies]\nbilling-core = { path = \"../libs/billing\" }\n",
+    );
+    write(
+        "app/src/main.rs",
+        "mod cli;\nuse billing_core::Invoice;\n\nfn main() {\n    cli::run(Invoice);\n}\n",
+    );
+    // `cli.rs` keeps its submodules in `cli/`
+    write(
+        "app/src/cli.rs",
+        "mod commands;\n#[path = \"generated/version.rs\"]\nmod version;\n\npub fn run<T>(_: T) {}\n",
+    );
+    write(
+        "app/src/cli/commands.rs",
+        "const HELP: &str = include_str!(\"../../help.txt\");\n",
+    );
+    write(
+        "app/src/generated/version.rs",
+        "pub const VERSION: &str = \"1\";\n",
+    );
+    write("app/help.txt", "usage: app\n");
+    write(
+        "libs/billing/Cargo.toml",
+        "[package]\nname = \"billing-core\"\n",
+    );
+    write(
+        "libs/billing/src/lib.rs",
+        "mod invoice;\npub mod unused;\n\npub use invoice::Invoice;\n",
+    );
+    write("libs/billing/src/invoice.rs", "pub struct Invoice;\n");
+    write("libs/billing/src/unused.rs", "pub struct Unused;\n");
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["app/src/main.rs".to_string()]),
+        trace_imports: true,
+        semantic_depth: 4,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "app/src/cli.rs",
+        "app/src/cli/commands.rs",
+        "app/src/generated/version.rs",
+        "app/help.txt",
+        "libs/billing/src/invoice.rs",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+    assert!(
+        !expanded_files.contains_key(&root.join("libs/billing/src/unused.rs")),
+        "Only the module defining the re-exported item should be traced"
+    );
+}
+
+#[test]
+fn test_c_and_cpp_includes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    // `engine/` headers are found through the compilation database
+    write(
+        "build/compile_commands.json",
+        &format!(
+            r#"[{{"directory": "{}", "file": "../app/main.cpp", "arguments": ["c++", "-I../engine/include", "-c", "../app/main.cpp"]}}]"#,
+            root.join("build").display()
+        ),
+    );
+    write(
+        "app/main.cpp",
+        "#include <vector>\n#include \"config.h\"\n#include \"engine/physics.hpp\"\n\nint main() { return step(); }\n",
+    );
+    write("app/config.h", "#define MAX_BODIES 64\n");
+    // Headers pull in the sources implementing them
+    write(
+        "engine/include/engine/physics.hpp",
+        "#pragma once\nint step();\n",
+    );
+    write(
+        "engine/src/engine/physics.cpp",
+        "#include \"engine/physics.hpp\"\n#include \"../vector_math.h\"\n\nint step() { return dot(); }\n",
+    );
+    write("engine/src/vector_math.h", "int dot(void);\n");
+    write("engine/src/vector_math.c", "int dot(void) { return 0; }\n");
+    write("engine/src/unused.c", "void unused(void) {}\n");
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["app/main.cpp".to_string()]),
+        trace_imports: true,
+        semantic_depth: 4,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "app/config.h",
+        "engine/include/engine/physics.hpp",
+        "engine/src/engine/physics.cpp",
+        "engine/src/vector_math.h",
+        "engine/src/vector_math.c",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+    assert!(!expanded_files.contains_key(&root.join("engine/src/unused.c")));
+}
+
+#[test]
+fn test_c_include_paths() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(
+        "src/main.c",
+        "#include \"net/socket.h\"\n\nint main(void) { return open_socket(); }\n",
+    );
+    write(
+        "third_party/include/net/socket.h",
+        "int open_socket(void);\n",
+    );
+
+    let mut config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["src/main.c".to_string()]),
+        trace_imports: true,
+        semantic_depth: 2,
+        ..Default::default()
+    };
+
+    // Without a compilation database the header is only found when configured
+    let expanded_files = process_files_with_trace_imports(root, &config);
+    assert!(!expanded_files.contains_key(&root.join("third_party/include/net/socket.h")));
+
+    config.include_paths = vec![PathBuf::from("third_party/include")];
+    let expanded_files = process_files_with_trace_imports(root, &config);
+    assert!(
+        expanded_files.contains_key(&root.join("third_party/include/net/socket.h")),
+        "Should trace the header found through the include path, got {:?}",
+        expanded_files.keys()
+    );
+}
+
+#[test]
+fn test_csharp_namespaces_and_project_references() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(
+        "Shop.Api/Shop.Api.csproj",
+        "<Project Sdk=\"Microsoft.NET.Sdk.Web\">\n  <ItemGroup>\n    <ProjectReference Include=\"..\\Shop.Core\\Shop.Core.csproj\" />\n  </ItemGroup>\n</Project>\n",
+    );
+    write(
+        "Shop.Api/Program.cs",
+        "using System.Linq;\nusing Shop.Core.Models;\nusing static Shop.Core.Guard;\n\nnamespace Shop.Api;\n\npublic static class Program { }\n",
+    );
+    write(
+        "Shop.Core/Shop.Core.csproj",
+        "<Project Sdk=\"Microsoft.NET.Sdk\" />\n",
+    );
+    // Namespaces are not tied to directories
+    write(
+        "Shop.Core/Domain/Order.cs",
+        "namespace Shop.Core.Models\n{\n    public class Order { }\n}\n",
+    );
+    write(
+        "Shop.Core/Guard.cs",
+        "namespace Shop.Core;\n\npublic static class Guard { }\n",
+    );
+    write(
+        "Shop.Core/Cart.cs",
+        "namespace Shop.Core;\n\npublic class Cart { }\n",
+    );
+    write(
+        "Shop.Core/obj/Generated.cs",
+        "namespace Shop.Core.Models;\n\npublic class Generated { }\n",
+    );
+    // Projects that are not referenced do not contribute to the namespace
+    write(
+        "Shop.Admin/Shop.Admin.csproj",
+        "<Project Sdk=\"Microsoft.NET.Sdk\" />\n",
+    );
+    write(
+        "Shop.Admin/Refund.cs",
+        "namespace Shop.Core.Models;\n\npublic class Refund { }\n",
+    );
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["Shop.Api/Program.cs".to_string()]),
+        trace_imports: true,
+        semantic_depth: 3,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in ["Shop.Core/Domain/Order.cs", "Shop.Core/Guard.cs"] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+    for unexpected in [
+        "Shop.Core/Cart.cs",
+        "Shop.Core/obj/Generated.cs",
+        "Shop.Admin/Refund.cs",
+    ] {
+        assert!(
+            !expanded_files.contains_key(&root.join(unexpected)),
+            "Should not trace {unexpected}"
+        );
+    }
+}
+
+#[test]
+fn test_ruby_require_and_zeitwerk_constants() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write("Gemfile", "source 'https://rubygems.org'\ngem 'rails'\n");
+    write(
+        "app/controllers/admin/orders_controller.rb",
+        "require_relative '../../../lib/pricing'\n\nmodule Admin\n  class OrdersController < ApplicationController\n    include Auditable\n\n    def show\n      @order = Order.find(params[:id])\n    end\n  end\nend\n",
+    );
+    write(
+        "app/controllers/application_controller.rb",
+        "class ApplicationController < ActionController::Base\nend\n",
+    );
+    write(
+        "app/controllers/concerns/auditable.rb",
+        "module Auditable\nend\n",
+    );
+    write(
+        "app/models/order.rb",
+        "class Order < ApplicationRecord\nend\n",
+    );
+    write("app/models/invoice.rb", "class Invoice\nend\n");
+    write("lib/pricing.rb", "module Pricing\nend\n");
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["app/controllers/admin/*.rb".to_string()]),
+        trace_imports: true,
+        semantic_depth: 2,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "lib/pricing.rb",
+        "app/controllers/application_controller.rb",
+        "app/controllers/concerns/auditable.rb",
+        "app/models/order.rb",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+    assert!(!expanded_files.contains_key(&root.join("app/models/invoice.rb")));
+}
+
+#[test]
+fn test_php_use_statements_and_psr4_autoloading() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+
+    fs::create_dir_all(root.join(".git")).unwrap();
+    write(
+        "composer.json",
+        r#"{ "autoload": { "psr-4": { "App\\": "app/" } } }"#,
+    );
+    write(
+        "app/Http/Controllers/OrderController.php",
+        "<?php\n\nnamespace App\\Http\\Controllers;\n\nuse App\\Models\\{Order, Customer as Client};\nuse Illuminate\\Http\\Request;\n\nrequire_once __DIR__ . '/../../helpers.php';\n\nclass OrderController extends Controller\n{\n}\n",
+    );
+    write(
+        "app/Http/Controllers/Controller.php",
+        "<?php\n\nnamespace App\\Http\\Controllers;\n\nabstract class Controller\n{\n}\n",
+    );
+    write(
+        "app/Models/Order.php",
+        "<?php\n\nnamespace App\\Models;\n\nclass Order {}\n",
+    );
+    write(
+        "app/Models/Customer.php",
+        "<?php\n\nnamespace App\\Models;\n\nclass Customer {}\n",
+    );
+    write(
+        "app/Models/Invoice.php",
+        "<?php\n\nnamespace App\\Models;\n\nclass Invoice {}\n",
+    );
+    write("app/helpers.php", "<?php\n\nfunction money($amount) {}\n");
+
+    let config = Config {
+        paths: Some(vec![root.to_path_buf()]),
+        include: Some(vec!["app/Http/Controllers/OrderController.php".to_string()]),
+        trace_imports: true,
+        semantic_depth: 2,
+        ..Default::default()
+    };
+
+    let expanded_files = process_files_with_trace_imports(root, &config);
+
+    for expected in [
+        "app/Models/Order.php",
+        "app/Models/Customer.php",
+        "app/Http/Controllers/Controller.php",
+        "app/helpers.php",
+    ] {
+        assert!(
+            expanded_files.contains_key(&root.join(expected)),
+            "Should trace {expected}, got {:?}",
+            expanded_files.keys()
+        );
+    }
+    assert!(!expanded_files.contains_key(&root.join("app/Models/Invoice.php")));
+}
+
 #[test]
 fn test_mixed_language_imports() {
     let temp_dir = TempDir::new().unwrap();
@@ -16,6 +16,8 @@ fn test_server_info() {
         .contains("Context Creator MCP Server"));
     assert!(info.capabilities.tools.is_some());
     assert!(info.capabilities.tools.unwrap().list_changed.is_none());
+    assert!(info.capabilities.resources.is_some());
+    assert!(info.capabilities.prompts.is_some());
 }
 
 #[test]
@@ -29,3 +31,208 @@ fn test_server_creation() {
 
     assert_eq!(info1.instructions, info2.instructions);
 }
+
+#[tokio::test]
+async fn test_find_callers_tool() {
+    use context_creator::mcp_server::{FindCallersResponse, SymbolGraphRequest};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(
+        temp_dir.path().join("lib.py"),
+        "def helper():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp_dir.path().join("app.py"),
+        "from lib import helper\n\nhelper()\n",
+    )
+    .unwrap();
+
+    let server = ContextCreatorServer::new();
+    let request = SymbolGraphRequest {
+        path: temp_dir.path().to_path_buf(),
+        symbol: "helper".to_string(),
+        depth: None,
+    };
+    let response = server
+        .tools()
+        .call(
+            "find_callers",
+            serde_json::to_value(request).unwrap(),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let response: FindCallersResponse = serde_json::from_value(response).unwrap();
+
+    assert_eq!(response.callers.len(), 1);
+    assert_eq!(response.callers[0].file, std::path::Path::new("app.py"));
+    assert_eq!(response.callers[0].line, 3);
+    assert_eq!(response.files_analyzed, 2);
+}
+
+/// A small Python project with an ignored file
+fn python_project() -> tempfile::TempDir {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir(temp_dir.path().join("pkg")).unwrap();
+    std::fs::write(
+        temp_dir.path().join("pkg/lib.py"),
+        "def helper():\n    return 1\n",
+    )
+    .unwrap();
+    std::fs::write(
+        temp_dir.path().join("app.py"),
+        "from pkg.lib import helper\n\nhelper()\n",
+    )
+    .unwrap();
+    std::fs::write(temp_dir.path().join("secret.txt"), "token\n").unwrap();
+    std::fs::write(
+        temp_dir.path().join(".context-creator-ignore"),
+        "secret.txt\n",
+    )
+    .unwrap();
+    temp_dir
+}
+
+fn text_contents(result: &rmcp::model::ReadResourceResult) -> (&str, &str) {
+    match &result.contents[0] {
+        rmcp::model::ResourceContents::TextResourceContents {
+            text, mime_type, ..
+        } => (text, mime_type.as_deref().unwrap_or_default()),
+        other => panic!("expected text contents, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_resources_list_and_read_project_files() {
+    use context_creator::mcp_server::resources;
+
+    let temp_dir = python_project();
+    let root = temp_dir.path().canonicalize().unwrap();
+
+    let listed = resources::list_resources(&root, None).unwrap();
+    let names: Vec<_> = listed.resources.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(listed.resources[0].uri, resources::PROJECT_BUNDLE_URI);
+    assert!(names.contains(&"app.py"));
+    assert!(!names.contains(&"secret.txt"));
+    assert!(listed.next_cursor.is_none());
+
+    let app = listed
+        .resources
+        .iter()
+        .find(|r| r.name == "app.py")
+        .unwrap();
+    let read = resources::read_resource(&root, &app.uri).unwrap();
+    let (text, _) = text_contents(&read);
+    assert_eq!(text, "from pkg.lib import helper\n\nhelper()\n");
+
+    // Ignored files and files outside the project are not resources
+    let secret = format!("file://{}", root.join("secret.txt").display());
+    assert!(resources::read_resource(&root, &secret).is_err());
+    assert!(resources::read_resource(&root, "file:///etc/hostname").is_err());
+    assert!(resources::read_resource(&root, "https://example.com").is_err());
+}
+
+#[test]
+fn test_resources_read_context_bundle() {
+    use context_creator::mcp_server::resources;
+
+    let temp_dir = python_project();
+    let root = temp_dir.path().canonicalize().unwrap();
+
+    let read =
+        resources::read_resource(&root, "context://project?include=pkg%2F**&format=xml").unwrap();
+    let (text, mime_type) = text_contents(&read);
+    assert_eq!(mime_type, "application/xml");
+    assert!(text.contains("def helper()"));
+    assert!(!text.contains("from pkg.lib import helper"));
+
+    assert!(resources::read_resource(&root, "context://project?colour=blue").is_err());
+    assert_eq!(resources::resource_templates().len(), 1);
+}
+
+#[test]
+fn test_prompts_assemble_context() {
+    use context_creator::mcp_server::{project_index::ProjectIndex, prompts};
+
+    let temp_dir = python_project();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let projects = ProjectIndex::new();
+    let names: Vec<_> = prompts::list_prompts()
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    assert_eq!(names, vec!["review_diff", "explain_module", "find_callers"]);
+
+    let arguments = |pairs: &[(&str, &str)]| {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), serde_json::json!(value)))
+            .collect::<serde_json::Map<_, _>>()
+    };
+    let text = |result: rmcp::model::GetPromptResult| {
+        serde_json::to_value(&result.messages[0]).unwrap()["content"]["text"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+
+    let explained = prompts::get_prompt(
+        &root,
+        &projects,
+        "explain_module",
+        Some(&arguments(&[
+            ("module", "pkg"),
+            ("include_imports", "false"),
+        ])),
+    )
+    .unwrap();
+    let explained = text(explained);
+    assert!(explained.contains("Explain what `pkg` does"));
+    assert!(explained.contains("def helper()"));
+    assert!(!explained.contains("helper()\n\nhelper()"));
+
+    let callers = prompts::get_prompt(
+        &root,
+        &projects,
+        "find_callers",
+        Some(&arguments(&[("symbol", "helper")])),
+    )
+    .unwrap();
+    let callers = text(callers);
+    assert!(callers.contains("- app.py:3 calls `helper`"));
+    assert!(callers.contains("from pkg.lib import helper"));
+
+    assert!(prompts::get_prompt(&root, &projects, "explain_module", None).is_err());
+    assert!(prompts::get_prompt(&root, &projects, "unknown", None).is_err());
+}
+
+#[test]
+fn test_review_diff_prompt() {
+    use context_creator::mcp_server::{project_index::ProjectIndex, prompts};
+    use std::process::Command;
+
+    let temp_dir = python_project();
+    let root = temp_dir.path().canonicalize().unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(&root)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Initial commit"]);
+    std::fs::write(root.join("pkg/lib.py"), "def helper():\n    return 2\n").unwrap();
+
+    let result = prompts::get_prompt(&root, &ProjectIndex::new(), "review_diff", None).unwrap();
+    let message = serde_json::to_value(&result.messages[0]).unwrap();
+    let text = message["content"]["text"].as_str().unwrap();
+    assert!(text.contains("-    return 1\n+    return 2"));
+    assert!(text.contains("## Changed files"));
+    assert!(text.contains("pkg/lib.py"));
+    assert!(!text.contains("from pkg.lib import helper"));
+}
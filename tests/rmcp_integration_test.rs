@@ -1,12 +1,197 @@
 //! Integration tests for RMCP MCP server
 
 use anyhow::Result;
-use serde_json::json;
+use context_creator::mcp_server::rmcp_server::ContextCreatorServer;
+use rmcp::ServiceExt;
+use serde_json::{json, Value};
 use std::process::Stdio;
 use tempfile::TempDir;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadHalf, WriteHalf};
 use tokio::process::Command;
 
+/// A client talking JSON-RPC to a server running in this process
+struct InProcessClient {
+    reader: BufReader<ReadHalf<DuplexStream>>,
+    writer: WriteHalf<DuplexStream>,
+}
+
+impl InProcessClient {
+    /// Serve a new server and complete the initialization handshake
+    async fn start() -> Result<Self> {
+        let (client, server) = tokio::io::duplex(1 << 20);
+        tokio::spawn(async move {
+            if let Ok(service) = ContextCreatorServer::new().serve(server).await {
+                let _ = service.waiting().await;
+            }
+        });
+
+        let (reader, writer) = tokio::io::split(client);
+        let mut client = Self {
+            reader: BufReader::new(reader),
+            writer,
+        };
+        client
+            .send(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": {"name": "test", "version": "0.0.0"}
+                }
+            }))
+            .await?;
+        client.receive().await?;
+        client
+            .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await?;
+        Ok(client)
+    }
+
+    async fn send(&mut self, message: Value) -> Result<()> {
+        self.writer
+            .write_all(format!("{message}\n").as_bytes())
+            .await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    async fn receive(&mut self) -> Result<Value> {
+        let mut line = String::new();
+        let read = tokio::time::timeout(
+            std::time::Duration::from_secs(30),
+            self.reader.read_line(&mut line),
+        )
+        .await??;
+        anyhow::ensure!(read > 0, "server closed the connection");
+        Ok(serde_json::from_str(&line)?)
+    }
+
+    /// Call a tool and collect the notifications sent before its response
+    async fn call_tool(
+        &mut self,
+        id: u64,
+        name: &str,
+        arguments: Value,
+        meta: Option<Value>,
+    ) -> Result<(Vec<Value>, Value)> {
+        let mut params = json!({"name": name, "arguments": arguments});
+        if let Some(meta) = meta {
+            params["_meta"] = meta;
+        }
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": "tools/call", "params": params}))
+            .await?;
+
+        let mut notifications = Vec::new();
+        loop {
+            let message = self.receive().await?;
+            if message["id"] == id {
+                return Ok((notifications, message));
+            }
+            notifications.push(message);
+        }
+    }
+}
+
+/// The JSON a tool call returned
+fn tool_json(response: &Value) -> Value {
+    let text = response["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap_or_else(|| panic!("not a tool result: {response}"));
+    serde_json::from_str(text).unwrap()
+}
+
+#[tokio::test]
+async fn test_tools_are_served() -> Result<()> {
+    let mut client = InProcessClient::start().await?;
+    client
+        .send(json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list", "params": {}}))
+        .await?;
+    let response = client.receive().await?;
+
+    let tools: Vec<_> = response["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert!(tools.contains(&"analyze_local"));
+    assert!(tools.contains(&"get_context"));
+    assert!(tools.contains(&"find_callers"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_context_tool_returns_json() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    std::fs::write(temp_dir.path().join("app.py"), "print('hello')\n")?;
+
+    let mut client = InProcessClient::start().await?;
+    let (notifications, response) = client
+        .call_tool(
+            1,
+            "get_context",
+            json!({"path": temp_dir.path(), "format": "json"}),
+            None,
+        )
+        .await?;
+
+    // Progress is only reported when asked for
+    assert!(notifications.is_empty());
+    let response = tool_json(&response);
+    assert_eq!(response["file_count"], 1);
+    assert_eq!(response["files"][0]["path"], "app.py");
+    let context: Value = serde_json::from_str(response["context"].as_str().unwrap())?;
+    assert_eq!(context["files"][0]["path"], "app.py");
+    assert_eq!(context["files"][0]["language"], "Python");
+    assert_eq!(context["files"][0]["content"], "print('hello')\n");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_context_reports_progress() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    for i in 0..5 {
+        std::fs::write(
+            temp_dir.path().join(format!("module_{i}.py")),
+            format!("VALUE = {i}\n"),
+        )?;
+    }
+
+    let mut client = InProcessClient::start().await?;
+    let (notifications, response) = client
+        .call_tool(
+            1,
+            "get_context",
+            json!({"path": temp_dir.path()}),
+            Some(json!({"progressToken": "context-1"})),
+        )
+        .await?;
+
+    assert_eq!(tool_json(&response)["file_count"], 5);
+    assert!(!notifications.is_empty());
+    let mut last = 0;
+    for notification in &notifications {
+        assert_eq!(notification["method"], "notifications/progress");
+        let params = &notification["params"];
+        assert_eq!(params["progressToken"], "context-1");
+        assert_eq!(params["total"], 1000);
+        let progress = params["progress"].as_u64().unwrap();
+        assert!(progress > last && progress < 1000);
+        last = progress;
+    }
+    let messages: Vec<_> = notifications
+        .iter()
+        .map(|notification| notification["params"]["message"].as_str().unwrap())
+        .collect();
+    assert!(messages.iter().any(|message| message.starts_with("walk:")));
+    assert!(messages
+        .iter()
+        .any(|message| message.starts_with("render:")));
+    Ok(())
+}
+
 #[tokio::test]
 #[ignore = "RMCP stdio transport has initialization issues"]
 async fn test_rmcp_server_initialization() -> Result<()> {
@@ -211,6 +396,12 @@ async fn test_list_tools() -> Result<()> {
 
     assert!(tool_names.contains(&"analyze_local"));
     assert!(tool_names.contains(&"analyze_remote"));
+    assert!(tool_names.contains(&"get_context"));
+    assert!(tool_names.contains(&"get_imports"));
+    assert!(tool_names.contains(&"get_importers"));
+    assert!(tool_names.contains(&"find_callers"));
+    assert!(tool_names.contains(&"find_definition"));
+    assert!(tool_names.contains(&"get_type_hierarchy"));
     assert!(tool_names.contains(&"file_metadata"));
     assert!(tool_names.contains(&"search"));
     assert!(tool_names.contains(&"diff"));
@@ -221,3 +412,47 @@ async fn test_list_tools() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_streamable_http_transport() -> Result<()> {
+    use context_creator::mcp_server::rmcp_server::transport::{
+        streamable_http_router, STREAMABLE_HTTP_PATH,
+    };
+    use tower::ServiceExt as _;
+
+    let router = streamable_http_router(ContextCreatorServer::new());
+    let initialize = json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2025-03-26",
+            "capabilities": {},
+            "clientInfo": {"name": "test", "version": "0.0.0"}
+        }
+    });
+    let request = axum::http::Request::post(STREAMABLE_HTTP_PATH)
+        .header("content-type", "application/json")
+        .header("accept", "application/json, text/event-stream")
+        .body(axum::body::Body::from(initialize.to_string()))?;
+
+    let response = router.oneshot(request).await?;
+    assert!(response.status().is_success());
+    assert!(response.headers().contains_key("mcp-session-id"));
+
+    // The response arrives as a server-sent event
+    let body = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        axum::body::to_bytes(response.into_body(), usize::MAX),
+    )
+    .await??;
+    let body = String::from_utf8(body.to_vec())?;
+    let data = body
+        .lines()
+        .find_map(|line| line.strip_prefix("data:"))
+        .expect("no event in the response");
+    let message: Value = serde_json::from_str(data.trim())?;
+    assert_eq!(message["id"], 0);
+    assert!(message["result"]["capabilities"]["tools"].is_object());
+    Ok(())
+}